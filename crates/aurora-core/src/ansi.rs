@@ -0,0 +1,240 @@
+//! Terminal-output interpretation for captured log lines.
+//!
+//! A beam run under a pseudo-terminal (`tty = true`) writes what it would write
+//! to a real terminal: SGR colour codes, and progress bars that redraw one line
+//! with a carriage return (`\r`) or an erase-line (`ESC[K`). Stripping those
+//! bytes blindly leaves every frame of a progress bar glued together; passing
+//! them through lets a terminal reinterpret them behind the renderer's back.
+//!
+//! [`render_line`] emulates the single line a terminal would display: it
+//! replays the cursor movements within the line, keeps the SGR styling as
+//! neutral [`Style`] runs (the TUI maps them to its own styles) and drops
+//! every other escape or control sequence. [`plain_text`] is the same line
+//! without styling, for the outputs that must stay clean text (headless logs,
+//! `--json`).
+
+/// A colour selected by an SGR sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    /// An index in the terminal palette: `0..=7` the basic colours, `8..=15`
+    /// their bright variants, `16..=255` the 256-colour cube and greyscale.
+    Indexed(u8),
+    /// A 24-bit colour (`38;2;r;g;b`).
+    Rgb(u8, u8, u8),
+}
+
+/// The SGR attributes in effect for a character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub bold: bool,
+    pub dim: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub reverse: bool,
+}
+
+/// A styled byte range of a [`RenderedLine::text`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StyledRun {
+    pub start: usize,
+    pub end: usize,
+    pub style: Style,
+}
+
+/// A line as a terminal would display it: the visible text plus the runs that
+/// carry a non-default style (unstyled text has no run).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RenderedLine {
+    pub text: String,
+    pub runs: Vec<StyledRun>,
+}
+
+/// Renders `input` the way a terminal would display it on a single line.
+///
+/// `\r` moves the cursor back to the first column, so the text that follows
+/// overwrites the previous frame; `ESC[K` erases (part of) the line; `ESC[nG`,
+/// `ESC[nC` and `ESC[nD` move the cursor within it; backspace moves it left.
+/// SGR sequences (`ESC[...m`) become styled runs. Every other CSI sequence,
+/// OSC sequences (hyperlinks, window titles) and control characters are
+/// dropped; the tab is kept.
+pub fn render_line(input: &str) -> RenderedLine {
+    let mut cells: Vec<(char, Style)> = Vec::new();
+    let mut cursor = 0usize;
+    let mut style = Style::default();
+
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => match chars.peek() {
+                // CSI: ESC [ params final, final byte in 0x40..=0x7E.
+                Some('[') => {
+                    chars.next();
+                    let mut params = String::new();
+                    let mut final_byte = None;
+                    for n in chars.by_ref() {
+                        if ('\x40'..='\x7e').contains(&n) {
+                            final_byte = Some(n);
+                            break;
+                        }
+                        params.push(n);
+                    }
+                    // Private sequences (`ESC[?25l` hides the cursor) carry a
+                    // marker byte and never affect the line's content.
+                    if params.starts_with(['?', '<', '=', '>']) {
+                        continue;
+                    }
+                    let args = parse_params(&params);
+                    let first = args.first().copied().unwrap_or(0);
+                    match final_byte {
+                        Some('m') => apply_sgr(&mut style, &args),
+                        Some('K') => match first {
+                            0 => cells.truncate(cursor),
+                            1 => {
+                                let end = (cursor + 1).min(cells.len());
+                                for cell in &mut cells[..end] {
+                                    *cell = (' ', Style::default());
+                                }
+                            }
+                            _ => cells.clear(),
+                        },
+                        Some('G') => cursor = first.max(1) as usize - 1,
+                        Some('C') => cursor += first.max(1) as usize,
+                        Some('D') => cursor = cursor.saturating_sub(first.max(1) as usize),
+                        _ => {}
+                    }
+                }
+                // OSC: ESC ] ... terminated by BEL (0x07) or ST (ESC \).
+                Some(']') => {
+                    chars.next();
+                    while let Some(n) = chars.next() {
+                        if n == '\x07' {
+                            break;
+                        }
+                        if n == '\x1b' {
+                            if chars.peek() == Some(&'\\') {
+                                chars.next();
+                            }
+                            break;
+                        }
+                    }
+                }
+                // Other short escape sequence: skip the next byte.
+                _ => {
+                    chars.next();
+                }
+            },
+            '\r' => cursor = 0,
+            '\x08' => cursor = cursor.saturating_sub(1),
+            c if c.is_control() && c != '\t' => {}
+            c => {
+                if cursor < cells.len() {
+                    cells[cursor] = (c, style);
+                } else {
+                    // A cursor moved past the end leaves blank columns behind,
+                    // as on a terminal.
+                    cells.resize(cursor, (' ', Style::default()));
+                    cells.push((c, style));
+                }
+                cursor += 1;
+            }
+        }
+    }
+
+    let mut line = RenderedLine::default();
+    for (c, cell_style) in cells {
+        let start = line.text.len();
+        line.text.push(c);
+        if cell_style == Style::default() {
+            continue;
+        }
+        match line.runs.last_mut() {
+            Some(run) if run.end == start && run.style == cell_style => run.end = line.text.len(),
+            _ => line.runs.push(StyledRun {
+                start,
+                end: line.text.len(),
+                style: cell_style,
+            }),
+        }
+    }
+    line
+}
+
+/// The text of `input` as [`render_line`] displays it, without styling.
+pub fn plain_text(input: &str) -> String {
+    render_line(input).text
+}
+
+/// Splits CSI parameters on `;` (and the `:` sub-parameter separator). An
+/// empty or malformed parameter reads as 0, the terminal default.
+fn parse_params(params: &str) -> Vec<u32> {
+    if params.is_empty() {
+        return vec![];
+    }
+    params
+        .split([';', ':'])
+        .map(|p| p.parse().unwrap_or(0))
+        .collect()
+}
+
+fn apply_sgr(style: &mut Style, args: &[u32]) {
+    // `ESC[m` is a reset, like `ESC[0m`.
+    if args.is_empty() {
+        *style = Style::default();
+        return;
+    }
+    let mut i = 0;
+    while i < args.len() {
+        match args[i] {
+            0 => *style = Style::default(),
+            1 => style.bold = true,
+            2 => style.dim = true,
+            3 => style.italic = true,
+            4 => style.underline = true,
+            7 => style.reverse = true,
+            22 => {
+                style.bold = false;
+                style.dim = false;
+            }
+            23 => style.italic = false,
+            24 => style.underline = false,
+            27 => style.reverse = false,
+            n @ 30..=37 => style.fg = Some(Color::Indexed((n - 30) as u8)),
+            n @ 90..=97 => style.fg = Some(Color::Indexed((n - 90 + 8) as u8)),
+            n @ 40..=47 => style.bg = Some(Color::Indexed((n - 40) as u8)),
+            n @ 100..=107 => style.bg = Some(Color::Indexed((n - 100 + 8) as u8)),
+            39 => style.fg = None,
+            49 => style.bg = None,
+            n @ (38 | 48) => {
+                let (color, consumed) = extended_color(&args[i + 1..]);
+                if n == 38 {
+                    style.fg = color.or(style.fg);
+                } else {
+                    style.bg = color.or(style.bg);
+                }
+                i += consumed;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+}
+
+/// Reads the colour that follows a `38`/`48`: `5;n` (palette) or `2;r;g;b`
+/// (24-bit). Returns the colour, if well-formed, and how many parameters it
+/// spans so the caller skips them.
+fn extended_color(args: &[u32]) -> (Option<Color>, usize) {
+    let byte = |v: u32| u8::try_from(v).ok();
+    match args {
+        [5, n, ..] => (byte(*n).map(Color::Indexed), 2),
+        [2, r, g, b, ..] => {
+            let rgb = byte(*r)
+                .zip(byte(*g))
+                .zip(byte(*b))
+                .map(|((r, g), b)| Color::Rgb(r, g, b));
+            (rgb, 4)
+        }
+        _ => (None, args.len()),
+    }
+}
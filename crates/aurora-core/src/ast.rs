@@ -1,11 +1,55 @@
 use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::time::Duration;
+
+/// A byte range of the Beamfile source, recorded by the parser so an error can
+/// point at the declaration it is about. The default (empty, at offset 0)
+/// marks a node that does not come from the source, such as the virtual beam
+/// of a multi-beam run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    /// True for a node built outside the parser (no position in the source).
+    pub fn is_synthetic(&self) -> bool {
+        *self == Span::default()
+    }
+
+    /// The 1-based line and column of the start of the span in `source`. The
+    /// column counts characters, not bytes.
+    pub fn line_col(&self, source: &str) -> (usize, usize) {
+        let start = floor_char_boundary(source, self.start);
+        let before = &source[..start];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        (line, before[line_start..].chars().count() + 1)
+    }
+}
+
+/// `index` clamped into `source` and moved back onto a character boundary.
+pub(crate) fn floor_char_boundary(source: &str, index: usize) -> usize {
+    let mut index = index.min(source.len());
+    while !source.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
 
 #[derive(Debug, Clone)]
 pub struct BeamFile {
     pub config: Option<AuroraConfig>,
     pub variables: Vec<Variable>,
     pub environment: Option<Environment>,
+    /// `profile "ci" { ... }` blocks, in declaration order; at most one is
+    /// applied to a run.
+    pub profiles: Vec<Profile>,
     pub beams: Vec<Beam>,
 }
 
@@ -14,24 +58,261 @@ pub struct AuroraConfig {
     pub version: String,
     pub default: Option<String>,
     pub max_parallelism: Option<usize>,
+    /// How many runs keep their logs under `.aurora/logs` (`0` disables
+    /// them); the runner's default applies when unset.
+    pub log_retention: Option<usize>,
+    /// Ambient variables forwarded to every beam past the allowlist
+    /// (`inherit_env = ["SSH_AUTH_SOCK", "AWS_*"]`).
+    pub inherit_env: Vec<String>,
+}
+
+/// A named overlay of the globals (`profile "ci" { ... }`), selected with
+/// `--profile` or `AURORA_PROFILE`.
+#[derive(Debug, Clone)]
+pub struct Profile {
+    pub name: String,
+    /// Where the name is declared.
+    pub span: Span,
+    /// `variables = { region = "eu" }`: values for declared global
+    /// variables, beaten only by `--var`.
+    pub variables: Vec<ProfileVariable>,
+    /// Declared after the global `environment {}` block, so a name here
+    /// overrides the global one.
+    pub environment: Option<Environment>,
+    /// Replaces `aurora { max_parallelism = N }`.
+    pub max_parallelism: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProfileVariable {
+    pub name: String,
+    /// Where the binding is written.
+    pub span: Span,
+    pub value: String,
 }
 
 #[derive(Debug, Clone)]
 pub struct Variable {
     pub name: String,
+    /// Where the name is declared.
+    pub span: Span,
     pub default: String,
     pub description: Option<String>,
+    /// A secret (`sensitive = true`): its value is masked in CI logs.
+    pub sensitive: bool,
+    /// What a value must be, checked on `--var` and in the picker.
+    pub constraints: Constraints,
+    /// Where else the value may come from, evaluated once per run by
+    /// [`crate::env::evaluate_variables`].
+    pub sources: VariableSources,
+    /// Where `default` (the value in effect) came from.
+    pub origin: ValueOrigin,
 }
 
-#[derive(Debug, Clone)]
+impl Variable {
+    /// Where the value in effect came from, as `--print-vars` shows it.
+    pub fn origin_label(&self) -> String {
+        match self.origin {
+            ValueOrigin::Unset => "unset".to_string(),
+            ValueOrigin::Literal => "default".to_string(),
+            ValueOrigin::Shell => format!(
+                "shell `{}`",
+                self.sources.shell.as_deref().unwrap_or_default()
+            ),
+            ValueOrigin::File => {
+                format!("file {}", self.sources.file.as_deref().unwrap_or_default())
+            }
+            ValueOrigin::Env => format!("env {}", self.sources.env.as_deref().unwrap_or_default()),
+            ValueOrigin::Profile => "profile".to_string(),
+            ValueOrigin::Cli => "--var".to_string(),
+        }
+    }
+}
+
+/// The sources a variable can take its value from besides a literal
+/// `default`, highest precedence first (`--var` and a profile still win
+/// over all).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VariableSources {
+    /// `from_env = "NAME"`: the host's variable, when it is set.
+    pub env: Option<String>,
+    /// `from_file = "VERSION"`: the file's contents, relative to the
+    /// Beamfile's directory, when it exists.
+    pub file: Option<String>,
+    /// `default = shell("...")`: the command's output, run on the host.
+    pub shell: Option<String>,
+}
+
+/// Where a variable's value came from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ValueOrigin {
+    /// Nothing set it: the value is empty.
+    #[default]
+    Unset,
+    /// A literal `default`.
+    Literal,
+    /// `default = shell(...)`.
+    Shell,
+    /// `from_file`.
+    File,
+    /// `from_env`.
+    Env,
+    /// The selected `profile`.
+    Profile,
+    /// `--var` (or the picker's variables form).
+    Cli,
+}
+
+/// The declared `type` of a variable or a param. Values stay strings once
+/// checked: the type only says which strings are accepted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ValueType {
+    #[default]
+    String,
+    /// A signed 64-bit integer.
+    Int,
+    /// `true` or `false`.
+    Bool,
+    /// Comma-separated items; `choices` and `pattern` apply to each item.
+    List,
+}
+
+impl ValueType {
+    pub fn name(self) -> &'static str {
+        match self {
+            ValueType::String => "string",
+            ValueType::Int => "int",
+            ValueType::Bool => "bool",
+            ValueType::List => "list",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        [
+            ValueType::String,
+            ValueType::Int,
+            ValueType::Bool,
+            ValueType::List,
+        ]
+        .into_iter()
+        .find(|t| t.name() == name)
+    }
+}
+
+/// The `type`, `choices` and `pattern` of a variable or a param. The default
+/// (a string, anything goes) accepts every value.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Constraints {
+    pub value_type: ValueType,
+    pub choices: Vec<String>,
+    /// A regular expression the whole value must match. The parser rejects
+    /// one that does not compile.
+    pub pattern: Option<String>,
+}
+
+impl Constraints {
+    /// Checks `value`, returning what was expected when it does not fit.
+    pub fn check(&self, value: &str) -> Result<(), String> {
+        match self.value_type {
+            ValueType::String => self.check_item(value),
+            ValueType::Int => match value.parse::<i64>() {
+                Ok(_) => self.check_item(value),
+                Err(_) => Err("expected an int".to_string()),
+            },
+            ValueType::Bool => match value {
+                "true" | "false" => self.check_item(value),
+                _ => Err("expected a bool (true or false)".to_string()),
+            },
+            ValueType::List => value
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .try_for_each(|item| {
+                    self.check_item(item)
+                        .map_err(|e| format!("item '{item}': {e}"))
+                }),
+        }
+    }
+
+    fn check_item(&self, value: &str) -> Result<(), String> {
+        if !self.choices.is_empty() && !self.choices.iter().any(|c| c == value) {
+            let mut message = format!("expected one of {}", self.choices.join(", "));
+            let candidates = self.choices.iter().map(String::as_str);
+            if let Some(choice) = crate::diagnostic::closest(value, candidates) {
+                message.push_str(&format!("; did you mean '{choice}'?"));
+            }
+            return Err(message);
+        }
+        if let Some(pattern) = &self.pattern {
+            if !anchored(pattern).is_ok_and(|re| re.is_match(value)) {
+                return Err(format!("expected a value matching `{pattern}`"));
+            }
+        }
+        Ok(())
+    }
+
+    /// What a signature shows of the constraints: the choices
+    /// (`staging|prod`), else a type other than string (`int`).
+    pub fn hint(&self) -> Option<String> {
+        if !self.choices.is_empty() {
+            return Some(self.choices.join("|"));
+        }
+        (self.value_type != ValueType::String).then(|| self.value_type.name().to_string())
+    }
+}
+
+/// `pattern` compiled to match a whole value.
+pub fn anchored(pattern: &str) -> Result<regex::Regex, regex::Error> {
+    regex::Regex::new(&format!("^(?:{pattern})$"))
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct Environment {
     /// Evaluated sequentially in declaration order
     pub vars: Vec<EnvVar>,
+    /// `load = [...]` entries, each evaluated where it is declared among
+    /// `vars`.
+    pub loads: Vec<EnvLoad>,
+}
+
+impl Environment {
+    /// Appends `overlay`, evaluated after this block's entries: a name it
+    /// declares again overrides this block's.
+    pub fn extend(&mut self, overlay: Environment) {
+        let offset = self.vars.len();
+        self.loads
+            .extend(overlay.loads.into_iter().map(|load| EnvLoad {
+                after: load.after + offset,
+                ..load
+            }));
+        self.vars.extend(overlay.vars);
+    }
+}
+
+/// `load = [".env", { path = ".env.local", optional = true }]`: dotenv files
+/// whose entries join the environment, in order.
+#[derive(Debug, Clone)]
+pub struct EnvLoad {
+    /// How many of the block's `vars` are declared before it.
+    pub after: usize,
+    pub files: Vec<EnvFile>,
+}
+
+#[derive(Debug, Clone)]
+pub struct EnvFile {
+    /// Relative to the Beamfile's directory.
+    pub path: String,
+    /// Where the path is written.
+    pub span: Span,
+    /// A missing file is skipped rather than an error.
+    pub optional: bool,
 }
 
 #[derive(Debug, Clone)]
 pub struct EnvVar {
     pub name: String,
+    /// Where the name is declared.
+    pub span: Span,
     pub value: EnvValue,
 }
 
@@ -48,6 +329,8 @@ pub enum EnvValue {
 pub struct Dependency {
     pub beam: String,
     pub params: BTreeMap<String, String>,
+    /// The whole entry in the `depends_on` list.
+    pub span: Span,
 }
 
 impl Dependency {
@@ -55,6 +338,7 @@ impl Dependency {
         Self {
             beam: beam.into(),
             params: BTreeMap::new(),
+            span: Span::default(),
         }
     }
 }
@@ -64,14 +348,29 @@ impl Dependency {
 #[derive(Debug, Clone)]
 pub struct Param {
     pub name: String,
+    /// Where the name is declared.
+    pub span: Span,
     pub default: Option<String>,
     pub description: Option<String>,
+    /// A secret: its value is masked in CI logs and not remembered by the
+    /// picker.
+    pub sensitive: bool,
+    /// What a value must be, checked when it is bound.
+    pub constraints: Constraints,
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct Beam {
     pub name: String,
+    /// Where the name is declared. An instance keeps the span of the beam it
+    /// was expanded from.
+    pub span: Span,
+    /// The `template` this beam extends (`extends = "rust-ci"`), already
+    /// merged into its fields by the parser.
+    pub extends: Option<String>,
     pub description: Option<String>,
+    /// `tags = ["ci", "fast"]`: labels `--tag` selects targets by.
+    pub tags: Vec<String>,
     pub depends_on: Vec<Dependency>,
     pub inputs: Vec<String>,
     pub outputs: Vec<String>,
@@ -95,6 +394,21 @@ pub struct Beam {
     pub condition: Option<Condition>,
     pub run: Option<Run>,
     pub allow_failure: bool,
+    /// Run the commands under a pseudo-terminal (`tty = true`) instead of
+    /// pipes, so tools that probe for a terminal keep their colours and
+    /// progress output. stdout and stderr then share the terminal and arrive
+    /// as a single stream.
+    pub tty: bool,
+    /// Grace period between the stop request (SIGTERM, `docker stop`) and the
+    /// SIGKILL when the beam is cancelled (`kill_timeout = "30s"`). `None`
+    /// uses the scheduler's default.
+    pub kill_timeout: Option<Duration>,
+    /// Ambient variables forwarded to this beam past the allowlist, on top
+    /// of the global `inherit_env` (`*` and `?` wildcards allowed).
+    pub inherit_env: Vec<String>,
+    /// The ambient values `inherit_env` matched for this instance (filled
+    /// with `env_overlay`). Machine context: kept out of the cache key.
+    pub inherited_env: BTreeMap<String, String>,
     /// Evaluated per-instance `environment {}` overlay (filled after
     /// expansion, empty when the beam declares no block). Shadows the global
     /// environment for this instance only, in execution and in the cache key.
@@ -126,7 +440,7 @@ pub enum ConditionClause {
     Shell(String),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Run {
     pub commands: Vec<String>,
     pub executor: Option<ExecutorConfig>,
@@ -289,6 +289,10 @@ pub struct BeamDefinition<'a> {
     /// them keeps the invariant trivial: an instance's key always covers its
     /// bindings, even for a param no hashed field references.
     pub bindings: Option<&'a BTreeMap<String, String>>,
+    /// Whether the beam runs under a pseudo-terminal. Tools format their
+    /// output differently on a terminal, and the recorded logs are replayed on
+    /// a hit, so toggling `tty` must not serve the other mode's logs.
+    pub tty: bool,
 }
 
 impl BeamDefinition<'_> {
@@ -339,6 +343,12 @@ impl BeamDefinition<'_> {
             }
         }
 
+        // Only folded in when set, so the keys of the (default) piped beams
+        // are unchanged.
+        if self.tty {
+            field("tty", "true");
+        }
+
         format!("{:x}", hasher.finalize())
     }
 }
@@ -1,15 +1,68 @@
+use crate::ast::BeamFile;
+use crate::diagnostic::Diagnostic;
 use petgraph::algo::{is_cyclic_directed, toposort};
 use petgraph::graph::{DiGraph, NodeIndex};
 use petgraph::Direction;
-use std::collections::{HashMap, HashSet};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum DagError {
     #[error("Unknown beam referenced as dependency: '{0}'")]
     UnknownBeam(String),
-    #[error("Cycle detected in beam dependency graph involving: '{0}'")]
-    Cycle(String),
+    /// The beams of the cycle in dependency order, the first one repeated at
+    /// the end: `a -> b -> a` reads "a depends on b, which depends on a".
+    #[error("Cycle detected in beam dependency graph: {}", .0.join(" -> "))]
+    Cycle(Vec<String>),
+}
+
+/// Checks the dependency graph of the declared beams before anything is
+/// expanded: an unknown dependency or a cycle is reported as a [`Diagnostic`]
+/// on the `depends_on` entry at fault, with a suggestion for a misspelled
+/// name. The scheduler checks the graph of instances again; this is the check
+/// that can still point into the Beamfile.
+pub fn validate(beam_file: &BeamFile) -> anyhow::Result<()> {
+    let declared = || beam_file.beams.iter().map(|b| b.name.as_str());
+    for beam in &beam_file.beams {
+        for dep in &beam.depends_on {
+            if !declared().any(|name| name == dep.beam) {
+                return Err(Diagnostic::new(
+                    DagError::UnknownBeam(dep.beam.clone()).to_string(),
+                    dep.span,
+                )
+                .with_label("not declared")
+                .with_suggestion(&dep.beam, declared())
+                .into());
+            }
+        }
+    }
+    let deps = beam_file
+        .beams
+        .iter()
+        .map(|b| (b.name.clone(), b.dependency_names()))
+        .collect();
+    match BeamGraph::from_deps(deps) {
+        Ok(_) => Ok(()),
+        Err(DagError::Cycle(path)) => {
+            // Points at the first edge of the cycle: where its first beam
+            // depends on the second.
+            let span = beam_file
+                .beams
+                .iter()
+                .find(|b| b.name == path[0])
+                .and_then(|b| {
+                    let next = path.get(1)?;
+                    b.depends_on.iter().find(|d| &d.beam == next)
+                })
+                .map(|d| d.span)
+                .unwrap_or_default();
+            Err(Diagnostic::new(DagError::Cycle(path).to_string(), span)
+                .with_label("the cycle starts here")
+                .into())
+        }
+        Err(err) => Err(err.into()),
+    }
 }
 
 pub struct BeamGraph {
@@ -47,7 +100,7 @@ impl BeamGraph {
         // with the unknown-dependency check above (also global): a cycle in any
         // branch fails the file, not only one reachable from a given target.
         if let Err(cycle) = toposort(&graph, None) {
-            return Err(DagError::Cycle(graph[cycle.node_id()].clone()));
+            return Err(DagError::Cycle(cycle_path(&graph, cycle.node_id())));
         }
 
         Ok(BeamGraph { graph, index })
@@ -147,11 +200,12 @@ impl BeamGraph {
 
         // Check for cycles
         if is_cyclic_directed(&sub.graph) {
-            return Err(DagError::Cycle(root.to_string()));
+            return Err(DagError::Cycle(vec![root.to_string()]));
         }
 
         // Topological sort
-        let sorted = toposort(&sub.graph, None).map_err(|_| DagError::Cycle(root.to_string()))?;
+        let sorted = toposort(&sub.graph, None)
+            .map_err(|cycle| DagError::Cycle(cycle_path(&sub.graph, cycle.node_id())))?;
 
         // Compute the level (longest path from any source) for each node
         // Level = max(level of all incoming neighbors) + 1, or 0 if no incoming
@@ -205,3 +259,35 @@ impl BeamGraph {
         }
     }
 }
+
+/// The shortest cycle through `start`, following dependencies (the incoming
+/// edges), as names with the first-added beam of the cycle at both ends. Just
+/// `[start]` if `start` is in no cycle after all.
+fn cycle_path(graph: &DiGraph<String, ()>, start: NodeIndex) -> Vec<String> {
+    let mut previous: HashMap<NodeIndex, NodeIndex> = HashMap::new();
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        for dep in graph.neighbors_directed(node, Direction::Incoming) {
+            if dep == start {
+                let mut path = vec![start];
+                let mut at = node;
+                while at != start {
+                    path.push(at);
+                    at = previous[&at];
+                }
+                path.reverse();
+                // Nodes are added in declaration order: start the cycle at the
+                // beam declared first, whichever node the sort tripped on.
+                let first = (0..path.len()).min_by_key(|&i| path[i]).unwrap_or(0);
+                path.rotate_left(first);
+                path.push(path[0]);
+                return path.iter().map(|&n| graph[n].clone()).collect();
+            }
+            if let Entry::Vacant(entry) = previous.entry(dep) {
+                entry.insert(node);
+                queue.push_back(dep);
+            }
+        }
+    }
+    vec![graph[start].clone()]
+}
@@ -0,0 +1,165 @@
+//! Errors that point into the Beamfile.
+//!
+//! A [`Diagnostic`] is an ordinary error (it travels through `anyhow` like
+//! any other, and its `Display` is the one-line message) that also knows where
+//! in the source it comes from. The CLI finds it in an error chain with
+//! [`find`] and renders it with the offending line, a caret under the span and
+//! an optional hint; the `--json` stream reports the same span.
+
+use std::fmt;
+
+use crate::ast::{floor_char_boundary, Span};
+
+/// Similarity above which a candidate is offered as a "did you mean". Jaro-Winkler
+/// rewards a shared prefix, which is what a typo usually preserves.
+const SUGGESTION_THRESHOLD: f64 = 0.8;
+
+/// The candidate closest to `input`, when one is close enough to be worth
+/// suggesting. Returns `None` rather than a distant, misleading guess.
+pub fn closest<'a>(input: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    candidates
+        .map(|candidate| (candidate, strsim::jaro_winkler(input, candidate)))
+        .filter(|(_, score)| *score >= SUGGESTION_THRESHOLD)
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(candidate, _)| candidate)
+}
+
+/// An error about a place in the Beamfile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub message: String,
+    /// What the error is about: a declaration, or the position pest stopped
+    /// at. A synthetic span (see [`Span::is_synthetic`]) renders without a
+    /// snippet.
+    pub span: Span,
+    /// A note printed next to the caret.
+    pub label: Option<String>,
+    /// A hint on how to fix it (`did you mean 'x'?`).
+    pub help: Option<String>,
+    /// A piece of source text that narrows `span` down: its first occurrence
+    /// at or after the start of the span, when there is one. The passes that
+    /// run after parsing only keep the span of a beam, not of every string in
+    /// it; `${var.x}` is found again from there.
+    pub needle: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn new(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            message: message.into(),
+            span,
+            label: None,
+            help: None,
+            needle: None,
+        }
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    /// A `did you mean` help, when a candidate is close to `input`.
+    pub fn with_suggestion<'a>(
+        self,
+        input: &str,
+        candidates: impl Iterator<Item = &'a str>,
+    ) -> Self {
+        match closest(input, candidates) {
+            Some(candidate) => self.with_help(format!("did you mean '{candidate}'?")),
+            None => self,
+        }
+    }
+
+    pub fn with_needle(mut self, needle: impl Into<String>) -> Self {
+        self.needle = Some(needle.into());
+        self
+    }
+
+    /// The span the error points at in `source`: the needle when it is found,
+    /// otherwise `span`.
+    pub fn locate(&self, source: &str) -> Span {
+        let found = self.needle.as_deref().and_then(|needle| {
+            let from = self.span.start.min(source.len());
+            let at = source.get(from..)?.find(needle)? + from;
+            Some(Span::new(at, at + needle.len()))
+        });
+        found.unwrap_or(self.span)
+    }
+
+    /// The diagnostic as the CLI prints it: the message, then `path:line:col`,
+    /// the source line with a caret under the span, and the help.
+    ///
+    /// ```text
+    /// error: unknown variable 'regoin' referenced in beam 'deploy'
+    ///  --> Beamfile:4:29
+    ///   |
+    /// 4 |   run { commands = ["deploy ${var.regoin}"] }
+    ///   |                             ^^^^^^^^^^^^^ not declared
+    ///   |
+    ///   = help: did you mean 'region'?
+    /// ```
+    pub fn render(&self, source: &str, path: &str) -> String {
+        let mut out = format!("error: {}\n", self.message);
+        let span = self.locate(source);
+        if span.is_synthetic() {
+            if let Some(help) = &self.help {
+                out.push_str(&format!("  = help: {help}\n"));
+            }
+            return out;
+        }
+        let (line, column) = span.line_col(source);
+        let text = source.lines().nth(line - 1).unwrap_or("");
+        let pad = " ".repeat(line.to_string().len());
+        // The caret covers the span up to the end of its first line, and at
+        // least one character (an error at the end of the input).
+        let start = floor_char_boundary(source, span.start);
+        let rest_of_line = text.chars().count().saturating_sub(column - 1);
+        let width = source
+            .get(start..span.end.clamp(start, source.len()))
+            .map_or(0, |s| s.chars().count())
+            .min(rest_of_line)
+            .max(1);
+        let indent: String = text
+            .chars()
+            .take(column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        out.push_str(&format!("{pad}--> {path}:{line}:{column}\n"));
+        out.push_str(&format!("{pad} |\n"));
+        out.push_str(&format!("{line} | {text}\n"));
+        out.push_str(&format!("{pad} | {indent}{}", "^".repeat(width)));
+        if let Some(label) = &self.label {
+            out.push_str(&format!(" {label}"));
+        }
+        out.push('\n');
+        if let Some(help) = &self.help {
+            out.push_str(&format!("{pad} |\n{pad} = help: {help}\n"));
+        }
+        out
+    }
+}
+
+/// The message, and the help after it: a consumer that only shows the
+/// one-line form still gets the suggestion.
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some(help) = &self.help {
+            write!(f, "; {help}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Diagnostic {}
+
+/// The first [`Diagnostic`] in the chain of `err`.
+pub fn find(err: &anyhow::Error) -> Option<&Diagnostic> {
+    err.chain().find_map(|e| e.downcast_ref::<Diagnostic>())
+}
@@ -0,0 +1,242 @@
+//! Dotenv files, as loaded by `environment { load = [...] }`.
+//!
+//! The usual rules: one `KEY=VALUE` per line, an optional `export` prefix,
+//! `#` comments, and three kinds of value:
+//! - unquoted: trimmed, ending at a ` #` comment, with `$VAR` expansion;
+//! - `'single'`: taken literally;
+//! - `"double"`: with `\n`, `\t`, `\"`, `\\` and `\$` escapes and `$VAR`
+//!   expansion, and may span lines.
+//!
+//! Expansion (`$VAR`, `${VAR}`, `${VAR:-default}`, `${VAR-default}`) sees
+//! the entries above it in the same file, then `visible`; an unknown name
+//! expands to nothing.
+
+use std::collections::HashMap;
+
+/// Parses `content`, returning its entries in order, or the first error as
+/// `line N: ...`.
+pub fn parse(
+    content: &str,
+    visible: &HashMap<String, String>,
+) -> Result<Vec<(String, String)>, String> {
+    let mut parser = Parser {
+        chars: content.chars().collect(),
+        pos: 0,
+        line: 1,
+    };
+    let mut entries: Vec<(String, String)> = vec![];
+    loop {
+        parser.skip_blank_lines_and_comments();
+        if parser.at_end() {
+            return Ok(entries);
+        }
+        let line = parser.line;
+        let (key, value) = parser
+            .entry(|name| {
+                entries
+                    .iter()
+                    .rev()
+                    .find(|(key, _)| key == name)
+                    .map(|(_, value)| value.clone())
+                    .or_else(|| visible.get(name).cloned())
+            })
+            .map_err(|e| format!("line {line}: {e}"))?;
+        entries.push((key, value));
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+}
+
+impl Parser {
+    fn at_end(&self) -> bool {
+        self.pos >= self.chars.len()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        if c == '\n' {
+            self.line += 1;
+        }
+        Some(c)
+    }
+
+    fn skip_spaces(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t')) {
+            self.bump();
+        }
+    }
+
+    fn skip_to_line_end(&mut self) {
+        while let Some(c) = self.bump() {
+            if c == '\n' {
+                break;
+            }
+        }
+    }
+
+    fn skip_blank_lines_and_comments(&mut self) {
+        loop {
+            while matches!(self.peek(), Some(' ' | '\t' | '\r' | '\n')) {
+                self.bump();
+            }
+            if self.peek() == Some('#') {
+                self.skip_to_line_end();
+            } else {
+                return;
+            }
+        }
+    }
+
+    fn entry(
+        &mut self,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> Result<(String, String), String> {
+        let mut key = self.name();
+        if key == "export" && matches!(self.peek(), Some(' ' | '\t')) {
+            self.skip_spaces();
+            key = self.name();
+        }
+        if key.is_empty() {
+            return Err("expected KEY=VALUE".to_string());
+        }
+        self.skip_spaces();
+        if self.bump() != Some('=') {
+            return Err(format!("expected `=` after {key}"));
+        }
+        self.skip_spaces();
+        let value = match self.peek() {
+            Some('\'') => {
+                self.bump();
+                self.quoted('\'', |_, c| Ok(c.to_string()))?
+            }
+            Some('"') => {
+                self.bump();
+                let raw = self.quoted('"', |parser, c| match c {
+                    '\\' => match parser.bump() {
+                        Some('n') => Ok("\n".to_string()),
+                        Some('r') => Ok("\r".to_string()),
+                        Some('t') => Ok("\t".to_string()),
+                        // Kept escaped so expansion leaves it a literal `$`.
+                        Some('$') => Ok("\\$".to_string()),
+                        Some('"') => Ok("\"".to_string()),
+                        // Kept escaped too, so it cannot escape a `$`.
+                        Some('\\') => Ok("\\\\".to_string()),
+                        Some(c) => Ok(format!("\\{c}")),
+                        None => Err("unterminated quoted value".to_string()),
+                    },
+                    c => Ok(c.to_string()),
+                })?;
+                expand(&raw, &lookup)
+            }
+            _ => {
+                let mut raw = String::new();
+                while let Some(c) = self.peek() {
+                    if c == '\n' || (c == '#' && raw.ends_with([' ', '\t'])) {
+                        break;
+                    }
+                    raw.push(c);
+                    self.bump();
+                }
+                expand(raw.trim_end(), &lookup)
+            }
+        };
+        // Only a comment may follow a quoted value.
+        self.skip_spaces();
+        match self.peek() {
+            None | Some('\n' | '\r' | '#') => {
+                self.skip_to_line_end();
+                Ok((key, value))
+            }
+            Some(_) => Err(format!("unexpected text after the value of {key}")),
+        }
+    }
+
+    fn name(&mut self) -> String {
+        let mut name = String::new();
+        while let Some(c) = self.peek() {
+            if c.is_ascii_alphanumeric() || matches!(c, '_' | '.') {
+                name.push(c);
+                self.bump();
+            } else {
+                break;
+            }
+        }
+        name
+    }
+
+    /// The value up to the closing `quote`, each character read through
+    /// `unescape`.
+    fn quoted(
+        &mut self,
+        quote: char,
+        unescape: impl Fn(&mut Self, char) -> Result<String, String>,
+    ) -> Result<String, String> {
+        let mut value = String::new();
+        loop {
+            match self.bump() {
+                None => return Err("unterminated quoted value".to_string()),
+                Some(c) if c == quote => return Ok(value),
+                Some(c) => value.push_str(&unescape(self, c)?),
+            }
+        }
+    }
+}
+
+/// Expands `$VAR`, `${VAR}`, `${VAR:-default}` and `${VAR-default}`; `\$`
+/// is a literal `$` and `\\` a single backslash.
+fn expand(raw: &str, lookup: &impl Fn(&str) -> Option<String>) -> String {
+    let mut out = String::new();
+    let mut rest = raw;
+    while let Some(i) = rest.find(['$', '\\']) {
+        out.push_str(&rest[..i]);
+        let tail = &rest[i..];
+        if let Some(after) = tail.strip_prefix("\\$") {
+            out.push('$');
+            rest = after;
+        } else if let Some(after) = tail.strip_prefix("\\\\") {
+            out.push('\\');
+            rest = after;
+        } else if let Some(after) = tail.strip_prefix('\\') {
+            out.push('\\');
+            rest = after;
+        } else if let Some(braced) = tail.strip_prefix("${") {
+            let Some(end) = braced.find('}') else {
+                out.push_str(tail);
+                return out;
+            };
+            let body = &braced[..end];
+            let value = if let Some((name, default)) = body.split_once(":-") {
+                lookup(name)
+                    .filter(|v| !v.is_empty())
+                    .unwrap_or_else(|| expand(default, lookup))
+            } else if let Some((name, default)) = body.split_once('-') {
+                lookup(name).unwrap_or_else(|| expand(default, lookup))
+            } else {
+                lookup(body).unwrap_or_default()
+            };
+            out.push_str(&value);
+            rest = &braced[end + 1..];
+        } else {
+            let name_len = tail[1..]
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(tail.len() - 1);
+            if name_len == 0 {
+                out.push('$');
+            } else {
+                out.push_str(&lookup(&tail[1..=name_len]).unwrap_or_default());
+            }
+            rest = &tail[1 + name_len..];
+        }
+    }
+    out.push_str(rest);
+    out
+}
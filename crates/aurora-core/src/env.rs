@@ -1,5 +1,7 @@
-use crate::ast::{EnvValue, EnvVar, Environment};
-use anyhow::{bail, Result};
+use crate::ast::{BeamFile, EnvFile, EnvValue, EnvVar, Environment, ValueOrigin};
+use crate::diagnostic::Diagnostic;
+use crate::dotenv;
+use anyhow::{anyhow, bail, Result};
 use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 
@@ -11,7 +13,8 @@ use std::path::Path;
 /// both locally and in containers (each variable is passed through
 /// `docker -e`). Only these variables, needed for a shell and common tools
 /// to work, are carried over. Any additional variable must be declared
-/// explicitly in the `environment { }` block.
+/// explicitly in the `environment { }` block, or asked for by name with
+/// `inherit_env` (see [`inherited`]).
 const ENV_ALLOWLIST: &[&str] = &[
     // POSIX / Unix
     "PATH",
@@ -49,37 +52,157 @@ const ENV_ALLOWLIST: &[&str] = &[
 /// variables).
 ///
 /// This is the single entry point for the ambient environment: callers must
-/// use it (or [`evaluate`], which builds on it) rather than
+/// use it (or [`ambient_env`] and [`evaluate`], which build on it) rather than
 /// `std::env::vars()`, so the allowlist is applied even when a Beamfile
 /// declares no `environment { }` block.
 pub fn base_env() -> HashMap<String, String> {
+    ambient()
+        .filter(|(k, _)| ENV_ALLOWLIST.contains(&k.as_str()) || k.starts_with("LC_"))
+        .collect()
+}
+
+/// [`base_env`] plus the ambient variables `inherit` asks for: what a run
+/// starts from once `inherit_env` and `--inherit-env` are applied.
+pub fn ambient_env(inherit: &[String]) -> HashMap<String, String> {
+    let mut env = base_env();
+    env.extend(inherited(inherit));
+    env
+}
+
+/// The ambient variables whose name matches one of `patterns`
+/// (`SSH_AUTH_SOCK`, `AWS_*`, `*_PROXY`): an explicit opt-in past the
+/// allowlist, with `*` matching any run of characters and `?` any one.
+pub fn inherited(patterns: &[String]) -> BTreeMap<String, String> {
+    if patterns.is_empty() {
+        return BTreeMap::new();
+    }
+    ambient()
+        .filter(|(k, _)| patterns.iter().any(|p| wildcard_match(p, k)))
+        .collect()
+}
+
+/// The whole process environment, as UTF-8.
+fn ambient() -> impl Iterator<Item = (String, String)> {
     // `std::env::vars()` panics while iterating if any ambient key or value is
     // not valid UTF-8, even one we would filter out. Iterate over the OS-string
     // form and skip entries that are not representable as UTF-8, so a single
     // latin-1 or binary-valued variable on the machine cannot crash Aurora.
-    std::env::vars_os()
-        .filter_map(|(k, v)| Some((k.into_string().ok()?, v.into_string().ok()?)))
-        .filter(|(k, _)| ENV_ALLOWLIST.contains(&k.as_str()) || k.starts_with("LC_"))
-        .collect()
+    std::env::vars_os().filter_map(|(k, v)| Some((k.into_string().ok()?, v.into_string().ok()?)))
+}
+
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    match pattern.chars().next() {
+        None => name.is_empty(),
+        Some('*') => {
+            let rest = &pattern[1..];
+            name.char_indices()
+                .map(|(i, _)| i)
+                .chain([name.len()])
+                .any(|i| wildcard_match(rest, &name[i..]))
+        }
+        Some(p) => match name.chars().next() {
+            Some(c) if p == '?' || p == c => {
+                wildcard_match(&pattern[p.len_utf8()..], &name[c.len_utf8()..])
+            }
+            _ => false,
+        },
+    }
 }
 
 /// Evaluates the variables of the `environment` block sequentially.
-/// shell(`...`) variables are executed, literals are copied as is.
-/// Each variable is available to the following ones (via the `result` map).
+/// shell(`...`) variables are executed, literals are copied as is, and
+/// `load = [...]` files are read where they are declared. Each variable is
+/// available to the following ones (via the `result` map).
 ///
 /// `shell(...)` values are always executed on the local host via `sh -c`,
 /// independently of any beam's executor: the environment is resolved once, on
 /// the host, before scheduling, and the result is then passed to every beam
 /// (including Docker beams, via `docker -e`).
 pub fn evaluate(env_block: &Environment, working_dir: &Path) -> Result<HashMap<String, String>> {
-    let mut result = base_env();
+    Ok(evaluate_declared(Some(env_block), base_env(), working_dir)?.env)
+}
+
+/// The global environment as [`evaluate_declared`] returns it.
+#[derive(Debug, Clone, Default)]
+pub struct EvaluatedEnv {
+    /// The declared variables merged on top of the ambient ones.
+    pub env: HashMap<String, String>,
+    /// Only what the block declares or loads, as the cache key needs it.
+    pub declared: BTreeMap<String, String>,
+}
+
+/// [`evaluate`] on top of `ambient` (see [`ambient_env`]), also keeping the
+/// declared half apart. Without a block the environment is `ambient` alone.
+///
+/// Only the declared half is part of a beam's definition. The ambient half
+/// (`PATH`, `HOME`, `TERM`, `PWD`, inherited variables, ...) is machine
+/// context: folding it into a cache key would make the key vary from one
+/// terminal or machine to the next, breaking the cache locally and ruling out
+/// sharing it.
+pub fn evaluate_declared(
+    env_block: Option<&Environment>,
+    ambient: HashMap<String, String>,
+    working_dir: &Path,
+) -> Result<EvaluatedEnv> {
+    let mut env = ambient;
+    let declared = match env_block {
+        Some(block) => evaluate_block(block, &mut env, working_dir)?,
+        None => BTreeMap::new(),
+    };
+    Ok(EvaluatedEnv { env, declared })
+}
 
-    for var in &env_block.vars {
-        let value = eval_value(var, &result, working_dir)?;
-        result.insert(var.name.clone(), value);
+/// Evaluates `env_block` in declaration order on top of `visible`, which
+/// each entry joins, and returns what the block declares or loads.
+fn evaluate_block(
+    env_block: &Environment,
+    visible: &mut HashMap<String, String>,
+    working_dir: &Path,
+) -> Result<BTreeMap<String, String>> {
+    let mut declared = BTreeMap::new();
+    let mut loads = env_block.loads.iter().peekable();
+    for index in 0..=env_block.vars.len() {
+        while let Some(load) = loads.next_if(|load| load.after == index) {
+            for file in &load.files {
+                for (name, value) in load_file(file, visible, working_dir)? {
+                    visible.insert(name.clone(), value.clone());
+                    declared.insert(name, value);
+                }
+            }
+        }
+        if let Some(var) = env_block.vars.get(index) {
+            let value = eval_value(var, visible, working_dir)?;
+            visible.insert(var.name.clone(), value.clone());
+            declared.insert(var.name.clone(), value);
+        }
     }
+    Ok(declared)
+}
 
-    Ok(result)
+/// The entries of a `load = [...]` dotenv file, expanded against `visible`;
+/// none for a missing `optional` one.
+fn load_file(
+    file: &EnvFile,
+    visible: &HashMap<String, String>,
+    working_dir: &Path,
+) -> Result<Vec<(String, String)>> {
+    let content = match std::fs::read_to_string(working_dir.join(&file.path)) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound && file.optional => {
+            return Ok(vec![]);
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(Diagnostic::new(
+                format!("environment file \"{}\" not found", file.path),
+                file.span,
+            )
+            .with_label("loaded here")
+            .with_help("mark it `{ path = \"...\", optional = true }` if it may be absent")
+            .into());
+        }
+        Err(e) => bail!("environment file \"{}\": {e}", file.path),
+    };
+    dotenv::parse(&content, visible).map_err(|e| anyhow!("environment file \"{}\", {e}", file.path))
 }
 
 /// Evaluates a single `environment {}` entry: a literal is copied as is, a
@@ -95,36 +218,116 @@ fn eval_value(
 ) -> Result<String> {
     match &var.value {
         EnvValue::Literal(s) => Ok(s.clone()),
-        EnvValue::Shell(cmd) => {
-            let output = std::process::Command::new("sh")
-                .arg("-c")
-                .arg(cmd)
-                .current_dir(working_dir)
-                .env_clear()
-                .envs(visible)
-                .output()?;
-            // A non-zero exit is a configuration error: failing here beats
-            // silently binding an empty variable that would break the beams
-            // relying on it in ways that are hard to diagnose.
-            if !output.status.success() {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                bail!(
-                    "environment variable '{}': shell command `{}` failed ({}){}",
-                    var.name,
-                    cmd,
-                    output.status,
-                    if stderr.trim().is_empty() {
-                        String::new()
-                    } else {
-                        format!(": {}", stderr.trim())
-                    }
-                );
+        EnvValue::Shell(cmd) => run_shell(
+            &format!("environment variable '{}'", var.name),
+            cmd,
+            visible,
+            working_dir,
+        ),
+    }
+}
+
+/// Runs a `shell(...)` command on the host for `owner` and returns its
+/// output, trailing newlines trimmed.
+fn run_shell(
+    owner: &str,
+    cmd: &str,
+    visible: &HashMap<String, String>,
+    working_dir: &Path,
+) -> Result<String> {
+    let output = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(cmd)
+        .current_dir(working_dir)
+        .env_clear()
+        .envs(visible)
+        .output()?;
+    // A non-zero exit is a configuration error: failing here beats silently
+    // binding an empty variable that would break the beams relying on it in
+    // ways that are hard to diagnose.
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!(
+            "{owner}: shell command `{cmd}` failed ({}){}",
+            output.status,
+            if stderr.trim().is_empty() {
+                String::new()
+            } else {
+                format!(": {}", stderr.trim())
             }
-            Ok(String::from_utf8_lossy(&output.stdout)
-                .trim_end_matches('\n')
-                .to_string())
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .trim_end_matches('\n')
+        .to_string())
+}
+
+/// Evaluates the sources of the global variables, once, before
+/// [`crate::parser::resolve_variables`] interpolates them.
+///
+/// The first source that yields a value wins: `from_env` when the variable
+/// is set on the host, then `from_file` when the file exists (relative to
+/// `working_dir`), then `default = shell(...)`, run on the host like an
+/// `environment {}` command. A variable already set by `--var` or the
+/// selected profile is left alone, and one without a source keeps its
+/// literal `default`. An evaluated value must pass the variable's
+/// constraints like any other.
+pub fn evaluate_variables(beam_file: &mut BeamFile, working_dir: &Path) -> Result<()> {
+    let visible = base_env();
+    for var in &mut beam_file.variables {
+        if matches!(var.origin, ValueOrigin::Cli | ValueOrigin::Profile) {
+            continue;
+        }
+        let owner = format!("variable '{}'", var.name);
+        let from_env = var
+            .sources
+            .env
+            .as_ref()
+            .and_then(|name| std::env::var(name).ok());
+        let from_file = match &var.sources.file {
+            Some(file) => match std::fs::read_to_string(working_dir.join(file)) {
+                Ok(content) => Some(content.trim_end_matches(['\n', '\r']).to_string()),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+                Err(e) => bail!("{owner}: cannot read {file}: {e}"),
+            },
+            None => None,
+        };
+        let (value, origin) = if let Some(value) = from_env {
+            (value, ValueOrigin::Env)
+        } else if let Some(value) = from_file {
+            (value, ValueOrigin::File)
+        } else if let Some(cmd) = &var.sources.shell {
+            (
+                run_shell(&owner, cmd, &visible, working_dir)?,
+                ValueOrigin::Shell,
+            )
+        } else if var.origin == ValueOrigin::Unset && var.sources.file.is_some() {
+            // Nothing to fall back on: an empty value would only fail later,
+            // somewhere less obvious.
+            bail!(
+                "{owner}: {} not found and no default declared",
+                var.sources.file.as_deref().unwrap_or_default()
+            );
+        } else {
+            continue;
+        };
+        var.origin = origin;
+        if let Err(e) = var.constraints.check(&value) {
+            // A secret stays out of the message.
+            let shown = if var.sensitive { "***" } else { &value };
+            return Err(Diagnostic::new(
+                format!(
+                    "invalid value \"{shown}\" for {owner} from {}: {e}",
+                    var.origin_label()
+                ),
+                var.span,
+            )
+            .with_label("declared here")
+            .into());
         }
+        var.default = value;
     }
+    Ok(())
 }
 
 /// Evaluates a beam's `environment {}` block per instance: sequential, on
@@ -137,37 +340,5 @@ pub fn evaluate_overlay(
     working_dir: &Path,
 ) -> Result<BTreeMap<String, String>> {
     let mut visible = base.clone();
-    let mut overlay = BTreeMap::new();
-    for var in &env_block.vars {
-        let value = eval_value(var, &visible, working_dir)?;
-        visible.insert(var.name.clone(), value.clone());
-        overlay.insert(var.name.clone(), value);
-    }
-    Ok(overlay)
-}
-
-/// Picks out of `evaluated` the variables the Beamfile's `environment {}` block
-/// actually declares, as the cache key needs them.
-///
-/// [`evaluate`] returns the declared variables merged on top of the allowlisted
-/// ambient ones, and only the declared half is part of a beam's definition. The
-/// ambient half (`PATH`, `HOME`, `TERM`, `PWD`, ...) is machine context: folding
-/// it into a cache key would make the key vary from one terminal or machine to
-/// the next, breaking the cache locally and ruling out sharing it.
-pub fn declared_only(
-    env_block: Option<&Environment>,
-    evaluated: &HashMap<String, String>,
-) -> BTreeMap<String, String> {
-    let Some(block) = env_block else {
-        return BTreeMap::new();
-    };
-    block
-        .vars
-        .iter()
-        .filter_map(|var| {
-            evaluated
-                .get(&var.name)
-                .map(|value| (var.name.clone(), value.clone()))
-        })
-        .collect()
+    evaluate_block(env_block, &mut visible, working_dir)
 }
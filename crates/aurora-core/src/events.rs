@@ -5,16 +5,31 @@
 //! that drives execution. Keeping them in their own module lets a consumer
 //! depend on the event model without depending on the scheduler internals.
 
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone)]
 pub enum BeamStatus {
     Pending,
     Running,
-    Success { duration: Duration, cached: bool },
-    Skipped { reason: SkipReason },
-    Failed { exit_code: i32, duration: Duration },
-    FailedAllowed { exit_code: i32, duration: Duration },
+    /// Cancelled while running: the command was asked to stop and is given
+    /// its grace period before being killed. Transient, like `Running`: the
+    /// beam still completes with a terminal status (normally `Cancelled`).
+    Stopping,
+    Success {
+        duration: Duration,
+        cached: bool,
+    },
+    Skipped {
+        reason: SkipReason,
+    },
+    Failed {
+        exit_code: i32,
+        duration: Duration,
+    },
+    FailedAllowed {
+        exit_code: i32,
+        duration: Duration,
+    },
     Cancelled,
 }
 
@@ -28,7 +43,32 @@ pub enum SkipReason {
     ConditionNotMet,
 }
 
-#[derive(Debug)]
+/// A step of a beam's processing, timed by the scheduler (see
+/// [`SchedulerEvent::BeamPhase`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BeamPhase {
+    /// Evaluating `skip_if` and the `condition { }` block.
+    Gate,
+    /// Hashing the declared input files.
+    InputHash,
+    /// Checking the cache entry and its outputs, and loading its logs on a hit.
+    CacheLookup,
+    /// Running the commands, up to their exit or their stop on cancellation.
+    Execute,
+}
+
+impl BeamPhase {
+    pub fn label(self) -> &'static str {
+        match self {
+            BeamPhase::Gate => "gate",
+            BeamPhase::InputHash => "input hash",
+            BeamPhase::CacheLookup => "cache lookup",
+            BeamPhase::Execute => "execute",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub enum SchedulerEvent {
     BeamStarted {
         name: String,
@@ -37,11 +77,26 @@ pub enum SchedulerEvent {
         name: String,
         status: BeamStatus,
     },
+    /// A running beam was cancelled and is stopping gracefully (see
+    /// [`BeamStatus::Stopping`]). Always followed by its `BeamCompleted`.
+    BeamStopping {
+        name: String,
+    },
     BeamOutput {
         name: String,
         line: String,
         is_stderr: bool,
     },
+    /// A phase of a started beam is over, with its monotonic bounds. Sent
+    /// between the beam's `BeamStarted` and `BeamCompleted`, only for the
+    /// phases that actually ran (no gate phase without `skip_if` or
+    /// `condition`, no cache phases without inputs or with `--no-cache`).
+    BeamPhase {
+        name: String,
+        phase: BeamPhase,
+        start: Instant,
+        end: Instant,
+    },
     /// A non-fatal advisory about a beam, surfaced to the user but never
     /// affecting the run's outcome. Emitted, for example, when a declared input
     /// pattern matches no file and so silently protects nothing in the cache.
@@ -63,3 +118,26 @@ pub enum SchedulerEvent {
 pub struct WatchTrigger {
     pub beamfile_changed: bool,
 }
+
+/// A request sent on the cancellation channel of
+/// [`crate::scheduler::Scheduler::run_cancellable`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CancelRequest {
+    /// Cancel one beam and, through it, its dependents. Ignored once the beam
+    /// has finished.
+    Beam(String),
+    /// Tear the whole run down, as the shutdown signal does (see
+    /// [`crate::scheduler::Scheduler::with_shutdown`]), while the caller keeps
+    /// going: the TUI stays open on the cancelled run.
+    Run,
+}
+
+/// A request to rerun a beam once the current run is over, from outside the
+/// front end (the control socket). The front end answers on `reply`, with
+/// the reason when it refuses.
+#[derive(Debug)]
+pub struct RerunRequest {
+    /// The beam to rerun; the run's target when `None`.
+    pub beam: Option<String>,
+    pub reply: tokio::sync::oneshot::Sender<Result<(), String>>,
+}
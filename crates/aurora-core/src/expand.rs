@@ -3,7 +3,8 @@
 //! everything downstream (scheduler, cache, TUI) keeps operating on plain
 //! `Beam`s keyed by a `String` identity.
 
-use crate::ast::{Beam, BeamFile, ConditionClause, Dependency, EnvValue};
+use crate::ast::{Beam, BeamFile, ConditionClause, Dependency, EnvValue, Param};
+use crate::diagnostic::Diagnostic;
 use crate::parser::{interpolate_tokens, is_ident};
 use anyhow::{anyhow, bail, Result};
 use std::collections::{BTreeMap, HashMap, HashSet};
@@ -25,13 +26,20 @@ pub struct Expansion {
 }
 
 /// `deploy <version> [env=staging]`: the signature shown by `--list`, the
-/// picker and binding error messages. Declaration order is CLI order.
+/// picker and binding error messages. Declaration order is CLI order. A typed
+/// param shows its choices or its type (`<env:staging|prod>`,
+/// `[replicas:int=3]`); a sensitive default is not shown.
 pub fn signature(beam: &Beam) -> String {
     let mut sig = beam.name.clone();
     for param in &beam.params {
+        let name = match param.constraints.hint() {
+            Some(hint) => format!("{}:{hint}", param.name),
+            None => param.name.clone(),
+        };
         match &param.default {
-            Some(default) => sig.push_str(&format!(" [{}={}]", param.name, default)),
-            None => sig.push_str(&format!(" <{}>", param.name)),
+            Some(_) if param.sensitive => sig.push_str(&format!(" [{name}=***]")),
+            Some(default) => sig.push_str(&format!(" [{name}={default}]")),
+            None => sig.push_str(&format!(" <{name}>")),
         }
     }
     sig
@@ -80,7 +88,8 @@ pub fn bind_cli_args(beam: &Beam, args: &[String]) -> Result<BTreeMap<String, St
     let mut positional: Vec<&String> = vec![];
     for arg in args {
         if let Some((key, value)) = arg.split_once('=') {
-            if beam.params.iter().any(|p| p.name == key) {
+            if let Some(param) = beam.params.iter().find(|p| p.name == key) {
+                check_arg(beam, param, arg, value)?;
                 if bound.insert(key.to_string(), value.to_string()).is_some() {
                     bail!("param '{key}' bound twice for beam '{}'", beam.name);
                 }
@@ -106,6 +115,9 @@ pub fn bind_cli_args(beam: &Beam, args: &[String]) -> Result<BTreeMap<String, St
                 signature(beam)
             );
         };
+        if let Some(param) = beam.params.iter().find(|p| p.name == name) {
+            check_arg(beam, param, value, value)?;
+        }
         bound.insert(name, value.clone());
     }
     for param in &beam.params {
@@ -127,28 +139,53 @@ pub fn bind_cli_args(beam: &Beam, args: &[String]) -> Result<BTreeMap<String, St
     Ok(bound)
 }
 
+/// Checks the value `arg` binds to `param` against its constraints; the error
+/// quotes the argument as it was given.
+fn check_arg(beam: &Beam, param: &Param, arg: &str, value: &str) -> Result<()> {
+    param.constraints.check(value).map_err(|e| {
+        anyhow!(
+            "invalid argument '{arg}' for param '{}' of beam '{}': {e}",
+            param.name,
+            beam.name
+        )
+    })
+}
+
 /// Interpolates `${param.x}` from `bindings` into `s`. `${arg...}`/`${args}`
 /// get the migration diagnostic; an unbound param is a hard error; any other
 /// `${...}` survives verbatim for the shell. Bound values are inserted
 /// literally and never re-interpolated (same anti-injection rule as the old
-/// argument pass).
-fn interpolate_params(s: &str, bindings: &BTreeMap<String, String>, beam: &str) -> Result<String> {
+/// argument pass). An error points at the token, in `beam` (the declared
+/// beam the string belongs to).
+fn interpolate_params(s: &str, bindings: &BTreeMap<String, String>, beam: &Beam) -> Result<String> {
+    let name = &beam.name;
     interpolate_tokens(s, |inner| {
+        let token = format!("${{{inner}}}");
         if inner == "args" || inner.strip_prefix("arg.").is_some() {
-            return Some(Err(anyhow!(
-                "beam '{beam}' references '${{{inner}}}': arguments were replaced by \
-                 params; declare `param \"...\" {{}}` and reference `${{param.<name>}}`"
-            )));
+            return Some(Err(Diagnostic::new(
+                format!(
+                    "beam '{name}' references '{token}': arguments were replaced by \
+                     params; declare `param \"...\" {{}}` and reference `${{param.<name>}}`"
+                ),
+                beam.span,
+            )
+            .with_needle(token)
+            .into()));
         }
-        let name = inner.strip_prefix("param.")?;
-        if !is_ident(name) {
+        let param = inner.strip_prefix("param.")?;
+        if !is_ident(param) {
             return None;
         }
-        Some(match bindings.get(name) {
+        Some(match bindings.get(param) {
             Some(value) => Ok(value.clone()),
-            None => Err(anyhow!(
-                "unknown param '{name}' referenced in beam '{beam}'"
-            )),
+            None => Err(Diagnostic::new(
+                format!("unknown param '{param}' referenced in beam '{name}'"),
+                beam.span,
+            )
+            .with_label("not declared")
+            .with_suggestion(param, beam.params.iter().map(|p| p.name.as_str()))
+            .with_needle(token)
+            .into()),
         })
     })
 }
@@ -156,23 +193,42 @@ fn interpolate_params(s: &str, bindings: &BTreeMap<String, String>, beam: &str)
 /// Resolves one `depends_on` edge into the child's full binding set: explicit
 /// bindings (interpolated in the parent's context) plus the child's defaults.
 fn bind_edge(
-    parent_name: &str,
+    parent: &Beam,
     parent_bindings: &BTreeMap<String, String>,
     dep: &Dependency,
     child: &Beam,
 ) -> Result<BTreeMap<String, String>> {
+    let parent_name = &parent.name;
     let mut bound: BTreeMap<String, String> = BTreeMap::new();
     for (key, raw) in &dep.params {
         if !child.params.iter().any(|p| p.name == *key) {
-            bail!(
-                "beam '{parent_name}' binds unknown param '{key}' of dependency '{}'",
-                child.name
-            );
+            return Err(Diagnostic::new(
+                format!(
+                    "beam '{parent_name}' binds unknown param '{key}' of dependency '{}'",
+                    child.name
+                ),
+                dep.span,
+            )
+            .with_label(format!("'{}' declares no param '{key}'", child.name))
+            .with_suggestion(key, child.params.iter().map(|p| p.name.as_str()))
+            .into());
         }
-        bound.insert(
-            key.clone(),
-            interpolate_params(raw, parent_bindings, parent_name)?,
-        );
+        let value = interpolate_params(raw, parent_bindings, parent)?;
+        if let Some(param) = child.params.iter().find(|p| p.name == *key) {
+            if let Err(e) = param.constraints.check(&value) {
+                return Err(Diagnostic::new(
+                    format!(
+                        "beam '{parent_name}' binds '{value}' to param '{key}' of dependency \
+                         '{}': {e}",
+                        child.name
+                    ),
+                    dep.span,
+                )
+                .with_label("invalid value")
+                .into());
+            }
+        }
+        bound.insert(key.clone(), value);
     }
     for param in &child.params {
         if bound.contains_key(&param.name) {
@@ -182,14 +238,18 @@ fn bind_edge(
             Some(default) => {
                 bound.insert(param.name.clone(), default.clone());
             }
-            None => bail!(
-                "dependency '{}' of beam '{parent_name}' requires param '{}': bind it \
-                 with {{ beam = \"{}\", params = {{ {} = \"...\" }} }}",
-                child.name,
-                param.name,
-                child.name,
-                param.name
-            ),
+            None => {
+                return Err(Diagnostic::new(
+                    format!(
+                        "dependency '{}' of beam '{parent_name}' requires param '{}': bind \
+                         it with {{ beam = \"{}\", params = {{ {} = \"...\" }} }}",
+                        child.name, param.name, child.name, param.name
+                    ),
+                    dep.span,
+                )
+                .with_label(format!("'{}' is not bound", param.name))
+                .into())
+            }
         }
     }
     Ok(bound)
@@ -202,25 +262,24 @@ fn instantiate(source: &Beam, id: &str, bindings: &BTreeMap<String, String>) ->
     let mut beam = source.clone();
     beam.name = id.to_string();
     beam.bindings = bindings.clone();
-    let src = &source.name;
     if let Some(dir) = &mut beam.dir {
-        *dir = interpolate_params(dir, bindings, src)?;
+        *dir = interpolate_params(dir, bindings, source)?;
     }
     if let Some(skip_if) = &mut beam.skip_if {
-        *skip_if = interpolate_params(skip_if, bindings, src)?;
+        *skip_if = interpolate_params(skip_if, bindings, source)?;
     }
     if let Some(condition) = &mut beam.condition {
         for ConditionClause::Shell(clause) in &mut condition.clauses {
-            *clause = interpolate_params(clause, bindings, src)?;
+            *clause = interpolate_params(clause, bindings, source)?;
         }
     }
     if let Some(run) = &mut beam.run {
         for cmd in &mut run.commands {
-            *cmd = interpolate_params(cmd, bindings, src)?;
+            *cmd = interpolate_params(cmd, bindings, source)?;
         }
         if let Some(exec_cfg) = &mut run.executor {
             for value in exec_cfg.config.values_mut() {
-                *value = interpolate_params(value, bindings, src)?;
+                *value = interpolate_params(value, bindings, source)?;
             }
         }
     }
@@ -228,10 +287,13 @@ fn instantiate(source: &Beam, id: &str, bindings: &BTreeMap<String, String>) ->
         for var in &mut environment.vars {
             match &mut var.value {
                 EnvValue::Literal(s) | EnvValue::Shell(s) => {
-                    *s = interpolate_params(s, bindings, src)?;
+                    *s = interpolate_params(s, bindings, source)?;
                 }
             }
         }
+        for file in environment.loads.iter_mut().flat_map(|l| &mut l.files) {
+            file.path = interpolate_params(&file.path, bindings, source)?;
+        }
     }
     Ok(beam)
 }
@@ -246,14 +308,24 @@ fn instantiate(source: &Beam, id: &str, bindings: &BTreeMap<String, String>) ->
 /// beam is failing their command. `upfront` is the declared beam whose
 /// validation failed (`None` for the invoked target's own closure, reported
 /// verbatim).
+///
+/// A [`Diagnostic`] keeps its span: only its message takes the preface.
 fn adorn_upfront(upfront: Option<&str>, err: anyhow::Error) -> anyhow::Error {
-    match upfront {
-        None => err,
-        Some(beam) => anyhow!(
-            "beam '{beam}' has an invalid configuration; Aurora validates every declared \
-             beam before running, so this aborts the run even though '{beam}' was not \
-             requested: {err}"
-        ),
+    let Some(beam) = upfront else {
+        return err;
+    };
+    let preface = format!(
+        "beam '{beam}' has an invalid configuration; Aurora validates every declared \
+         beam before running, so this aborts the run even though '{beam}' was not \
+         requested"
+    );
+    match err.downcast::<Diagnostic>() {
+        Ok(diagnostic) => Diagnostic {
+            message: format!("{preface}: {}", diagnostic.message),
+            ..diagnostic
+        }
+        .into(),
+        Err(err) => anyhow!("{preface}: {err}"),
     }
 }
 
@@ -275,12 +347,20 @@ fn drain_worklist(
             continue;
         }
         if depth > MAX_INSTANTIATION_DEPTH {
+            let span = by_name
+                .get(name.as_str())
+                .map(|b| b.span)
+                .unwrap_or_default();
             return Err(adorn_upfront(
                 upfront,
-                anyhow!(
-                    "instantiation depth exceeded ({MAX_INSTANTIATION_DEPTH}): divergent \
-                     parameter cycle involving beam '{name}'"
-                ),
+                Diagnostic::new(
+                    format!(
+                        "instantiation depth exceeded ({MAX_INSTANTIATION_DEPTH}): divergent \
+                         parameter cycle involving beam '{name}'"
+                    ),
+                    span,
+                )
+                .into(),
             ));
         }
         // The target came from `by_name`; every other name was pushed from a
@@ -296,13 +376,19 @@ fn drain_worklist(
             let Some(child) = by_name.get(dep.beam.as_str()) else {
                 // Unknown dependency: keep the raw name so `BeamGraph::from_deps`
                 // reports it exactly as before.
-                edges.push(Dependency::named(dep.beam.clone()));
+                edges.push(Dependency {
+                    span: dep.span,
+                    ..Dependency::named(dep.beam.clone())
+                });
                 continue;
             };
-            let child_bindings = bind_edge(&source.name, &bindings, dep, child)
-                .map_err(|e| adorn_upfront(upfront, e))?;
+            let child_bindings =
+                bind_edge(source, &bindings, dep, child).map_err(|e| adorn_upfront(upfront, e))?;
             let child_id = instance_id(&child.name, &child_bindings);
-            edges.push(Dependency::named(child_id));
+            edges.push(Dependency {
+                span: dep.span,
+                ..Dependency::named(child_id)
+            });
             worklist.push((child.name.clone(), child_bindings, depth + 1));
         }
         instance.depends_on = edges;
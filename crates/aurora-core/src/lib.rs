@@ -1,8 +1,12 @@
+pub mod ansi;
 pub mod ast;
 pub mod cache;
 pub mod dag;
+pub mod diagnostic;
+pub mod dotenv;
 pub mod env;
 pub mod events;
 pub mod expand;
 pub mod parser;
 pub mod scheduler;
+pub mod trace_context;
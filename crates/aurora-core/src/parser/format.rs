@@ -0,0 +1,571 @@
+//! The canonical formatting of a Beamfile (`aurora fmt`).
+//!
+//! The grammar keeps comments out of the syntax tree, so they are read from
+//! the source separately and attached by position: a comment on the line an
+//! item ends on trails that item, the others lead the item after them, and
+//! those after a block's last item stay at its end. An item keeps its
+//! comments when the canonical field order moves it.
+//!
+//! The canonical style:
+//! - two spaces of indentation, one item per line;
+//! - a blank line around every item that spans several lines, and before a
+//!   commented one;
+//! - the fields of a `beam` or a `template` in a fixed order: `extends`,
+//!   `description`, `tags`, `param`s,
+//!   `depends_on`, `dir`, `inputs`, `outputs`, `skip_if`, `allow_failure`,
+//!   `tty`, `kill_timeout`, `condition`, `environment`, `run` (params keep
+//!   their order: it is the CLI's positional order);
+//! - the fields of a `profile` in the order `variables`, `max_parallelism`,
+//!   `environment`;
+//! - the `=` of an `environment` block and of a profile's `variables`
+//!   aligned;
+//! - a list on one line when it fits in 100 columns, otherwise one element
+//!   per line with a trailing comma;
+//! - a `run`, `param`, `variable` or `executor` block holding a single field
+//!   on one line when it fits.
+
+use anyhow::{Context, Result};
+use pest::iterators::Pair;
+
+use super::{parse_syntax, Rule};
+
+/// The column a line should not go past.
+const WIDTH: usize = 100;
+const INDENT: &str = "  ";
+
+/// Re-emits `source` in the canonical style. Fails when it does not parse.
+pub fn format(source: &str) -> Result<String> {
+    let file = parse_syntax(source)?
+        .next()
+        .context("Failed to parse Beamfile")?;
+    let mut builder = Builder::new(source);
+    let blocks: Vec<Pair<Rule>> = file
+        .into_inner()
+        .filter(|pair| pair.as_rule() != Rule::EOI)
+        .collect();
+    let (items, dangling) = builder.items(0, source.len(), blocks);
+
+    let mut lines = vec![];
+    render_items(&items, &dangling, 0, false, &mut lines);
+    if lines.is_empty() {
+        return Ok(String::new());
+    }
+    let mut out = lines
+        .iter()
+        .map(|line| line.trim_end())
+        .collect::<Vec<_>>()
+        .join("\n");
+    out.push('\n');
+    Ok(out)
+}
+
+struct Comment {
+    pos: usize,
+    /// From the `#`, without the line break.
+    text: String,
+    /// Whether a blank line follows it in the source.
+    blank_after: bool,
+}
+
+struct Item {
+    leading: Vec<Comment>,
+    node: Node,
+    trailing: Option<String>,
+    /// Its place in the canonical order of its block's fields.
+    rank: usize,
+}
+
+enum Node {
+    /// `key = value`.
+    Assign { key: String, value: String },
+    /// A list element.
+    Inline(String),
+    /// `key = [...]`, or `key += [...]`.
+    List {
+        key: String,
+        /// `=` or `+=`.
+        op: &'static str,
+        elems: Vec<Item>,
+        dangling: Vec<Comment>,
+    },
+    /// `head { ... }`.
+    Block {
+        head: String,
+        items: Vec<Item>,
+        dangling: Vec<Comment>,
+        /// May sit on one line when it holds a single field.
+        compact: bool,
+        /// Aligns the `=` of its fields.
+        align: bool,
+    },
+}
+
+/// Builds the formatting tree from the syntax tree, handing out the comments
+/// of the source as it goes.
+struct Builder<'a> {
+    source: &'a str,
+    /// Each comment, until an item takes it.
+    comments: Vec<Option<Comment>>,
+}
+
+impl<'a> Builder<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            comments: scan_comments(source).into_iter().map(Some).collect(),
+        }
+    }
+
+    /// Takes the comments within `from..to`.
+    fn take(&mut self, from: usize, to: usize) -> Vec<Comment> {
+        self.comments
+            .iter_mut()
+            .filter(|slot| slot.as_ref().is_some_and(|c| (from..to).contains(&c.pos)))
+            .filter_map(Option::take)
+            .collect()
+    }
+
+    fn same_line(&self, a: usize, b: usize) -> bool {
+        !self.source[a.min(b)..a.max(b)].contains('\n')
+    }
+
+    /// The items of a container whose contents lie within `open..close`,
+    /// with the comments between them, and the comments after the last one.
+    fn items(
+        &mut self,
+        open: usize,
+        close: usize,
+        children: Vec<Pair<'a, Rule>>,
+    ) -> (Vec<Item>, Vec<Comment>) {
+        let mut items: Vec<Item> = vec![];
+        let mut cursor = open;
+        for child in children {
+            let (start, end) = (child.as_span().start(), child.as_span().end());
+            let mut item = self.item(child);
+            // What the item's own containers left: comments between its
+            // tokens (`commands = # ...`), which lead it instead.
+            let inside = self.take(start, end);
+            let mut gap = self.take(cursor, start);
+            self.trail(&mut items, cursor, &mut gap);
+            gap.extend(inside);
+            item.leading = gap;
+            items.push(item);
+            cursor = end;
+        }
+        let mut dangling = self.take(cursor, close);
+        self.trail(&mut items, cursor, &mut dangling);
+        (items, dangling)
+    }
+
+    /// Moves the first of `gap` to the last item when it sits on the line
+    /// that item ends on.
+    fn trail(&self, items: &mut [Item], end: usize, gap: &mut Vec<Comment>) {
+        if let (Some(last), Some(first)) = (items.last_mut(), gap.first()) {
+            if self.same_line(end, first.pos) {
+                last.trailing = Some(gap.remove(0).text);
+            }
+        }
+    }
+
+    fn item(&mut self, pair: Pair<'a, Rule>) -> Item {
+        let rank = rank(pair.as_rule());
+        let node = match pair.as_rule() {
+            // Wrappers around the actual rule.
+            Rule::block
+            | Rule::aurora_field
+            | Rule::variable_field
+            | Rule::param_field
+            | Rule::value_constraint
+            | Rule::beam_field
+            | Rule::profile_field
+            | Rule::run_field
+            | Rule::dep_entry
+            | Rule::env_file
+            | Rule::condition_body => return self.item(pair.into_inner().next().unwrap()),
+            Rule::aurora_block => self.block(pair, false, false),
+            Rule::variable_block | Rule::param_block | Rule::beam_run | Rule::run_executor => {
+                self.block(pair, true, false)
+            }
+            Rule::beam_block
+            | Rule::template_block
+            | Rule::beam_condition
+            | Rule::profile_block => self.block(pair, false, false),
+            Rule::environment_block => self.block(pair, false, true),
+            // `variables = { ... }`: a block of bindings under the key.
+            Rule::profile_variables => {
+                let mut node = self.block(pair.into_inner().next().unwrap(), true, true);
+                if let Node::Block { head, .. } = &mut node {
+                    *head = "variables =".to_string();
+                }
+                node
+            }
+            Rule::beam_depends_on
+            | Rule::beam_tags
+            | Rule::beam_inputs
+            | Rule::beam_outputs
+            | Rule::value_choices
+            | Rule::run_commands
+            | Rule::condition_any
+            | Rule::condition_all
+            | Rule::env_load
+            | Rule::aurora_inherit_env
+            | Rule::beam_inherit_env => self.list(pair),
+            Rule::string => Node::Inline(pair.as_str().to_string()),
+            Rule::dep_object => Node::Inline(dep_object(pair)),
+            Rule::env_file_object => {
+                let mut inner = pair.into_inner();
+                let path = inner.next().unwrap().as_str();
+                Node::Inline(match inner.next() {
+                    Some(optional) => {
+                        format!("{{ path = {path}, optional = {} }}", optional.as_str())
+                    }
+                    None => format!("{{ path = {path} }}"),
+                })
+            }
+            Rule::condition_clause => {
+                let shell = pair.into_inner().next().unwrap();
+                Node::Inline(format!("{{ shell = {} }}", value(last(shell))))
+            }
+            // `key = value`.
+            _ => Node::Assign {
+                key: key(&pair),
+                value: value(last(pair)),
+            },
+        };
+        Item {
+            leading: vec![],
+            node,
+            trailing: None,
+            rank,
+        }
+    }
+
+    fn block(&mut self, pair: Pair<'a, Rule>, compact: bool, align: bool) -> Node {
+        let (start, end) = (pair.as_span().start(), pair.as_span().end());
+        let keyword = key(&pair);
+        let mut inner = pair.into_inner().peekable();
+        // A named block (`beam "x"`, `executor "docker"`) leads with its name.
+        let name = inner
+            .next_if(|p| p.as_rule() == Rule::string)
+            .map(|p| (p.as_str().to_string(), p.as_span().end()));
+        let head = match &name {
+            Some((name, _)) => format!("{keyword} {name}"),
+            None => keyword,
+        };
+        let after_head = name.map_or(start, |(_, end)| end);
+        let open = after_head + self.source[after_head..end].find('{').map_or(0, |i| i + 1);
+        let (mut items, dangling) = self.items(open, end - 1, inner.collect());
+        items.sort_by_key(|item| item.rank);
+        Node::Block {
+            head,
+            items,
+            dangling,
+            compact,
+            align,
+        }
+    }
+
+    fn list(&mut self, pair: Pair<'a, Rule>) -> Node {
+        let key = key(&pair);
+        let end = pair.as_span().end();
+        let mut inner = pair.into_inner().peekable();
+        let op = match inner.next_if(|p| p.as_rule() == Rule::append) {
+            Some(_) => "+=",
+            None => "=",
+        };
+        // `depends_on = [...]` and `inputs = [...]` hold the list in a pair of
+        // its own; a condition's clauses are its direct children.
+        let (open, close, children): (usize, usize, Vec<Pair<Rule>>) =
+            match inner.next_if(|p| matches!(p.as_rule(), Rule::string_list | Rule::dep_list)) {
+                Some(list) => (
+                    list.as_span().start() + 1,
+                    list.as_span().end() - 1,
+                    list.into_inner().collect(),
+                ),
+                None => {
+                    let children: Vec<Pair<Rule>> = inner.collect();
+                    let start = children.first().map_or(end - 1, |c| c.as_span().start());
+                    (
+                        self.source[..start].rfind('[').map_or(start, |i| i + 1),
+                        end - 1,
+                        children,
+                    )
+                }
+            };
+        let (elems, dangling) = self.items(open, close, children);
+        Node::List {
+            key,
+            op,
+            elems,
+            dangling,
+        }
+    }
+}
+
+/// The canonical place of a field within its block. Fields of the same rank
+/// keep their order.
+fn rank(rule: Rule) -> usize {
+    match rule {
+        Rule::aurora_version => 0,
+        Rule::aurora_default => 1,
+        Rule::aurora_parallelism => 2,
+        Rule::aurora_log_retention => 3,
+        Rule::aurora_inherit_env => 4,
+
+        Rule::var_default | Rule::var_from_env | Rule::var_from_file | Rule::param_default => 0,
+        Rule::var_description | Rule::param_description => 1,
+        Rule::value_type => 2,
+        Rule::value_choices => 3,
+        Rule::value_pattern => 4,
+        Rule::var_sensitive | Rule::param_sensitive => 5,
+
+        Rule::beam_extends => 0,
+        Rule::beam_description => 1,
+        Rule::beam_tags => 2,
+        Rule::param_block => 3,
+        Rule::beam_depends_on => 4,
+        Rule::beam_dir => 5,
+        Rule::beam_inputs => 6,
+        Rule::beam_outputs => 7,
+        Rule::beam_skip_if => 8,
+        Rule::beam_allow_failure => 9,
+        Rule::beam_tty => 10,
+        Rule::beam_kill_timeout => 11,
+        Rule::beam_condition => 12,
+        Rule::beam_inherit_env | Rule::environment_block => 13,
+        Rule::variable_block => 14,
+        Rule::beam_run => 15,
+
+        Rule::profile_variables => 0,
+        Rule::profile_parallelism => 1,
+
+        Rule::run_commands => 0,
+        Rule::run_executor => 1,
+        _ => 0,
+    }
+}
+
+/// The keyword a pair starts with: a field's key, a block's kind.
+fn key(pair: &Pair<Rule>) -> String {
+    pair.as_str()
+        .split(|c: char| c.is_whitespace() || matches!(c, '=' | '+' | '{' | '"' | '#'))
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn last(pair: Pair<Rule>) -> Pair<Rule> {
+    pair.into_inner().last().unwrap()
+}
+
+/// A value as written: strings keep their escapes.
+fn value(pair: Pair<Rule>) -> String {
+    match pair.as_rule() {
+        Rule::env_value => value(pair.into_inner().next().unwrap()),
+        Rule::shell_call => format!("shell({})", last(pair).as_str()),
+        _ => pair.as_str().to_string(),
+    }
+}
+
+/// `{ beam = "x", params = { k = "v" } }`, always on one line.
+fn dep_object(pair: Pair<Rule>) -> String {
+    let mut inner = pair.into_inner();
+    let beam = inner.next().unwrap().as_str();
+    match inner.next() {
+        None => format!("{{ beam = {beam} }}"),
+        Some(params) => {
+            let bindings: Vec<String> = params
+                .into_inner()
+                .map(|binding| {
+                    let mut parts = binding.into_inner();
+                    let name = parts.next().unwrap().as_str();
+                    format!("{name} = {}", parts.next().unwrap().as_str())
+                })
+                .collect();
+            if bindings.is_empty() {
+                format!("{{ beam = {beam}, params = {{}} }}")
+            } else {
+                format!(
+                    "{{ beam = {beam}, params = {{ {} }} }}",
+                    bindings.join(", ")
+                )
+            }
+        }
+    }
+}
+
+/// Every comment of `source`, skipping the `#` inside strings.
+fn scan_comments(source: &str) -> Vec<Comment> {
+    let bytes = source.as_bytes();
+    let mut comments = vec![];
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+                i += 1;
+            }
+            b'#' => {
+                let end = source[i..].find('\n').map_or(source.len(), |n| i + n);
+                let rest = &source[end..];
+                let space = rest.len() - rest.trim_start().len();
+                comments.push(Comment {
+                    pos: i,
+                    text: source[i..end].trim_end().to_string(),
+                    blank_after: rest[..space].matches('\n').count() >= 2,
+                });
+                i = end;
+            }
+            _ => i += 1,
+        }
+    }
+    comments
+}
+
+fn indent(depth: usize) -> String {
+    INDENT.repeat(depth)
+}
+
+fn fits(depth: usize, line: &str) -> bool {
+    depth * INDENT.len() + line.chars().count() <= WIDTH
+}
+
+fn has_comments(item: &Item) -> bool {
+    !item.leading.is_empty() || item.trailing.is_some()
+}
+
+/// `node` on a single line, when it has no comment that forces it apart.
+fn inline(node: &Node) -> Option<String> {
+    match node {
+        Node::Assign { key, value } => Some(format!("{key} = {value}")),
+        Node::Inline(text) => Some(text.clone()),
+        Node::List {
+            key,
+            op,
+            elems,
+            dangling,
+        } => {
+            if !dangling.is_empty() || elems.iter().any(has_comments) {
+                return None;
+            }
+            let elems: Option<Vec<String>> = elems.iter().map(|e| inline(&e.node)).collect();
+            Some(format!("{key} {op} [{}]", elems?.join(", ")))
+        }
+        Node::Block {
+            head,
+            items,
+            dangling,
+            compact,
+            ..
+        } => match items.as_slice() {
+            [] if dangling.is_empty() => Some(format!("{head} {{}}")),
+            [item] if *compact && dangling.is_empty() && !has_comments(item) => {
+                inline(&item.node).map(|field| format!("{head} {{ {field} }}"))
+            }
+            _ => None,
+        },
+    }
+}
+
+fn render_node(node: &Node, depth: usize, key_width: usize) -> Vec<String> {
+    let pad = indent(depth);
+    if let Node::Assign { key, value } = node {
+        return vec![format!("{pad}{key:<key_width$} = {value}")];
+    }
+    if let Some(line) = inline(node).filter(|line| fits(depth, line)) {
+        return vec![format!("{pad}{line}")];
+    }
+    let mut lines = vec![];
+    match node {
+        Node::List {
+            key,
+            op,
+            elems,
+            dangling,
+        } => {
+            lines.push(format!("{pad}{key} {op} ["));
+            for elem in elems {
+                comments(&elem.leading, depth + 1, true, &mut lines);
+                let mut elem_lines = render_node(&elem.node, depth + 1, 0);
+                if let Some(last) = elem_lines.last_mut() {
+                    last.push(',');
+                }
+                push_trailing(&mut elem_lines, elem.trailing.as_deref());
+                lines.extend(elem_lines);
+            }
+            comments(dangling, depth + 1, false, &mut lines);
+            lines.push(format!("{pad}]"));
+        }
+        Node::Block {
+            head,
+            items,
+            dangling,
+            align,
+            ..
+        } => {
+            lines.push(format!("{pad}{head} {{"));
+            render_items(items, dangling, depth + 1, *align, &mut lines);
+            lines.push(format!("{pad}}}"));
+        }
+        Node::Assign { .. } | Node::Inline(_) => unreachable!("always inline"),
+    }
+    lines
+}
+
+/// Renders the fields of a block (or the blocks of the file) at `depth`.
+fn render_items(
+    items: &[Item],
+    dangling: &[Comment],
+    depth: usize,
+    align: bool,
+    out: &mut Vec<String>,
+) {
+    let key_width = match align {
+        true => items
+            .iter()
+            .filter_map(|item| match &item.node {
+                Node::Assign { key, .. } => Some(key.chars().count()),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0),
+        false => 0,
+    };
+    let mut previous_spans_lines = false;
+    for (index, item) in items.iter().enumerate() {
+        let mut lines = render_node(&item.node, depth, key_width);
+        let spans_lines = lines.len() > 1;
+        if index > 0 && (previous_spans_lines || spans_lines || !item.leading.is_empty()) {
+            out.push(String::new());
+        }
+        comments(&item.leading, depth, true, out);
+        push_trailing(&mut lines, item.trailing.as_deref());
+        out.extend(lines);
+        previous_spans_lines = spans_lines;
+    }
+    if !dangling.is_empty() && previous_spans_lines {
+        out.push(String::new());
+    }
+    comments(dangling, depth, false, out);
+}
+
+/// Writes `comments` at `depth`, keeping the blank lines between them. The
+/// blank line after the last one is kept when an item follows it.
+fn comments(comments: &[Comment], depth: usize, item_follows: bool, out: &mut Vec<String>) {
+    for (index, comment) in comments.iter().enumerate() {
+        out.push(format!("{}{}", indent(depth), comment.text));
+        if comment.blank_after && (item_follows || index + 1 < comments.len()) {
+            out.push(String::new());
+        }
+    }
+}
+
+fn push_trailing(lines: &mut [String], trailing: Option<&str>) {
+    if let (Some(last), Some(trailing)) = (lines.last_mut(), trailing) {
+        last.push_str("  ");
+        last.push_str(trailing);
+    }
+}
@@ -1,9 +1,13 @@
 use crate::ast::*;
-use anyhow::{bail, Context, Result};
+use crate::diagnostic::Diagnostic;
+use anyhow::{bail, Result};
 use pest::iterators::Pair;
 use pest::Parser;
 use pest_derive::Parser;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
+use std::time::Duration;
+
+pub mod format;
 
 #[derive(Parser)]
 #[grammar = "parser/aurora.pest"]
@@ -16,62 +20,243 @@ struct AuroraParser;
 const MAX_BEAMFILE_BYTES: usize = 1024 * 1024;
 
 pub fn parse(input: &str) -> Result<BeamFile> {
-    if input.len() > MAX_BEAMFILE_BYTES {
-        bail!(
-            "Beamfile too large: {} bytes (maximum {} bytes)",
-            input.len(),
-            MAX_BEAMFILE_BYTES
-        );
-    }
-    let pairs = AuroraParser::parse(Rule::beamfile, input).context("Failed to parse Beamfile")?;
+    let pairs = parse_syntax(input)?;
 
     let mut beam_file = BeamFile {
         config: None,
         variables: vec![],
         environment: None,
+        profiles: vec![],
         beams: vec![],
     };
 
-    for pair in pairs {
-        if pair.as_rule() == Rule::beamfile {
-            for block_pair in pair.into_inner() {
-                match block_pair.as_rule() {
-                    Rule::block => {
-                        let inner = block_pair.into_inner().next().unwrap();
-                        parse_block(inner, &mut beam_file)?;
-                    }
-                    Rule::EOI => {}
-                    _ => {}
-                }
-            }
-        }
+    let blocks: Vec<Pair<Rule>> = pairs
+        .filter(|pair| pair.as_rule() == Rule::beamfile)
+        .flat_map(|pair| pair.into_inner())
+        .filter(|pair| pair.as_rule() == Rule::block)
+        .map(|block| block.into_inner().next().unwrap())
+        .collect();
+
+    // Templates first: a beam may extend one declared after it.
+    let templates = Templates::collect(input, &blocks)?;
+    for block in blocks {
+        parse_block(block, &mut beam_file, &templates)?;
+    }
+    // A template no beam extends is still checked.
+    for (name, span, _) in &templates.blocks {
+        templates.base(name, *span, &mut vec![])?;
     }
 
     // Reject duplicate beam names up front. Downstream the beams are keyed by
     // name (the scheduler's map, the graph's index), so a duplicate silently
     // drops the earlier definition and merges its edges; surfacing it here
     // turns a copy-paste mistake into a clear error instead.
-    let mut seen = HashSet::new();
+    let mut seen = HashMap::new();
     for beam in &beam_file.beams {
-        if !seen.insert(beam.name.as_str()) {
-            bail!("duplicate beam name '{}'", beam.name);
+        if let Some(first) = seen.insert(beam.name.as_str(), beam.span) {
+            let (line, _) = first.line_col(input);
+            return Err(
+                Diagnostic::new(format!("duplicate beam name '{}'", beam.name), beam.span)
+                    .with_label(format!("first declared on line {line}"))
+                    .into(),
+            );
         }
     }
 
     // Reject duplicate param names within a beam: they would collide as CLI
     // positional slots and as `depends_on` binding keys.
     for beam in &beam_file.beams {
-        let mut seen_params = HashSet::new();
+        let mut seen_params = HashMap::new();
         for param in &beam.params {
-            if !seen_params.insert(param.name.as_str()) {
-                bail!("duplicate param '{}' in beam '{}'", param.name, beam.name);
+            if let Some(first) = seen_params.insert(param.name.as_str(), param.span) {
+                let (line, _) = first.line_col(input);
+                return Err(Diagnostic::new(
+                    format!("duplicate param '{}' in beam '{}'", param.name, beam.name),
+                    param.span,
+                )
+                .with_label(format!("first declared on line {line}"))
+                .into());
             }
         }
     }
 
+    check_profiles(input, &beam_file)?;
+
     Ok(beam_file)
 }
 
+/// The `template` blocks, merged into the beams that extend them.
+struct Templates<'i> {
+    /// Each name, where it is declared and its block, in declaration order.
+    blocks: Vec<(String, Span, Pair<'i, Rule>)>,
+}
+
+impl<'i> Templates<'i> {
+    fn collect(input: &str, blocks: &[Pair<'i, Rule>]) -> Result<Self> {
+        let mut templates = Templates { blocks: vec![] };
+        for block in blocks
+            .iter()
+            .filter(|b| b.as_rule() == Rule::template_block)
+        {
+            let name_pair = block.clone().into_inner().next().unwrap();
+            let span = span_of(&name_pair);
+            let name = unquote(name_pair);
+            if let Some((_, first, _)) = templates.blocks.iter().find(|(n, ..)| *n == name) {
+                let (line, _) = first.line_col(input);
+                return Err(
+                    Diagnostic::new(format!("duplicate template name '{name}'"), span)
+                        .with_label(format!("first declared on line {line}"))
+                        .into(),
+                );
+            }
+            templates.blocks.push((name, span, block.clone()));
+        }
+        Ok(templates)
+    }
+
+    /// What a beam extending `name` starts from: the template's fields,
+    /// merged over those of the template it extends in turn. `chain` holds
+    /// the templates being resolved, so a cycle is an error rather than a
+    /// stack overflow.
+    fn base(&self, name: &str, span: Span, chain: &mut Vec<String>) -> Result<Beam> {
+        if let Some(start) = chain.iter().position(|n| n == name) {
+            let cycle = [&chain[start..], &[name.to_string()]].concat();
+            return Err(Diagnostic::new(
+                format!("template '{name}' extends itself: {}", cycle.join(" -> ")),
+                span,
+            )
+            .into());
+        }
+        let Some((_, _, pair)) = self.blocks.iter().find(|(n, ..)| n == name) else {
+            return Err(Diagnostic::new(format!("unknown template '{name}'"), span)
+                .with_label("not declared")
+                .with_suggestion(name, self.blocks.iter().map(|(n, ..)| n.as_str()))
+                .into());
+        };
+        chain.push(name.to_string());
+        let base = parse_beam_block(pair.clone(), self, chain)?;
+        chain.pop();
+        Ok(base)
+    }
+}
+
+/// A profile name is declared once, and binds only declared global variables,
+/// to values that fit their constraints: a typo would otherwise only surface
+/// as a run with the wrong values.
+fn check_profiles(input: &str, beam_file: &BeamFile) -> Result<()> {
+    let mut seen = HashMap::new();
+    for profile in &beam_file.profiles {
+        if let Some(first) = seen.insert(profile.name.as_str(), profile.span) {
+            let (line, _) = first.line_col(input);
+            return Err(Diagnostic::new(
+                format!("duplicate profile name '{}'", profile.name),
+                profile.span,
+            )
+            .with_label(format!("first declared on line {line}"))
+            .into());
+        }
+        for binding in &profile.variables {
+            let Some(variable) = beam_file.variables.iter().find(|v| v.name == binding.name) else {
+                return Err(Diagnostic::new(
+                    format!(
+                        "unknown variable '{}' in profile '{}'",
+                        binding.name, profile.name
+                    ),
+                    binding.span,
+                )
+                .with_label("not declared")
+                .with_suggestion(
+                    &binding.name,
+                    beam_file.variables.iter().map(|v| v.name.as_str()),
+                )
+                .into());
+            };
+            if let Err(e) = variable.constraints.check(&binding.value) {
+                let value = if variable.sensitive {
+                    "***"
+                } else {
+                    binding.value.as_str()
+                };
+                return Err(Diagnostic::new(
+                    format!(
+                        "invalid value \"{value}\" for variable '{}' in profile '{}': {e}",
+                        binding.name, profile.name
+                    ),
+                    binding.span,
+                )
+                .with_label("set here")
+                .into());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The syntax tree of a Beamfile, before any semantic check: what the
+/// formatter works on.
+fn parse_syntax(input: &str) -> Result<pest::iterators::Pairs<'_, Rule>> {
+    if input.len() > MAX_BEAMFILE_BYTES {
+        bail!(
+            "Beamfile too large: {} bytes (maximum {} bytes)",
+            input.len(),
+            MAX_BEAMFILE_BYTES
+        );
+    }
+    AuroraParser::parse(Rule::beamfile, input).map_err(|e| syntax_error(input, e).into())
+}
+
+/// A pest error as a [`Diagnostic`] at the position the parser stopped at,
+/// with the grammar's rule names turned into words.
+fn syntax_error(input: &str, err: pest::error::Error<Rule>) -> Diagnostic {
+    let span = match err.location {
+        pest::error::InputLocation::Pos(at) => {
+            let next = input[at..].chars().next().map_or(0, char::len_utf8);
+            Span::new(at, at + next)
+        }
+        pest::error::InputLocation::Span((start, end)) => Span::new(start, end),
+    };
+    let err = err.renamed_rules(describe_rule);
+    Diagnostic::new(
+        format!("Failed to parse Beamfile: {}", err.variant.message()),
+        span,
+    )
+}
+
+/// What a grammar rule stands for, in a syntax error.
+fn describe_rule(rule: &Rule) -> String {
+    match rule {
+        Rule::EOI => "end of file",
+        Rule::ident => "a name",
+        Rule::number => "a number",
+        Rule::string => "a string",
+        Rule::bool => "`true` or `false`",
+        Rule::string_list => "a list of strings",
+        Rule::append => "`=` or `+=`",
+        Rule::block => {
+            "a block (`aurora`, `variable`, `environment`, `profile`, `template` or `beam`)"
+        }
+        Rule::aurora_field => "an `aurora` field",
+        Rule::profile_field => "a `profile` field",
+        Rule::variable_field => "a `variable` field",
+        Rule::param_field => "a `param` field",
+        Rule::env_var => "an environment variable",
+        Rule::env_value => "a string or `shell(...)`",
+        Rule::env_file => "a file path or `{ path = \"...\", optional = true }`",
+        Rule::beam_field => "a beam field",
+        Rule::dep_list => "a list of dependencies",
+        Rule::dep_entry => "a dependency",
+        Rule::dep_params => "param bindings",
+        Rule::dep_binding => "a param binding",
+        Rule::condition_body => "`any` or `all`",
+        Rule::condition_clause => "a `{ shell = \"...\" }` clause",
+        Rule::run_field => "a `run` field",
+        Rule::executor_field => "an executor field",
+        Rule::var_ref => "`var.<name>`",
+        other => return format!("{other:?}"),
+    }
+    .to_string()
+}
+
 /// Resolves variable references now that any `--var` override has been applied
 /// to `Variable.default`.
 ///
@@ -99,38 +284,38 @@ pub fn resolve_variables(beam_file: &mut BeamFile) -> Result<()> {
         .collect();
 
     for beam in &mut beam_file.beams {
-        let beam_name = beam.name.clone();
+        let owner = (beam.name.clone(), beam.span);
         let vars = &globals;
 
         if let Some(dir) = &mut beam.dir {
-            *dir = interpolate_command(dir, vars, &beam_name)?;
+            *dir = interpolate_command(dir, vars, &owner)?;
         }
         // Gates run through the shell like `run.commands`, so they take the
         // same `${var.x}` interpolation: otherwise a parameterized gate keeps
         // its literal token, becomes a bad substitution, and silently stops
         // gating.
         if let Some(skip_if) = &mut beam.skip_if {
-            *skip_if = interpolate_command(skip_if, vars, &beam_name)?;
+            *skip_if = interpolate_command(skip_if, vars, &owner)?;
         }
         if let Some(condition) = &mut beam.condition {
             for ConditionClause::Shell(clause) in &mut condition.clauses {
-                *clause = interpolate_command(clause, vars, &beam_name)?;
+                *clause = interpolate_command(clause, vars, &owner)?;
             }
         }
         if let Some(run) = &mut beam.run {
             for cmd in &mut run.commands {
-                *cmd = interpolate_command(cmd, vars, &beam_name)?;
+                *cmd = interpolate_command(cmd, vars, &owner)?;
             }
             if let Some(exec_cfg) = &mut run.executor {
                 for val in exec_cfg.config.values_mut() {
                     if let Some(var_name) = val.strip_prefix("var.") {
                         match vars.get(var_name) {
                             Some(resolved) => *val = resolved.clone(),
-                            None => bail!(
-                                "unknown variable '{}' referenced in beam '{}'",
-                                var_name,
-                                beam_name
-                            ),
+                            None => {
+                                return Err(unknown_variable(var_name, vars, &owner)
+                                    .with_needle(val.as_str())
+                                    .into())
+                            }
                         }
                     }
                 }
@@ -140,7 +325,7 @@ pub fn resolve_variables(beam_file: &mut BeamFile) -> Result<()> {
         // a global into a dependency's declared param.
         for dep in &mut beam.depends_on {
             for value in dep.params.values_mut() {
-                *value = interpolate_command(value, vars, &beam_name)?;
+                *value = interpolate_command(value, vars, &owner)?;
             }
         }
         // A beam's own `environment {}` overlay may reference `${var.x}` too,
@@ -151,10 +336,13 @@ pub fn resolve_variables(beam_file: &mut BeamFile) -> Result<()> {
             for var in &mut environment.vars {
                 match &mut var.value {
                     EnvValue::Literal(s) | EnvValue::Shell(s) => {
-                        *s = interpolate_command(s, vars, &beam_name)?;
+                        *s = interpolate_command(s, vars, &owner)?;
                     }
                 }
             }
+            for file in environment.loads.iter_mut().flat_map(|l| &mut l.files) {
+                file.path = interpolate_command(&file.path, vars, &owner)?;
+            }
         }
     }
     Ok(())
@@ -193,8 +381,12 @@ pub(crate) fn interpolate_tokens(
 
 /// Interpolates `${var.<name>}` tokens in `s`. Non-`var` `${...}` sequences are
 /// copied verbatim so shell parameter expansion still works. An unknown
-/// variable is a hard error identified by `beam`.
-fn interpolate_command(s: &str, vars: &HashMap<String, String>, beam: &str) -> Result<String> {
+/// variable is a hard error identified by `beam` (its name and span).
+fn interpolate_command(
+    s: &str,
+    vars: &HashMap<String, String>,
+    beam: &(String, Span),
+) -> Result<String> {
     interpolate_tokens(s, |inner| {
         let name = inner.strip_prefix("var.")?;
         if !is_ident(name) {
@@ -202,15 +394,30 @@ fn interpolate_command(s: &str, vars: &HashMap<String, String>, beam: &str) -> R
         }
         Some(match vars.get(name) {
             Some(v) => Ok(v.clone()),
-            None => Err(anyhow::anyhow!(
-                "unknown variable '{}' referenced in beam '{}'",
-                name,
-                beam
-            )),
+            None => Err(unknown_variable(name, vars, beam)
+                .with_needle(format!("${{{inner}}}"))
+                .into()),
         })
     })
 }
 
+/// The error for a reference to an undeclared variable, pointing at the beam
+/// that makes it.
+fn unknown_variable(
+    name: &str,
+    vars: &HashMap<String, String>,
+    (beam, span): &(String, Span),
+) -> Diagnostic {
+    let mut declared: Vec<&str> = vars.keys().map(String::as_str).collect();
+    declared.sort_unstable();
+    Diagnostic::new(
+        format!("unknown variable '{name}' referenced in beam '{beam}'"),
+        *span,
+    )
+    .with_label("not declared")
+    .with_suggestion(name, declared.into_iter())
+}
+
 /// True when `s` is a valid identifier: the grammar's `ident` rule matches the
 /// whole string. Validating against the grammar rather than re-implementing the
 /// character classes by hand keeps this in lockstep with `aurora.pest`, so the
@@ -222,12 +429,15 @@ pub(crate) fn is_ident(s: &str) -> bool {
         .is_some_and(|pair| pair.as_str() == s)
 }
 
-fn parse_block(pair: Pair<Rule>, bf: &mut BeamFile) -> Result<()> {
+fn parse_block(pair: Pair<Rule>, bf: &mut BeamFile, templates: &Templates) -> Result<()> {
     match pair.as_rule() {
         Rule::aurora_block => bf.config = Some(parse_aurora_block(pair)?),
         Rule::variable_block => bf.variables.push(parse_variable_block(pair)?),
         Rule::environment_block => bf.environment = Some(parse_environment_block(pair)?),
-        Rule::beam_block => bf.beams.push(parse_beam_block(pair)?),
+        Rule::profile_block => bf.profiles.push(parse_profile_block(pair)?),
+        Rule::beam_block => bf
+            .beams
+            .push(parse_beam_block(pair, templates, &mut vec![])?),
         _ => {}
     }
     Ok(())
@@ -238,6 +448,8 @@ fn parse_aurora_block(pair: Pair<Rule>) -> Result<AuroraConfig> {
         version: "1".to_string(),
         default: None,
         max_parallelism: None,
+        log_retention: None,
+        inherit_env: vec![],
     };
     for field_wrapper in pair.into_inner() {
         // aurora_field is a wrapper rule: unwrap to get the actual field rule
@@ -255,19 +467,102 @@ fn parse_aurora_block(pair: Pair<Rule>) -> Result<AuroraConfig> {
             Rule::aurora_parallelism => {
                 cfg.max_parallelism = Some(field.into_inner().next().unwrap().as_str().parse()?);
             }
+            Rule::aurora_log_retention => {
+                cfg.log_retention = Some(field.into_inner().next().unwrap().as_str().parse()?);
+            }
+            Rule::aurora_inherit_env => {
+                cfg.inherit_env = parse_string_list(field.into_inner().next().unwrap());
+            }
             _ => {}
         }
     }
     Ok(cfg)
 }
 
+fn parse_profile_block(pair: Pair<Rule>) -> Result<Profile> {
+    let mut inner = pair.into_inner();
+    let name_pair = inner.next().unwrap();
+    let span = span_of(&name_pair);
+    let mut profile = Profile {
+        name: unquote(name_pair),
+        span,
+        variables: vec![],
+        environment: None,
+        max_parallelism: None,
+    };
+    for field_wrapper in inner {
+        // profile_field is a wrapper rule: unwrap to get the actual field rule
+        let field = match field_wrapper.as_rule() {
+            Rule::profile_field => field_wrapper.into_inner().next().unwrap(),
+            _ => continue,
+        };
+        match field.as_rule() {
+            Rule::profile_variables => {
+                let params = field.into_inner().next().unwrap();
+                for binding in params.into_inner() {
+                    if binding.as_rule() != Rule::dep_binding {
+                        continue;
+                    }
+                    let span = span_of(&binding);
+                    let mut kv = binding.into_inner();
+                    let name = kv.next().unwrap().as_str().to_string();
+                    let value = unquote(kv.next().unwrap());
+                    if profile.variables.iter().any(|v| v.name == name) {
+                        return Err(Diagnostic::new(
+                            format!("variable '{name}' set twice in profile '{}'", profile.name),
+                            span,
+                        )
+                        .into());
+                    }
+                    profile
+                        .variables
+                        .push(ProfileVariable { name, span, value });
+                }
+            }
+            Rule::profile_parallelism => {
+                profile.max_parallelism =
+                    Some(field.into_inner().next().unwrap().as_str().parse()?);
+            }
+            Rule::environment_block => {
+                profile.environment = Some(parse_environment_block(field)?);
+            }
+            _ => {}
+        }
+    }
+    Ok(profile)
+}
+
+/// Parses a duration written with a unit: `500ms`, `30s`, `2m`. A bare number
+/// is rejected rather than guessed: `10` could mean seconds or milliseconds.
+fn parse_duration(raw: &str) -> Result<Duration> {
+    let split = raw.find(|c: char| !c.is_ascii_digit()).unwrap_or(raw.len());
+    let (digits, unit) = raw.split_at(split);
+    let Ok(value) = digits.parse::<u64>() else {
+        bail!("expected a number followed by a unit (ms, s or m), such as \"30s\"");
+    };
+    match unit {
+        "ms" => Ok(Duration::from_millis(value)),
+        "s" => Ok(Duration::from_secs(value)),
+        "m" => Ok(Duration::from_secs(value.saturating_mul(60))),
+        "" => bail!("missing unit (ms, s or m), such as \"{value}s\""),
+        other => bail!("unknown unit \"{other}\" (expected ms, s or m)"),
+    }
+}
+
 fn parse_variable_block(pair: Pair<Rule>) -> Result<Variable> {
     let mut inner = pair.into_inner();
-    let name = unquote(inner.next().unwrap());
+    let name_pair = inner.next().unwrap();
+    let span = span_of(&name_pair);
+    let name = unquote(name_pair);
     let mut var = Variable {
         name,
+        span,
         default: String::new(),
         description: None,
+        sensitive: false,
+        constraints: Constraints::default(),
+        sources: VariableSources::default(),
+        origin: ValueOrigin::Unset,
     };
     for field_wrapper in inner {
         // variable_field is a wrapper rule: unwrap to get the actual field rule
@@ -277,23 +572,132 @@ fn parse_variable_block(pair: Pair<Rule>) -> Result<Variable> {
         };
         match field.as_rule() {
             Rule::var_default => {
-                var.default = unquote(field.into_inner().next().unwrap());
+                let value = field.into_inner().next().unwrap();
+                if value.as_rule() == Rule::shell_call {
+                    var.sources.shell = Some(unquote(value.into_inner().next().unwrap()));
+                } else {
+                    var.default = unquote(value);
+                    var.origin = ValueOrigin::Literal;
+                }
+            }
+            Rule::var_from_env => {
+                var.sources.env = Some(unquote(field.into_inner().next().unwrap()));
+            }
+            Rule::var_from_file => {
+                var.sources.file = Some(unquote(field.into_inner().next().unwrap()));
             }
             Rule::var_description => {
                 var.description = Some(unquote(field.into_inner().next().unwrap()));
             }
+            Rule::var_sensitive => {
+                var.sensitive = field.into_inner().next().unwrap().as_str() == "true";
+            }
+            Rule::value_constraint => parse_constraint(field, &mut var.constraints)?,
             _ => {}
         }
     }
+    // Without a literal `default` the value is empty until `--var` (or one
+    // of the sources, evaluated later) sets it.
+    if var.origin == ValueOrigin::Literal {
+        check_default(
+            &format!("variable '{}'", var.name),
+            &var.default,
+            &var.constraints,
+            var.span,
+        )?;
+    }
     Ok(var)
 }
 
+/// One of `type`, `choices` and `pattern`, into `constraints`.
+fn parse_constraint(pair: Pair<Rule>, constraints: &mut Constraints) -> Result<()> {
+    let field = pair.into_inner().next().unwrap();
+    match field.as_rule() {
+        Rule::value_type => {
+            let value = field.into_inner().next().unwrap();
+            let span = span_of(&value);
+            let name = unquote(value);
+            constraints.value_type = ValueType::from_name(&name).ok_or_else(|| {
+                Diagnostic::new(format!("unknown type \"{name}\""), span)
+                    .with_label("expected string, int, bool or list")
+                    .with_suggestion(&name, ["string", "int", "bool", "list"].into_iter())
+            })?;
+        }
+        Rule::value_choices => {
+            constraints.choices = parse_string_list(field.into_inner().next().unwrap());
+        }
+        Rule::value_pattern => {
+            let value = field.into_inner().next().unwrap();
+            let span = span_of(&value);
+            let pattern = unquote(value);
+            if let Err(e) = anchored(&pattern) {
+                // The regex crate's message spans several lines; its last one
+                // says what is wrong.
+                let reason = e.to_string();
+                let reason = reason.lines().last().unwrap_or_default().trim();
+                return Err(Diagnostic::new(
+                    format!("invalid pattern \"{pattern}\": {reason}"),
+                    span,
+                )
+                .into());
+            }
+            constraints.pattern = Some(pattern);
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// A declared default must pass its own constraints. One that references a
+/// variable (`${var.x}`) is only known later, and checked where it is bound.
+fn check_default(owner: &str, default: &str, constraints: &Constraints, span: Span) -> Result<()> {
+    if default.contains("${") {
+        return Ok(());
+    }
+    constraints.check(default).map_err(|e| {
+        Diagnostic::new(
+            format!("invalid default \"{default}\" for {owner}: {e}"),
+            span,
+        )
+        .with_label("declared here")
+        .into()
+    })
+}
+
 fn parse_environment_block(pair: Pair<Rule>) -> Result<Environment> {
     let mut vars = vec![];
+    let mut loads = vec![];
     for var_pair in pair.into_inner() {
-        if var_pair.as_rule() == Rule::env_var {
+        if var_pair.as_rule() == Rule::env_load {
+            let files = var_pair
+                .into_inner()
+                .map(|file| {
+                    let entry = file.into_inner().next().unwrap();
+                    let (path, optional) = match entry.as_rule() {
+                        Rule::env_file_object => {
+                            let mut inner = entry.into_inner();
+                            let path = inner.next().unwrap();
+                            let optional = inner.next().is_some_and(|b| b.as_str() == "true");
+                            (path, optional)
+                        }
+                        _ => (entry, false),
+                    };
+                    EnvFile {
+                        span: span_of(&path),
+                        path: unquote(path),
+                        optional,
+                    }
+                })
+                .collect();
+            loads.push(EnvLoad {
+                after: vars.len(),
+                files,
+            });
+        } else if var_pair.as_rule() == Rule::env_var {
             let mut inner = var_pair.into_inner();
-            let name = inner.next().unwrap().as_str().to_string();
+            let name_pair = inner.next().unwrap();
+            let span = span_of(&name_pair);
+            let name = name_pair.as_str().to_string();
             let val_pair = inner.next().unwrap();
             // val_pair is env_value, which contains shell_call or string
             let child = val_pair.into_inner().next().unwrap();
@@ -305,37 +709,71 @@ fn parse_environment_block(pair: Pair<Rule>) -> Result<Environment> {
                 Rule::string => EnvValue::Literal(unquote(child)),
                 _ => bail!("Unexpected env_value rule: {:?}", child.as_rule()),
             };
-            vars.push(EnvVar { name, value });
+            vars.push(EnvVar { name, span, value });
         }
     }
-    Ok(Environment { vars })
+    Ok(Environment { vars, loads })
 }
 
-fn parse_beam_block(pair: Pair<Rule>) -> Result<Beam> {
+/// A `beam` block, or a `template` one (which reads the same). With
+/// `extends`, the template's fields come first and the block's own apply on
+/// top of them: a scalar replaces the template's, a list replaces it (`=`)
+/// or appends to it (`+=`), and the maps merge: a param, an `environment`
+/// entry or an executor setting declared again replaces the template's.
+/// `chain` holds the templates being resolved (see [`Templates::base`]).
+fn parse_beam_block(
+    pair: Pair<Rule>,
+    templates: &Templates,
+    chain: &mut Vec<String>,
+) -> Result<Beam> {
     let mut inner = pair.into_inner();
-    let name = unquote(inner.next().unwrap());
-    let mut beam = Beam {
-        name,
-        ..Beam::default()
+    let name_pair = inner.next().unwrap();
+    let span = span_of(&name_pair);
+    let name = unquote(name_pair);
+    // beam_field is a wrapper rule: unwrap to get the actual field rules
+    let fields: Vec<Pair<Rule>> = inner
+        .filter(|wrapper| wrapper.as_rule() == Rule::beam_field)
+        .map(|wrapper| wrapper.into_inner().next().unwrap())
+        .collect();
+    let extends = fields
+        .iter()
+        .find(|field| field.as_rule() == Rule::beam_extends)
+        .map(|field| field.clone().into_inner().next().unwrap());
+    let mut beam = match extends {
+        Some(template) => {
+            let template_span = span_of(&template);
+            let template = unquote(template);
+            let mut base = templates.base(&template, template_span, chain)?;
+            base.extends = Some(template);
+            base
+        }
+        None => Beam::default(),
     };
-    for field_wrapper in inner {
-        // beam_field is a wrapper rule: unwrap to get the actual field rule
-        let field = match field_wrapper.as_rule() {
-            Rule::beam_field => field_wrapper.into_inner().next().unwrap(),
-            _ => continue,
-        };
+    beam.name = name;
+    beam.span = span;
+    // The template's params, which a param of the same name replaces in
+    // place; a name declared twice in the block itself stays a duplicate.
+    let mut inherited_params: Vec<String> = beam.params.iter().map(|p| p.name.clone()).collect();
+    for field in fields {
         match field.as_rule() {
             Rule::beam_description => {
                 beam.description = Some(unquote(field.into_inner().next().unwrap()));
             }
             Rule::beam_depends_on => {
-                beam.depends_on = parse_dep_list(field.into_inner().next().unwrap())?;
+                let (append, list) = list_field(field);
+                merge_list(&mut beam.depends_on, append, parse_dep_list(list)?);
+            }
+            Rule::beam_tags => {
+                let (append, list) = list_field(field);
+                merge_list(&mut beam.tags, append, parse_string_list(list));
             }
             Rule::beam_inputs => {
-                beam.inputs = parse_string_list(field.into_inner().next().unwrap());
+                let (append, list) = list_field(field);
+                merge_list(&mut beam.inputs, append, parse_string_list(list));
             }
             Rule::beam_outputs => {
-                beam.outputs = parse_string_list(field.into_inner().next().unwrap());
+                let (append, list) = list_field(field);
+                merge_list(&mut beam.outputs, append, parse_string_list(list));
             }
             Rule::beam_dir => {
                 beam.dir = Some(unquote(field.into_inner().next().unwrap()));
@@ -346,6 +784,28 @@ fn parse_beam_block(pair: Pair<Rule>) -> Result<Beam> {
             Rule::beam_allow_failure => {
                 beam.allow_failure = field.into_inner().next().unwrap().as_str() == "true";
             }
+            Rule::beam_kill_timeout => {
+                let value = field.into_inner().next().unwrap();
+                let span = span_of(&value);
+                let raw = unquote(value);
+                let timeout = parse_duration(&raw).map_err(|e| {
+                    Diagnostic::new(
+                        format!(
+                            "invalid kill_timeout \"{raw}\" in beam '{}': {e}",
+                            beam.name
+                        ),
+                        span,
+                    )
+                })?;
+                beam.kill_timeout = Some(timeout);
+            }
+            Rule::beam_tty => {
+                beam.tty = field.into_inner().next().unwrap().as_str() == "true";
+            }
+            Rule::beam_inherit_env => {
+                let (append, list) = list_field(field);
+                merge_list(&mut beam.inherit_env, append, parse_string_list(list));
+            }
             Rule::beam_condition => {
                 beam.condition = Some(parse_condition(field)?);
             }
@@ -359,24 +819,44 @@ fn parse_beam_block(pair: Pair<Rule>) -> Result<Beam> {
                 // instance ids, so it is rejected up front: untrusted input,
                 // hard error over a silent footgun.
                 if !is_ident(&param.name) {
-                    bail!(
-                        "param name '{}' in beam '{}' is not a valid identifier",
-                        param.name,
-                        beam.name
-                    );
+                    return Err(Diagnostic::new(
+                        format!(
+                            "param name '{}' in beam '{}' is not a valid identifier",
+                            param.name, beam.name
+                        ),
+                        param.span,
+                    )
+                    .into());
+                }
+                match inherited_params.iter().position(|name| *name == param.name) {
+                    Some(index) => {
+                        inherited_params.remove(index);
+                        let existing = beam.params.iter_mut().find(|p| p.name == param.name);
+                        *existing.unwrap() = param;
+                    }
+                    None => beam.params.push(param),
                 }
-                beam.params.push(param);
             }
             Rule::environment_block => {
-                beam.environment = Some(parse_environment_block(field)?);
+                let environment = parse_environment_block(field)?;
+                match &mut beam.environment {
+                    Some(base) => base.extend(environment),
+                    None => beam.environment = Some(environment),
+                }
+            }
+            Rule::variable_block => {
+                return Err(Diagnostic::new(
+                    format!(
+                        "beam '{}' declares a `variable {{}}` block: beam-local variables \
+                         were replaced by `param` with a `default`",
+                        beam.name
+                    ),
+                    span_of(&field),
+                )
+                .into())
             }
-            Rule::variable_block => bail!(
-                "beam '{}' declares a `variable {{}}` block: beam-local variables were \
-                 replaced by `param` with a `default`",
-                beam.name
-            ),
             Rule::beam_run => {
-                beam.run = Some(parse_run(field)?);
+                beam.run = Some(parse_run(field, beam.run.take().unwrap_or_default())?);
             }
             _ => {}
         }
@@ -386,11 +866,16 @@ fn parse_beam_block(pair: Pair<Rule>) -> Result<Beam> {
 
 fn parse_param_block(pair: Pair<Rule>) -> Result<Param> {
     let mut inner = pair.into_inner();
-    let name = unquote(inner.next().unwrap());
+    let name_pair = inner.next().unwrap();
+    let span = span_of(&name_pair);
+    let name = unquote(name_pair);
     let mut param = Param {
         name,
+        span,
         default: None,
         description: None,
+        sensitive: false,
+        constraints: Constraints::default(),
     };
     for field_wrapper in inner {
         let field = match field_wrapper.as_rule() {
@@ -404,9 +889,21 @@ fn parse_param_block(pair: Pair<Rule>) -> Result<Param> {
             Rule::param_description => {
                 param.description = Some(unquote(field.into_inner().next().unwrap()));
             }
+            Rule::param_sensitive => {
+                param.sensitive = field.into_inner().next().unwrap().as_str() == "true";
+            }
+            Rule::value_constraint => parse_constraint(field, &mut param.constraints)?,
             _ => {}
         }
     }
+    if let Some(default) = &param.default {
+        check_default(
+            &format!("param '{}'", param.name),
+            default,
+            &param.constraints,
+            param.span,
+        )?;
+    }
     Ok(param)
 }
 
@@ -416,9 +913,13 @@ fn parse_dep_list(pair: Pair<Rule>) -> Result<Vec<Dependency>> {
         if entry.as_rule() != Rule::dep_entry {
             continue;
         }
+        let span = span_of(&entry);
         let inner = entry.into_inner().next().unwrap();
         match inner.as_rule() {
-            Rule::string => deps.push(Dependency::named(unquote(inner))),
+            Rule::string => deps.push(Dependency {
+                span,
+                ..Dependency::named(unquote(inner))
+            }),
             Rule::dep_object => {
                 let mut parts = inner.into_inner();
                 let beam = unquote(parts.next().unwrap());
@@ -428,15 +929,22 @@ fn parse_dep_list(pair: Pair<Rule>) -> Result<Vec<Dependency>> {
                         if binding.as_rule() != Rule::dep_binding {
                             continue;
                         }
+                        let binding_span = span_of(&binding);
                         let mut kv = binding.into_inner();
                         let key = kv.next().unwrap().as_str().to_string();
                         let value = unquote(kv.next().unwrap());
                         if params.insert(key.clone(), value).is_some() {
-                            bail!("param '{key}' bound twice in a depends_on entry for '{beam}'");
+                            return Err(Diagnostic::new(
+                                format!(
+                                    "param '{key}' bound twice in a depends_on entry for '{beam}'"
+                                ),
+                                binding_span,
+                            )
+                            .into());
                         }
                     }
                 }
-                deps.push(Dependency { beam, params });
+                deps.push(Dependency { beam, params, span });
             }
             other => bail!("unexpected depends_on entry: {other:?}"),
         }
@@ -468,9 +976,8 @@ fn parse_condition(pair: Pair<Rule>) -> Result<Condition> {
     Ok(Condition { op, clauses })
 }
 
-fn parse_run(pair: Pair<Rule>) -> Result<Run> {
-    let mut commands = vec![];
-    let mut executor = None;
+/// A `run` block, over `run`: the template's when the beam extends one.
+fn parse_run(pair: Pair<Rule>, mut run: Run) -> Result<Run> {
     for field_wrapper in pair.into_inner() {
         // run_field is a wrapper rule: unwrap to get the actual field rule
         let field = match field_wrapper.as_rule() {
@@ -479,7 +986,8 @@ fn parse_run(pair: Pair<Rule>) -> Result<Run> {
         };
         match field.as_rule() {
             Rule::run_commands => {
-                commands = parse_string_list(field.into_inner().next().unwrap());
+                let (append, list) = list_field(field);
+                merge_list(&mut run.commands, append, parse_string_list(list));
             }
             Rule::run_executor => {
                 let mut inner = field.into_inner();
@@ -498,12 +1006,36 @@ fn parse_run(pair: Pair<Rule>) -> Result<Run> {
                         config.insert(key, value);
                     }
                 }
-                executor = Some(ExecutorConfig { name, config });
+                // The same executor merges its settings; another one
+                // replaces it.
+                match &mut run.executor {
+                    Some(executor) if executor.name == name => executor.config.extend(config),
+                    _ => run.executor = Some(ExecutorConfig { name, config }),
+                }
             }
             _ => {}
         }
     }
-    Ok(Run { commands, executor })
+    Ok(run)
+}
+
+/// The list of a list field, and whether it appends to the template's
+/// (`+=`) rather than replacing it (`=`).
+fn list_field(field: Pair<Rule>) -> (bool, Pair<Rule>) {
+    let mut inner = field.into_inner();
+    let first = inner.next().unwrap();
+    match first.as_rule() {
+        Rule::append => (true, inner.next().unwrap()),
+        _ => (false, first),
+    }
+}
+
+fn merge_list<T>(current: &mut Vec<T>, append: bool, list: Vec<T>) {
+    if append {
+        current.extend(list);
+    } else {
+        *current = list;
+    }
 }
 
 fn parse_string_list(pair: Pair<Rule>) -> Vec<String> {
@@ -513,6 +1045,12 @@ fn parse_string_list(pair: Pair<Rule>) -> Vec<String> {
         .collect()
 }
 
+/// Where `pair` is in the source.
+fn span_of(pair: &Pair<Rule>) -> Span {
+    let span = pair.as_span();
+    Span::new(span.start(), span.end())
+}
+
 /// Strips quotes from a `string` rule pair and processes escape sequences.
 fn unquote(pair: Pair<Rule>) -> String {
     let raw = if pair.as_rule() == Rule::string {
@@ -1,8 +1,9 @@
 use crate::ast::{Beam, Condition, ConditionClause, ConditionOp, Run};
 use crate::cache::{BeamCache, BeamDefinition};
 use crate::dag::BeamGraph;
+use crate::trace_context::{TraceContext, TRACEPARENT};
 use anyhow::Result;
-use aurora_executor_api::{ExecutionInput, ExecutionOutput, Executor};
+use aurora_executor_api::{ExecutionInput, ExecutionOutput, Executor, StopSignal};
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -13,7 +14,18 @@ use tokio::task::JoinSet;
 // The event/status contract lives in `crate::events`. Re-exported here so the
 // scheduler's long-standing `scheduler::{SchedulerEvent, BeamStatus, ...}` path
 // keeps working.
-pub use crate::events::{BeamStatus, SchedulerEvent, SkipReason};
+pub use crate::events::{BeamPhase, BeamStatus, CancelRequest, SchedulerEvent, SkipReason};
+
+/// How long a cancelled beam's command gets to exit after the stop request
+/// before it is killed, unless the beam sets `kill_timeout`. Matches
+/// `docker stop`'s own default.
+pub const DEFAULT_KILL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Extra time, past the grace period, that a stopping executor gets to reap
+/// its killed command and return. Past it, the execution is dropped, which
+/// kills outright (the executors' drop guards): a straggler holding the output
+/// pipes open must not keep the beam stopping forever.
+const STOP_SETTLE: Duration = Duration::from_secs(2);
 
 /// Outcome of a beam task, used to drive downstream scheduling.
 enum BeamOutcome {
@@ -43,6 +55,9 @@ pub struct Scheduler {
     /// per-beam cancellation channel: that one targets a named beam, this one
     /// tears the run down.
     shutdown: Option<oneshot::Receiver<()>>,
+    /// The trace the run is exported to, if any: each beam gets its span's
+    /// `TRACEPARENT`.
+    trace: Option<TraceContext>,
 }
 
 impl Scheduler {
@@ -66,6 +81,7 @@ impl Scheduler {
             env,
             declared_env: BTreeMap::new(),
             shutdown: None,
+            trace: None,
         }
     }
 
@@ -94,6 +110,15 @@ impl Scheduler {
         self
     }
 
+    /// Exports `TRACEPARENT` to every beam, naming the beam's span in `trace`
+    /// (see [`TraceContext::beam_span_id`]) so the tools it runs can attach
+    /// their own spans under it. Only the commands' environment carries it,
+    /// never the cache key: it changes on every run.
+    pub fn with_trace_context(mut self, trace: TraceContext) -> Self {
+        self.trace = Some(trace);
+        self
+    }
+
     /// Disables the cache for this run: no cache hit is honored and no result
     /// is persisted. Backs the `--no-cache` CLI flag.
     pub fn without_cache(mut self) -> Self {
@@ -105,7 +130,7 @@ impl Scheduler {
     /// silent channel (the sender stays alive for the whole run, so there is
     /// never any cancellation).
     pub async fn run(self, root: &str, pre_success: &[String]) -> Result<bool> {
-        let (_cancel_tx, cancel_rx) = mpsc::unbounded_channel::<String>();
+        let (_cancel_tx, cancel_rx) = mpsc::unbounded_channel::<CancelRequest>();
         self.run_cancellable(root, pre_success, cancel_rx).await
     }
 
@@ -113,7 +138,7 @@ impl Scheduler {
         mut self,
         root: &str,
         pre_success: &[String],
-        mut cancel_rx: mpsc::UnboundedReceiver<String>,
+        mut cancel_rx: mpsc::UnboundedReceiver<CancelRequest>,
     ) -> Result<bool> {
         let mut shutdown = self.shutdown.take();
         let deps: Vec<(String, Vec<String>)> = self
@@ -192,35 +217,39 @@ impl Scheduler {
                     run.cancels.remove(&name);
 
                     match outcome {
-                        BeamOutcome::Ok => {
+                        // A run tearing down spawns nothing more, so the
+                        // dependents of a beam that still ended Ok (it
+                        // finished in the same instant, or tolerates its
+                        // cancellation) are reported Cancelled rather than
+                        // left Pending.
+                        BeamOutcome::Ok if !run.shutting_down => {
                             self.unblock_dependents(&mut run, &graph, &name, &nodes, &pre, &semaphore);
                         }
+                        BeamOutcome::Ok => {
+                            self.cancel_dependents(&mut run, &graph, &name, &nodes).await;
+                        }
                         BeamOutcome::Failed | BeamOutcome::Cancelled => {
                             overall_success = false;
                             self.cancel_dependents(&mut run, &graph, &name, &nodes).await;
                         }
                     }
                 }
-                Some(name) = cancel_rx.recv() => {
+                Some(request) = cancel_rx.recv() => match request {
                     // Cancellation request from the TUI: trigger the beam's
                     // oneshot if it is running. Ignored if it has already finished.
-                    if let Some(s) = run.cancels.remove(&name) {
-                        let _ = s.send(());
+                    CancelRequest::Beam(name) => {
+                        if let Some(s) = run.cancels.remove(&name) {
+                            let _ = s.send(());
+                        }
                     }
-                }
+                    CancelRequest::Run => {
+                        overall_success = false;
+                        run.tear_down();
+                    }
+                },
                 _ = wait_for_shutdown(&mut shutdown), if !run.shutting_down => {
-                    // Tear the run down: cancel every running beam, and stop
-                    // spawning. A torn-down run never completed, so it must
-                    // report failure regardless of how its in-flight beams
-                    // resolve. Without this, a running `allow_failure` beam (or
-                    // a beam that finishes in the same instant) returns Ok, its
-                    // suppressed dependents leave `overall_success` untouched,
-                    // and the aborted run would falsely report success.
-                    run.shutting_down = true;
                     overall_success = false;
-                    for (_, cancel) in run.cancels.drain() {
-                        let _ = cancel.send(());
-                    }
+                    run.tear_down();
                 }
             }
         }
@@ -356,6 +385,7 @@ impl Scheduler {
             cache: self.cache.clone(),
             cache_enabled: self.cache_enabled,
             working_dir: self.working_dir.clone(),
+            traceparent: self.trace.as_ref().map(|t| t.traceparent(beam_name)),
         };
 
         let handle = set.spawn(run_beam_task(beam, executor, cancel_rx, task_env));
@@ -421,6 +451,20 @@ impl RunLoop {
             shutting_down: false,
         }
     }
+
+    /// Tears the run down: cancels every running beam and stops spawning.
+    ///
+    /// A torn-down run never completed, so the caller must report failure
+    /// regardless of how its in-flight beams resolve. Without that, a running
+    /// `allow_failure` beam (or a beam that finishes in the same instant)
+    /// returns Ok, its suppressed dependents leave the outcome untouched, and
+    /// the aborted run would falsely report success.
+    fn tear_down(&mut self) {
+        self.shutting_down = true;
+        for (_, cancel) in self.cancels.drain() {
+            let _ = cancel.send(());
+        }
+    }
 }
 
 /// The shared scheduler state a beam task needs, cloned once per spawn. Grouped
@@ -433,6 +477,7 @@ struct TaskEnv {
     cache: Arc<BeamCache>,
     cache_enabled: bool,
     working_dir: PathBuf,
+    traceparent: Option<String>,
 }
 
 /// Runs a single beam to completion: acquires the parallelism permit, applies
@@ -452,22 +497,39 @@ async fn run_beam_task(
         cache,
         cache_enabled,
         working_dir,
+        traceparent,
     } = task_env;
 
     // The per-instance `environment {}` overlay shadows the global
     // environment for this beam only: applied before the gates, so
     // `skip_if`/`condition` see it too, and folded into the declared half
     // that feeds the cache key, so an overlay change busts the cache exactly
-    // like a global environment change would.
-    let (env, declared_env) = if beam.env_overlay.is_empty() {
+    // like a global environment change would. The beam's own `inherit_env`
+    // variables go under every declared one, and stay out of the key like all
+    // ambient ones.
+    let (env, declared_env) = if beam.env_overlay.is_empty() && beam.inherited_env.is_empty() {
         (env, declared_env)
     } else {
         let mut env = env;
+        env.extend(
+            beam.inherited_env
+                .iter()
+                .filter(|(k, _)| !declared_env.contains_key(*k))
+                .map(|(k, v)| (k.clone(), v.clone())),
+        );
         env.extend(beam.env_overlay.iter().map(|(k, v)| (k.clone(), v.clone())));
         let mut declared_env = declared_env;
         declared_env.extend(beam.env_overlay.clone());
         (env, declared_env)
     };
+    let env = match traceparent {
+        Some(traceparent) => {
+            let mut env = env;
+            env.insert(TRACEPARENT.to_string(), traceparent);
+            env
+        }
+        None => env,
+    };
 
     let _permit = match sem {
         // The semaphore is owned by the scheduler and never closed while the
@@ -591,6 +653,7 @@ async fn run_beam_task(
     // (`skip_if = "curl ..."`), so the evaluation is raced against
     // cancellation: otherwise a cancelled beam would stay parked here and the
     // whole run could not drain until the gate exited on its own.
+    let gate_start = Instant::now();
     let gate = tokio::select! {
         reason = gate_skip_reason(&beam, &working_dir, &env) => reason,
         _ = &mut cancel_rx => {
@@ -608,6 +671,9 @@ async fn run_beam_task(
             return (beam.name, outcome);
         }
     };
+    if beam.skip_if.is_some() || beam.condition.is_some() {
+        send_phase(&tx, &beam.name, BeamPhase::Gate, gate_start, Instant::now()).await;
+    }
     if let Some(reason) = gate {
         let _ = tx
             .send(SchedulerEvent::BeamCompleted {
@@ -622,9 +688,9 @@ async fn run_beam_task(
     // not merely "did its input files change?". So it covers the beam's
     // definition as well: its resolved commands (which already carry the
     // variables and the `--var` overrides), the executor and its settings,
-    // the working directory, the declared environment, and the instance's
-    // resolved param bindings. Hashing the inputs alone would serve the
-    // previous run's result after an edit to any of these.
+    // the working directory, the declared environment, the instance's
+    // resolved param bindings and the `tty` mode. Hashing the inputs alone would
+    // serve the previous run's result after an edit to any of these.
     let run = beam.run.as_ref();
     let executor_config = run.and_then(|r| r.executor.as_ref());
     let definition_hash = BeamDefinition {
@@ -634,6 +700,7 @@ async fn run_beam_task(
         dir: beam.dir.as_deref(),
         env: Some(&declared_env),
         bindings: Some(&beam.bindings),
+        tty: beam.tty,
     }
     .hash();
 
@@ -641,7 +708,7 @@ async fn run_beam_task(
     // hashes the inputs (reading whole files) and stats the outputs, so it runs
     // on a blocking thread rather than stalling the async runtime.
     let inputs_hash = if cache_enabled && !beam.inputs.is_empty() {
-        let (lookup, dead_patterns) = cache_lookup_blocking(
+        let (lookup, dead_patterns, timings) = cache_lookup_blocking(
             &cache,
             &beam.name,
             &beam.inputs,
@@ -650,6 +717,24 @@ async fn run_beam_task(
             &working_dir,
         )
         .await;
+        if let Some(timings) = timings {
+            send_phase(
+                &tx,
+                &beam.name,
+                BeamPhase::InputHash,
+                timings[0],
+                timings[1],
+            )
+            .await;
+            send_phase(
+                &tx,
+                &beam.name,
+                BeamPhase::CacheLookup,
+                timings[1],
+                timings[2],
+            )
+            .await;
+        }
         // A pattern that matched no file silently protects nothing in the
         // cache: warn once per run so a typo'd input surfaces instead of
         // masking a stale hit.
@@ -683,42 +768,77 @@ async fn run_beam_task(
     // Execute, streaming output live and racing against cancellation.
     let run = beam.run.as_ref().unwrap();
     let (out_tx, fwd_handle) = spawn_output_forwarder(tx.clone(), beam.name.clone());
-    let input = ExecutionInput {
+    let mut input = ExecutionInput {
         commands: run.commands.clone(),
         env,
         working_dir: working_dir.clone(),
         config: build_executor_config(run),
+        tty: beam.tty,
         output_tx: Some(out_tx),
+        stop: None,
+    };
+
+    // A graceful stop lets the command clean up (release its locks, flush its
+    // reports) between the cancellation and the kill. An executor that cannot
+    // stop gracefully is simply dropped, which kills at once.
+    let kill_timeout = beam.kill_timeout.unwrap_or(DEFAULT_KILL_TIMEOUT);
+    let stop = if executor.supports_graceful_stop() {
+        let (handle, signal) = StopSignal::new(kill_timeout);
+        input.stop = Some(signal);
+        Some(handle)
+    } else {
+        None
     };
 
     let start = Instant::now();
     // Race between the execution and a cancellation request. If the
-    // cancellation wins, the `executor.execute(input)` future is
-    // dropped: its child process is killed (kill_on_drop) and we emit
-    // Cancelled.
-    let result = tokio::select! {
-        r = executor.execute(input) => r,
-        _ = &mut cancel_rx => {
-            let _ = tx.send(SchedulerEvent::BeamCompleted {
+    // cancellation wins, the command is stopped (gracefully when the executor
+    // supports it) and we emit Cancelled.
+    let mut execution = executor.execute(input);
+    let finished = tokio::select! {
+        r = &mut execution => Some(r),
+        _ = &mut cancel_rx => None,
+    };
+    let Some(result) = finished else {
+        if let Some(stop) = stop {
+            let _ = tx
+                .send(SchedulerEvent::BeamStopping {
+                    name: beam.name.clone(),
+                })
+                .await;
+            stop.request();
+            // The executor escalates to a kill after `kill_timeout` on its
+            // own; the bound only guards against one that never returns.
+            let _ = tokio::time::timeout(kill_timeout + STOP_SETTLE, &mut execution).await;
+        }
+        // Dropping the execution kills whatever is left (kill_on_drop and the
+        // executors' guards) and releases the output channel, so the
+        // forwarder can flush what the command wrote while stopping before
+        // the beam is reported cancelled.
+        drop(execution);
+        let _ = fwd_handle.await;
+        send_phase(&tx, &beam.name, BeamPhase::Execute, start, Instant::now()).await;
+        let _ = tx
+            .send(SchedulerEvent::BeamCompleted {
                 name: beam.name.clone(),
                 status: BeamStatus::Cancelled,
-            }).await;
-            let _ = fwd_handle.await;
-            // A cancelled `allow_failure` beam is treated as a tolerated
-            // failure: its displayed status stays Cancelled, but for
-            // scheduling purposes it counts as a success (dependents
-            // unblocked, overall run not failed). Otherwise, the
-            // cancellation is propagated.
-            let outcome = if beam.allow_failure {
-                BeamOutcome::Ok
-            } else {
-                BeamOutcome::Cancelled
-            };
-            return (beam.name, outcome);
-        }
+            })
+            .await;
+        // A cancelled `allow_failure` beam is treated as a tolerated
+        // failure: its displayed status stays Cancelled, but for
+        // scheduling purposes it counts as a success (dependents
+        // unblocked, overall run not failed). Otherwise, the
+        // cancellation is propagated.
+        let outcome = if beam.allow_failure {
+            BeamOutcome::Ok
+        } else {
+            BeamOutcome::Cancelled
+        };
+        return (beam.name, outcome);
     };
     let (stdout_lines, stderr_lines) = fwd_handle.await.unwrap_or_default();
     let duration = start.elapsed();
+    send_phase(&tx, &beam.name, BeamPhase::Execute, start, start + duration).await;
 
     // Side effects tied to the outcome: persist the cache on success,
     // surface the error message on failure to spawn.
@@ -832,6 +952,10 @@ enum CacheLookup {
 /// files), and on a hash and output match loads the recorded logs. The cache
 /// is filesystem-backed and synchronous by design, so it must not run on the
 /// async runtime where it would stall other beams and delay cancellation.
+///
+/// Also returns when the hashing started, when it ended (the lookup starting
+/// there) and when the lookup ended, for the phase events; `None` when the
+/// probe did not run to completion.
 async fn cache_lookup_blocking(
     cache: &Arc<BeamCache>,
     beam_name: &str,
@@ -839,7 +963,7 @@ async fn cache_lookup_blocking(
     outputs: &[String],
     definition_hash: &str,
     working_dir: &Path,
-) -> (CacheLookup, Vec<String>) {
+) -> (CacheLookup, Vec<String>, Option<[Instant; 3]>) {
     let cache = cache.clone();
     let beam_name = beam_name.to_string();
     let inputs = inputs.to_vec();
@@ -852,21 +976,43 @@ async fn cache_lookup_blocking(
         // key an entry, or a beam whose inputs vanished would stay cached
         // forever. `dead_patterns` carries the individual patterns that matched
         // nothing, surfaced as a warning by the caller.
+        let hash_start = Instant::now();
         let Ok(inputs) = cache.hash_inputs_at(&working_dir, &inputs) else {
-            return (CacheLookup::Miss { hash: None }, vec![]);
+            return (CacheLookup::Miss { hash: None }, vec![], None);
         };
+        let hash_end = Instant::now();
         let dead_patterns = inputs.dead_patterns;
         let hash = inputs.hash.map(|h| BeamCache::key(&h, &definition_hash));
+        let mut lookup = CacheLookup::Miss { hash: hash.clone() };
         if let Some(ref hash) = hash {
             if cache.is_valid(&beam_name, hash, &outputs, &working_dir) {
                 let (stdout, stderr) = cache.load_logs(&beam_name);
-                return (CacheLookup::Hit { stdout, stderr }, dead_patterns);
+                lookup = CacheLookup::Hit { stdout, stderr };
             }
         }
-        (CacheLookup::Miss { hash }, dead_patterns)
+        let timings = [hash_start, hash_end, Instant::now()];
+        (lookup, dead_patterns, Some(timings))
     })
     .await
-    .unwrap_or((CacheLookup::Miss { hash: None }, vec![]))
+    .unwrap_or((CacheLookup::Miss { hash: None }, vec![], None))
+}
+
+/// Reports a finished phase of `beam_name` (see [`SchedulerEvent::BeamPhase`]).
+async fn send_phase(
+    tx: &mpsc::Sender<SchedulerEvent>,
+    beam_name: &str,
+    phase: BeamPhase,
+    start: Instant,
+    end: Instant,
+) {
+    let _ = tx
+        .send(SchedulerEvent::BeamPhase {
+            name: beam_name.to_string(),
+            phase,
+            start,
+            end,
+        })
+        .await;
 }
 
 /// Persists a beam's cache entry on a blocking thread. Failures are ignored:
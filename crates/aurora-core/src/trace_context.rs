@@ -0,0 +1,114 @@
+//! W3C trace context for a run exported to a tracing backend.
+//!
+//! A run is one trace: its root span, then one span per beam. The beam span
+//! ids are derived from the trace id and the beam name, so the scheduler can
+//! hand a beam its `TRACEPARENT` before it starts and the exporter can name
+//! the same span once the beam is over, without the two sharing any state.
+
+use sha2::{Digest, Sha256};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The environment variable carrying the context, in and out.
+pub const TRACEPARENT: &str = "TRACEPARENT";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceContext {
+    /// 32 lowercase hex digits.
+    pub trace_id: String,
+    /// 16 lowercase hex digits.
+    pub root_span_id: String,
+    /// The span the run's root span belongs to, when Aurora itself runs
+    /// inside a trace (a `TRACEPARENT` in its own environment).
+    pub parent_span_id: Option<String>,
+}
+
+impl TraceContext {
+    /// A context for a new run: it continues `traceparent` when that is a
+    /// valid W3C value, and starts a new trace otherwise.
+    pub fn new(traceparent: Option<&str>) -> Self {
+        let root_span_id = random_hex(8);
+        match traceparent.and_then(parse_traceparent) {
+            Some((trace_id, parent)) => Self {
+                trace_id,
+                root_span_id,
+                parent_span_id: Some(parent),
+            },
+            None => Self {
+                trace_id: random_hex(16),
+                root_span_id,
+                parent_span_id: None,
+            },
+        }
+    }
+
+    /// The span id of `beam`'s span in this trace.
+    pub fn beam_span_id(&self, beam: &str) -> String {
+        let digest = Sha256::new()
+            .chain_update(self.trace_id.as_bytes())
+            .chain_update([0])
+            .chain_update(beam.as_bytes())
+            .finalize();
+        hex(&digest[..8])
+    }
+
+    /// The `TRACEPARENT` value handed to `beam`'s commands: its own span,
+    /// sampled.
+    pub fn traceparent(&self, beam: &str) -> String {
+        format!("00-{}-{}-01", self.trace_id, self.beam_span_id(beam))
+    }
+}
+
+/// The trace id and parent span id of a `version-trace-parent-flags` value,
+/// rejecting the all-zero ids the spec declares invalid.
+fn parse_traceparent(value: &str) -> Option<(String, String)> {
+    let mut parts = value.trim().split('-');
+    let (version, trace_id, parent, flags) =
+        (parts.next()?, parts.next()?, parts.next()?, parts.next()?);
+    let is_hex = |s: &str, len: usize| {
+        s.len() == len
+            && s.bytes()
+                .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+    };
+    let valid = is_hex(version, 2)
+        && version != "ff"
+        && is_hex(trace_id, 32)
+        && is_hex(parent, 16)
+        && is_hex(flags, 2)
+        && trace_id.bytes().any(|b| b != b'0')
+        && parent.bytes().any(|b| b != b'0');
+    valid.then(|| (trace_id.to_string(), parent.to_string()))
+}
+
+/// A new random span id, for spans that no one else needs to name.
+pub fn random_span_id() -> String {
+    random_hex(8)
+}
+
+/// `bytes` random bytes as hex. Ids only need to be unique, not
+/// unpredictable: the standard library's randomly keyed hasher, fed the
+/// clock, is enough and spares a dependency.
+fn random_hex(bytes: usize) -> String {
+    let mut out = Vec::with_capacity(bytes);
+    while out.len() < bytes {
+        let mut hasher = RandomState::new().build_hasher();
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default();
+        hasher.write_u128(nanos);
+        hasher.write_usize(out.len());
+        out.extend(hasher.finish().to_le_bytes());
+    }
+    out.truncate(bytes);
+    // An all-zero id is invalid; vanishingly unlikely, but cheap to rule out.
+    if out.iter().all(|&b| b == 0) {
+        out[0] = 1;
+    }
+    hex(&out)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
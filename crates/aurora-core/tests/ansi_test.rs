@@ -0,0 +1,80 @@
+use aurora_core::ansi::{plain_text, render_line, Color, Style};
+
+#[test]
+fn plain_text_strips_colours() {
+    assert_eq!(plain_text("\x1b[32mok\x1b[0m 3 tests"), "ok 3 tests");
+}
+
+#[test]
+fn carriage_return_overwrites_from_the_first_column() {
+    // A shorter frame leaves the tail of the longer one, as on a terminal.
+    assert_eq!(plain_text("downloading 10%\rDONE"), "DONEloading 10%");
+    assert_eq!(plain_text("10%\r20%\r30%"), "30%");
+}
+
+#[test]
+fn erase_line_clears_what_follows_the_cursor() {
+    assert_eq!(plain_text("downloading 10%\r\x1b[KDONE"), "DONE");
+    assert_eq!(plain_text("abcdef\x1b[3D\x1b[0K"), "abc");
+    assert_eq!(plain_text("old\x1b[2K\rnew"), "new");
+}
+
+#[test]
+fn cursor_moves_stay_within_the_line() {
+    assert_eq!(plain_text("abc\x1b[1Gx"), "xbc");
+    assert_eq!(plain_text("a\x1b[2Cb"), "a  b");
+    assert_eq!(plain_text("ab\x08c"), "ac");
+}
+
+#[test]
+fn other_sequences_and_controls_are_dropped() {
+    // Hidden cursor, OSC 8 hyperlink, bell; the tab is kept.
+    assert_eq!(
+        plain_text("\x1b[?25l\x1b]8;;https://x\x07link\x1b]8;;\x07\x07\tend"),
+        "link\tend"
+    );
+}
+
+#[test]
+fn sgr_becomes_styled_runs() {
+    let line = render_line("\x1b[1;31mFAIL\x1b[0m a \x1b[38;5;208mb\x1b[38;2;1;2;3mc\x1b[m");
+    assert_eq!(line.text, "FAIL a bc");
+    assert_eq!(line.runs.len(), 3);
+
+    assert_eq!((line.runs[0].start, line.runs[0].end), (0, 4));
+    assert_eq!(
+        line.runs[0].style,
+        Style {
+            fg: Some(Color::Indexed(1)),
+            bold: true,
+            ..Style::default()
+        }
+    );
+    assert_eq!(line.runs[1].style.fg, Some(Color::Indexed(208)));
+    assert_eq!(line.runs[2].style.fg, Some(Color::Rgb(1, 2, 3)));
+    assert_eq!((line.runs[2].start, line.runs[2].end), (8, 9));
+}
+
+#[test]
+fn bright_colours_and_resets() {
+    let line = render_line("\x1b[92;44mx\x1b[39my\x1b[49mz");
+    assert_eq!(line.runs[0].style.fg, Some(Color::Indexed(10)));
+    assert_eq!(line.runs[0].style.bg, Some(Color::Indexed(4)));
+    assert_eq!(line.runs[1].style.fg, None);
+    assert_eq!(line.runs[1].style.bg, Some(Color::Indexed(4)));
+    assert_eq!(line.runs.len(), 2, "z is unstyled");
+}
+
+#[test]
+fn overwritten_text_takes_the_new_style() {
+    let line = render_line("\x1b[31merror\x1b[0m\rok");
+    assert_eq!(line.text, "okror");
+    assert_eq!((line.runs[0].start, line.runs[0].end), (2, 5));
+}
+
+#[test]
+fn run_offsets_are_byte_offsets() {
+    let line = render_line("é\x1b[1mß\x1b[0m");
+    assert_eq!(line.text, "éß");
+    assert_eq!((line.runs[0].start, line.runs[0].end), (2, 4));
+}
@@ -55,13 +55,16 @@ fn test_environment_sequential_vars() {
         vars: vec![
             EnvVar {
                 name: "BRANCH".to_string(),
+                span: Span::default(),
                 value: EnvValue::Shell("git branch --show-current".to_string()),
             },
             EnvVar {
                 name: "MODE".to_string(),
+                span: Span::default(),
                 value: EnvValue::Literal("production".to_string()),
             },
         ],
+        loads: vec![],
     };
     assert_eq!(env.vars.len(), 2);
     assert!(matches!(&env.vars[0].value, EnvValue::Shell(_)));
@@ -75,13 +78,21 @@ fn test_beamfile_full() {
             version: "1".to_string(),
             default: Some("qa".to_string()),
             max_parallelism: Some(4),
+            log_retention: None,
+            inherit_env: vec![],
         }),
         variables: vec![Variable {
             name: "image".to_string(),
+            span: Span::default(),
             default: "ubuntu:22.04".to_string(),
             description: Some("Docker image".to_string()),
+            sensitive: false,
+            constraints: Constraints::default(),
+            sources: VariableSources::default(),
+            origin: ValueOrigin::Literal,
         }],
         environment: None,
+        profiles: vec![],
         beams: vec![],
     };
     assert_eq!(bf.config.as_ref().unwrap().default.as_deref(), Some("qa"));
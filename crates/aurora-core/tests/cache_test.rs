@@ -461,6 +461,25 @@ fn test_definition_hash_changes_with_the_dir() {
     );
 }
 
+#[test]
+fn test_definition_hash_changes_with_the_tty_mode() {
+    let cmds = vec!["cargo build".to_string()];
+    let piped = BeamDefinition {
+        commands: &cmds,
+        ..Default::default()
+    };
+    let tty = BeamDefinition {
+        commands: &cmds,
+        tty: true,
+        ..Default::default()
+    };
+    assert_ne!(
+        BeamCache::hash_with_definition("h", &piped),
+        BeamCache::hash_with_definition("h", &tty),
+        "a tool writes other logs to a terminal: replaying them across modes is wrong"
+    );
+}
+
 #[test]
 fn test_definition_hash_changes_with_a_declared_env_value() {
     let cmds = vec!["echo $GIT_SHA".to_string()];
@@ -499,6 +518,7 @@ fn test_definition_hash_is_stable_across_calls() {
                 dir: Some("api"),
                 env: Some(&env),
                 bindings: Some(&bindings),
+                tty: true,
             },
         )
     };
@@ -0,0 +1,168 @@
+use aurora_core::ast::{Constraints, ValueType};
+use aurora_core::diagnostic::find;
+use aurora_core::expand::{bind_cli_args, expand, signature};
+use aurora_core::parser::parse;
+
+const BEAMFILE: &str = r#"
+variable "replicas" {
+  type = "int"
+  default = "3"
+}
+
+beam "deploy" {
+  param "env" {
+    choices = ["staging", "prod"]
+  }
+  param "tag" {
+    pattern = "v[0-9]+"
+    default = "v1"
+  }
+  param "token" {
+    sensitive = true
+    default = "s3cr3t"
+  }
+  run { commands = ["./deploy ${param.env} ${param.tag}"] }
+}
+"#;
+
+fn constraints(value_type: ValueType) -> Constraints {
+    Constraints {
+        value_type,
+        ..Constraints::default()
+    }
+}
+
+#[test]
+fn constraints_are_parsed_on_variables_and_params() {
+    let beam_file = parse(BEAMFILE).unwrap();
+    assert_eq!(
+        beam_file.variables[0].constraints,
+        constraints(ValueType::Int)
+    );
+    let params = &beam_file.beams[0].params;
+    assert_eq!(params[0].constraints.choices, ["staging", "prod"]);
+    assert_eq!(params[1].constraints.pattern.as_deref(), Some("v[0-9]+"));
+    assert!(params[2].sensitive);
+    assert!(!params[0].sensitive);
+}
+
+#[test]
+fn each_type_accepts_its_own_values() {
+    let int = constraints(ValueType::Int);
+    assert_eq!(int.check("-12"), Ok(()));
+    assert_eq!(int.check("banana"), Err("expected an int".to_string()));
+
+    let bool = constraints(ValueType::Bool);
+    assert_eq!(bool.check("true"), Ok(()));
+    assert!(bool.check("yes").is_err());
+
+    let list = Constraints {
+        value_type: ValueType::List,
+        choices: vec!["a".to_string(), "b".to_string()],
+        pattern: None,
+    };
+    assert_eq!(list.check("a, b"), Ok(()));
+    assert_eq!(
+        list.check("a,c"),
+        Err("item 'c': expected one of a, b".to_string())
+    );
+
+    assert_eq!(constraints(ValueType::String).check("anything"), Ok(()));
+}
+
+#[test]
+fn a_pattern_matches_the_whole_value() {
+    let tag = Constraints {
+        pattern: Some("v[0-9]+".to_string()),
+        ..Constraints::default()
+    };
+    assert_eq!(tag.check("v12"), Ok(()));
+    assert_eq!(
+        tag.check("v12-rc"),
+        Err("expected a value matching `v[0-9]+`".to_string())
+    );
+}
+
+#[test]
+fn a_cli_argument_is_checked_against_its_param() {
+    let beam_file = parse(BEAMFILE).unwrap();
+    let deploy = &beam_file.beams[0];
+    let err = bind_cli_args(deploy, &["prodd".to_string()]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid argument 'prodd' for param 'env' of beam 'deploy': expected one of \
+         staging, prod; did you mean 'prod'?"
+    );
+
+    let err = bind_cli_args(deploy, &["prod".to_string(), "tag=latest".to_string()]).unwrap_err();
+    assert!(
+        err.to_string()
+            .starts_with("invalid argument 'tag=latest' for param 'tag'"),
+        "{err}"
+    );
+
+    let bound = bind_cli_args(deploy, &["prod".to_string(), "v2".to_string()]).unwrap();
+    assert_eq!(bound["tag"], "v2");
+}
+
+#[test]
+fn a_dependency_binding_is_checked_against_its_param() {
+    let source = r#"beam "ship" {
+  depends_on = [{ beam = "deploy", params = { env = "qa" } }]
+}
+beam "deploy" {
+  param "env" { choices = ["staging", "prod"] }
+}
+"#;
+    let err = expand(&parse(source).unwrap(), "ship", &[]).unwrap_err();
+    let diagnostic = find(&err).unwrap();
+    assert_eq!(
+        diagnostic.message,
+        "beam 'ship' binds 'qa' to param 'env' of dependency 'deploy': expected one of \
+         staging, prod"
+    );
+    assert_eq!(diagnostic.span.line_col(source), (2, 17));
+}
+
+#[test]
+fn a_default_must_pass_its_own_constraints() {
+    let source = "variable \"replicas\" {\n  type = \"int\"\n  default = \"many\"\n}\n";
+    let err = parse(source).unwrap_err();
+    let diagnostic = find(&err).unwrap();
+    assert_eq!(
+        diagnostic.message,
+        "invalid default \"many\" for variable 'replicas': expected an int"
+    );
+    assert_eq!(diagnostic.span.line_col(source), (1, 10));
+
+    // Without a default there is nothing to check yet.
+    assert!(parse("variable \"replicas\" { type = \"int\" }").is_ok());
+}
+
+#[test]
+fn an_unknown_type_or_a_bad_pattern_is_a_parse_error() {
+    let source = "beam \"a\" {\n  param \"n\" { type = \"integer\" }\n}\n";
+    let err = parse(source).unwrap_err();
+    let diagnostic = find(&err).unwrap();
+    assert_eq!(diagnostic.message, "unknown type \"integer\"");
+    assert_eq!(diagnostic.help.as_deref(), Some("did you mean 'int'?"));
+    assert_eq!(diagnostic.span.line_col(source), (2, 22));
+
+    let err = parse("variable \"v\" { pattern = \"[a-\" }").unwrap_err();
+    assert!(
+        err.to_string().starts_with("invalid pattern \"[a-\""),
+        "{err}"
+    );
+}
+
+#[test]
+fn the_signature_shows_choices_types_and_hides_secrets() {
+    let beam_file = parse(BEAMFILE).unwrap();
+    assert_eq!(
+        signature(&beam_file.beams[0]),
+        "deploy <env:staging|prod> [tag=v1] [token=***]"
+    );
+    let typed =
+        parse("beam \"scale\" { param \"n\" { type = \"int\"\n default = \"2\" } }").unwrap();
+    assert_eq!(signature(&typed.beams[0]), "scale [n:int=2]");
+}
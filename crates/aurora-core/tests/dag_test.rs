@@ -38,6 +38,24 @@ fn test_cycle_detection() {
     assert!(matches!(result, Err(DagError::Cycle(_))));
 }
 
+#[test]
+fn test_cycle_error_lists_the_path() {
+    let deps = vec![
+        ("root", vec!["x"]),
+        ("x", vec!["y"]),
+        ("y", vec!["z"]),
+        ("z", vec!["x"]),
+    ];
+    let err = BeamGraph::from_deps(deps).err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        "Cycle detected in beam dependency graph: x -> y -> z -> x"
+    );
+
+    let err = BeamGraph::from_deps(vec![("a", vec!["a"])]).err().unwrap();
+    assert!(matches!(err, DagError::Cycle(path) if path == ["a", "a"]));
+}
+
 #[test]
 fn test_cycle_in_unrelated_branch_is_detected() {
     // The cycle is in a branch not reachable from the intended target `root`.
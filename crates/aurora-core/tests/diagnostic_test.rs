@@ -0,0 +1,132 @@
+use aurora_core::dag::validate;
+use aurora_core::diagnostic::{find, Diagnostic};
+use aurora_core::expand::expand;
+use aurora_core::parser::{parse, resolve_variables};
+
+/// The diagnostic `err` carries, rendered against `source`.
+fn rendered(err: anyhow::Error, source: &str) -> String {
+    find(&err)
+        .unwrap_or_else(|| panic!("no diagnostic in: {err:?}"))
+        .render(source, "Beamfile")
+}
+
+#[test]
+fn an_unknown_variable_points_at_its_reference_with_a_suggestion() {
+    let source = r#"variable "region" { default = "eu" }
+beam "deploy" {
+  run { commands = ["./deploy ${var.regoin}"] }
+}
+"#;
+    let mut beam_file = parse(source).unwrap();
+    let err = resolve_variables(&mut beam_file).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "unknown variable 'regoin' referenced in beam 'deploy'; did you mean 'region'?"
+    );
+    assert_eq!(
+        rendered(err, source),
+        r#"error: unknown variable 'regoin' referenced in beam 'deploy'
+ --> Beamfile:3:31
+  |
+3 |   run { commands = ["./deploy ${var.regoin}"] }
+  |                               ^^^^^^^^^^^^^ not declared
+  |
+  = help: did you mean 'region'?
+"#
+    );
+}
+
+#[test]
+fn a_duplicate_beam_points_at_the_second_declaration() {
+    let source = "beam \"a\" {}\n\nbeam \"a\" {}\n";
+    assert_eq!(
+        rendered(parse(source).unwrap_err(), source),
+        r#"error: duplicate beam name 'a'
+ --> Beamfile:3:6
+  |
+3 | beam "a" {}
+  |      ^^^ first declared on line 1
+"#
+    );
+}
+
+#[test]
+fn a_syntax_error_points_where_the_parser_stopped() {
+    let source = "beam \"a\" {\n  inputs = [ }\n}\n";
+    let err = parse(source).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Failed to parse Beamfile: expected a string"
+    );
+    let span = find(&err).unwrap().span;
+    assert_eq!(span.line_col(source), (2, 14));
+}
+
+#[test]
+fn a_cycle_names_its_whole_path() {
+    let source = r#"beam "a" { depends_on = ["b"] }
+beam "b" { depends_on = ["c"] }
+beam "c" { depends_on = ["a"] }
+"#;
+    let err = validate(&parse(source).unwrap()).unwrap_err();
+    assert_eq!(
+        rendered(err, source),
+        r#"error: Cycle detected in beam dependency graph: a -> b -> c -> a
+ --> Beamfile:1:26
+  |
+1 | beam "a" { depends_on = ["b"] }
+  |                          ^^^ the cycle starts here
+"#
+    );
+}
+
+#[test]
+fn an_unknown_dependency_suggests_a_declared_beam() {
+    let source = "beam \"deploy\" { depends_on = [\"biuld\"] }\nbeam \"build\" {}\n";
+    let err = validate(&parse(source).unwrap()).unwrap_err();
+    let diagnostic = find(&err).unwrap();
+    assert_eq!(
+        diagnostic.message,
+        "Unknown beam referenced as dependency: 'biuld'"
+    );
+    assert_eq!(diagnostic.help.as_deref(), Some("did you mean 'build'?"));
+    assert_eq!(diagnostic.span.line_col(source), (1, 31));
+}
+
+#[test]
+fn binding_errors_point_at_the_dependency_entry() {
+    let source = r#"beam "deploy" {
+  depends_on = [{ beam = "push", params = { tags = "1" } }]
+}
+beam "push" { param "tag" {} }
+"#;
+    let err = expand(&parse(source).unwrap(), "deploy", &[]).unwrap_err();
+    let diagnostic = find(&err).unwrap();
+    assert_eq!(diagnostic.span.line_col(source), (2, 17));
+    assert_eq!(diagnostic.help.as_deref(), Some("did you mean 'tag'?"));
+}
+
+#[test]
+fn an_out_of_closure_beam_keeps_its_span_under_the_preface() {
+    let source = r#"aurora { default = "ok" }
+beam "ok" {}
+beam "other" {
+  run { commands = ["echo ${param.nope}"] }
+}
+"#;
+    let err = expand(&parse(source).unwrap(), "ok", &[]).unwrap_err();
+    let diagnostic: &Diagnostic = find(&err).unwrap();
+    assert!(diagnostic
+        .message
+        .starts_with("beam 'other' has an invalid configuration"));
+    assert_eq!(diagnostic.locate(source).line_col(source), (4, 27));
+}
+
+#[test]
+fn a_synthetic_span_renders_the_message_alone() {
+    let diagnostic = Diagnostic::new("no place", Default::default()).with_help("try again");
+    assert_eq!(
+        diagnostic.render("", "Beamfile"),
+        "error: no place\n  = help: try again\n"
+    );
+}
@@ -0,0 +1,85 @@
+use aurora_core::dotenv::parse;
+use std::collections::HashMap;
+
+fn entries(content: &str) -> Vec<(String, String)> {
+    let visible = HashMap::from([("HOME".to_string(), "/home/me".to_string())]);
+    parse(content, &visible).unwrap()
+}
+
+fn pairs(entries: &[(String, String)]) -> Vec<(&str, &str)> {
+    entries
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect()
+}
+
+#[test]
+fn lines_comments_and_export() {
+    let parsed = entries(
+        "# settings\n\nexport PORT=8080\nNAME = demo  # inline comment\nURL=http://x/#anchor\r\nEMPTY=\n",
+    );
+    assert_eq!(
+        pairs(&parsed),
+        [
+            ("PORT", "8080"),
+            ("NAME", "demo"),
+            ("URL", "http://x/#anchor"),
+            ("EMPTY", ""),
+        ]
+    );
+}
+
+#[test]
+fn quoting_follows_the_usual_rules() {
+    let parsed = entries(concat!(
+        "SINGLE='$HOME \\n stays'\n",
+        "DOUBLE=\"line\\nnext \\\"q\\\" \\$HOME\" # comment\n",
+        "MULTI=\"a\nb\"\n",
+    ));
+    assert_eq!(
+        pairs(&parsed),
+        [
+            ("SINGLE", "$HOME \\n stays"),
+            ("DOUBLE", "line\nnext \"q\" $HOME"),
+            ("MULTI", "a\nb"),
+        ]
+    );
+}
+
+#[test]
+fn expansion_sees_earlier_entries_then_the_environment() {
+    let parsed = entries(concat!(
+        "DATA=$HOME/data\n",
+        "CACHE=\"${DATA}/cache\"\n",
+        "LEVEL=${LOG_LEVEL:-info}\n",
+        "MISSING=[$NOPE]\n",
+        "PRICE=\\$5\n",
+    ));
+    assert_eq!(
+        pairs(&parsed),
+        [
+            ("DATA", "/home/me/data"),
+            ("CACHE", "/home/me/data/cache"),
+            ("LEVEL", "info"),
+            ("MISSING", "[]"),
+            ("PRICE", "$5"),
+        ]
+    );
+}
+
+#[test]
+fn a_malformed_line_is_reported_with_its_number() {
+    let visible = HashMap::new();
+    assert_eq!(
+        parse("A=1\nnot a pair\n", &visible).unwrap_err(),
+        "line 2: expected `=` after not"
+    );
+    assert_eq!(
+        parse("A=1\nB=\"open\n", &visible).unwrap_err(),
+        "line 2: unterminated quoted value"
+    );
+    assert_eq!(
+        parse("A='x' y\n", &visible).unwrap_err(),
+        "line 1: unexpected text after the value of A"
+    );
+}
@@ -1,4 +1,4 @@
-use aurora_core::ast::{EnvValue, EnvVar, Environment};
+use aurora_core::ast::{EnvValue, EnvVar, Environment, Span};
 use aurora_core::env::{base_env, evaluate_overlay};
 use std::path::Path;
 
@@ -8,13 +8,16 @@ fn overlay_evaluates_sequentially_with_base_visible() {
         vars: vec![
             EnvVar {
                 name: "A".to_string(),
+                span: Span::default(),
                 value: EnvValue::Literal("one".to_string()),
             },
             EnvVar {
                 name: "B".to_string(),
+                span: Span::default(),
                 value: EnvValue::Shell("echo \"$A-two\"".to_string()),
             },
         ],
+        loads: vec![],
     };
     let overlay = evaluate_overlay(&block, &base_env(), Path::new(".")).unwrap();
     assert_eq!(overlay.get("A").map(String::as_str), Some("one"));
@@ -26,8 +29,10 @@ fn overlay_shell_failure_is_a_hard_error() {
     let block = Environment {
         vars: vec![EnvVar {
             name: "BAD".to_string(),
+            span: Span::default(),
             value: EnvValue::Shell("exit 3".to_string()),
         }],
+        loads: vec![],
     };
     let err = evaluate_overlay(&block, &base_env(), Path::new("."))
         .unwrap_err()
@@ -42,8 +47,10 @@ fn overlay_sees_global_declared_values() {
     let block = Environment {
         vars: vec![EnvVar {
             name: "FROM_GLOBAL".to_string(),
+            span: Span::default(),
             value: EnvValue::Shell("echo \"$GLOBAL\"".to_string()),
         }],
+        loads: vec![],
     };
     let overlay = evaluate_overlay(&block, &base, Path::new(".")).unwrap();
     assert_eq!(overlay.get("FROM_GLOBAL").map(String::as_str), Some("g"));
@@ -1,5 +1,5 @@
-use aurora_core::ast::{EnvValue, EnvVar, Environment};
-use aurora_core::env::{base_env, evaluate};
+use aurora_core::ast::{EnvValue, EnvVar, Environment, Span};
+use aurora_core::env::{ambient_env, base_env, evaluate, evaluate_declared, inherited};
 use std::path::Path;
 
 /// The whole point of the allowlist is that an untrusted Beamfile never sees
@@ -54,7 +54,10 @@ fn base_env_survives_non_utf8_ambient_var() {
 #[test]
 fn evaluate_empty_block_applies_allowlist() {
     std::env::set_var("AURORA_TEST_SECRET2", "leak-me");
-    let block = Environment { vars: vec![] };
+    let block = Environment {
+        vars: vec![],
+        loads: vec![],
+    };
 
     let env = evaluate(&block, Path::new(".")).unwrap();
 
@@ -69,8 +72,10 @@ fn evaluate_fails_when_shell_command_exits_non_zero() {
     let block = Environment {
         vars: vec![EnvVar {
             name: "BROKEN".to_string(),
+            span: Span::default(),
             value: EnvValue::Shell("exit 3".to_string()),
         }],
+        loads: vec![],
     };
 
     let result = evaluate(&block, Path::new("."));
@@ -89,13 +94,16 @@ fn evaluate_chains_shell_variables_sequentially() {
         vars: vec![
             EnvVar {
                 name: "A".to_string(),
+                span: Span::default(),
                 value: EnvValue::Shell("echo one".to_string()),
             },
             EnvVar {
                 name: "B".to_string(),
+                span: Span::default(),
                 value: EnvValue::Shell("echo \"$A-two\"".to_string()),
             },
         ],
+        loads: vec![],
     };
 
     let env = evaluate(&block, Path::new(".")).unwrap();
@@ -103,3 +111,74 @@ fn evaluate_chains_shell_variables_sequentially() {
     assert_eq!(env.get("A").map(String::as_str), Some("one"));
     assert_eq!(env.get("B").map(String::as_str), Some("one-two"));
 }
+
+/// `load = [...]` files are read where they are declared among the
+/// variables, and their entries count as declared (they reach the cache key).
+#[test]
+fn evaluate_loads_dotenv_files_in_declaration_order() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join(".env"),
+        "STAGE=dev\nURL=http://$HOST/$STAGE\n",
+    )
+    .unwrap();
+    let beam_file = aurora_core::parser::parse(
+        r#"environment {
+  HOST = "localhost"
+  load = [".env", { path = ".env.local", optional = true }]
+  LABEL = shell("echo $STAGE")
+}"#,
+    )
+    .unwrap();
+    let block = beam_file.environment.unwrap();
+
+    let evaluated = evaluate_declared(Some(&block), base_env(), dir.path()).unwrap();
+
+    assert_eq!(evaluated.env["URL"], "http://localhost/dev");
+    assert_eq!(evaluated.env["LABEL"], "dev");
+    assert_eq!(
+        evaluated.declared.keys().collect::<Vec<_>>(),
+        ["HOST", "LABEL", "STAGE", "URL"]
+    );
+    assert!(!evaluated.declared.contains_key("PATH"));
+
+    // Not optional: a missing file is an error.
+    let beam_file =
+        aurora_core::parser::parse("environment {\n  load = [\".env.local\"]\n}").unwrap();
+    let err = evaluate(&beam_file.environment.unwrap(), dir.path()).unwrap_err();
+    assert!(
+        err.to_string()
+            .starts_with("environment file \".env.local\" not found; mark it"),
+        "{err}"
+    );
+}
+
+/// `inherit_env` forwards exactly the ambient variables its patterns name,
+/// wildcards included, and nothing else past the allowlist.
+#[test]
+fn inherited_matches_names_and_wildcards() {
+    std::env::set_var("AURORA_TEST_FWD_ONE", "1");
+    std::env::set_var("AURORA_TEST_FWD_TWO", "2");
+    std::env::set_var("AURORA_TEST_KEEP_OUT", "secret");
+
+    let forwarded = inherited(&[
+        "AURORA_TEST_FWD_*".to_string(),
+        "AURORA_TEST_?EEP".to_string(),
+    ]);
+    let names: Vec<&str> = forwarded.keys().map(String::as_str).collect();
+    assert_eq!(names, ["AURORA_TEST_FWD_ONE", "AURORA_TEST_FWD_TWO"]);
+
+    let ambient = ambient_env(&["AURORA_TEST_KEEP_OUT".to_string()]);
+    assert_eq!(ambient["AURORA_TEST_KEEP_OUT"], "secret");
+    assert!(!ambient.contains_key("AURORA_TEST_FWD_ONE"));
+    assert!(ambient.contains_key("PATH"));
+    assert!(inherited(&[]).is_empty());
+
+    for name in [
+        "AURORA_TEST_FWD_ONE",
+        "AURORA_TEST_FWD_TWO",
+        "AURORA_TEST_KEEP_OUT",
+    ] {
+        std::env::remove_var(name);
+    }
+}
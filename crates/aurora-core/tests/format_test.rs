@@ -0,0 +1,390 @@
+use aurora_core::parser::format::format;
+use aurora_core::parser::parse;
+
+/// Formats `source`, checking that the result is stable and means the same.
+fn fmt(source: &str) -> String {
+    let formatted = format(source).unwrap();
+    assert_eq!(format(&formatted).unwrap(), formatted, "not idempotent");
+    assert_eq!(
+        meaning(source),
+        meaning(&formatted),
+        "the formatted Beamfile means something else"
+    );
+    formatted
+}
+
+/// The parsed Beamfile, without the spans that move with the layout.
+fn meaning(source: &str) -> String {
+    let mut debug = format!("{:?}", parse(source).unwrap());
+    while let Some(start) = debug.find("span: Span {") {
+        let end = start + debug[start..].find('}').unwrap() + 1;
+        debug.replace_range(start..end, "");
+    }
+    debug
+}
+
+#[test]
+fn fields_are_reindented_and_put_in_the_canonical_order() {
+    let source = r#"
+aurora {default="check"
+    version = "1" }
+beam "test" {
+    run { commands = ["cargo test"] }
+        inputs=["src/**/*.rs"]
+  depends_on = [ "fmt" ]
+description = "Run the tests"
+}
+beam "fmt" { run { commands = ["cargo fmt"] } }
+"#;
+    assert_eq!(
+        fmt(source),
+        r#"aurora {
+  version = "1"
+  default = "check"
+}
+
+beam "test" {
+  description = "Run the tests"
+  depends_on = ["fmt"]
+  inputs = ["src/**/*.rs"]
+  run { commands = ["cargo test"] }
+}
+
+beam "fmt" {
+  run { commands = ["cargo fmt"] }
+}
+"#
+    );
+}
+
+#[test]
+fn comments_are_kept_and_move_with_their_field() {
+    let source = r#"# Build file of the project.
+
+aurora { default = "build" }  # the CI target
+
+beam "build" {
+  # Cached on the sources.
+  inputs = ["src/**"]
+  description = "Build" # shown in --list
+  run {
+    commands = [
+      "make", # first
+      # then
+      "make install",
+    ]
+  }
+  # the end
+}
+# trailing notes
+"#;
+    assert_eq!(
+        fmt(source),
+        r#"# Build file of the project.
+
+aurora {
+  default = "build"
+}  # the CI target
+
+beam "build" {
+  description = "Build"  # shown in --list
+
+  # Cached on the sources.
+  inputs = ["src/**"]
+
+  run {
+    commands = [
+      "make",  # first
+      # then
+      "make install",
+    ]
+  }
+
+  # the end
+}
+
+# trailing notes
+"#
+    );
+}
+
+#[test]
+fn environment_blocks_align_their_values() {
+    let source = r#"
+environment {
+  A = "1"
+  LONG_NAME = shell("git rev-parse HEAD")
+  MID = "x"
+}
+"#;
+    assert_eq!(
+        fmt(source),
+        r#"environment {
+  A         = "1"
+  LONG_NAME = shell("git rev-parse HEAD")
+  MID       = "x"
+}
+"#
+    );
+}
+
+#[test]
+fn lists_wrap_past_the_line_width() {
+    let source = r#"
+beam "ci" {
+  depends_on = ["format-check", "clippy-all-targets", "unit-tests", "integration-tests", "docs-build"]
+  run { commands = ["a", "b"] }
+}
+"#;
+    assert_eq!(
+        fmt(source),
+        r#"beam "ci" {
+  depends_on = [
+    "format-check",
+    "clippy-all-targets",
+    "unit-tests",
+    "integration-tests",
+    "docs-build",
+  ]
+
+  run { commands = ["a", "b"] }
+}
+"#
+    );
+
+    // A short list written over several lines goes back on one.
+    let source = "beam \"a\" {\n  inputs = [\n    \"x\",\n  ]\n}\n";
+    assert_eq!(fmt(source), "beam \"a\" {\n  inputs = [\"x\"]\n}\n");
+}
+
+#[test]
+fn params_dependencies_conditions_and_executors() {
+    let source = r#"
+variable "image" { default = "rust:1" }
+variable "region" {
+  description = "Where to deploy"
+  default = "eu"
+}
+beam "deploy" {
+  run {
+    executor "docker" { image = var.image }
+    commands = ["./deploy ${param.version} ${param.env}"]
+  }
+  condition { all = [{ shell = "test -f dist/app" } { shell = "true" }] }
+  param "version" { description = "Version to deploy" }
+  param "env"     { default = "staging" }
+  depends_on = [{ beam = "build", params = { version = "${param.version}" } }, "lint"]
+  kill_timeout = "30s"
+  allow_failure = true
+  environment {
+    TARGET = "${param.env}"
+  }
+}
+beam "build" {
+  param "version" {}
+}
+beam "lint" {}
+"#;
+    assert_eq!(
+        fmt(source),
+        r#"variable "image" { default = "rust:1" }
+
+variable "region" {
+  default = "eu"
+  description = "Where to deploy"
+}
+
+beam "deploy" {
+  param "version" { description = "Version to deploy" }
+  param "env" { default = "staging" }
+  depends_on = [{ beam = "build", params = { version = "${param.version}" } }, "lint"]
+  allow_failure = true
+  kill_timeout = "30s"
+
+  condition {
+    all = [{ shell = "test -f dist/app" }, { shell = "true" }]
+  }
+
+  environment {
+    TARGET = "${param.env}"
+  }
+
+  run {
+    commands = ["./deploy ${param.version} ${param.env}"]
+    executor "docker" { image = var.image }
+  }
+}
+
+beam "build" {
+  param "version" {}
+}
+
+beam "lint" {}
+"#
+    );
+}
+
+#[test]
+fn a_string_keeps_its_escapes_and_a_hash_inside_it_is_no_comment() {
+    let source = "beam \"a\" { run { commands = [\"echo \\\"#1\\\" \\\\ done\"] } }\n";
+    assert_eq!(
+        fmt(source),
+        "beam \"a\" {\n  run { commands = [\"echo \\\"#1\\\" \\\\ done\"] }\n}\n"
+    );
+}
+
+#[test]
+fn the_repository_beamfile_is_formatted() {
+    let source = include_str!("../../../Beamfile");
+    assert_eq!(fmt(source), source);
+}
+
+#[test]
+fn a_syntax_error_is_reported() {
+    assert!(format("beam \"a\" { inputs = [ }").is_err());
+    assert_eq!(format("").unwrap(), "");
+}
+
+#[test]
+fn constraints_follow_the_default_and_the_description() {
+    let source = r#"
+variable "replicas" {
+  sensitive = true
+  pattern = "[0-9]+"
+  type = "int"
+  default = "2"
+}
+beam "deploy" {
+  param "env" { choices = ["staging", "prod"]
+    description = "Where" }
+}
+"#;
+    assert_eq!(
+        fmt(source),
+        r#"variable "replicas" {
+  default = "2"
+  type = "int"
+  pattern = "[0-9]+"
+  sensitive = true
+}
+
+beam "deploy" {
+  param "env" {
+    description = "Where"
+    choices = ["staging", "prod"]
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn variable_sources_keep_their_order_and_a_shell_default() {
+    let source = r#"variable "version" { from_env="VERSION"
+  default=shell("git describe --tags")
+  from_file = "VERSION" }
+"#;
+    assert_eq!(
+        fmt(source),
+        r#"variable "version" {
+  from_env = "VERSION"
+  default = shell("git describe --tags")
+  from_file = "VERSION"
+}
+"#
+    );
+}
+
+#[test]
+fn environment_loads_keep_their_place_among_the_variables() {
+    let source = r#"environment {
+  HOST="localhost"
+  load=[".env",{path=".env.local",optional=true}]
+  LONG_NAME = "x"
+}
+"#;
+    assert_eq!(
+        fmt(source),
+        r#"environment {
+  HOST      = "localhost"
+  load = [".env", { path = ".env.local", optional = true }]
+  LONG_NAME = "x"
+}
+"#
+    );
+}
+
+#[test]
+fn profiles_put_their_variables_first_and_align_them() {
+    let source = r#"variable "region" { default = "us" }
+variable "tier" { default = "small" }
+profile "ci" { environment { MODE="ci" }
+  max_parallelism=2
+  variables={region="eu",tier="large"} }
+profile "dev" { variables = { region = "local" } }
+"#;
+    assert_eq!(
+        fmt(source),
+        r#"variable "region" { default = "us" }
+variable "tier" { default = "small" }
+
+profile "ci" {
+  variables = {
+    region = "eu"
+    tier   = "large"
+  }
+
+  max_parallelism = 2
+
+  environment {
+    MODE = "ci"
+  }
+}
+
+profile "dev" {
+  variables = { region = "local" }
+}
+"#
+    );
+}
+
+#[test]
+fn templates_put_extends_first_and_keep_appends() {
+    let source = r#"template "ci" { inputs=["src/**"]
+  run { commands = ["make"] } }
+beam "build" { inputs+=["build.rs"]
+  description="Build"
+  extends="ci" }
+"#;
+    assert_eq!(
+        fmt(source),
+        r#"template "ci" {
+  inputs = ["src/**"]
+  run { commands = ["make"] }
+}
+
+beam "build" {
+  extends = "ci"
+  description = "Build"
+  inputs += ["build.rs"]
+}
+"#
+    );
+}
+
+#[test]
+fn tags_follow_the_description() {
+    let source = r#"beam "test" { depends_on = ["fmt"]
+  tags=["ci","fast"]
+  description="Run the tests" }
+"#;
+    assert_eq!(
+        fmt(source),
+        r#"beam "test" {
+  description = "Run the tests"
+  tags = ["ci", "fast"]
+  depends_on = ["fmt"]
+}
+"#
+    );
+}
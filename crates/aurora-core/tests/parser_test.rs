@@ -36,6 +36,17 @@ beam "qa" {
     assert_eq!(bf.beams[0].dependency_names(), vec!["lint", "test"]);
 }
 
+#[test]
+fn test_parse_log_retention() {
+    let input = r#"
+aurora {
+  log_retention = 5
+}
+"#;
+    let bf = parse(input).unwrap();
+    assert_eq!(bf.config.unwrap().log_retention, Some(5));
+}
+
 #[test]
 fn test_parse_environment_block() {
     let input = r#"
@@ -388,6 +399,74 @@ beam "build" {
     );
 }
 
+#[test]
+fn test_parse_tty() {
+    let input = r#"
+beam "build" {
+  tty = true
+  run { commands = ["cargo build"] }
+}
+beam "test" {
+  run { commands = ["cargo test"] }
+}
+"#;
+    let bf = parse(input).unwrap();
+    let build = bf.beams.iter().find(|b| b.name == "build").unwrap();
+    let test = bf.beams.iter().find(|b| b.name == "test").unwrap();
+    assert!(build.tty, "tty = true must be read");
+    assert!(!test.tty, "beams run piped by default");
+}
+
+#[test]
+fn test_parse_kill_timeout() {
+    let input = r#"
+beam "apply" {
+  kill_timeout = "90s"
+  run { commands = ["terraform apply"] }
+}
+beam "quick" {
+  kill_timeout = "500ms"
+  run { commands = ["true"] }
+}
+beam "test" {
+  run { commands = ["cargo test"] }
+}
+"#;
+    let bf = parse(input).unwrap();
+    let timeout_of = |name: &str| {
+        bf.beams
+            .iter()
+            .find(|b| b.name == name)
+            .unwrap()
+            .kill_timeout
+    };
+    assert_eq!(
+        timeout_of("apply"),
+        Some(std::time::Duration::from_secs(90))
+    );
+    assert_eq!(
+        timeout_of("quick"),
+        Some(std::time::Duration::from_millis(500))
+    );
+    assert_eq!(timeout_of("test"), None, "the scheduler default applies");
+}
+
+#[test]
+fn test_kill_timeout_requires_a_unit() {
+    for (raw, expected) in [
+        ("30", "missing unit"),
+        ("30h", "unknown unit \"h\""),
+        ("soon", "expected a number"),
+    ] {
+        let input = format!(
+            "beam \"b\" {{\n  kill_timeout = \"{raw}\"\n  run {{ commands = [\"true\"] }}\n}}\n"
+        );
+        let err = format!("{:#}", parse(&input).unwrap_err());
+        assert!(err.contains("invalid kill_timeout"), "got: {err}");
+        assert!(err.contains(expected), "{raw}: got: {err}");
+    }
+}
+
 #[test]
 fn interpolates_var_in_command() {
     let input = r#"
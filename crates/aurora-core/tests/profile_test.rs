@@ -0,0 +1,81 @@
+use aurora_core::diagnostic::find;
+use aurora_core::parser::parse;
+
+const BEAMFILE: &str = r#"
+variable "region" { default = "us" }
+
+variable "tier" {
+  default = "small"
+  choices = ["small", "large"]
+}
+
+profile "ci" {
+  variables       = { region = "eu", tier = "large" }
+  max_parallelism = 2
+  environment {
+    load = [{ path = ".env.ci", optional = true }]
+    MODE = "ci"
+  }
+}
+
+profile "dev" {}
+"#;
+
+#[test]
+fn profiles_are_parsed_in_order() {
+    let beam_file = parse(BEAMFILE).unwrap();
+    let names: Vec<&str> = beam_file.profiles.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["ci", "dev"]);
+
+    let ci = &beam_file.profiles[0];
+    let bindings: Vec<(&str, &str)> = ci
+        .variables
+        .iter()
+        .map(|v| (v.name.as_str(), v.value.as_str()))
+        .collect();
+    assert_eq!(bindings, [("region", "eu"), ("tier", "large")]);
+    assert_eq!(ci.max_parallelism, Some(2));
+    let environment = ci.environment.as_ref().unwrap();
+    assert_eq!(environment.vars[0].name, "MODE");
+    assert_eq!(environment.loads[0].after, 0);
+
+    let dev = &beam_file.profiles[1];
+    assert!(dev.variables.is_empty());
+    assert!(dev.environment.is_none());
+    assert_eq!(dev.max_parallelism, None);
+}
+
+#[test]
+fn a_profile_binds_only_declared_variables() {
+    let source = "variable \"region\" { default = \"us\" }\nprofile \"ci\" { variables = { regoin = \"eu\" } }\n";
+    let err = parse(source).unwrap_err();
+    let diagnostic = find(&err).unwrap();
+    assert_eq!(
+        diagnostic.message,
+        "unknown variable 'regoin' in profile 'ci'"
+    );
+    assert_eq!(diagnostic.help.as_deref(), Some("did you mean 'region'?"));
+    assert_eq!(diagnostic.span.line_col(source), (2, 30));
+}
+
+#[test]
+fn a_profile_value_must_fit_the_constraints() {
+    let source = BEAMFILE.replace("tier = \"large\"", "tier = \"huge\"");
+    let err = parse(&source).unwrap_err();
+    assert_eq!(
+        find(&err).unwrap().message,
+        "invalid value \"huge\" for variable 'tier' in profile 'ci': expected one of small, large"
+    );
+}
+
+#[test]
+fn a_profile_name_is_declared_once() {
+    let source = "profile \"ci\" {}\nprofile \"ci\" {}\n";
+    let err = parse(source).unwrap_err();
+    let diagnostic = find(&err).unwrap();
+    assert_eq!(diagnostic.message, "duplicate profile name 'ci'");
+    assert_eq!(
+        diagnostic.label.as_deref(),
+        Some("first declared on line 1")
+    );
+}
@@ -1,5 +1,5 @@
 use aurora_core::ast::{Beam, Dependency, Run};
-use aurora_core::scheduler::{BeamStatus, Scheduler, SchedulerEvent};
+use aurora_core::scheduler::{BeamStatus, CancelRequest, Scheduler, SchedulerEvent};
 use aurora_executor_api::Executor;
 use aurora_executor_local::LocalExecutor;
 use std::collections::HashMap;
@@ -43,7 +43,7 @@ async fn test_cancel_running_beam_cancels_it_and_dependents() {
     ];
 
     let (tx, mut rx) = mpsc::channel(256);
-    let (cancel_tx, cancel_rx) = mpsc::unbounded_channel::<String>();
+    let (cancel_tx, cancel_rx) = mpsc::unbounded_channel::<CancelRequest>();
 
     let handle = tokio::spawn(async move {
         Scheduler::new(
@@ -66,7 +66,9 @@ async fn test_cancel_running_beam_cancels_it_and_dependents() {
         if let SchedulerEvent::BeamStarted { name } = &evt {
             if name == "slow" && !slow_started {
                 slow_started = true;
-                cancel_tx.send("slow".to_string()).unwrap();
+                cancel_tx
+                    .send(CancelRequest::Beam("slow".to_string()))
+                    .unwrap();
             }
         }
         let done = matches!(evt, SchedulerEvent::AllDone { .. });
@@ -118,7 +120,7 @@ async fn test_cancel_beam_blocked_in_skip_if_gate() {
     gated.skip_if = Some("sleep 30".to_string());
 
     let (tx, mut rx) = mpsc::channel(256);
-    let (cancel_tx, cancel_rx) = mpsc::unbounded_channel::<String>();
+    let (cancel_tx, cancel_rx) = mpsc::unbounded_channel::<CancelRequest>();
 
     let handle = tokio::spawn(async move {
         Scheduler::new(
@@ -142,7 +144,9 @@ async fn test_cancel_beam_blocked_in_skip_if_gate() {
         if let SchedulerEvent::BeamStarted { name } = &evt {
             if name == "gated" && !started {
                 started = true;
-                cancel_tx.send("gated".to_string()).unwrap();
+                cancel_tx
+                    .send(CancelRequest::Beam("gated".to_string()))
+                    .unwrap();
             }
         }
         let done = matches!(evt, SchedulerEvent::AllDone { .. });
@@ -184,7 +188,7 @@ async fn test_cancel_allow_failure_beam_does_not_cancel_dependents() {
     let after = make_beam("after", vec!["slow"], vec!["echo after"]);
 
     let (tx, mut rx) = mpsc::channel(256);
-    let (cancel_tx, cancel_rx) = mpsc::unbounded_channel::<String>();
+    let (cancel_tx, cancel_rx) = mpsc::unbounded_channel::<CancelRequest>();
 
     let handle = tokio::spawn(async move {
         Scheduler::new(
@@ -206,7 +210,9 @@ async fn test_cancel_allow_failure_beam_does_not_cancel_dependents() {
         if let SchedulerEvent::BeamStarted { name } = &evt {
             if name == "slow" && !slow_started {
                 slow_started = true;
-                cancel_tx.send("slow".to_string()).unwrap();
+                cancel_tx
+                    .send(CancelRequest::Beam("slow".to_string()))
+                    .unwrap();
             }
         }
         let done = matches!(evt, SchedulerEvent::AllDone { .. });
@@ -242,3 +248,158 @@ async fn test_cancel_allow_failure_beam_does_not_cancel_dependents() {
         "cancelling an allow_failure beam must not fail the run"
     );
 }
+
+// A cancelled beam is stopped, not killed outright: it is reported as
+// stopping, its command gets SIGTERM and the time to run its handler, and
+// what the handler prints still reaches the events before the beam settles
+// as Cancelled.
+#[cfg(unix)]
+#[tokio::test]
+async fn test_cancel_stops_the_command_gracefully() {
+    let mut slow = make_beam(
+        "slow",
+        vec![],
+        vec![
+            "trap 'echo released the lock; exit 0' TERM",
+            "echo ready",
+            "sleep 30 & wait",
+        ],
+    );
+    slow.kill_timeout = Some(Duration::from_secs(5));
+
+    let (tx, mut rx) = mpsc::channel(256);
+    let (cancel_tx, cancel_rx) = mpsc::unbounded_channel::<CancelRequest>();
+
+    let handle = tokio::spawn(async move {
+        Scheduler::new(
+            vec![slow],
+            local_executors(),
+            tx,
+            None,
+            std::path::PathBuf::from("/tmp"),
+            HashMap::new(),
+        )
+        .run_cancellable("slow", &[], cancel_rx)
+        .await
+        .unwrap()
+    });
+
+    // Cancel once the trap is installed, i.e. once `ready` is printed.
+    let mut events = vec![];
+    while let Some(evt) = rx.recv().await {
+        if let SchedulerEvent::BeamOutput { line, .. } = &evt {
+            if line == "ready" {
+                cancel_tx
+                    .send(CancelRequest::Beam("slow".to_string()))
+                    .unwrap();
+            }
+        }
+        let done = matches!(evt, SchedulerEvent::AllDone { .. });
+        events.push(evt);
+        if done {
+            break;
+        }
+    }
+
+    let overall = tokio::time::timeout(Duration::from_secs(5), handle)
+        .await
+        .expect("the scheduler did not finish after cancellation")
+        .unwrap();
+    assert!(!overall, "the run must fail after a cancellation");
+
+    let position = |pred: &dyn Fn(&SchedulerEvent) -> bool| events.iter().position(pred);
+    let stopping =
+        position(&|e| matches!(e, SchedulerEvent::BeamStopping { name } if name == "slow"))
+            .expect("the beam must be reported as stopping");
+    let cleanup = position(
+        &|e| matches!(e, SchedulerEvent::BeamOutput { line, .. } if line == "released the lock"),
+    )
+    .expect("the SIGTERM handler must run and its output be forwarded");
+    let completed = position(&|e| {
+        matches!(
+            e,
+            SchedulerEvent::BeamCompleted { name, status: BeamStatus::Cancelled } if name == "slow"
+        )
+    })
+    .expect("slow must be cancelled");
+    assert!(
+        stopping < cleanup,
+        "stopping is reported before the handler's output"
+    );
+    assert!(
+        cleanup < completed,
+        "the handler's output precedes the completion"
+    );
+}
+
+// Cancelling the whole run stops every running beam and starts nothing more,
+// even behind an `allow_failure` beam (whose cancellation alone would unblock
+// its dependent). Every beam left behind is reported Cancelled, so a view
+// kept open on the run shows nothing stuck Pending.
+#[tokio::test]
+async fn test_cancel_run_tears_the_whole_run_down() {
+    let mut tolerant = make_beam("tolerant", vec![], vec!["sleep 30"]);
+    tolerant.allow_failure = true;
+    let beams = vec![
+        tolerant,
+        make_beam("after", vec!["tolerant"], vec!["echo after"]),
+        make_beam("other", vec![], vec!["sleep 30"]),
+        make_beam("root", vec!["after", "other"], vec!["echo root"]),
+    ];
+
+    let (tx, mut rx) = mpsc::channel(256);
+    let (cancel_tx, cancel_rx) = mpsc::unbounded_channel::<CancelRequest>();
+
+    let handle = tokio::spawn(async move {
+        Scheduler::new(
+            beams,
+            local_executors(),
+            tx,
+            None,
+            std::path::PathBuf::from("/tmp"),
+            HashMap::new(),
+        )
+        .run_cancellable("root", &[], cancel_rx)
+        .await
+        .unwrap()
+    });
+
+    let mut started = 0;
+    let mut events = vec![];
+    while let Some(evt) = rx.recv().await {
+        if let SchedulerEvent::BeamStarted { .. } = &evt {
+            started += 1;
+            if started == 2 {
+                cancel_tx.send(CancelRequest::Run).unwrap();
+            }
+        }
+        let done = matches!(evt, SchedulerEvent::AllDone { .. });
+        events.push(evt);
+        if done {
+            break;
+        }
+    }
+
+    let overall = tokio::time::timeout(Duration::from_secs(5), handle)
+        .await
+        .expect("the scheduler did not finish after cancelling the run")
+        .unwrap();
+    assert!(!overall, "a cancelled run fails");
+
+    for beam in ["tolerant", "after", "other", "root"] {
+        let status = events.iter().rev().find_map(|e| match e {
+            SchedulerEvent::BeamCompleted { name, status } if name == beam => Some(status.clone()),
+            _ => None,
+        });
+        assert!(
+            matches!(status, Some(BeamStatus::Cancelled)),
+            "{beam} must be cancelled, got {status:?}"
+        );
+    }
+    assert!(
+        !events
+            .iter()
+            .any(|e| matches!(e, SchedulerEvent::BeamStarted { name } if name == "after")),
+        "no beam may start once the run is cancelled"
+    );
+}
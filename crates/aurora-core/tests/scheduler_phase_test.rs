@@ -0,0 +1,130 @@
+use aurora_core::ast::{Beam, Run};
+use aurora_core::scheduler::{BeamPhase, Scheduler, SchedulerEvent};
+use aurora_executor_api::Executor;
+use aurora_executor_local::LocalExecutor;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+fn local_executors() -> HashMap<String, Arc<dyn Executor>> {
+    let mut m: HashMap<String, Arc<dyn Executor>> = HashMap::new();
+    m.insert("local".into(), Arc::new(LocalExecutor::new()));
+    m
+}
+
+fn beam(commands: &[&str]) -> Beam {
+    Beam {
+        name: "build".to_string(),
+        run: Some(Run {
+            commands: commands.iter().map(|c| c.to_string()).collect(),
+            executor: None,
+        }),
+        ..Beam::default()
+    }
+}
+
+async fn run_events(beam: Beam, working_dir: &Path) -> Vec<SchedulerEvent> {
+    let name = beam.name.clone();
+    let (tx, mut rx) = mpsc::channel(64);
+    Scheduler::new(
+        vec![beam],
+        local_executors(),
+        tx,
+        None,
+        working_dir.to_path_buf(),
+        HashMap::new(),
+    )
+    .run(&name, &[])
+    .await
+    .unwrap();
+    let mut events = vec![];
+    while let Ok(e) = rx.try_recv() {
+        events.push(e);
+    }
+    events
+}
+
+/// The phases reported, in order, checking that each one is well-formed and
+/// starts no earlier than the previous one ended.
+fn phases(events: &[SchedulerEvent]) -> Vec<BeamPhase> {
+    let mut previous_end = None;
+    events
+        .iter()
+        .filter_map(|e| match e {
+            SchedulerEvent::BeamPhase {
+                phase, start, end, ..
+            } => {
+                assert!(start <= end, "{phase:?} ends before it starts");
+                if let Some(previous_end) = previous_end {
+                    assert!(
+                        *start >= previous_end,
+                        "{phase:?} overlaps the previous phase"
+                    );
+                }
+                previous_end = Some(*end);
+                Some(*phase)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn a_plain_beam_reports_only_its_execution() {
+    let dir = tempfile::tempdir().unwrap();
+    let events = run_events(beam(&["true"]), dir.path()).await;
+    assert_eq!(phases(&events), vec![BeamPhase::Execute]);
+
+    // Between the beam's start and its completion.
+    let position = |pred: fn(&SchedulerEvent) -> bool| events.iter().position(pred).unwrap();
+    let started = position(|e| matches!(e, SchedulerEvent::BeamStarted { .. }));
+    let phase = position(|e| matches!(e, SchedulerEvent::BeamPhase { .. }));
+    let completed = position(|e| matches!(e, SchedulerEvent::BeamCompleted { .. }));
+    assert!(started < phase && phase < completed);
+}
+
+#[tokio::test]
+async fn gate_hashing_lookup_and_execution_are_reported_in_order() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("in.txt"), "v1").unwrap();
+    let cached = || Beam {
+        skip_if: Some("false".to_string()),
+        inputs: vec!["in.txt".to_string()],
+        outputs: vec!["out.txt".to_string()],
+        ..beam(&["echo done > out.txt"])
+    };
+
+    let first = run_events(cached(), dir.path()).await;
+    assert_eq!(
+        phases(&first),
+        vec![
+            BeamPhase::Gate,
+            BeamPhase::InputHash,
+            BeamPhase::CacheLookup,
+            BeamPhase::Execute
+        ]
+    );
+
+    // A cache hit stops at the lookup.
+    let second = run_events(cached(), dir.path()).await;
+    assert_eq!(
+        phases(&second),
+        vec![
+            BeamPhase::Gate,
+            BeamPhase::InputHash,
+            BeamPhase::CacheLookup
+        ]
+    );
+}
+
+#[tokio::test]
+async fn a_skipped_beam_reports_its_gate_only() {
+    let dir = tempfile::tempdir().unwrap();
+    let skipped = Beam {
+        skip_if: Some("true".to_string()),
+        ..beam(&["true"])
+    };
+    let events = run_events(skipped, dir.path()).await;
+    assert_eq!(phases(&events), vec![BeamPhase::Gate]);
+}
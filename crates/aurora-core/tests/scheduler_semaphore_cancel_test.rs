@@ -1,5 +1,5 @@
 use aurora_core::ast::{Beam, Dependency, Run};
-use aurora_core::scheduler::{BeamStatus, Scheduler, SchedulerEvent};
+use aurora_core::scheduler::{BeamStatus, CancelRequest, Scheduler, SchedulerEvent};
 use aurora_executor_api::Executor;
 use aurora_executor_local::LocalExecutor;
 use std::collections::HashMap;
@@ -38,7 +38,7 @@ fn aggregate(name: &str, deps: &[&str]) -> Beam {
 #[tokio::test]
 async fn cancel_reaches_a_beam_queued_on_the_semaphore() {
     let (tx, mut rx) = mpsc::channel(64);
-    let (cancel_tx, cancel_rx) = mpsc::unbounded_channel::<String>();
+    let (cancel_tx, cancel_rx) = mpsc::unbounded_channel::<CancelRequest>();
 
     let scheduler = Scheduler::new(
         vec![
@@ -58,8 +58,12 @@ async fn cancel_reaches_a_beam_queued_on_the_semaphore() {
 
     // The initial spawn populates the cancel registry synchronously before the
     // event loop, so these buffered cancellations are honored once processed.
-    cancel_tx.send("a".to_string()).unwrap();
-    cancel_tx.send("b".to_string()).unwrap();
+    cancel_tx
+        .send(CancelRequest::Beam("a".to_string()))
+        .unwrap();
+    cancel_tx
+        .send(CancelRequest::Beam("b".to_string()))
+        .unwrap();
 
     let mut a_cancelled = false;
     let mut b_cancelled = false;
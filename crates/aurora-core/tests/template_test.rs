@@ -0,0 +1,207 @@
+use aurora_core::ast::{Beam, BeamFile, EnvValue};
+use aurora_core::diagnostic::find;
+use aurora_core::parser::parse;
+
+const BEAMFILE: &str = r#"
+beam "build" {
+  extends     = "rust-ci"
+  description = "Build the crate"
+  param "profile" { default = "release" }
+  inputs += ["build.rs"]
+  outputs = ["target/release/app"]
+  environment {
+    RUST_LOG = "debug"
+  }
+  run {
+    commands = ["cargo build --${param.profile}"]
+    executor "docker" { image = "rust:1.81" }
+  }
+}
+
+beam "lint" {
+  extends = "rust-ci"
+  run {
+    commands += ["cargo clippy"]
+    executor "local" {}
+  }
+}
+
+beam "fetch" {}
+
+template "rust-ci" {
+  extends     = "base"
+  description = "A Rust CI step"
+  param "profile" {
+    default = "debug"
+    choices = ["debug", "release"]
+  }
+  param "features" { default = "" }
+  inputs  = ["src/**/*.rs", "Cargo.toml"]
+  outputs = ["target/"]
+  environment {
+    RUST_LOG         = "info"
+    CARGO_TERM_COLOR = "always"
+  }
+  run {
+    commands = ["cargo build"]
+    executor "docker" {
+      image   = "rust:1.80"
+      workdir = "/src"
+    }
+  }
+}
+
+template "base" {
+  depends_on    = ["fetch"]
+  allow_failure = true
+}
+"#;
+
+fn beam<'a>(beam_file: &'a BeamFile, name: &str) -> &'a Beam {
+    beam_file.beams.iter().find(|b| b.name == name).unwrap()
+}
+
+#[test]
+fn templates_are_not_beams() {
+    let beam_file = parse(BEAMFILE).unwrap();
+    let names: Vec<&str> = beam_file.beams.iter().map(|b| b.name.as_str()).collect();
+    assert_eq!(names, ["build", "lint", "fetch"]);
+    assert_eq!(
+        beam(&beam_file, "build").extends.as_deref(),
+        Some("rust-ci")
+    );
+    assert_eq!(beam(&beam_file, "fetch").extends, None);
+}
+
+#[test]
+fn scalars_override_and_lists_append_or_replace() {
+    let beam_file = parse(BEAMFILE).unwrap();
+    let build = beam(&beam_file, "build");
+    assert_eq!(build.description.as_deref(), Some("Build the crate"));
+    // From the template the template extends.
+    assert!(build.allow_failure);
+    assert_eq!(build.depends_on[0].beam, "fetch");
+    assert_eq!(build.inputs, ["src/**/*.rs", "Cargo.toml", "build.rs"]);
+    assert_eq!(build.outputs, ["target/release/app"]);
+
+    let lint = beam(&beam_file, "lint");
+    assert_eq!(lint.description.as_deref(), Some("A Rust CI step"));
+    let commands = &lint.run.as_ref().unwrap().commands;
+    assert_eq!(commands, &["cargo build", "cargo clippy"]);
+}
+
+#[test]
+fn params_environment_and_executor_settings_merge() {
+    let beam_file = parse(BEAMFILE).unwrap();
+    let build = beam(&beam_file, "build");
+
+    // A param declared again replaces the template's, in its place.
+    let params: Vec<(&str, Option<&str>)> = build
+        .params
+        .iter()
+        .map(|p| (p.name.as_str(), p.default.as_deref()))
+        .collect();
+    assert_eq!(
+        params,
+        [("profile", Some("release")), ("features", Some(""))]
+    );
+    assert!(build.params[0].constraints.choices.is_empty());
+
+    // The beam's entries are evaluated after the template's.
+    let environment: Vec<(&str, &str)> = build
+        .environment
+        .as_ref()
+        .unwrap()
+        .vars
+        .iter()
+        .map(|v| match &v.value {
+            EnvValue::Literal(value) => (v.name.as_str(), value.as_str()),
+            EnvValue::Shell(_) => unreachable!(),
+        })
+        .collect();
+    assert_eq!(
+        environment,
+        [
+            ("RUST_LOG", "info"),
+            ("CARGO_TERM_COLOR", "always"),
+            ("RUST_LOG", "debug")
+        ]
+    );
+
+    let run = build.run.as_ref().unwrap();
+    assert_eq!(run.commands, ["cargo build --${param.profile}"]);
+    let executor = run.executor.as_ref().unwrap();
+    assert_eq!(executor.name, "docker");
+    assert_eq!(executor.config["image"], "rust:1.81");
+    assert_eq!(executor.config["workdir"], "/src");
+
+    // Another executor replaces the template's with its own settings.
+    let lint = beam(&beam_file, "lint");
+    let executor = lint.run.as_ref().unwrap().executor.as_ref().unwrap();
+    assert_eq!(executor.name, "local");
+    assert!(executor.config.is_empty());
+}
+
+#[test]
+fn an_unknown_template_is_reported_with_a_suggestion() {
+    let source = "template \"rust-ci\" {}\nbeam \"build\" { extends = \"rust_ci\" }\n";
+    let err = parse(source).unwrap_err();
+    let diagnostic = find(&err).unwrap();
+    assert_eq!(diagnostic.message, "unknown template 'rust_ci'");
+    assert_eq!(diagnostic.help.as_deref(), Some("did you mean 'rust-ci'?"));
+    assert_eq!(diagnostic.span.line_col(source), (2, 26));
+}
+
+#[test]
+fn a_template_cycle_is_reported() {
+    let source = "template \"a\" { extends = \"b\" }\ntemplate \"b\" { extends = \"a\" }\n";
+    let err = parse(source).unwrap_err();
+    let diagnostic = find(&err).unwrap();
+    assert_eq!(
+        diagnostic.message,
+        "template 'a' extends itself: a -> b -> a"
+    );
+}
+
+#[test]
+fn a_template_name_is_declared_once() {
+    let source = "template \"ci\" {}\ntemplate \"ci\" {}\n";
+    let err = parse(source).unwrap_err();
+    let diagnostic = find(&err).unwrap();
+    assert_eq!(diagnostic.message, "duplicate template name 'ci'");
+    assert_eq!(diagnostic.span.line_col(source), (2, 10));
+}
+
+#[test]
+fn a_param_declared_twice_in_the_beam_itself_is_a_duplicate() {
+    let source = r#"template "ci" { param "p" {} }
+beam "build" {
+  extends = "ci"
+  param "p" {}
+  param "p" {}
+}
+"#;
+    let err = parse(source).unwrap_err();
+    let diagnostic = find(&err).unwrap();
+    assert_eq!(diagnostic.message, "duplicate param 'p' in beam 'build'");
+    assert_eq!(diagnostic.span.line_col(source), (5, 9));
+}
+
+#[test]
+fn tags_are_a_list_a_beam_appends_to_or_replaces() {
+    let source = r#"template "ci" { tags = ["ci"] }
+beam "test" {
+  extends = "ci"
+  tags += ["fast"]
+}
+beam "bench" {
+  extends = "ci"
+  tags    = ["slow"]
+}
+beam "docs" {}
+"#;
+    let beam_file = parse(source).unwrap();
+    assert_eq!(beam(&beam_file, "test").tags, ["ci", "fast"]);
+    assert_eq!(beam(&beam_file, "bench").tags, ["slow"]);
+    assert!(beam(&beam_file, "docs").tags.is_empty());
+}
@@ -0,0 +1,124 @@
+use aurora_core::ast::{Beam, Run};
+use aurora_core::scheduler::{BeamStatus, Scheduler, SchedulerEvent, SkipReason};
+use aurora_core::trace_context::TraceContext;
+use aurora_executor_api::Executor;
+use aurora_executor_local::LocalExecutor;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+const PARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+#[test]
+fn a_valid_traceparent_is_continued() {
+    let context = TraceContext::new(Some(PARENT));
+    assert_eq!(context.trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+    assert_eq!(context.parent_span_id.as_deref(), Some("00f067aa0ba902b7"));
+    assert_ne!(context.root_span_id, "00f067aa0ba902b7");
+}
+
+#[test]
+fn an_invalid_traceparent_starts_a_new_trace() {
+    for value in [
+        "garbage",
+        "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+        "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+        "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+    ] {
+        let context = TraceContext::new(Some(value));
+        assert_eq!(context.parent_span_id, None, "{value}");
+        assert_eq!(context.trace_id.len(), 32);
+        assert_ne!(context.trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+    }
+    let (a, b) = (TraceContext::new(None), TraceContext::new(None));
+    assert_ne!(a.trace_id, b.trace_id, "each run is a trace of its own");
+    assert_eq!(a.root_span_id.len(), 16);
+}
+
+#[test]
+fn beam_span_ids_are_stable_per_trace_and_beam() {
+    let context = TraceContext::new(Some(PARENT));
+    let build = context.beam_span_id("build");
+    assert_eq!(build.len(), 16);
+    assert_eq!(build, context.beam_span_id("build"));
+    assert_ne!(build, context.beam_span_id("test"));
+    assert_eq!(
+        context.traceparent("build"),
+        format!("00-4bf92f3577b34da6a3ce929d0e0e4736-{build}-01")
+    );
+}
+
+fn local_executors() -> HashMap<String, Arc<dyn Executor>> {
+    let mut m: HashMap<String, Arc<dyn Executor>> = HashMap::new();
+    m.insert("local".into(), Arc::new(LocalExecutor::new()));
+    m
+}
+
+async fn run(dir: &Path, context: Option<TraceContext>) -> BeamStatus {
+    let beam = Beam {
+        name: "build".to_string(),
+        inputs: vec!["in.txt".to_string()],
+        outputs: vec!["tp.txt".to_string()],
+        run: Some(Run {
+            commands: vec!["printf '%s' \"$TRACEPARENT\" > tp.txt".to_string()],
+            executor: None,
+        }),
+        ..Beam::default()
+    };
+    let (tx, mut rx) = mpsc::channel(64);
+    let scheduler = Scheduler::new(
+        vec![beam],
+        local_executors(),
+        tx,
+        None,
+        dir.to_path_buf(),
+        HashMap::new(),
+    );
+    let scheduler = match context {
+        Some(context) => scheduler.with_trace_context(context),
+        None => scheduler,
+    };
+    scheduler.run("build", &[]).await.unwrap();
+    let mut status = None;
+    while let Ok(event) = rx.try_recv() {
+        if let SchedulerEvent::BeamCompleted { status: s, .. } = event {
+            status = Some(s);
+        }
+    }
+    status.expect("the beam completed")
+}
+
+#[tokio::test]
+async fn beams_get_their_span_as_traceparent_outside_the_cache_key() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("in.txt"), "v1").unwrap();
+
+    let context = TraceContext::new(None);
+    run(dir.path(), Some(context.clone())).await;
+    let seen = std::fs::read_to_string(dir.path().join("tp.txt")).unwrap();
+    assert_eq!(seen, context.traceparent("build"));
+
+    // A new trace does not invalidate the cached result.
+    let status = run(dir.path(), Some(TraceContext::new(None))).await;
+    assert!(
+        matches!(
+            status,
+            BeamStatus::Skipped {
+                reason: SkipReason::Cached
+            }
+        ),
+        "{status:?}"
+    );
+}
+
+#[tokio::test]
+async fn without_a_trace_no_traceparent_is_set() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("in.txt"), "v1").unwrap();
+    run(dir.path(), None).await;
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("tp.txt")).unwrap(),
+        ""
+    );
+}
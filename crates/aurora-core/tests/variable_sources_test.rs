@@ -0,0 +1,126 @@
+use aurora_core::ast::{ValueOrigin, VariableSources};
+use aurora_core::diagnostic::find;
+use aurora_core::env::evaluate_variables;
+use aurora_core::parser::{parse, resolve_variables};
+
+const BEAMFILE: &str = r#"
+variable "version" {
+  from_env  = "AURORA_TEST_SOURCES_VERSION"
+  from_file = "VERSION"
+  default   = shell("echo from-shell")
+}
+
+variable "image" {
+  default = "ubuntu"
+}
+
+beam "release" {
+  run {
+    executor "docker" { image = var.image }
+    commands = ["./release ${var.version}"]
+  }
+}
+"#;
+
+#[test]
+fn sources_are_parsed_and_a_shell_default_is_not_a_literal() {
+    let beam_file = parse(BEAMFILE).unwrap();
+    let version = &beam_file.variables[0];
+    assert_eq!(
+        version.sources,
+        VariableSources {
+            env: Some("AURORA_TEST_SOURCES_VERSION".to_string()),
+            file: Some("VERSION".to_string()),
+            shell: Some("echo from-shell".to_string()),
+        }
+    );
+    assert_eq!(version.origin, ValueOrigin::Unset);
+    assert_eq!(version.default, "");
+    assert_eq!(beam_file.variables[1].origin, ValueOrigin::Literal);
+}
+
+#[test]
+fn the_first_source_with_a_value_wins() {
+    let dir = tempfile::tempdir().unwrap();
+
+    // No env, no file: the command runs, in the Beamfile's directory.
+    let mut beam_file = parse(BEAMFILE).unwrap();
+    evaluate_variables(&mut beam_file, dir.path()).unwrap();
+    assert_eq!(beam_file.variables[0].default, "from-shell");
+    assert_eq!(
+        beam_file.variables[0].origin_label(),
+        "shell `echo from-shell`"
+    );
+    assert_eq!(beam_file.variables[1].origin, ValueOrigin::Literal);
+
+    // The file beats the command; its trailing newline is dropped.
+    std::fs::write(dir.path().join("VERSION"), "1.4.2\n").unwrap();
+    let mut beam_file = parse(BEAMFILE).unwrap();
+    evaluate_variables(&mut beam_file, dir.path()).unwrap();
+    assert_eq!(beam_file.variables[0].default, "1.4.2");
+    assert_eq!(beam_file.variables[0].origin_label(), "file VERSION");
+
+    // The environment beats the file.
+    std::env::set_var("AURORA_TEST_SOURCES_VERSION", "2.0.0");
+    let mut beam_file = parse(BEAMFILE).unwrap();
+    evaluate_variables(&mut beam_file, dir.path()).unwrap();
+    std::env::remove_var("AURORA_TEST_SOURCES_VERSION");
+    assert_eq!(beam_file.variables[0].default, "2.0.0");
+    assert_eq!(beam_file.variables[0].origin, ValueOrigin::Env);
+
+    // The evaluated value reaches commands and executor configs alike.
+    resolve_variables(&mut beam_file).unwrap();
+    let run = beam_file.beams[0].run.as_ref().unwrap();
+    assert_eq!(run.commands, ["./release 2.0.0"]);
+    assert_eq!(run.executor.as_ref().unwrap().config["image"], "ubuntu");
+}
+
+#[test]
+fn a_cli_override_is_never_evaluated_over() {
+    let source = "variable \"sha\" { default = shell(\"exit 3\") }";
+    let mut beam_file = parse(source).unwrap();
+    beam_file.variables[0].default = "abc123".to_string();
+    beam_file.variables[0].origin = ValueOrigin::Cli;
+    // The failing command is not even run.
+    evaluate_variables(&mut beam_file, std::path::Path::new(".")).unwrap();
+    assert_eq!(beam_file.variables[0].default, "abc123");
+    assert_eq!(beam_file.variables[0].origin_label(), "--var");
+
+    let mut beam_file = parse(source).unwrap();
+    let err = evaluate_variables(&mut beam_file, std::path::Path::new(".")).unwrap_err();
+    assert!(
+        err.to_string()
+            .starts_with("variable 'sha': shell command `exit 3` failed"),
+        "{err}"
+    );
+}
+
+#[test]
+fn a_missing_file_falls_back_or_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut beam_file =
+        parse("variable \"v\" {\n  from_file = \"VERSION\"\n  default = \"0.0.0\"\n}").unwrap();
+    evaluate_variables(&mut beam_file, dir.path()).unwrap();
+    assert_eq!(beam_file.variables[0].default, "0.0.0");
+    assert_eq!(beam_file.variables[0].origin_label(), "default");
+
+    let mut beam_file = parse("variable \"v\" { from_file = \"VERSION\" }").unwrap();
+    let err = evaluate_variables(&mut beam_file, dir.path()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "variable 'v': VERSION not found and no default declared"
+    );
+}
+
+#[test]
+fn an_evaluated_value_must_pass_the_constraints() {
+    let source = "variable \"replicas\" {\n  type = \"int\"\n  default = shell(\"echo many\")\n}\n";
+    let mut beam_file = parse(source).unwrap();
+    let err = evaluate_variables(&mut beam_file, std::path::Path::new(".")).unwrap_err();
+    let diagnostic = find(&err).unwrap();
+    assert_eq!(
+        diagnostic.message,
+        "invalid value \"many\" for variable 'replicas' from shell `echo many`: expected an int"
+    );
+    assert_eq!(diagnostic.span.line_col(source), (1, 10));
+}
@@ -2,9 +2,10 @@ use anyhow::Result;
 use async_trait::async_trait;
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::time::Duration;
 use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
 use tokio::process::Child;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ExecutionInput {
@@ -13,10 +14,64 @@ pub struct ExecutionInput {
     pub working_dir: PathBuf,
     /// Executor-specific configuration (e.g. {"image": "ubuntu:22.04"} for docker)
     pub config: serde_json::Value,
+    /// Run the commands under a pseudo-terminal rather than pipes (the beam's
+    /// `tty = true`). An executor that cannot allocate one runs them piped.
+    #[serde(default)]
+    pub tty: bool,
     /// Optional channel to stream output lines in real time.
     /// `(line, is_stderr)`
     #[serde(skip)]
     pub output_tx: Option<mpsc::Sender<(String, bool)>>,
+    /// Set when the caller may cancel the execution gracefully (see
+    /// [`Executor::supports_graceful_stop`]).
+    #[serde(skip)]
+    pub stop: Option<StopSignal>,
+}
+
+/// A graceful-stop request, observed by a running execution.
+///
+/// Cancelling by dropping the execution future can only kill outright (a
+/// `Drop` cannot wait). An executor that supports a graceful stop instead
+/// watches this signal: once it fires, it asks the command to stop (SIGTERM,
+/// `docker stop`), waits up to [`StopSignal::grace`] for it to exit, then
+/// kills it, and returns the output gathered so far.
+#[derive(Debug, Clone)]
+pub struct StopSignal {
+    rx: watch::Receiver<bool>,
+    grace: Duration,
+}
+
+/// The caller's half of a [`StopSignal`].
+#[derive(Debug)]
+pub struct StopHandle {
+    tx: watch::Sender<bool>,
+}
+
+impl StopSignal {
+    /// A new signal with its handle. `grace` is how long the command gets to
+    /// exit on its own once the stop is requested.
+    pub fn new(grace: Duration) -> (StopHandle, StopSignal) {
+        let (tx, rx) = watch::channel(false);
+        (StopHandle { tx }, StopSignal { rx, grace })
+    }
+
+    pub fn grace(&self) -> Duration {
+        self.grace
+    }
+
+    /// Resolves once the stop is requested. Never resolves when the handle is
+    /// dropped without requesting it: the execution then runs to completion.
+    pub async fn requested(&mut self) {
+        if self.rx.wait_for(|stop| *stop).await.is_err() {
+            std::future::pending::<()>().await
+        }
+    }
+}
+
+impl StopHandle {
+    pub fn request(&self) {
+        let _ = self.tx.send(true);
+    }
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -37,6 +92,12 @@ impl ExecutionOutput {
 pub trait Executor: Send + Sync {
     fn name(&self) -> &str;
     async fn execute(&self, input: ExecutionInput) -> Result<ExecutionOutput>;
+
+    /// Whether `execute` honours [`ExecutionInput::stop`]. An executor that
+    /// does not is cancelled by dropping its future, immediately.
+    fn supports_graceful_stop(&self) -> bool {
+        false
+    }
 }
 
 /// Reads a stream line by line, forwarding each line to `output_tx` (when set)
@@ -111,3 +172,29 @@ pub async fn pump_child(
         stderr: stderr_lines.join("\n").into_bytes(),
     })
 }
+
+/// Like [`pump_child`], for a child whose stdout and stderr both write to a
+/// pseudo-terminal: `terminal` is the reading side (the pty master), and its
+/// single stream is reported as stdout. The terminal does not preserve the
+/// split, so the output's stderr is always empty.
+///
+/// Lines are split on `\n` only: a `\r` inside a line (a progress bar
+/// redrawing itself) is kept for the consumer to interpret. The reader must
+/// end (EOF or an error, which is how a pty master reports that every writer
+/// has closed) once the child and its descendants are gone.
+pub async fn pump_terminal<R>(
+    mut child: Child,
+    terminal: R,
+    output_tx: Option<mpsc::Sender<(String, bool)>>,
+) -> Result<ExecutionOutput>
+where
+    R: AsyncRead + Unpin,
+{
+    let (lines, status) = tokio::join!(collect_stream(terminal, false, output_tx), child.wait());
+
+    Ok(ExecutionOutput {
+        exit_code: status?.code().unwrap_or(-1),
+        stdout: lines.join("\n").into_bytes(),
+        stderr: Vec::new(),
+    })
+}
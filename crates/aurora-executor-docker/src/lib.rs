@@ -3,6 +3,7 @@ use async_trait::async_trait;
 use aurora_executor_api::{pump_child, ExecutionInput, ExecutionOutput, Executor};
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
 use tokio::process::Command;
 
 /// Monotonic counter making each container name unique within this process.
@@ -25,6 +26,7 @@ pub fn build_run_args(
     volumes: &[String],
     env: &HashMap<String, String>,
     script: &str,
+    tty: bool,
 ) -> Vec<String> {
     let mut args = vec![
         "run".to_string(),
@@ -40,6 +42,13 @@ pub fn build_run_args(
         "/app".to_string(),
     ];
 
+    // `tty = true`: Docker allocates the pseudo-terminal inside the container
+    // and relays it on the client's stdout (stderr shares it). No `-i`: stdin
+    // stays detached, as for the local executor.
+    if tty {
+        args.push("-t".to_string());
+    }
+
     for vol in volumes {
         args.push("-v".to_string());
         args.push(vol.clone());
@@ -59,6 +68,19 @@ pub fn build_run_args(
     args
 }
 
+/// Builds the `docker stop` argument vector. The grace period is in whole
+/// seconds, rounded up so a sub-second timeout still gives the container a
+/// chance to exit rather than none.
+pub fn build_stop_args(name: &str, grace: Duration) -> Vec<String> {
+    let secs = grace.as_secs() + u64::from(grace.subsec_nanos() > 0);
+    vec![
+        "stop".to_string(),
+        "--time".to_string(),
+        secs.to_string(),
+        name.to_string(),
+    ]
+}
+
 /// Removes the container by name with `docker rm -f` when dropped while still
 /// armed. `kill_on_drop` SIGKILLs the `docker run` client, but the container
 /// is a child of the daemon: it keeps running and `--rm` never fires. Removing
@@ -200,6 +222,10 @@ impl Executor for DockerExecutor {
         "docker"
     }
 
+    fn supports_graceful_stop(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, input: ExecutionInput) -> Result<ExecutionOutput> {
         let image = input.config["image"]
             .as_str()
@@ -245,7 +271,7 @@ impl Executor for DockerExecutor {
         let script = format!("set -e\n{}", input.commands.join("\n"));
 
         let name = next_container_name();
-        let args = build_run_args(&name, &image, &volumes, &input.env, &script);
+        let args = build_run_args(&name, &image, &volumes, &input.env, &script, input.tty);
 
         let mut cmd = Command::new("docker");
         cmd.kill_on_drop(true)
@@ -258,7 +284,30 @@ impl Executor for DockerExecutor {
         // Armed while the container runs: on cancellation the execution future
         // is dropped and the guard removes the container by name.
         let mut cleanup = ContainerCleanup::new(name);
-        let output = pump_child(child, input.output_tx).await;
+        let pump = pump_child(child, input.output_tx);
+
+        // Graceful stop: `docker stop` sends the container SIGTERM and, past
+        // the grace period, SIGKILL (the daemon escalates on its own). The
+        // `docker run` client then exits with the container, ending the pump.
+        let output = match input.stop {
+            Some(mut stop) => {
+                let mut pump = std::pin::pin!(pump);
+                tokio::select! {
+                    output = &mut pump => output,
+                    _ = stop.requested() => {
+                        let _ = Command::new("docker")
+                            .args(build_stop_args(&cleanup.name, stop.grace()))
+                            .stdout(std::process::Stdio::null())
+                            .stderr(std::process::Stdio::null())
+                            .kill_on_drop(true)
+                            .status()
+                            .await;
+                        pump.await
+                    }
+                }
+            }
+            None => pump.await,
+        };
 
         // The container exited on its own; `--rm` removed it, so nothing left
         // to force-remove.
@@ -1,7 +1,8 @@
 // These tests require Docker. Marked #[ignore] by default.
 use aurora_executor_api::{ExecutionInput, Executor};
-use aurora_executor_docker::{build_run_args, DockerExecutor};
+use aurora_executor_docker::{build_run_args, build_stop_args, DockerExecutor};
 use std::collections::HashMap;
+use std::time::Duration;
 
 // Does NOT require Docker: the container must be named so it can be removed by
 // name if `docker run` is killed on cancellation (the container is a child of
@@ -14,6 +15,7 @@ fn run_args_name_the_container_before_the_image() {
         &["/w:/app:rw".to_string()],
         &HashMap::new(),
         "echo hi",
+        false,
     );
 
     let name_pos = args
@@ -35,10 +37,47 @@ fn run_args_name_the_container_before_the_image() {
 #[test]
 fn run_args_pass_env_and_volumes() {
     let env = HashMap::from([("MY_VAR".to_string(), "v".to_string())]);
-    let args = build_run_args("n", "img", &["/w:/app:rw".to_string()], &env, "echo hi");
+    let args = build_run_args(
+        "n",
+        "img",
+        &["/w:/app:rw".to_string()],
+        &env,
+        "echo hi",
+        false,
+    );
     let joined = args.join(" ");
     assert!(joined.contains("-v /w:/app:rw"), "volume missing: {joined}");
     assert!(joined.contains("-e MY_VAR=v"), "env missing: {joined}");
+    assert!(!args.contains(&"-t".to_string()), "no tty unless asked");
+}
+
+// Does NOT require Docker: `tty = true` allocates a terminal in the container,
+// as a `docker run` option (before the `--` separator), never with `-i`.
+#[test]
+fn run_args_allocate_a_tty_on_request() {
+    let args = build_run_args("n", "img", &[], &HashMap::new(), "echo hi", true);
+    let tty = args
+        .iter()
+        .position(|a| a == "-t")
+        .expect("-t must be present");
+    let sep = args.iter().position(|a| a == "--").expect("-- separator");
+    assert!(tty < sep, "-t must precede the -- separator");
+    assert!(!args.contains(&"-i".to_string()), "stdin stays detached");
+}
+
+// Does NOT require Docker: a graceful stop hands the beam's kill_timeout to
+// `docker stop`, which sends SIGTERM and kills the container after that many
+// seconds. A sub-second grace is rounded up, never down to an instant kill.
+#[test]
+fn stop_args_carry_the_grace_period() {
+    assert_eq!(
+        build_stop_args("aurora-42-0", Duration::from_secs(10)),
+        vec!["stop", "--time", "10", "aurora-42-0"]
+    );
+    assert_eq!(
+        build_stop_args("n", Duration::from_millis(1500)),
+        vec!["stop", "--time", "2", "n"]
+    );
 }
 
 // Does NOT require Docker: validates that dangerous volumes are rejected
@@ -59,7 +98,9 @@ async fn test_dangerous_volume_is_rejected() {
             // `volumes` is a comma-separated string, matching what the
             // executor config actually carries through the real pipeline.
             config: serde_json::json!({ "image": "alpine:3.19", "volumes": vol }),
+            tty: false,
             output_tx: None,
+            stop: None,
         };
         let result = executor.execute(input).await;
         assert!(result.is_err(), "dangerous volume accepted: {vol}");
@@ -79,7 +120,9 @@ async fn test_dangerous_volume_in_list_is_rejected() {
             "image": "alpine:3.19",
             "volumes": "/tmp/ok:/ok:ro,/etc:/etc:ro",
         }),
+        tty: false,
         output_tx: None,
+        stop: None,
     };
     let result = executor.execute(input).await;
     assert!(result.is_err(), "dangerous volume in a list accepted");
@@ -102,7 +145,9 @@ async fn test_symlink_to_forbidden_path_is_rejected() {
         env: HashMap::new(),
         working_dir: std::env::current_dir().unwrap(),
         config: serde_json::json!({ "image": "alpine:3.19", "volumes": spec }),
+        tty: false,
         output_tx: None,
+        stop: None,
     };
     let result = executor.execute(input).await;
     assert!(result.is_err(), "symlink to /etc accepted");
@@ -125,7 +170,9 @@ async fn test_dotdot_traversal_to_forbidden_path_is_rejected() {
             env: HashMap::new(),
             working_dir: std::env::current_dir().unwrap(),
             config: serde_json::json!({ "image": "alpine:3.19", "volumes": vol }),
+            tty: false,
             output_tx: None,
+            stop: None,
         };
         let result = executor.execute(input).await;
         assert!(result.is_err(), "dotdot traversal accepted: {vol}");
@@ -143,7 +190,9 @@ async fn test_image_starting_with_dash_is_rejected() {
             env: HashMap::new(),
             working_dir: std::env::current_dir().unwrap(),
             config: serde_json::json!({ "image": image }),
+            tty: false,
             output_tx: None,
+            stop: None,
         };
         let result = executor.execute(input).await;
         assert!(result.is_err(), "dangerous image accepted: {image:?}");
@@ -159,7 +208,9 @@ async fn test_docker_echo() {
         env: HashMap::new(),
         working_dir: std::env::current_dir().unwrap(),
         config: serde_json::json!({ "image": "alpine:3.19" }),
+        tty: false,
         output_tx: None,
+        stop: None,
     };
     let output = executor.execute(input).await.unwrap();
     assert_eq!(output.exit_code, 0);
@@ -180,7 +231,9 @@ async fn cancellation_removes_the_container() {
         env: HashMap::new(),
         working_dir: std::env::current_dir().unwrap(),
         config: serde_json::json!({ "image": "alpine:3.19" }),
+        tty: false,
         output_tx: None,
+        stop: None,
     };
 
     let fut = exec.execute(input);
@@ -219,7 +272,9 @@ async fn test_docker_env_vars() {
         env: HashMap::from([("MY_VAR".to_string(), "aurora_docker".to_string())]),
         working_dir: std::env::current_dir().unwrap(),
         config: serde_json::json!({ "image": "alpine:3.19" }),
+        tty: false,
         output_tx: None,
+        stop: None,
     };
     let output = executor.execute(input).await.unwrap();
     let stdout = String::from_utf8(output.stdout).unwrap();
@@ -1,16 +1,20 @@
 use anyhow::{bail, Result};
 use async_trait::async_trait;
-use aurora_executor_api::{pump_child, ExecutionInput, ExecutionOutput, Executor};
+use aurora_executor_api::{pump_child, pump_terminal, ExecutionInput, ExecutionOutput, Executor};
 use std::path::{Path, PathBuf};
 use tokio::process::Command;
 
+#[cfg(unix)]
+mod pty;
+
 /// Kills the child's process group with SIGKILL when dropped while still armed.
 /// `kill_on_drop` only reaches the direct `sh`; a command that backgrounds a
 /// job (`... &`) or spawns workers leaves those descendants reparented to init
 /// on cancellation. The child leads its own group (see `process_group(0)`
 /// below), so signalling the negative pgid reaps the whole subtree. The guard
 /// is disarmed once the child has exited normally, so a recycled group id is
-/// never signalled.
+/// never signalled. A graceful stop signals the same group through
+/// [`ProcessGroupKiller::signal`] before the guard ever drops.
 #[cfg(unix)]
 struct ProcessGroupKiller {
     pgid: i32,
@@ -29,18 +33,23 @@ impl ProcessGroupKiller {
     fn disarm(&mut self) {
         self.armed = false;
     }
+
+    /// Sends `signal` to every process in the group.
+    fn signal(&self, signal: libc::c_int) {
+        // SAFETY: `kill(2)` with a negative pgid targets every process in
+        // that group. The group was created by `process_group(0)`, so its
+        // id equals the child leader's pid; the call has no other effect.
+        unsafe {
+            libc::kill(-self.pgid, signal);
+        }
+    }
 }
 
 #[cfg(unix)]
 impl Drop for ProcessGroupKiller {
     fn drop(&mut self) {
         if self.armed {
-            // SAFETY: `kill(2)` with a negative pgid targets every process in
-            // that group. The group was created by `process_group(0)`, so its
-            // id equals the child leader's pid; the call has no other effect.
-            unsafe {
-                libc::kill(-self.pgid, libc::SIGKILL);
-            }
+            self.signal(libc::SIGKILL);
         }
     }
 }
@@ -138,6 +147,11 @@ impl Executor for LocalExecutor {
         "local"
     }
 
+    /// On Unix only: stopping gracefully means signalling the process group.
+    fn supports_graceful_stop(&self) -> bool {
+        cfg!(unix)
+    }
+
     async fn execute(&self, input: ExecutionInput) -> Result<ExecutionOutput> {
         let path_var = input.env.get("PATH");
 
@@ -183,10 +197,35 @@ impl Executor for LocalExecutor {
             .env_clear()
             .envs(&input.env)
             .stdin(std::process::Stdio::null())
-            .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::piped())
             .kill_on_drop(true);
 
+        // `tty = true`: stdout and stderr write to a pseudo-terminal, so the
+        // command sees a terminal and keeps its colours and progress bars.
+        // stdin stays detached even then (see above): the pty is only ever
+        // read. A terminal without a `TERM` reads as a dumb one to most tools,
+        // so one is provided when the environment does not carry it.
+        #[cfg(unix)]
+        let terminal = if input.tty {
+            let pty = pty::open()?;
+            command
+                .stdout(std::process::Stdio::from(pty.slave.try_clone()?))
+                .stderr(std::process::Stdio::from(pty.slave));
+            if !input.env.contains_key("TERM") {
+                command.env("TERM", "xterm-256color");
+            }
+            Some(pty.master)
+        } else {
+            None
+        };
+        #[cfg(not(unix))]
+        let terminal: Option<std::fs::File> = None;
+
+        if terminal.is_none() {
+            command
+                .stdout(std::process::Stdio::piped())
+                .stderr(std::process::Stdio::piped());
+        }
+
         // Run the child as the leader of its own process group so cancellation
         // can reap its whole subtree, not just the direct `sh` (see
         // `ProcessGroupKiller`).
@@ -194,11 +233,50 @@ impl Executor for LocalExecutor {
         command.process_group(0);
 
         let child = command.spawn()?;
+        // The command holds Aurora's copies of the pty slave: release them now,
+        // or the master would never see the end of the output.
+        drop(command);
 
         #[cfg(unix)]
         let mut group_killer = child.id().map(ProcessGroupKiller::new);
 
-        let output = pump_child(child, input.output_tx).await;
+        let output_tx = input.output_tx;
+        let pump = async move {
+            match terminal {
+                Some(master) => {
+                    pump_terminal(child, tokio::fs::File::from_std(master), output_tx).await
+                }
+                None => pump_child(child, output_tx).await,
+            }
+        };
+
+        // Graceful stop: SIGTERM the whole group, so the command can release
+        // its locks and flush its reports, and keep collecting its output for
+        // the grace period. Only a command still running after it gets the
+        // SIGKILL. The group outlives its leader while any member is alive, so
+        // signalling it after `sh` exited still reaches the stragglers.
+        #[cfg(unix)]
+        let output = match (input.stop, group_killer.as_ref()) {
+            (Some(mut stop), Some(killer)) => {
+                let mut pump = std::pin::pin!(pump);
+                tokio::select! {
+                    output = &mut pump => output,
+                    _ = stop.requested() => {
+                        killer.signal(libc::SIGTERM);
+                        match tokio::time::timeout(stop.grace(), &mut pump).await {
+                            Ok(output) => output,
+                            Err(_) => {
+                                killer.signal(libc::SIGKILL);
+                                pump.await
+                            }
+                        }
+                    }
+                }
+            }
+            _ => pump.await,
+        };
+        #[cfg(not(unix))]
+        let output = pump.await;
 
         // The child exited on its own: disarm the guard so its process group is
         // not signalled after the fact (the id may have been recycled).
@@ -0,0 +1,69 @@
+//! Pseudo-terminal allocation for `tty = true` beams.
+
+use std::fs::File;
+use std::os::fd::{FromRawFd, OwnedFd};
+
+/// Terminal size advertised to the child. Tools size their progress bars from
+/// it; 120 columns keeps a bar readable in the TUI's log panel without
+/// wrapping on a typical screen.
+const COLUMNS: u16 = 120;
+const ROWS: u16 = 40;
+
+/// An open pseudo-terminal pair: the child writes to `slave`, Aurora reads
+/// what it wrote from `master`.
+pub(crate) struct Pty {
+    pub master: File,
+    pub slave: OwnedFd,
+}
+
+/// Opens a pseudo-terminal pair. Both descriptors are made close-on-exec, so
+/// the children spawned for other beams do not inherit them: a stray copy of
+/// the slave would keep the master from ever reporting the end of the output.
+pub(crate) fn open() -> std::io::Result<Pty> {
+    let mut master = -1;
+    let mut slave = -1;
+    let size = libc::winsize {
+        ws_row: ROWS,
+        ws_col: COLUMNS,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+    // SAFETY: `openpty(3)` writes two descriptors into the provided ints; the
+    // name and termios pointers may be null, and `size` outlives the call and
+    // is only read (the pointer is `*mut` in some platforms' signatures).
+    let rc = unsafe {
+        libc::openpty(
+            &mut master,
+            &mut slave,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            std::ptr::addr_of!(size).cast_mut(),
+        )
+    };
+    if rc != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    // SAFETY: `openpty` succeeded, so both are open descriptors that nothing
+    // else owns.
+    let (master, slave) = unsafe { (OwnedFd::from_raw_fd(master), OwnedFd::from_raw_fd(slave)) };
+    for fd in [&master, &slave] {
+        set_cloexec(fd)?;
+    }
+    Ok(Pty {
+        master: File::from(master),
+        slave,
+    })
+}
+
+fn set_cloexec(fd: &OwnedFd) -> std::io::Result<()> {
+    use std::os::fd::AsRawFd;
+    // SAFETY: plain `fcntl(2)` flag manipulation on a descriptor we own.
+    let rc = unsafe {
+        let flags = libc::fcntl(fd.as_raw_fd(), libc::F_GETFD);
+        libc::fcntl(fd.as_raw_fd(), libc::F_SETFD, flags | libc::FD_CLOEXEC)
+    };
+    if rc == -1 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
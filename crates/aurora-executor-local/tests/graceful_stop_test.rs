@@ -0,0 +1,101 @@
+#![cfg(unix)]
+
+use aurora_executor_api::{ExecutionInput, Executor, StopSignal};
+use aurora_executor_local::LocalExecutor;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+fn base_env() -> HashMap<String, String> {
+    HashMap::from([(
+        "PATH".to_string(),
+        std::env::var("PATH").unwrap_or_default(),
+    )])
+}
+
+/// Runs `commands` with a stop signal of the given grace, requests the stop
+/// once the command printed `ready`, and returns the output with the time
+/// the stop took.
+async fn stop_once_ready(
+    commands: &[&str],
+    grace: Duration,
+) -> (aurora_executor_api::ExecutionOutput, Duration) {
+    let (tx, mut rx) = mpsc::channel(16);
+    let (handle, stop) = StopSignal::new(grace);
+    let input = ExecutionInput {
+        commands: commands.iter().map(|c| c.to_string()).collect(),
+        env: base_env(),
+        working_dir: std::env::current_dir().unwrap(),
+        config: serde_json::json!({}),
+        tty: false,
+        output_tx: Some(tx),
+        stop: Some(stop),
+    };
+
+    let execution = tokio::spawn(async move { LocalExecutor::new().execute(input).await });
+    while let Some((line, _)) = rx.recv().await {
+        if line == "ready" {
+            break;
+        }
+    }
+    let requested = Instant::now();
+    handle.request();
+    let output = tokio::time::timeout(Duration::from_secs(10), execution)
+        .await
+        .expect("a stopped execution must end")
+        .unwrap()
+        .unwrap();
+    (output, requested.elapsed())
+}
+
+/// A stop sends SIGTERM first: the command's own handler runs (releasing its
+/// locks, flushing its reports) and what it prints is still collected.
+#[tokio::test]
+async fn test_stop_lets_the_command_handle_sigterm() {
+    let (output, _) = stop_once_ready(
+        &[
+            "trap 'echo cleanup; exit 0' TERM",
+            "echo ready",
+            "sleep 30 & wait",
+        ],
+        Duration::from_secs(5),
+    )
+    .await;
+    assert_eq!(output.exit_code, 0, "the handler's exit code is kept");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "ready\ncleanup");
+}
+
+/// A command that ignores SIGTERM is killed once the grace period is over,
+/// rather than holding the run forever.
+#[tokio::test]
+async fn test_stop_escalates_to_sigkill_after_the_grace_period() {
+    let grace = Duration::from_millis(300);
+    let (output, took) =
+        stop_once_ready(&["trap '' TERM", "echo ready", "sleep 30 & wait"], grace).await;
+    assert_ne!(output.exit_code, 0);
+    assert!(took >= grace, "killed before the grace period: {took:?}");
+    assert!(
+        took < Duration::from_secs(5),
+        "not killed after the grace period: {took:?}"
+    );
+}
+
+/// Without a stop request the signal changes nothing: the command runs to
+/// completion.
+#[tokio::test]
+async fn test_unrequested_stop_lets_the_command_finish() {
+    let (handle, stop) = StopSignal::new(Duration::from_millis(10));
+    let input = ExecutionInput {
+        commands: vec!["sleep 0.2".to_string(), "echo done".to_string()],
+        env: base_env(),
+        working_dir: std::env::current_dir().unwrap(),
+        config: serde_json::json!({}),
+        tty: false,
+        output_tx: None,
+        stop: Some(stop),
+    };
+    drop(handle);
+    let output = LocalExecutor::new().execute(input).await.unwrap();
+    assert_eq!(output.exit_code, 0);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "done");
+}
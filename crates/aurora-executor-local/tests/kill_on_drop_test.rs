@@ -26,7 +26,9 @@ async fn test_kill_on_drop_terminates_child() {
         env,
         working_dir: dir.clone(),
         config: serde_json::json!({}),
+        tty: false,
         output_tx: None,
+        stop: None,
     };
 
     let fut = exec.execute(input);
@@ -70,7 +72,9 @@ async fn test_cancellation_kills_backgrounded_grandchild() {
         env,
         working_dir: dir.clone(),
         config: serde_json::json!({}),
+        tty: false,
         output_tx: None,
+        stop: None,
     };
 
     let fut = exec.execute(input);
@@ -19,7 +19,9 @@ async fn test_execute_echo() {
         env: base_env(),
         working_dir: std::env::current_dir().unwrap(),
         config: serde_json::json!({}),
+        tty: false,
         output_tx: None,
+        stop: None,
     };
     let output = executor.execute(input).await.unwrap();
     assert_eq!(output.exit_code, 0);
@@ -34,7 +36,9 @@ async fn test_execute_multi_commands() {
         env: base_env(),
         working_dir: std::env::current_dir().unwrap(),
         config: serde_json::json!({}),
+        tty: false,
         output_tx: None,
+        stop: None,
     };
     let output = executor.execute(input).await.unwrap();
     assert_eq!(output.exit_code, 0);
@@ -51,7 +55,9 @@ async fn test_execute_failing_command() {
         env: base_env(),
         working_dir: std::env::current_dir().unwrap(),
         config: serde_json::json!({}),
+        tty: false,
         output_tx: None,
+        stop: None,
     };
     let output = executor.execute(input).await.unwrap();
     assert_ne!(output.exit_code, 0);
@@ -67,7 +73,9 @@ async fn test_invalid_utf8_does_not_truncate_output() {
         env: base_env(),
         working_dir: std::env::current_dir().unwrap(),
         config: serde_json::json!({}),
+        tty: false,
         output_tx: None,
+        stop: None,
     };
     let output = executor.execute(input).await.unwrap();
     assert_eq!(output.exit_code, 0);
@@ -90,7 +98,9 @@ async fn test_invalid_utf8_does_not_kill_child() {
         env: base_env(),
         working_dir: std::env::current_dir().unwrap(),
         config: serde_json::json!({}),
+        tty: false,
         output_tx: None,
+        stop: None,
     };
     let output = executor.execute(input).await.unwrap();
     assert_eq!(output.exit_code, 0, "child killed by SIGPIPE");
@@ -113,7 +123,9 @@ async fn test_env_vars_passed() {
         },
         working_dir: std::env::current_dir().unwrap(),
         config: serde_json::json!({}),
+        tty: false,
         output_tx: None,
+        stop: None,
     };
     let output = executor.execute(input).await.unwrap();
     let stdout = String::from_utf8(output.stdout).unwrap();
@@ -21,7 +21,9 @@ async fn run_in(dir: PathBuf, env: HashMap<String, String>, commands: &[&str]) -
         env,
         working_dir: dir,
         config: serde_json::json!({}),
+        tty: false,
         output_tx: None,
+        stop: None,
     };
     match executor.execute(input).await {
         Ok(output) => ExecutionOutcome {
@@ -32,7 +32,9 @@ async fn test_child_stdin_is_detached_from_aurora() {
         env: base_env(),
         working_dir: std::env::current_dir().unwrap(),
         config: serde_json::json!({}),
+        tty: false,
         output_tx: None,
+        stop: None,
     };
     let output = executor.execute(input).await.unwrap();
     assert_eq!(
@@ -52,7 +54,9 @@ async fn test_child_reading_stdin_gets_eof_immediately() {
         env: base_env(),
         working_dir: std::env::current_dir().unwrap(),
         config: serde_json::json!({}),
+        tty: false,
         output_tx: None,
+        stop: None,
     };
     let output = executor.execute(input).await.unwrap();
     assert_eq!(output.exit_code, 0);
@@ -0,0 +1,112 @@
+#![cfg(unix)]
+
+use aurora_executor_api::{ExecutionInput, Executor};
+use aurora_executor_local::LocalExecutor;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// The executor no longer inherits the ambient environment: at least PATH
+/// must therefore be provided so that `sh` and binaries can be resolved.
+fn base_env() -> HashMap<String, String> {
+    HashMap::from([(
+        "PATH".to_string(),
+        std::env::var("PATH").unwrap_or_default(),
+    )])
+}
+
+fn tty_input(commands: &[&str]) -> ExecutionInput {
+    ExecutionInput {
+        commands: commands.iter().map(|c| c.to_string()).collect(),
+        env: base_env(),
+        working_dir: std::env::current_dir().unwrap(),
+        config: serde_json::json!({}),
+        tty: true,
+        output_tx: None,
+        stop: None,
+    }
+}
+
+/// `tty = true` is the whole point: the command must see a terminal on its
+/// stdout and stderr, so tools keep their colours and progress output.
+#[tokio::test]
+async fn test_tty_beam_sees_a_terminal() {
+    let output = LocalExecutor::new()
+        .execute(tty_input(&["test -t 1 && test -t 2 && echo terminal"]))
+        .await
+        .unwrap();
+    assert_eq!(output.exit_code, 0);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "terminal");
+}
+
+/// Piped beams keep their pipes.
+#[tokio::test]
+async fn test_piped_beam_sees_no_terminal() {
+    let mut input = tty_input(&["test -t 1 && echo terminal || echo pipe"]);
+    input.tty = false;
+    let output = LocalExecutor::new().execute(input).await.unwrap();
+    assert_eq!(String::from_utf8(output.stdout).unwrap().trim(), "pipe");
+}
+
+/// stdin stays detached under a pty: the terminal is only ever read.
+#[tokio::test]
+async fn test_tty_beam_stdin_is_not_the_terminal() {
+    let output = LocalExecutor::new()
+        .execute(tty_input(&["test -t 0 && echo terminal || echo detached"]))
+        .await
+        .unwrap();
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "detached");
+}
+
+/// stderr shares the terminal, so it arrives in the single stream, in order;
+/// the terminal's `\r\n` line endings are normalised while a `\r` inside a
+/// line (a progress redraw) is kept for the consumer to interpret.
+#[tokio::test]
+async fn test_tty_merges_streams_and_keeps_inner_carriage_returns() {
+    let (tx, mut rx) = tokio::sync::mpsc::channel(16);
+    let mut input = tty_input(&[
+        "printf 'out\\n'",
+        "printf 'err\\n' >&2",
+        "printf '10%%\\r100%%\\n'",
+    ]);
+    input.output_tx = Some(tx);
+    let output = LocalExecutor::new().execute(input).await.unwrap();
+
+    let mut lines = vec![];
+    while let Ok((line, is_stderr)) = rx.try_recv() {
+        assert!(!is_stderr, "a terminal has a single stream");
+        lines.push(line);
+    }
+    assert_eq!(lines, vec!["out", "err", "10%\r100%"]);
+    assert!(output.stderr.is_empty());
+}
+
+/// A terminal needs a type: without one in the environment, tools would
+/// treat it as dumb and drop colours anyway. A declared `TERM` wins.
+#[tokio::test]
+async fn test_tty_beam_gets_a_term_unless_declared() {
+    let output = LocalExecutor::new()
+        .execute(tty_input(&["echo $TERM"]))
+        .await
+        .unwrap();
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "xterm-256color");
+
+    let mut input = tty_input(&["echo $TERM"]);
+    input.env.insert("TERM".to_string(), "screen".to_string());
+    let output = LocalExecutor::new().execute(input).await.unwrap();
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "screen");
+}
+
+/// The exit code survives the terminal, and the run ends once the child does
+/// (the master reports the end of output when the last writer closes).
+#[tokio::test]
+async fn test_tty_beam_reports_exit_code_and_terminates() {
+    let output = tokio::time::timeout(
+        Duration::from_secs(10),
+        LocalExecutor::new().execute(tty_input(&["echo bye", "exit 3"])),
+    )
+    .await
+    .expect("a tty beam must end when its command does")
+    .unwrap();
+    assert_eq!(output.exit_code, 3);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "bye");
+}
@@ -1,7 +1,10 @@
-use aurora_core::events::{BeamStatus, SchedulerEvent, SkipReason, WatchTrigger};
+use crate::picker::form::{FormField, FormKind, FormOutcome, ParamForm};
+use aurora_core::ansi::{self, StyledRun};
+use aurora_core::ast::Constraints;
+use aurora_core::events::{BeamStatus, CancelRequest, SchedulerEvent, SkipReason, WatchTrigger};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use std::cmp::Reverse;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::time::Instant;
 
 // ── BeamView ─────────────────────────────────────────────────────
@@ -13,7 +16,15 @@ pub struct BeamView {
     pub status: BeamStatus,
     pub stdout: Vec<String>,
     pub stderr: Vec<String>,
+    /// SGR styling of each `stdout` line, index-aligned with it (empty for an
+    /// unstyled line, and possibly shorter than `stdout` when lines are pushed
+    /// directly): the colours a `tty = true` beam wrote.
+    pub stdout_runs: Vec<Vec<StyledRun>>,
+    /// Same as `stdout_runs`, for `stderr`.
+    pub stderr_runs: Vec<Vec<StyledRun>>,
     pub started_at: Option<Instant>,
+    /// When its `BeamCompleted` arrived: the end of its bar in the timeline.
+    pub finished_at: Option<Instant>,
 }
 
 impl BeamView {
@@ -24,7 +35,10 @@ impl BeamView {
             status: BeamStatus::Pending,
             stdout: vec![],
             stderr: vec![],
+            stdout_runs: vec![],
+            stderr_runs: vec![],
             started_at: None,
+            finished_at: None,
         }
     }
 
@@ -32,6 +46,7 @@ impl BeamView {
         match &self.status {
             BeamStatus::Pending => "─",
             BeamStatus::Running => "⣴",
+            BeamStatus::Stopping => "◍",
             BeamStatus::Success { cached: true, .. } => "✦",
             BeamStatus::Success { cached: false, .. } => "✔",
             BeamStatus::Skipped { .. } => "◌",
@@ -66,7 +81,7 @@ impl BeamView {
     pub fn empty_placeholder(&self) -> &'static str {
         match self.status {
             BeamStatus::Pending => "(waiting to start)",
-            BeamStatus::Running => "(no output yet)",
+            BeamStatus::Running | BeamStatus::Stopping => "(no output yet)",
             _ => "(no output)",
         }
     }
@@ -88,6 +103,35 @@ impl BeamView {
         stdout.chain(sep).chain(stderr).chain(placeholder)
     }
 
+    /// The styled runs of the displayed line `idx` (in [`Self::iter_log_lines`]
+    /// order). Empty for the separator, the placeholder and unstyled lines.
+    pub fn log_line_runs(&self, idx: usize) -> &[StyledRun] {
+        let runs = if idx < self.stdout.len() {
+            self.stdout_runs.get(idx)
+        } else {
+            // Skip the stdout lines and the `── stderr ──` separator.
+            idx.checked_sub(self.stdout.len() + 1)
+                .and_then(|i| self.stderr_runs.get(i))
+        };
+        runs.map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Appends a raw output line, interpreted as a terminal would display it
+    /// (see [`aurora_core::ansi::render_line`]): the text is kept for search,
+    /// wrapping and copy, the colours as runs for rendering.
+    pub fn push_log_line(&mut self, raw: &str, is_stderr: bool) {
+        let rendered = ansi::render_line(raw);
+        let (lines, runs) = if is_stderr {
+            (&mut self.stderr, &mut self.stderr_runs)
+        } else {
+            (&mut self.stdout, &mut self.stdout_runs)
+        };
+        // Realign first if lines were pushed without runs.
+        runs.resize(lines.len(), Vec::new());
+        lines.push(rendered.text);
+        runs.push(rendered.runs);
+    }
+
     pub fn log_line_count(&self) -> usize {
         self.iter_log_lines().count()
     }
@@ -123,53 +167,16 @@ impl BeamView {
     }
 }
 
-/// Strips ANSI escape sequences and control characters from a captured
-/// log line. Tools (deptrac, phpcs, ...) emit raw color and cursor-positioning
-/// codes: left as-is, ratatui would write them to the terminal which would
-/// reinterpret them, corrupting the display (shifted text, leftovers from the
-/// previous screen). The carriage return is stripped because it would
-/// rewrite the line; the tab is kept.
+/// Reduces a captured log line to the text a terminal would display, without
+/// any escape sequence or control character. Tools (deptrac, phpcs, ...) emit
+/// raw color and cursor-positioning codes: left as-is, ratatui would write
+/// them to the terminal which would reinterpret them, corrupting the display
+/// (shifted text, leftovers from the previous screen). A carriage return
+/// rewrites the line from its start, as on a terminal, so a progress bar reads
+/// as its last frame; the tab is kept. The colours themselves are kept apart,
+/// see [`BeamView::push_log_line`].
 pub fn sanitize_log_line(input: &str) -> String {
-    let mut out = String::with_capacity(input.len());
-    let mut chars = input.chars().peekable();
-    while let Some(c) = chars.next() {
-        match c {
-            '\x1b' => match chars.peek() {
-                // CSI: ESC [ ... final byte in 0x40..=0x7E (e.g. SGR colors).
-                Some('[') => {
-                    chars.next();
-                    for n in chars.by_ref() {
-                        if ('\x40'..='\x7e').contains(&n) {
-                            break;
-                        }
-                    }
-                }
-                // OSC: ESC ] ... terminated by BEL (0x07) or ST (ESC \).
-                Some(']') => {
-                    chars.next();
-                    while let Some(n) = chars.next() {
-                        if n == '\x07' {
-                            break;
-                        }
-                        if n == '\x1b' {
-                            if chars.peek() == Some(&'\\') {
-                                chars.next();
-                            }
-                            break;
-                        }
-                    }
-                }
-                // Other short escape sequence: skip the next byte.
-                _ => {
-                    chars.next();
-                }
-            },
-            '\r' => {}
-            c if c.is_control() && c != '\t' => {}
-            c => out.push(c),
-        }
-    }
-    out
+    ansi::plain_text(input)
 }
 
 /// Splits a logical line into visual segments of at most `width` characters.
@@ -312,10 +319,24 @@ pub enum FocusPanel {
 
 #[derive(Debug, PartialEq)]
 pub enum PickerAction {
-    Launch(Vec<String>),
+    Launch(PickerLaunch),
     Quit,
 }
 
+/// What the picker launches.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PickerLaunch {
+    /// The beams to run, in selection order.
+    pub beams: Vec<String>,
+    /// The values entered in the params form, per beam, as `name=value`
+    /// arguments for `expand::bind_cli_args`. A beam without params, or whose
+    /// params all keep their defaults, has no entry.
+    pub args: BTreeMap<String, Vec<String>>,
+    /// The global variables changed in the variables form, as `name=value`
+    /// overrides (the `--var` syntax).
+    pub var_overrides: Vec<String>,
+}
+
 #[derive(Debug, PartialEq)]
 pub enum ExecutionAction {
     Quit,
@@ -344,9 +365,45 @@ pub struct PickerBeam {
     /// Display form with the param signature (`deploy <version> [env=staging]`);
     /// equals `name` for a param-less beam.
     pub signature: String,
-    /// True when the beam declares a param without a default: it cannot be
-    /// launched from the picker (no value input here), only from the CLI.
-    pub requires_args: bool,
+    /// The beam's declared params, in declaration order. Launching a beam
+    /// with params opens a form to fill them in.
+    pub params: Vec<PickerParam>,
+}
+
+impl PickerBeam {
+    /// True when a param has no default: the beam cannot run until a value
+    /// is entered for it.
+    pub fn requires_args(&self) -> bool {
+        self.params.iter().any(|p| p.default.is_none())
+    }
+}
+
+/// A beam param as offered by the params form.
+#[derive(Debug, Clone, Default)]
+pub struct PickerParam {
+    pub name: String,
+    pub default: Option<String>,
+    pub description: Option<String>,
+    /// The value entered the last time the beam was launched from the picker.
+    /// Pre-fills the form ahead of the default.
+    pub last: Option<String>,
+    /// Masked in the form.
+    pub sensitive: bool,
+    /// What the form accepts.
+    pub constraints: Constraints,
+}
+
+/// A global `variable` as offered by the variables form.
+#[derive(Debug, Clone)]
+pub struct PickerVariable {
+    pub name: String,
+    /// The current value: the default, or the `--var` override.
+    pub value: String,
+    pub description: Option<String>,
+    /// Masked in the form.
+    pub sensitive: bool,
+    /// What the form accepts.
+    pub constraints: Constraints,
 }
 
 pub struct PickerState {
@@ -358,9 +415,17 @@ pub struct PickerState {
     pub search_input: bool,
     pub show_deps: bool,
     pub checked: Vec<bool>,
-    /// Transient advisory shown in the footer (e.g. a parameterized beam
-    /// cannot be launched/checked from here). Cleared on the next keypress.
+    /// Transient advisory shown in the footer (e.g. a required param left
+    /// empty in the form). Cleared on the next keypress.
     pub notice: Option<String>,
+    /// The Beamfile's global variables, editable with `v`.
+    pub variables: Vec<PickerVariable>,
+    /// The values the variables had when the picker opened: a launch only
+    /// overrides the ones edited since.
+    variable_baseline: Vec<String>,
+    /// The open form (params before a launch, or the variables), capturing
+    /// the keyboard.
+    pub form: Option<ParamForm>,
 }
 
 impl PickerState {
@@ -375,9 +440,19 @@ impl PickerState {
             show_deps: true,
             checked: vec![false; len],
             notice: None,
+            variables: vec![],
+            variable_baseline: vec![],
+            form: None,
         }
     }
 
+    /// Offers the Beamfile's global variables for editing (`v`).
+    pub fn with_variables(mut self, variables: Vec<PickerVariable>) -> Self {
+        self.variable_baseline = variables.iter().map(|v| v.value.clone()).collect();
+        self.variables = variables;
+        self
+    }
+
     pub fn filtered(&self) -> Vec<(usize, &PickerBeam, u32)> {
         use crate::picker::fuzzy::fuzzy_score;
         if self.search.is_empty() {
@@ -426,6 +501,10 @@ impl PickerState {
         // advisory, not a persistent banner.
         self.notice = None;
 
+        if self.form.is_some() {
+            return self.handle_form_key(key);
+        }
+
         let count = self.filtered().len();
 
         // Filter input mode (`/`): keystrokes feed the filter. Enter
@@ -477,17 +556,11 @@ impl PickerState {
             KeyCode::End => self.selected = count.saturating_sub(1),
             KeyCode::Char(' ') => {
                 if let Some(idx) = self.filtered().get(self.selected).map(|(i, _, _)| *i) {
-                    if self.beams[idx].requires_args {
-                        self.notice = Some(format!(
-                            "'{}' requires arguments: run `aurora {}`",
-                            self.beams[idx].name, self.beams[idx].signature
-                        ));
-                    } else {
-                        self.checked[idx] = !self.checked[idx];
-                    }
+                    self.checked[idx] = !self.checked[idx];
                 }
             }
             KeyCode::Char('d') => self.show_deps = !self.show_deps,
+            KeyCode::Char('v') => self.open_variables_form(),
             _ => {}
         }
         None
@@ -514,31 +587,119 @@ impl PickerState {
     }
 
     /// Launch action: the checked beams if any exist, otherwise the beam
-    /// selected in the filtered list. A beam that requires arguments cannot be
-    /// launched from here (no value input in the picker): a notice is set
-    /// instead, pointing at the CLI invocation.
+    /// selected in the filtered list. When one of them declares params, the
+    /// params form opens first, and the launch happens when it is submitted.
     fn launch(&mut self) -> Option<PickerAction> {
-        let checked = self.selected_beam_indices();
-        if !checked.is_empty() {
-            let names = checked
-                .iter()
-                .map(|&i| self.beams[i].name.clone())
-                .collect();
-            return Some(PickerAction::Launch(names));
+        let mut indices = self.selected_beam_indices();
+        if indices.is_empty() {
+            let (idx, _, _) = self.filtered().get(self.selected).copied()?;
+            indices.push(idx);
         }
-        let beam = {
-            let filtered = self.filtered();
-            let (_, b, _) = filtered.get(self.selected)?;
-            (*b).clone()
-        };
-        if beam.requires_args {
-            self.notice = Some(format!(
-                "'{}' requires arguments: run `aurora {}`",
-                beam.name, beam.signature
-            ));
-            return None;
+        let fields: Vec<FormField> = indices
+            .iter()
+            .flat_map(|&i| {
+                let beam = &self.beams[i];
+                beam.params.iter().map(|p| FormField {
+                    beam: Some(beam.name.clone()),
+                    name: p.name.clone(),
+                    value: p
+                        .last
+                        .clone()
+                        .or_else(|| p.default.clone())
+                        .unwrap_or_default(),
+                    default: p.default.clone(),
+                    description: p.description.clone(),
+                    sensitive: p.sensitive,
+                    constraints: p.constraints.clone(),
+                })
+            })
+            .collect();
+        let beams: Vec<String> = indices
+            .iter()
+            .map(|&i| self.beams[i].name.clone())
+            .collect();
+        if fields.is_empty() {
+            return Some(PickerAction::Launch(self.launch_of(beams, None)));
+        }
+        self.form = Some(ParamForm::new(FormKind::Params { beams }, fields));
+        None
+    }
+
+    fn open_variables_form(&mut self) {
+        if self.variables.is_empty() {
+            self.notice = Some("the Beamfile declares no global variable".to_string());
+            return;
+        }
+        let fields = self
+            .variables
+            .iter()
+            .zip(&self.variable_baseline)
+            .map(|(v, baseline)| FormField {
+                beam: None,
+                name: v.name.clone(),
+                value: v.value.clone(),
+                default: Some(baseline.clone()),
+                description: v.description.clone(),
+                sensitive: v.sensitive,
+                constraints: v.constraints.clone(),
+            })
+            .collect();
+        self.form = Some(ParamForm::new(FormKind::Variables, fields));
+    }
+
+    fn handle_form_key(&mut self, key: KeyEvent) -> Option<PickerAction> {
+        let form = self.form.as_mut()?;
+        match form.handle_key(key) {
+            FormOutcome::Editing => None,
+            FormOutcome::Cancelled => {
+                self.form = None;
+                None
+            }
+            FormOutcome::Missing(message) | FormOutcome::Invalid(message) => {
+                self.notice = Some(message);
+                None
+            }
+            FormOutcome::Submitted => {
+                let form = self.form.take()?;
+                match &form.kind {
+                    FormKind::Params { beams } => Some(PickerAction::Launch(
+                        self.launch_of(beams.clone(), Some(&form)),
+                    )),
+                    FormKind::Variables => {
+                        for (variable, field) in self.variables.iter_mut().zip(form.fields) {
+                            variable.value = field.value;
+                        }
+                        None
+                    }
+                }
+            }
+        }
+    }
+
+    /// The launch of `beams`, with the values entered in the params `form`
+    /// (if any) and the variables edited so far.
+    fn launch_of(&self, beams: Vec<String>, form: Option<&ParamForm>) -> PickerLaunch {
+        let args = form
+            .map(|form| {
+                beams
+                    .iter()
+                    .map(|b| (b.clone(), form.args_for(b)))
+                    .filter(|(_, args)| !args.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let var_overrides = self
+            .variables
+            .iter()
+            .zip(&self.variable_baseline)
+            .filter(|(v, baseline)| &v.value != *baseline)
+            .map(|(v, _)| format!("{}={}", v.name, v.value))
+            .collect();
+        PickerLaunch {
+            beams,
+            args,
+            var_overrides,
         }
-        Some(PickerAction::Launch(vec![beam.name]))
     }
 }
 
@@ -586,6 +747,9 @@ impl WatchUiState {
 
 // ── ExecutionState ───────────────────────────────────────────────
 
+/// Why `r`, `x` and `X` do nothing in a replay.
+const REPLAY_NOTICE: &str = "this is a replay of a recorded run: nothing to rerun or cancel";
+
 pub struct ExecutionState {
     pub beams: Vec<BeamView>,
     pub selected: usize,
@@ -608,9 +772,20 @@ pub struct ExecutionState {
     /// `r` on it surfaces a notice instead of spawning an empty run. Empty by
     /// default (every beam launchable).
     non_launchable: HashSet<String>,
+    /// The view plays back a recorded run (`aurora replay`): nothing runs, so
+    /// rerun and cancellation are refused with a notice.
+    replay: bool,
     /// Transient advisory shown in the footer (e.g. a beam cannot be rerun from
     /// the sidebar). Cleared on the next keypress, like the picker's notice.
     pub notice: Option<String>,
+    /// A cancellation awaiting confirmation (`x` / `X`): while set, the
+    /// confirmation popup is shown and captures the keyboard.
+    pub confirm_cancel: Option<CancelRequest>,
+    /// The timeline panel (`t`) is shown in place of the logs.
+    pub show_timeline: bool,
+    /// When the current run (or rerun) was launched: the origin of the
+    /// timeline.
+    pub run_started_at: Instant,
 }
 
 impl ExecutionState {
@@ -629,7 +804,11 @@ impl ExecutionState {
             filter_input: false,
             run_set,
             non_launchable: HashSet::new(),
+            replay: false,
             notice: None,
+            confirm_cancel: None,
+            show_timeline: false,
+            run_started_at: Instant::now(),
         }
     }
 
@@ -653,6 +832,11 @@ impl ExecutionState {
         !self.non_launchable.contains(name)
     }
 
+    /// Turns the view into the playback of a recorded run.
+    pub fn set_replay(&mut self) {
+        self.replay = true;
+    }
+
     /// Number of beams in the current run: the status bar's denominator.
     pub fn run_total(&self) -> usize {
         self.beams
@@ -742,8 +926,19 @@ impl ExecutionState {
                 }
             }
             SchedulerEvent::BeamCompleted { name, status } => {
+                // Confirming the cancellation of a beam that just ended would
+                // cancel nothing: drop the question.
+                if self.confirm_cancel.as_ref() == Some(&CancelRequest::Beam(name.clone())) {
+                    self.confirm_cancel = None;
+                }
                 if let Some(b) = self.beams.iter_mut().find(|b| b.name == name) {
                     b.status = status;
+                    b.finished_at = Some(Instant::now());
+                }
+            }
+            SchedulerEvent::BeamStopping { name } => {
+                if let Some(b) = self.beams.iter_mut().find(|b| b.name == name) {
+                    b.status = BeamStatus::Stopping;
                 }
             }
             SchedulerEvent::BeamOutput {
@@ -752,26 +947,90 @@ impl ExecutionState {
                 is_stderr,
             } => {
                 if let Some(b) = self.beams.iter_mut().find(|b| b.name == name) {
-                    let line = sanitize_log_line(&line);
-                    if is_stderr {
-                        b.stderr.push(line);
-                    } else {
-                        b.stdout.push(line);
-                    }
+                    b.push_log_line(&line, is_stderr);
                 }
             }
             SchedulerEvent::Warning { name, message } => {
                 if let Some(b) = self.beams.iter_mut().find(|b| b.name == name) {
-                    b.stderr
-                        .push(sanitize_log_line(&format!("warning: {message}")));
+                    b.push_log_line(&format!("warning: {message}"), true);
                 }
             }
+            // The timeline draws whole beams; phases are for `--trace`.
+            SchedulerEvent::BeamPhase { .. } => {}
             SchedulerEvent::AllDone { success } => {
                 self.done = Some(success);
+                self.confirm_cancel = None;
             }
         }
     }
 
+    /// `x`: asks to confirm cancelling the selected beam (and, through it, its
+    /// dependents). Only a running beam can be cancelled; any other answers
+    /// with a notice.
+    pub fn request_cancel_selected(&mut self) {
+        if self.replay {
+            self.notice = Some(REPLAY_NOTICE.to_string());
+            return;
+        }
+        let beam = &self.beams[self.selected];
+        match beam.status {
+            BeamStatus::Running => {
+                self.confirm_cancel = Some(CancelRequest::Beam(beam.name.clone()));
+            }
+            BeamStatus::Stopping => {
+                self.notice = Some(format!("'{}' is already stopping", beam.name));
+            }
+            _ => self.notice = Some(format!("'{}' is not running", beam.name)),
+        }
+    }
+
+    /// `X`: asks to confirm cancelling the whole run. The view stays open on
+    /// the cancelled run, for inspection and rerun.
+    pub fn request_cancel_run(&mut self) {
+        if self.replay {
+            self.notice = Some(REPLAY_NOTICE.to_string());
+        } else if self.done.is_some() {
+            self.notice = Some("the run is already over".to_string());
+        } else {
+            self.confirm_cancel = Some(CancelRequest::Run);
+        }
+    }
+
+    /// Answers the confirmation popup: `y` / `Enter` returns the cancellation
+    /// to send, `n` / `Esc` dismisses it. Any other key leaves the question
+    /// open.
+    pub fn handle_confirm_key(&mut self, key: KeyEvent) -> Option<CancelRequest> {
+        match key.code {
+            KeyCode::Char('y') | KeyCode::Enter => self.confirm_cancel.take(),
+            KeyCode::Char('n') | KeyCode::Esc => {
+                self.confirm_cancel = None;
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// A rerun asked for from outside the TUI (the control socket): what `r`
+    /// does on `name`, which becomes the selection. Returns the rerun's root
+    /// and the beams it takes as already done, or why it cannot start now.
+    pub fn remote_rerun(&mut self, name: &str) -> Result<(String, Vec<String>), String> {
+        if self.done.is_none() {
+            return Err("a run is in progress".to_string());
+        }
+        let Some(index) = self.beams.iter().position(|b| b.name == name) else {
+            return Err(format!("unknown beam `{name}`"));
+        };
+        if !self.is_launchable(name) {
+            return Err(format!(
+                "`{name}` declares a required param and cannot be launched from the TUI"
+            ));
+        }
+        self.selected = index;
+        let (root, to_rerun, pre_success) = self.compute_rerun(index);
+        self.reset_for_rerun(&to_rerun);
+        Ok((root, pre_success))
+    }
+
     /// Computes the beams to rerun starting from the selected beam.
     /// Returns (root_name, to_rerun, pre_success).
     pub fn compute_rerun(&self, selected: usize) -> (String, Vec<String>, Vec<String>) {
@@ -815,17 +1074,22 @@ impl ExecutionState {
         (root_name, to_rerun, pre_success)
     }
 
-    /// Resets the listed beams to Pending and clears their logs. Also resets exec.done.
+    /// Resets the listed beams to Pending and clears their logs. Also resets
+    /// exec.done and restarts the timeline.
     pub fn reset_for_rerun(&mut self, names: &[String]) {
         for beam in self.beams.iter_mut() {
             if names.contains(&beam.name) {
                 beam.status = BeamStatus::Pending;
                 beam.stdout.clear();
                 beam.stderr.clear();
+                beam.stdout_runs.clear();
+                beam.stderr_runs.clear();
                 beam.started_at = None;
+                beam.finished_at = None;
             }
         }
         self.done = None;
+        self.run_started_at = Instant::now();
     }
 
     pub fn handle_key(&mut self, key: KeyEvent) -> Option<ExecutionAction> {
@@ -849,6 +1113,15 @@ impl ExecutionState {
                 self.show_deps = !self.show_deps;
                 None
             }
+            KeyCode::Char('t') => {
+                // The timeline is navigated from the beam list: the selected
+                // bar follows the sidebar selection.
+                self.show_timeline = !self.show_timeline;
+                if self.show_timeline {
+                    self.focus = FocusPanel::Beams;
+                }
+                None
+            }
             KeyCode::Char('q') => Some(ExecutionAction::Quit),
             KeyCode::Enter => Some(ExecutionAction::OpenLogView {
                 beam_index: self.selected,
@@ -865,6 +1138,10 @@ impl ExecutionState {
                             | BeamStatus::Skipped { .. }
                     );
                     let name = beam.name.clone();
+                    if self.replay {
+                        self.notice = Some(REPLAY_NOTICE.to_string());
+                        return None;
+                    }
                     if rerunnable {
                         // A beam with a required param has no runnable instance:
                         // rerunning it would schedule an unknown root and reset
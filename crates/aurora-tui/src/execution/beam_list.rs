@@ -35,7 +35,7 @@ pub fn render_beam_list(
             let in_run = state.is_in_run(&beam.name);
             let symbol = match &beam.status {
                 _ if !in_run => "·",
-                BeamStatus::Running => {
+                BeamStatus::Running | BeamStatus::Stopping => {
                     SPINNER_FRAMES[(tick / 2 % SPINNER_FRAMES.len() as u64) as usize]
                 }
                 _ => beam.status_symbol(),
@@ -103,6 +103,8 @@ fn duration_label(status: &BeamStatus, beam: &BeamView) -> String {
         | BeamStatus::FailedAllowed { duration, .. } => {
             format!(" [{}]", compact_duration(duration.as_secs_f64(), true))
         }
+        // Transient: the command was asked to stop and is winding down.
+        BeamStatus::Stopping => " [stopping…]".to_string(),
         BeamStatus::Running => {
             if let Some(t) = beam.started_at {
                 format!(" [{}]", compact_duration(t.elapsed().as_secs_f64(), false))
@@ -116,7 +118,7 @@ fn duration_label(status: &BeamStatus, beam: &BeamView) -> String {
 
 /// Compact and bounded format for a duration: under a minute « 12.34s » (or « 12s »
 /// without decimals), then « 1m02s », then « 1h05m ». Width bounded to ~7 columns.
-fn compact_duration(secs: f64, decimals: bool) -> String {
+pub(crate) fn compact_duration(secs: f64, decimals: bool) -> String {
     if secs < 60.0 {
         if decimals {
             format!("{:.2}s", secs)
@@ -136,7 +138,7 @@ fn compact_duration(secs: f64, decimals: bool) -> String {
 
 /// Fits a beam name to `budget` columns: pads with spaces if it is
 /// shorter, truncates with « … » if it is longer.
-fn fit_name(name: &str, budget: usize) -> String {
+pub(crate) fn fit_name(name: &str, budget: usize) -> String {
     let count = name.chars().count();
     if budget == 0 {
         String::new()
@@ -151,7 +153,7 @@ fn fit_name(name: &str, budget: usize) -> String {
     }
 }
 
-fn status_color(status: &BeamStatus) -> Color {
+pub(crate) fn status_color(status: &BeamStatus) -> Color {
     match status {
         BeamStatus::Success { .. } => Color::Green,
         BeamStatus::Skipped { .. } => Color::Cyan,
@@ -159,6 +161,7 @@ fn status_color(status: &BeamStatus) -> Color {
         BeamStatus::FailedAllowed { .. } => Color::Yellow,
         BeamStatus::Cancelled => Color::Magenta,
         BeamStatus::Running => Color::Yellow,
+        BeamStatus::Stopping => Color::Magenta,
         BeamStatus::Pending => Color::DarkGray,
     }
 }
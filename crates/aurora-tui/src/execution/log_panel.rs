@@ -1,4 +1,5 @@
 use crate::app::{wrap_log_line, BeamView, LogKind, LogSearch, LogViewState};
+use aurora_core::ansi::{self, StyledRun};
 use ratatui::{
     layout::Rect,
     style::{Color, Modifier, Style},
@@ -31,26 +32,26 @@ pub fn render_log_panel(
             LogKind::Placeholder => Style::default().fg(Color::DarkGray),
         };
         let highlightable = matches!(kind, LogKind::Stdout | LogKind::Stderr);
+        let runs = beam.log_line_runs(idx);
+        // Byte offset of the segment in the line: the runs are relative to the
+        // whole line, the wrapped segments are consecutive slices of it.
+        let mut offset = 0;
         for segment in wrap_log_line(text, width) {
-            let line = match &needle {
-                Some(n) if highlightable => {
-                    let ranges = match_ranges(segment, n);
-                    if ranges.is_empty() {
-                        Line::from(Span::styled(segment.to_string(), base))
-                    } else {
-                        let hl = if current_line == Some(idx) {
-                            Style::default().fg(Color::Black).bg(Color::Yellow)
-                        } else {
-                            Style::default()
-                                .fg(Color::Yellow)
-                                .add_modifier(Modifier::BOLD)
-                        };
-                        Line::from(highlight_spans(segment, &ranges, base, hl))
-                    }
-                }
-                _ => Line::from(Span::styled(segment.to_string(), base)),
+            let ranges = match &needle {
+                Some(n) if highlightable => match_ranges(segment, n),
+                _ => vec![],
             };
-            lines.push(line);
+            let hl = if current_line == Some(idx) {
+                Style::default().fg(Color::Black).bg(Color::Yellow)
+            } else {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            };
+            lines.push(Line::from(styled_spans(
+                segment, offset, runs, &ranges, base, hl,
+            )));
+            offset += segment.len();
         }
     }
 
@@ -148,23 +149,87 @@ fn match_ranges(haystack: &str, needle_lower: &str) -> Vec<(usize, usize)> {
         .collect()
 }
 
-fn highlight_spans(
+/// Splits `text` (a wrapped segment starting at byte `offset` of its line)
+/// into spans: the line's SGR `runs` are patched over `base`, and the search
+/// `ranges` (relative to `text`) over both, so a match stays visible on a
+/// coloured line.
+fn styled_spans(
     text: &str,
+    offset: usize,
+    runs: &[StyledRun],
     ranges: &[(usize, usize)],
     base: Style,
     hl: Style,
 ) -> Vec<Span<'static>> {
-    let mut spans = vec![];
-    let mut last = 0;
+    let runs: Vec<(usize, usize, Style)> = runs
+        .iter()
+        .filter(|r| r.end > offset && r.start < offset + text.len())
+        .map(|r| {
+            (
+                r.start.saturating_sub(offset),
+                (r.end - offset).min(text.len()),
+                to_ratatui(&r.style),
+            )
+        })
+        .collect();
+
+    // Every run and match boundary starts a new span.
+    let mut cuts: Vec<usize> = vec![0, text.len()];
+    for &(start, end, _) in &runs {
+        cuts.extend([start, end]);
+    }
     for &(start, end) in ranges {
-        if start > last {
-            spans.push(Span::styled(text[last..start].to_string(), base));
+        cuts.extend([start, end]);
+    }
+    cuts.sort_unstable();
+    cuts.dedup();
+
+    let mut spans = vec![];
+    for pair in cuts.windows(2) {
+        let (start, end) = (pair[0], pair[1]);
+        if start == end {
+            continue;
+        }
+        let mut style = base;
+        if let Some(&(_, _, run)) = runs.iter().find(|&&(s, e, _)| s <= start && end <= e) {
+            style = style.patch(run);
         }
-        spans.push(Span::styled(text[start..end].to_string(), hl));
-        last = end;
+        if ranges.iter().any(|&(s, e)| s <= start && end <= e) {
+            style = style.patch(hl);
+        }
+        spans.push(Span::styled(text[start..end].to_string(), style));
     }
-    if last < text.len() {
-        spans.push(Span::styled(text[last..].to_string(), base));
+    if spans.is_empty() {
+        spans.push(Span::styled(String::new(), base));
     }
     spans
 }
+
+/// Maps a captured SGR style onto ratatui's. Palette colours keep their index,
+/// so the user's terminal theme decides how "red" looks, as it would for the
+/// tool run directly.
+fn to_ratatui(style: &ansi::Style) -> Style {
+    let color = |c: ansi::Color| match c {
+        ansi::Color::Indexed(i) => Color::Indexed(i),
+        ansi::Color::Rgb(r, g, b) => Color::Rgb(r, g, b),
+    };
+    let mut out = Style::default();
+    if let Some(fg) = style.fg {
+        out = out.fg(color(fg));
+    }
+    if let Some(bg) = style.bg {
+        out = out.bg(color(bg));
+    }
+    for (on, modifier) in [
+        (style.bold, Modifier::BOLD),
+        (style.dim, Modifier::DIM),
+        (style.italic, Modifier::ITALIC),
+        (style.underline, Modifier::UNDERLINED),
+        (style.reverse, Modifier::REVERSED),
+    ] {
+        if on {
+            out = out.add_modifier(modifier);
+        }
+    }
+    out
+}
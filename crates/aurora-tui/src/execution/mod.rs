@@ -2,3 +2,4 @@ pub mod beam_list;
 pub mod deps_panel;
 pub mod log_panel;
 pub mod split_layout;
+pub mod timeline;
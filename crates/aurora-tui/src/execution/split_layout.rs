@@ -1,5 +1,5 @@
 use crate::app::{ExecutionState, FocusPanel, LogSearch, LogViewState};
-use crate::execution::{beam_list, deps_panel, log_panel};
+use crate::execution::{beam_list, deps_panel, log_panel, timeline};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Style},
@@ -18,6 +18,7 @@ pub struct ExecutionLayout {
     pub beams: Rect,
     /// Present only when the dependency panel is shown (key « d »).
     pub deps: Option<Rect>,
+    /// The logs, or the timeline in their place (key « t »).
     pub logs: Rect,
     pub footer: Rect,
 }
@@ -77,14 +78,18 @@ pub fn render_execution(
     if let Some(deps) = layout.deps {
         deps_panel::render_deps_panel(f, exec, deps);
     }
-    log_panel::render_log_panel(
-        f,
-        beam,
-        log_state,
-        Some(search),
-        layout.logs,
-        !beams_focused,
-    );
+    if exec.show_timeline {
+        timeline::render_timeline(f, exec, layout.logs, !beams_focused);
+    } else {
+        log_panel::render_log_panel(
+            f,
+            beam,
+            log_state,
+            Some(search),
+            layout.logs,
+            !beams_focused,
+        );
+    }
 
     let footer = Layout::default()
         .direction(Direction::Vertical)
@@ -142,6 +147,9 @@ pub fn render_execution(
             crate::widgets::help_popup::HelpContext::Execution,
         );
     }
+    if let Some(request) = &exec.confirm_cancel {
+        crate::widgets::confirm_popup::render_confirm_popup(f, area, request);
+    }
 }
 
 /// Prompt shown while typing the beam list filter.
@@ -0,0 +1,279 @@
+//! Timeline panel (key « t »): each beam of the run drawn as a bar over
+//! wall-clock time, so where the parallelism was lost reads at a glance.
+//!
+//! A bar has two segments: queued (every dependency done, waiting for a
+//! parallelism slot) then active (from `BeamStarted` to `BeamCompleted`, or
+//! to now while running). The times come from the events alone, recorded as
+//! they arrive (see [`ExecutionState::apply_event`]). The critical path is the
+//! chain of beams that gated the end of the run: from the beam that finished
+//! last, back through the dependency each one waited for last.
+
+use crate::app::{BeamView, ExecutionState};
+use crate::execution::beam_list::{compact_duration, fit_name, status_color};
+use aurora_core::events::{BeamStatus, SkipReason};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+/// One row of the timeline. Offsets are relative to the start of the run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimelineBar {
+    /// Index of the beam in [`ExecutionState::beams`].
+    pub index: usize,
+    /// Ready (every dependency done) to started, when the beam had to wait.
+    pub queued: Option<(Duration, Duration)>,
+    /// Started to completed, or to now while the beam runs.
+    pub active: Option<(Duration, Duration)>,
+    /// The result was replayed from the cache rather than run.
+    pub cached: bool,
+    pub critical: bool,
+}
+
+/// The bars of the beams in the run, in sidebar order, with the time they
+/// span (at least one millisecond, so an instant run still scales).
+pub fn timeline_bars(state: &ExecutionState, now: Instant) -> (Vec<TimelineBar>, Duration) {
+    let origin = state.run_started_at;
+    let offset = |t: Instant| t.saturating_duration_since(origin);
+    let critical = critical_path(state, now);
+
+    let mut span = Duration::from_millis(1);
+    let bars = state
+        .visible_indices()
+        .into_iter()
+        .filter(|&i| state.is_in_run(&state.beams[i].name))
+        .map(|i| {
+            let beam = &state.beams[i];
+            let started = started_in_run(beam, origin);
+            let end = started.map(|_| beam.finished_at.unwrap_or(now).max(origin));
+            let waited_until = started.unwrap_or(now);
+            let queued = match ready_at(state, beam, origin) {
+                Some(ready) if beam.finished_at.is_none() || started.is_some() => {
+                    (ready < waited_until).then(|| (offset(ready), offset(waited_until)))
+                }
+                _ => None,
+            };
+            let active = started.zip(end).map(|(s, e)| (offset(s), offset(e)));
+            for (_, to) in queued.iter().chain(active.iter()) {
+                span = span.max(*to);
+            }
+            TimelineBar {
+                index: i,
+                queued,
+                active,
+                cached: is_cached(&beam.status),
+                critical: critical.contains(&i),
+            }
+        })
+        .collect();
+    (bars, span)
+}
+
+/// Indices of the beams on the critical path: the in-run beam that finished
+/// last (or is still running), then, repeatedly, the dependency it waited
+/// for last. Empty before anything has started.
+pub fn critical_path(state: &ExecutionState, now: Instant) -> HashSet<usize> {
+    let origin = state.run_started_at;
+    let index: HashMap<&str, usize> = state
+        .beams
+        .iter()
+        .enumerate()
+        .map(|(i, b)| (b.name.as_str(), i))
+        .collect();
+    let end_of = |i: usize| {
+        let beam = &state.beams[i];
+        started_in_run(beam, origin).map(|_| beam.finished_at.unwrap_or(now))
+    };
+
+    let mut path = HashSet::new();
+    let mut current = (0..state.beams.len())
+        .filter(|&i| state.is_in_run(&state.beams[i].name))
+        .filter_map(|i| end_of(i).map(|end| (end, i)))
+        .max()
+        .map(|(_, i)| i);
+    while let Some(i) = current {
+        if !path.insert(i) {
+            break;
+        }
+        current = state.beams[i]
+            .depends_on
+            .iter()
+            .filter_map(|dep| index.get(dep.as_str()).copied())
+            .filter_map(|d| end_of(d).map(|end| (end, d)))
+            .max()
+            .map(|(_, d)| d);
+    }
+    path
+}
+
+/// When the beam started, if it did in this run (a beam reused from a
+/// previous run, on a rerun, started before `origin`).
+fn started_in_run(beam: &BeamView, origin: Instant) -> Option<Instant> {
+    beam.started_at.filter(|&t| t >= origin)
+}
+
+/// When every dependency of `beam` was done, so the scheduler could start
+/// it: the latest dependency completion, or the run start without any.
+/// `None` while a dependency is still to complete.
+fn ready_at(state: &ExecutionState, beam: &BeamView, origin: Instant) -> Option<Instant> {
+    let mut ready = origin;
+    for dep in &beam.depends_on {
+        let Some(dep) = state.beams.iter().find(|b| &b.name == dep) else {
+            continue;
+        };
+        ready = ready.max(dep.finished_at?);
+    }
+    Some(ready)
+}
+
+fn is_cached(status: &BeamStatus) -> bool {
+    matches!(
+        status,
+        BeamStatus::Success { cached: true, .. }
+            | BeamStatus::Skipped {
+                reason: SkipReason::Cached
+            }
+    )
+}
+
+/// Cells of a bar `width` columns wide scaled over `span`: `░` queued, `█`
+/// active, `▒` cached. A non-empty active segment takes at least one cell, so
+/// a beam that ran in a few milliseconds is still visible.
+fn bar_cells(bar: &TimelineBar, span: Duration, width: usize) -> Vec<char> {
+    let mut cells = vec![' '; width];
+    if width == 0 {
+        return cells;
+    }
+    let col =
+        |d: Duration| ((d.as_secs_f64() / span.as_secs_f64()) * width as f64).round() as usize;
+    let mut fill = |(from, to): (Duration, Duration), ch: char, min_one: bool| {
+        let start = col(from).min(width - 1);
+        let mut end = col(to).min(width);
+        if min_one && end <= start {
+            end = start + 1;
+        }
+        for cell in &mut cells[start..end] {
+            *cell = ch;
+        }
+    };
+    if let Some(queued) = bar.queued {
+        fill(queued, '░', false);
+    }
+    if let Some(active) = bar.active {
+        fill(active, if bar.cached { '▒' } else { '█' }, true);
+    }
+    cells
+}
+
+pub fn render_timeline(f: &mut Frame, state: &ExecutionState, area: Rect, focused: bool) {
+    let (bars, span) = timeline_bars(state, Instant::now());
+    let inner_w = area.width.saturating_sub(2) as usize;
+    let inner_h = area.height.saturating_sub(2) as usize;
+
+    // Name column, bar, then the duration on the right (as in the sidebar).
+    let name_w = bars
+        .iter()
+        .map(|b| state.beams[b.index].name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .min(inner_w / 3);
+    let dur_w = 8;
+    let bar_w = inner_w.saturating_sub(name_w + dur_w + 4);
+
+    let mut lines: Vec<Line> = bars
+        .iter()
+        .map(|bar| {
+            let beam = &state.beams[bar.index];
+            let selected = bar.index == state.selected;
+            let name_style = match (selected, bar.critical) {
+                (true, _) => Style::default()
+                    .fg(Color::White)
+                    .add_modifier(Modifier::BOLD),
+                (false, true) => Style::default().fg(Color::Yellow),
+                (false, false) => Style::default().fg(Color::Gray),
+            };
+            let marker = if bar.critical { "◆ " } else { "  " };
+            let color = if bar.cached {
+                Color::Cyan
+            } else {
+                status_color(&beam.status)
+            };
+            let cells = bar_cells(bar, span, bar_w);
+            let mut spans = vec![
+                Span::styled(marker, Style::default().fg(Color::Yellow)),
+                Span::styled(fit_name(&beam.name, name_w), name_style),
+                Span::raw(" "),
+            ];
+            // Consecutive cells of the same kind share a span.
+            let mut run = String::new();
+            let mut run_ch = None;
+            for ch in cells.into_iter().chain(std::iter::once('\0')) {
+                if Some(ch) != run_ch && !run.is_empty() {
+                    let style = match run_ch {
+                        Some('░') => Style::default().fg(Color::DarkGray),
+                        _ => Style::default().fg(color),
+                    };
+                    spans.push(Span::styled(std::mem::take(&mut run), style));
+                }
+                run_ch = Some(ch);
+                if ch != '\0' {
+                    run.push(ch);
+                }
+            }
+            let duration = bar
+                .active
+                .map(|(from, to)| compact_duration((to - from).as_secs_f64(), true))
+                .unwrap_or_default();
+            spans.push(Span::styled(
+                format!(" {duration:>7}"),
+                Style::default().fg(Color::DarkGray),
+            ));
+            Line::from(spans)
+        })
+        .collect();
+
+    // Keep the selected beam in view when the run has more rows than fit.
+    let selected_row = bars.iter().position(|b| b.index == state.selected);
+    let body_h = inner_h.saturating_sub(1);
+    let scroll = selected_row
+        .map(|row| (row + 1).saturating_sub(body_h))
+        .unwrap_or(0);
+    let mut visible: Vec<Line> = lines
+        .drain(scroll.min(lines.len())..)
+        .take(body_h)
+        .collect();
+    visible.resize(body_h, Line::from(""));
+    visible.push(Line::from(vec![
+        Span::styled(" ░", Style::default().fg(Color::DarkGray)),
+        Span::raw(" queued  "),
+        Span::raw("█ running  "),
+        Span::styled("▒", Style::default().fg(Color::Cyan)),
+        Span::raw(" cached  "),
+        Span::styled("◆", Style::default().fg(Color::Yellow)),
+        Span::raw(" critical path"),
+    ]));
+
+    let border_style = if focused {
+        Style::default().fg(Color::Yellow)
+    } else {
+        Style::default()
+    };
+    let title = format!(
+        " Timeline: {} (Enter logs, t close) ",
+        compact_duration(span.as_secs_f64(), true)
+    );
+    f.render_widget(
+        Paragraph::new(visible).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .border_style(border_style),
+        ),
+        area,
+    );
+}
@@ -5,12 +5,12 @@ pub mod picker;
 pub mod widgets;
 
 use anyhow::Result;
-pub use app::PickerBeam;
 use app::{
     ExecutionAction, ExecutionState, FocusPanel, LogSearch, LogViewState, PickerAction,
     PickerState, WatchUiState,
 };
-use aurora_core::events::{BeamStatus, SchedulerEvent, WatchTrigger};
+pub use app::{PickerBeam, PickerLaunch, PickerParam, PickerVariable};
+use aurora_core::events::{BeamStatus, CancelRequest, RerunRequest, SchedulerEvent, WatchTrigger};
 use crossterm::{
     event::{self, Event, KeyCode, KeyModifiers},
     execute,
@@ -52,30 +52,109 @@ pub struct ReloadResult {
     /// with no value to bind).
     pub non_launchable: Vec<String>,
     pub rx: mpsc::Receiver<SchedulerEvent>,
-    pub cancel_tx: mpsc::UnboundedSender<String>,
+    pub cancel_tx: mpsc::UnboundedSender<CancelRequest>,
 }
 
 #[allow(clippy::too_many_arguments)]
 pub async fn run_execution_tui(
+    beam_info: Vec<(String, Vec<String>)>,
+    target: String,
+    run_set: Vec<String>,
+    non_launchable: Vec<String>,
+    watch_preset: bool,
+    rx: mpsc::Receiver<SchedulerEvent>,
+    cancel_tx: mpsc::UnboundedSender<CancelRequest>,
+    rerun: impl Fn(
+        String,
+        Vec<String>,
+    ) -> (
+        mpsc::Receiver<SchedulerEvent>,
+        mpsc::UnboundedSender<CancelRequest>,
+    ),
+    start_watch: impl Fn(
+        String,
+    )
+        -> anyhow::Result<(Box<dyn Send>, mpsc::Receiver<WatchTrigger>, Vec<String>)>,
+    reload: impl Fn() -> anyhow::Result<ReloadResult>,
+    rerun_requests: mpsc::UnboundedReceiver<RerunRequest>,
+    profile: Option<String>,
+) -> Result<()> {
+    execution_tui(
+        beam_info,
+        target,
+        run_set,
+        non_launchable,
+        watch_preset,
+        rx,
+        cancel_tx,
+        rerun,
+        start_watch,
+        reload,
+        rerun_requests,
+        false,
+        profile,
+    )
+    .await
+}
+
+/// The execution view over a recorded run (`aurora replay`): `rx` carries the
+/// recording's events. Nothing runs, so rerun, cancellation and watch are
+/// refused.
+pub async fn run_replay_tui(
+    beam_info: Vec<(String, Vec<String>)>,
+    target: String,
+    rx: mpsc::Receiver<SchedulerEvent>,
+) -> Result<()> {
+    let run_set = beam_info.iter().map(|(name, _)| name.clone()).collect();
+    // Held for the whole view, so the channel never reads as closed.
+    let (cancel_tx, _cancel_rx) = mpsc::unbounded_channel();
+    let (_rerun_tx, rerun_requests) = mpsc::unbounded_channel();
+    let rerun = |_root: String, _pre_success: Vec<String>| {
+        (mpsc::channel(1).1, mpsc::unbounded_channel().0)
+    };
+    execution_tui(
+        beam_info,
+        target,
+        run_set,
+        vec![],
+        false,
+        rx,
+        cancel_tx,
+        rerun,
+        |_| anyhow::bail!("a replay has nothing to watch"),
+        || anyhow::bail!("a replay has no Beamfile to reload"),
+        rerun_requests,
+        true,
+        None,
+    )
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn execution_tui(
     beam_info: Vec<(String, Vec<String>)>,
     target: String,
     run_set: Vec<String>,
     non_launchable: Vec<String>,
     watch_preset: bool,
     mut rx: mpsc::Receiver<SchedulerEvent>,
-    mut cancel_tx: mpsc::UnboundedSender<String>,
+    mut cancel_tx: mpsc::UnboundedSender<CancelRequest>,
     rerun: impl Fn(
         String,
         Vec<String>,
     ) -> (
         mpsc::Receiver<SchedulerEvent>,
-        mpsc::UnboundedSender<String>,
+        mpsc::UnboundedSender<CancelRequest>,
     ),
     start_watch: impl Fn(
         String,
     )
         -> anyhow::Result<(Box<dyn Send>, mpsc::Receiver<WatchTrigger>, Vec<String>)>,
     reload: impl Fn() -> anyhow::Result<ReloadResult>,
+    mut rerun_requests: mpsc::UnboundedReceiver<RerunRequest>,
+    replay: bool,
+    // The `--profile` the run applies, shown in the status bar.
+    profile: Option<String>,
 ) -> Result<()> {
     // Adopted from the reload path when a Beamfile change rebinds the target.
     let mut target = target;
@@ -95,6 +174,9 @@ pub async fn run_execution_tui(
         // Declared beams with no runnable instance still appear in the sidebar
         // (see the composition root), but pressing `r` on one is refused.
         exec.set_non_launchable(non_launchable);
+        if replay {
+            exec.set_replay();
+        }
         let mut log_state = LogViewState::new(0);
         let mut search = LogSearch::new();
         let mut show_help = false;
@@ -191,6 +273,21 @@ pub async fn run_execution_tui(
                     }
                 }
 
+                // Reruns asked for over the control socket: honoured like `r`
+                // once the run is over, refused with the reason otherwise.
+                while let Ok(request) = rerun_requests.try_recv() {
+                    let beam = request.beam.unwrap_or_else(|| target.clone());
+                    let answer = exec.remote_rerun(&beam).map(|(root, pre_success)| {
+                        log_state = LogViewState::new(exec.selected);
+                        search.clear();
+                        exec.focus_run_on(&root);
+                        let (new_rx, new_cancel) = rerun(root, pre_success);
+                        rx = new_rx;
+                        cancel_tx = new_cancel;
+                    });
+                    let _ = request.reply.send(answer);
+                }
+
                 // Dimensions of the log panel and total visual height of the
                 // selected beam, used to drive scroll and auto-scroll in
                 // visual lines.
@@ -211,12 +308,17 @@ pub async fn run_execution_tui(
                 // A watch error takes priority for one render, then clears: it
                 // must not linger silently once shown.
                 let watch_error_label = watch_error.take().map(|e| format!("watch error: {e}"));
-                let watch_label = watch_error_label.as_deref().or_else(|| {
-                    crate::widgets::status_bar::watch_status_label(
-                        watch.armed,
-                        watch.pending.is_some(),
-                    )
-                });
+                let watch_label = watch_error_label
+                    .as_deref()
+                    .or_else(|| {
+                        crate::widgets::status_bar::watch_status_label(
+                            watch.armed,
+                            watch.pending.is_some(),
+                        )
+                    })
+                    .or(replay.then_some("replay"));
+                let status_label =
+                    crate::widgets::status_bar::status_label(profile.as_deref(), watch_label);
 
                 let watch_notice_line = if watch.armed {
                     watch_notice.as_deref()
@@ -231,7 +333,7 @@ pub async fn run_execution_tui(
                         &search,
                         tick,
                         show_help,
-                        watch_label,
+                        status_label.as_deref(),
                         watch_notice_line,
                     );
                 })?;
@@ -248,6 +350,15 @@ pub async fn run_execution_tui(
                             continue;
                         }
 
+                        // Cancellation confirmation popup (`x` / `X`): also
+                        // captures everything until answered.
+                        if exec.confirm_cancel.is_some() {
+                            if let Some(request) = exec.handle_confirm_key(key) {
+                                let _ = cancel_tx.send(request);
+                            }
+                            continue;
+                        }
+
                         // Beam filter input mode (`/` on the beams panel):
                         // keystrokes filter the list. Enter locks it in,
                         // Esc clears it. The selection follows the filter and the
@@ -325,8 +436,8 @@ pub async fn run_execution_tui(
                         ) {
                             ExecKeyOutcome::Continue => {}
                             ExecKeyOutcome::Quit => return Ok(()),
-                            ExecKeyOutcome::CancelSelected(name) => {
-                                let _ = cancel_tx.send(name);
+                            ExecKeyOutcome::Cancel(request) => {
+                                let _ = cancel_tx.send(request);
                             }
                             ExecKeyOutcome::Rerun { root, pre_success } => {
                                 log_state = LogViewState::new(exec.selected);
@@ -371,7 +482,12 @@ pub async fn run_execution_tui(
     })
 }
 
-pub fn run_picker(beams: Vec<PickerBeam>) -> Result<Option<Vec<String>>> {
+/// Runs the picker until a launch (or a quit, `None`). `variables` are the
+/// Beamfile's global variables, offered for editing before the launch.
+pub fn run_picker(
+    beams: Vec<PickerBeam>,
+    variables: Vec<PickerVariable>,
+) -> Result<Option<PickerLaunch>> {
     tokio::task::block_in_place(|| {
         install_terminal_panic_hook();
         enable_raw_mode()?;
@@ -380,15 +496,15 @@ pub fn run_picker(beams: Vec<PickerBeam>) -> Result<Option<Vec<String>>> {
         let backend = CrosstermBackend::new(stdout);
         let mut terminal = Terminal::new(backend)?;
 
-        let mut state = PickerState::new(beams);
+        let mut state = PickerState::new(beams).with_variables(variables);
 
-        let result = (|| -> Result<Option<Vec<String>>> {
+        let result = (|| -> Result<Option<PickerLaunch>> {
             loop {
                 terminal.draw(|f| picker_view::render_picker(f, &state))?;
                 if event::poll(Duration::from_millis(100))? {
                     if let Event::Key(key) = event::read()? {
                         match state.handle_key(key) {
-                            Some(PickerAction::Launch(names)) => return Ok(Some(names)),
+                            Some(PickerAction::Launch(launch)) => return Ok(Some(launch)),
                             Some(PickerAction::Quit) => return Ok(None),
                             None => {}
                         }
@@ -421,7 +537,7 @@ fn log_panel_dims(width: u16, height: u16, show_deps: bool) -> (u16, u16) {
 enum ExecKeyOutcome {
     Continue,
     Quit,
-    CancelSelected(String),
+    Cancel(CancelRequest),
     Rerun {
         root: String,
         pre_success: Vec<String>,
@@ -463,9 +579,11 @@ fn handle_execution_key(
         KeyCode::Char('q') => {
             let beam = &exec.beams[exec.selected];
             if matches!(beam.status, BeamStatus::Running) {
-                return ExecKeyOutcome::CancelSelected(beam.name.clone());
+                return ExecKeyOutcome::Cancel(CancelRequest::Beam(beam.name.clone()));
             }
         }
+        KeyCode::Char('x') => exec.request_cancel_selected(),
+        KeyCode::Char('X') => exec.request_cancel_run(),
         KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
             return ExecKeyOutcome::Quit;
         }
@@ -477,9 +595,16 @@ fn handle_execution_key(
             let half = (log_h / 2).max(1) as i32;
             log_state.scroll_lines(half, total_visual, log_h);
         }
-        KeyCode::Char('d') => {
+        KeyCode::Char('d') | KeyCode::Char('t') => {
             let _ = exec.handle_key(key);
         }
+        // From the timeline, Enter jumps to the logs of the selected bar.
+        KeyCode::Enter if exec.show_timeline => {
+            exec.show_timeline = false;
+            exec.focus = FocusPanel::Logs;
+            log_state.beam_index = exec.selected;
+            log_state.scroll_locked = false;
+        }
         KeyCode::Char('?') => *show_help = true,
         KeyCode::Down | KeyCode::Char('j') => match exec.focus {
             FocusPanel::Beams => {
@@ -614,13 +739,13 @@ fn apply_watch_trigger(
         Vec<String>,
     ) -> (
         mpsc::Receiver<SchedulerEvent>,
-        mpsc::UnboundedSender<String>,
+        mpsc::UnboundedSender<CancelRequest>,
     ),
     exec: &mut ExecutionState,
     log_state: &mut LogViewState,
     search: &mut LogSearch,
     rx: &mut mpsc::Receiver<SchedulerEvent>,
-    cancel_tx: &mut mpsc::UnboundedSender<String>,
+    cancel_tx: &mut mpsc::UnboundedSender<CancelRequest>,
     watch_guard: &mut Option<Box<dyn Send>>,
     watch_trigger_rx: &mut Option<mpsc::Receiver<WatchTrigger>>,
     watch_notice: &mut Option<String>,
@@ -754,7 +879,7 @@ mod tests {
         let mut log_state = LogViewState::new(0);
         let mut search = LogSearch::new();
         let (_tx, mut rx) = mpsc::channel(1);
-        let (mut cancel_tx, _cr) = mpsc::unbounded_channel::<String>();
+        let (mut cancel_tx, _cr) = mpsc::unbounded_channel::<CancelRequest>();
         let mut watch_guard: Option<Box<dyn Send>> = None;
         let mut watch_trigger_rx: Option<mpsc::Receiver<WatchTrigger>> = None;
         let mut watch_notice: Option<String> = None;
@@ -762,7 +887,7 @@ mod tests {
 
         let reload = || -> anyhow::Result<ReloadResult> {
             let (_t, r) = mpsc::channel(1);
-            let (c, _cr) = mpsc::unbounded_channel::<String>();
+            let (c, _cr) = mpsc::unbounded_channel::<CancelRequest>();
             Ok(ReloadResult {
                 beam_info: vec![
                     ("prep".to_string(), vec![]),
@@ -782,10 +907,10 @@ mod tests {
                      _pre: Vec<String>|
          -> (
             mpsc::Receiver<SchedulerEvent>,
-            mpsc::UnboundedSender<String>,
+            mpsc::UnboundedSender<CancelRequest>,
         ) {
             let (_t, r) = mpsc::channel(1);
-            let (c, _cr) = mpsc::unbounded_channel::<String>();
+            let (c, _cr) = mpsc::unbounded_channel::<CancelRequest>();
             (r, c)
         };
 
@@ -850,6 +975,26 @@ mod tests {
         handle_execution_key(key(code), exec, log_state, search, show_help, METRICS)
     }
 
+    #[test]
+    fn t_toggles_the_timeline_and_enter_jumps_to_the_logs() {
+        let (mut exec, mut ls, mut s, mut help) = fixture();
+        exec.focus = FocusPanel::Logs;
+        press(KeyCode::Char('t'), &mut exec, &mut ls, &mut s, &mut help);
+        assert!(exec.show_timeline);
+        assert_eq!(exec.focus, FocusPanel::Beams, "bars follow the beam list");
+
+        press(KeyCode::Down, &mut exec, &mut ls, &mut s, &mut help);
+        let out = press(KeyCode::Enter, &mut exec, &mut ls, &mut s, &mut help);
+        assert_eq!(out, ExecKeyOutcome::Continue);
+        assert!(!exec.show_timeline);
+        assert_eq!(exec.focus, FocusPanel::Logs);
+        assert_eq!(ls.beam_index, 1);
+
+        press(KeyCode::Char('t'), &mut exec, &mut ls, &mut s, &mut help);
+        press(KeyCode::Char('t'), &mut exec, &mut ls, &mut s, &mut help);
+        assert!(!exec.show_timeline, "t closes the timeline");
+    }
+
     // Regression: a plain « d » in the runner loop must toggle the dependency
     // panel. The original bug was that `run_execution_tui`'s dispatch did not
     // route the key to `ExecutionState::handle_key`.
@@ -912,6 +1057,49 @@ mod tests {
         assert!(!exec.show_deps, "Tab does not touch the dependencies");
     }
 
+    #[test]
+    fn x_asks_to_confirm_cancelling_the_running_beam() {
+        let (mut exec, mut ls, mut s, mut help) = fixture();
+        exec.apply_event(SchedulerEvent::BeamStarted {
+            name: "build".to_string(),
+        });
+        let out = press(KeyCode::Char('x'), &mut exec, &mut ls, &mut s, &mut help);
+        assert_eq!(
+            out,
+            ExecKeyOutcome::Continue,
+            "nothing is sent before confirming"
+        );
+        assert_eq!(
+            exec.confirm_cancel,
+            Some(CancelRequest::Beam("build".to_string()))
+        );
+    }
+
+    #[test]
+    fn x_on_a_beam_that_is_not_running_is_refused_with_a_notice() {
+        let (mut exec, mut ls, mut s, mut help) = fixture();
+        press(KeyCode::Char('x'), &mut exec, &mut ls, &mut s, &mut help);
+        assert_eq!(exec.confirm_cancel, None);
+        assert!(exec.notice.as_deref().unwrap().contains("not running"));
+    }
+
+    #[test]
+    fn shift_x_asks_to_confirm_cancelling_the_run_until_it_is_over() {
+        let (mut exec, mut ls, mut s, mut help) = fixture();
+        let shift_x = KeyEvent::new(KeyCode::Char('X'), KeyModifiers::SHIFT);
+        handle_execution_key(shift_x, &mut exec, &mut ls, &mut s, &mut help, METRICS);
+        assert_eq!(exec.confirm_cancel, Some(CancelRequest::Run));
+
+        exec.apply_event(SchedulerEvent::AllDone { success: true });
+        assert_eq!(
+            exec.confirm_cancel, None,
+            "a finished run drops the question"
+        );
+        handle_execution_key(shift_x, &mut exec, &mut ls, &mut s, &mut help, METRICS);
+        assert_eq!(exec.confirm_cancel, None);
+        assert!(exec.notice.is_some(), "a finished run cannot be cancelled");
+    }
+
     #[test]
     fn w_toggles_watch() {
         let (mut exec, mut ls, mut s, mut help) = fixture();
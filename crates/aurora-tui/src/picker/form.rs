@@ -0,0 +1,156 @@
+use aurora_core::ast::Constraints;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// What a form edits: the params of the beams about to be launched, or the
+/// Beamfile's global variables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormKind {
+    /// The params of `beams` (the launch, in selection order). Submitting it
+    /// launches them.
+    Params { beams: Vec<String> },
+    /// The global `variable`s. Submitting it keeps the values for the launch.
+    Variables,
+}
+
+/// One editable value of a form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormField {
+    /// The beam the param belongs to; `None` for a global variable.
+    pub beam: Option<String>,
+    pub name: String,
+    pub value: String,
+    /// The declared default. A param without one is required: it cannot be
+    /// submitted empty.
+    pub default: Option<String>,
+    pub description: Option<String>,
+    /// Shown masked.
+    pub sensitive: bool,
+    /// Checked on submit.
+    pub constraints: Constraints,
+}
+
+impl FormField {
+    pub fn is_required(&self) -> bool {
+        self.beam.is_some() && self.default.is_none()
+    }
+
+    /// Why the value cannot be submitted, if it cannot. An empty param value
+    /// keeps the default, so only a variable's is checked.
+    fn invalid(&self) -> Option<String> {
+        if self.beam.is_some() && self.value.is_empty() {
+            return None;
+        }
+        let e = self.constraints.check(&self.value).err()?;
+        Some(match &self.beam {
+            Some(beam) => format!("invalid value for param '{}' of '{beam}': {e}", self.name),
+            None => format!("invalid value for variable '{}': {e}", self.name),
+        })
+    }
+}
+
+/// Outcome of a key pressed in a form.
+#[derive(Debug, PartialEq, Eq)]
+pub enum FormOutcome {
+    /// Still editing.
+    Editing,
+    /// Enter with every required value filled in.
+    Submitted,
+    /// Esc: the form is discarded.
+    Cancelled,
+    /// Enter with a required value left empty; the focus moved to it.
+    Missing(String),
+    /// Enter with a value its constraints reject; the focus moved to it.
+    Invalid(String),
+}
+
+/// A popup form over the picker, editing one value per field. Values are
+/// typed at the end of the field (no cursor movement): they are short, and
+/// `Ctrl+U` clears a field to retype it.
+#[derive(Debug, Clone)]
+pub struct ParamForm {
+    pub kind: FormKind,
+    pub fields: Vec<FormField>,
+    pub focused: usize,
+}
+
+impl ParamForm {
+    pub fn new(kind: FormKind, fields: Vec<FormField>) -> Self {
+        ParamForm {
+            kind,
+            fields,
+            focused: 0,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> FormOutcome {
+        match key.code {
+            KeyCode::Esc => return FormOutcome::Cancelled,
+            KeyCode::Enter => {
+                return match self
+                    .fields
+                    .iter()
+                    .position(|f| f.is_required() && f.value.is_empty())
+                {
+                    Some(idx) => {
+                        self.focused = idx;
+                        let field = &self.fields[idx];
+                        FormOutcome::Missing(format!(
+                            "'{}' needs a value for param '{}'",
+                            field.beam.as_deref().unwrap_or_default(),
+                            field.name
+                        ))
+                    }
+                    None => match self.fields.iter().position(|f| f.invalid().is_some()) {
+                        Some(idx) => {
+                            self.focused = idx;
+                            FormOutcome::Invalid(self.fields[idx].invalid().unwrap_or_default())
+                        }
+                        None => FormOutcome::Submitted,
+                    },
+                };
+            }
+            KeyCode::Tab | KeyCode::Down => self.focus_next(),
+            KeyCode::BackTab | KeyCode::Up => self.focus_prev(),
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                if let Some(field) = self.fields.get_mut(self.focused) {
+                    field.value.clear();
+                }
+            }
+            KeyCode::Backspace => {
+                if let Some(field) = self.fields.get_mut(self.focused) {
+                    field.value.pop();
+                }
+            }
+            KeyCode::Char(c) => {
+                if let Some(field) = self.fields.get_mut(self.focused) {
+                    field.value.push(c);
+                }
+            }
+            _ => {}
+        }
+        FormOutcome::Editing
+    }
+
+    fn focus_next(&mut self) {
+        if !self.fields.is_empty() {
+            self.focused = (self.focused + 1) % self.fields.len();
+        }
+    }
+
+    fn focus_prev(&mut self) {
+        if !self.fields.is_empty() {
+            self.focused = (self.focused + self.fields.len() - 1) % self.fields.len();
+        }
+    }
+
+    /// The values entered for `beam`'s params, as `name=value` arguments (the
+    /// form `expand::bind_cli_args` binds by name). An empty optional value is
+    /// left out, so the param keeps its default.
+    pub fn args_for(&self, beam: &str) -> Vec<String> {
+        self.fields
+            .iter()
+            .filter(|f| f.beam.as_deref() == Some(beam) && !f.value.is_empty())
+            .map(|f| format!("{}={}", f.name, f.value))
+            .collect()
+    }
+}
@@ -1,3 +1,4 @@
 pub mod deps_panel;
+pub mod form;
 pub mod fuzzy;
 pub mod view;
@@ -1,10 +1,12 @@
 use crate::app::PickerState;
+use crate::picker::form::{FormKind, ParamForm};
 use crate::picker::fuzzy::match_indices;
+use crate::widgets::help_popup::{heading, popup_area};
 use ratatui::{
-    layout::{Alignment, Constraint, Direction, Layout},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
     Frame,
 };
 
@@ -62,27 +64,16 @@ pub fn render_picker(f: &mut Frame, state: &PickerState) {
             .map(|(display_i, (orig_idx, beam, _score))| {
                 let is_selected = display_i == state.selected;
                 let is_checked = state.checked[*orig_idx];
-                // A beam requiring arguments has no way to receive them here
-                // (no value input in the picker): its row is dimmed to signal
-                // it cannot be launched or checked from this view.
-                let dimmed = beam.requires_args;
                 let checkbox = if is_checked { "[x] " } else { "[ ] " };
                 let prefix = if is_selected { "▶ " } else { "  " };
-                let prefix_style = if dimmed {
-                    Style::default().fg(Color::DarkGray)
-                } else {
-                    Style::default()
-                };
 
                 let name_spans = if !state.search.is_empty() {
                     let indices = match_indices(&state.search, &beam.name);
-                    highlight_name(&beam.name, &indices, is_selected, dimmed)
+                    highlight_name(&beam.name, &indices, is_selected)
                 } else {
                     vec![Span::styled(
                         beam.name.clone(),
-                        if dimmed {
-                            Style::default().fg(Color::DarkGray)
-                        } else if is_selected {
+                        if is_selected {
                             Style::default()
                                 .fg(Color::White)
                                 .add_modifier(Modifier::BOLD)
@@ -92,13 +83,11 @@ pub fn render_picker(f: &mut Frame, state: &PickerState) {
                     )]
                 };
 
-                let mut spans = vec![Span::styled(
-                    format!("{}{}", prefix, checkbox),
-                    prefix_style,
-                )];
+                let mut spans = vec![Span::raw(format!("{}{}", prefix, checkbox))];
                 spans.extend(name_spans);
                 // Param signature suffix (e.g. " <version> [env=staging]"):
                 // informational, dimmed, and not part of the fuzzy-matched name.
+                // The values themselves are asked for by the form at launch.
                 let suffix = &beam.signature[beam.name.len()..];
                 if !suffix.is_empty() {
                     spans.push(Span::styled(
@@ -130,13 +119,16 @@ pub fn render_picker(f: &mut Frame, state: &PickerState) {
     } else {
         ("d", "deps")
     };
-    let hints = [
-        ("/", "filter"),
-        ("↑↓", "nav"),
-        ("Space", "sel"),
-        deps_hint,
-        ("Enter", "run"),
-        ("Esc", "quit"),
+    let mut hints = vec![("/", "filter"), ("↑↓", "nav"), ("Space", "sel"), deps_hint];
+    if !state.variables.is_empty() {
+        hints.push(("v", "vars"));
+    }
+    hints.extend([("Enter", "run"), ("Esc", "quit")]);
+    let form_hints = [
+        ("Tab/↑↓", "field"),
+        ("Ctrl+U", "clear"),
+        ("Enter", "confirm"),
+        ("Esc", "cancel"),
     ];
 
     // Footer on 2 lines, like the execution screen: status line then
@@ -148,21 +140,126 @@ pub fn render_picker(f: &mut Frame, state: &PickerState) {
 
     let status = status_line(state, &filtered, selected_count);
     f.render_widget(Paragraph::new(status), footer[0]);
-    // The notice (a parameterized beam refused at Enter/Space) takes priority
+    // The notice (a required param left empty, a value rejected) takes priority
     // for the one frame it is shown: it is a direct answer to the key the
     // user just pressed, ahead of the filter prompt or the static hints.
     if let Some(notice) = &state.notice {
         f.render_widget(notice_bar(notice), footer[1]);
+    } else if state.form.is_some() {
+        crate::widgets::status_bar::render_hints(f, footer[1], &form_hints);
     } else if state.search_input {
         f.render_widget(search_bar(&state.search), footer[1]);
     } else {
         crate::widgets::status_bar::render_hints(f, footer[1], &hints);
     }
+
+    if let Some(form) = &state.form {
+        render_form(f, area, form);
+    }
+}
+
+/// The params (or variables) form, as a popup over the picker in the help
+/// popup's style. Each field reads `name = value`, followed by a dimmed line
+/// with its description and default; the focused field carries the cursor.
+/// A form taller than the popup scrolls to keep the focused field visible.
+fn render_form(f: &mut Frame, area: Rect, form: &ParamForm) {
+    let title = match &form.kind {
+        FormKind::Params { beams } if beams.len() == 1 => format!("Run '{}'", beams[0]),
+        FormKind::Params { beams } => format!("Run {} beams", beams.len()),
+        FormKind::Variables => "Global variables".to_string(),
+    };
+    let mut lines = vec![heading(&title)];
+    let width = form.fields.iter().map(|f| f.name.len()).max().unwrap_or(0);
+    let mut group: Option<&str> = None;
+    let mut focused_line = 0;
+    let grouped = matches!(&form.kind, FormKind::Params { beams } if beams.len() > 1);
+    for (i, field) in form.fields.iter().enumerate() {
+        if grouped && field.beam.as_deref() != group {
+            group = field.beam.as_deref();
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                format!(" {}", group.unwrap_or_default()),
+                Style::default().fg(Color::Cyan),
+            )));
+        } else if i == 0 {
+            lines.push(Line::from(""));
+        }
+        let focused = i == form.focused;
+        if focused {
+            focused_line = lines.len();
+        }
+        let (marker, style) = if focused {
+            ("▶ ", Style::default().fg(Color::Yellow))
+        } else {
+            ("  ", Style::default())
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("{marker}{:<width$} = ", field.name), style),
+            Span::styled(
+                if field.sensitive {
+                    "*".repeat(field.value.chars().count())
+                } else {
+                    field.value.clone()
+                },
+                style.add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(if focused { "▏" } else { "" }, style),
+        ]));
+        let mut detail = field.description.clone().unwrap_or_default();
+        let note = match &field.default {
+            _ if field.is_required() => "required".to_string(),
+            Some(_) if field.sensitive => "default: hidden".to_string(),
+            Some(default) if default.is_empty() => "default: empty".to_string(),
+            Some(default) => format!("default: {default}"),
+            None => String::new(),
+        };
+        let constraints = &field.constraints;
+        let hint = if !constraints.choices.is_empty() {
+            format!("one of {}", constraints.choices.join("|"))
+        } else if let Some(pattern) = &constraints.pattern {
+            format!("matching {pattern}")
+        } else {
+            constraints
+                .hint()
+                .map(|hint| format!("type: {hint}"))
+                .unwrap_or_default()
+        };
+        for note in [hint, note] {
+            if note.is_empty() {
+                continue;
+            }
+            if !detail.is_empty() {
+                detail.push_str(" · ");
+            }
+            detail.push_str(&note);
+        }
+        lines.push(Line::from(Span::styled(
+            format!("    {detail}"),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    let popup = popup_area(area, 64, lines.len() as u16 + 2);
+    let inner_height = popup.height.saturating_sub(2) as usize;
+    // Keep the focused field and its detail line in view.
+    let scroll = (focused_line + 2).saturating_sub(inner_height) as u16;
+    f.render_widget(Clear, popup);
+    f.render_widget(
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(" Enter to confirm, Esc to cancel "),
+            )
+            .scroll((scroll, 0)),
+        popup,
+    );
 }
 
-/// Advisory line shown when a parameterized beam is refused at Enter/Space.
-/// Same footer slot and color family as the search prompt, so it reads as
-/// part of the same status area rather than an unrelated overlay.
+/// Advisory line answering the key just pressed (e.g. a required param left
+/// empty in the form). Same footer slot and color family as the search
+/// prompt, so it reads as part of the same status area rather than an
+/// unrelated overlay.
 fn notice_bar(message: &str) -> Paragraph<'static> {
     Paragraph::new(format!(" ⚠ {} ", message)).style(Style::default().fg(Color::Yellow))
 }
@@ -203,10 +300,10 @@ fn status_line(
         )
     } else {
         match filtered.get(state.selected).map(|(_, b, _)| *b) {
-            // A beam that requires arguments cannot be launched here: Enter only
-            // surfaces its usage notice, so the preview says so rather than
-            // promising a run that will not happen.
-            Some(beam) if beam.requires_args => "Enter shows usage".to_string(),
+            // A beam with params asks for their values before it runs.
+            Some(beam) if !beam.params.is_empty() => {
+                format!("Enter asks the params of « {} »", beam.name)
+            }
             Some(beam) => format!("Enter runs « {} »", beam.name),
             None => String::new(),
         }
@@ -220,22 +317,7 @@ fn status_line(
     ])
 }
 
-fn highlight_name(
-    name: &str,
-    indices: &[usize],
-    selected: bool,
-    dimmed: bool,
-) -> Vec<Span<'static>> {
-    // A dimmed row (a beam that requires arguments) reads as uniformly
-    // DarkGray: the match highlight is suppressed too, so the whole row keeps
-    // the same "cannot launch from here" look whether or not the filter is
-    // active.
-    if dimmed {
-        return name
-            .chars()
-            .map(|ch| Span::styled(ch.to_string(), Style::default().fg(Color::DarkGray)))
-            .collect();
-    }
+fn highlight_name(name: &str, indices: &[usize], selected: bool) -> Vec<Span<'static>> {
     let base_style = if selected {
         Style::default()
             .fg(Color::White)
@@ -0,0 +1,44 @@
+use super::help_popup::{heading, popup_area};
+use aurora_core::events::CancelRequest;
+use ratatui::{
+    layout::{Alignment, Rect},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+/// Asks to confirm a cancellation requested with `x` (one beam) or `X` (the
+/// whole run). Drawn like the help popup, over the execution view, which
+/// keeps updating underneath.
+pub fn render_confirm_popup(f: &mut Frame, area: Rect, request: &CancelRequest) {
+    let mut lines = match request {
+        CancelRequest::Beam(name) => vec![
+            heading(&format!("Cancel '{name}'?")),
+            Line::from(""),
+            Line::from(" Its dependents that have not started are cancelled"),
+            Line::from(" too. The command gets its kill_timeout to exit."),
+        ],
+        CancelRequest::Run => vec![
+            heading("Cancel the whole run?"),
+            Line::from(""),
+            Line::from(" Every running beam is stopped and no other starts."),
+            Line::from(" The view stays open: r reruns a beam afterwards."),
+        ],
+    };
+    lines.extend([
+        Line::from(""),
+        Line::from(" y / Enter  Cancel"),
+        Line::from(" n / Esc    Keep running"),
+    ]);
+
+    let popup_area = popup_area(area, 56, lines.len() as u16 + 2);
+    f.render_widget(Clear, popup_area);
+    let popup = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Confirm: y / n "),
+        )
+        .alignment(Alignment::Left);
+    f.render_widget(popup, popup_area);
+}
@@ -12,28 +12,38 @@ pub enum HelpContext {
     LogView,
 }
 
-pub fn render_help_popup(f: &mut Frame, area: Rect, ctx: HelpContext) {
-    let popup_width = 60u16.min(area.width.saturating_sub(4));
-    let popup_height = 16u16.min(area.height.saturating_sub(4));
-    let x = area.x + (area.width.saturating_sub(popup_width)) / 2;
-    let y = area.y + (area.height.saturating_sub(popup_height)) / 2;
-    let popup_area = Rect {
-        x,
-        y,
+/// A `width` x `height` rectangle centered in `area`, shrunk to keep a
+/// two-cell margin on small terminals. Shared by the popups so they all sit
+/// in the same place.
+pub(crate) fn popup_area(area: Rect, width: u16, height: u16) -> Rect {
+    let popup_width = width.min(area.width.saturating_sub(4));
+    let popup_height = height.min(area.height.saturating_sub(4));
+    Rect {
+        x: area.x + (area.width.saturating_sub(popup_width)) / 2,
+        y: area.y + (area.height.saturating_sub(popup_height)) / 2,
         width: popup_width,
         height: popup_height,
-    };
+    }
+}
+
+/// Bold yellow section heading, as used at the top of every popup.
+pub(crate) fn heading(text: &str) -> Line<'static> {
+    Line::from(Span::styled(
+        format!(" {text}"),
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD),
+    ))
+}
+
+pub fn render_help_popup(f: &mut Frame, area: Rect, ctx: HelpContext) {
+    let popup_area = popup_area(area, 60, 20);
 
     f.render_widget(Clear, popup_area);
 
     let lines = match ctx {
         HelpContext::Picker => vec![
-            Line::from(Span::styled(
-                " Picker",
-                Style::default()
-                    .fg(Color::Yellow)
-                    .add_modifier(Modifier::BOLD),
-            )),
+            heading("Picker"),
             Line::from(""),
             Line::from(" ↑↓ / jk    Navigate"),
             Line::from(" Space      Select/deselect"),
@@ -41,23 +51,13 @@ pub fn render_help_popup(f: &mut Frame, area: Rect, ctx: HelpContext) {
             Line::from(" Enter      Run the selected beam"),
             Line::from(" Esc / q    Quit"),
             Line::from(""),
-            Line::from(Span::styled(
-                " Search",
-                Style::default()
-                    .fg(Color::Yellow)
-                    .add_modifier(Modifier::BOLD),
-            )),
+            heading("Search"),
             Line::from(""),
             Line::from(" (type)     Filter by name/description"),
             Line::from(" Backspace  Delete a character"),
         ],
         HelpContext::Execution => vec![
-            Line::from(Span::styled(
-                " Execution",
-                Style::default()
-                    .fg(Color::Yellow)
-                    .add_modifier(Modifier::BOLD),
-            )),
+            heading("Execution"),
             Line::from(""),
             Line::from(" ↑↓ / jk    Navigate beams (or scroll logs if logs focused)"),
             Line::from(" ←→ / Tab   Toggle focus beams / logs"),
@@ -68,18 +68,16 @@ pub fn render_help_popup(f: &mut Frame, area: Rect, ctx: HelpContext) {
             Line::from(" n / N      Next / previous match"),
             Line::from(" y          Copy the logs to the clipboard"),
             Line::from(" d          Show / hide the dependencies"),
+            Line::from(" t          Timeline in place of the logs (Enter: logs)"),
             Line::from(" r          Rerun the beam (if Failed/Cancelled)"),
             Line::from(" q          Cancel the selected beam (if running)"),
+            Line::from(" x          Cancel the selected beam and its dependents"),
+            Line::from(" X          Cancel the whole run (the view stays open)"),
             Line::from(" ?          Close this help"),
             Line::from(" Esc        Quit"),
         ],
         HelpContext::LogView => vec![
-            Line::from(Span::styled(
-                " Log view",
-                Style::default()
-                    .fg(Color::Yellow)
-                    .add_modifier(Modifier::BOLD),
-            )),
+            heading("Log view"),
             Line::from(""),
             Line::from(" ↑↓ / jk    Scroll line by line"),
             Line::from(" PgUp/Dn    Scroll by page"),
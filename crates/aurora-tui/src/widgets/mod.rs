@@ -1,3 +1,4 @@
+pub mod confirm_popup;
 pub mod help_popup;
 pub mod status_bar;
 
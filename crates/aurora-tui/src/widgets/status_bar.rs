@@ -23,15 +23,17 @@ const RUNNING_FULL: &[(&str, &str)] = &[
     ("/", "search"),
     ("y", "copy"),
     ("d", "deps"),
+    ("t", "timeline"),
     ("?", "help"),
-    ("q", "cancel"),
+    ("x", "cancel"),
+    ("X", "cancel all"),
 ];
 const RUNNING_ESSENTIAL: &[(&str, &str)] = &[
     ("↑↓", "beam"),
     ("←→", "focus"),
     ("/", "search"),
     ("?", "help"),
-    ("q", "cancel"),
+    ("x", "cancel"),
 ];
 const DONE_FULL: &[(&str, &str)] = &[
     ("↑↓", "beam"),
@@ -42,6 +44,7 @@ const DONE_FULL: &[(&str, &str)] = &[
     ("y", "copy"),
     ("r", "rerun"),
     ("d", "deps"),
+    ("t", "timeline"),
     ("w", "watch"),
     ("?", "help"),
     ("q", "quit"),
@@ -182,7 +185,7 @@ pub struct StatusBreakdown {
 
 impl StatusBreakdown {
     /// Counts finished beams by status. Unfinished statuses (Pending,
-    /// Running) are ignored. `Cancelled` is counted separately, never as a failure.
+    /// Running, Stopping) are ignored. `Cancelled` is counted separately, never as a failure.
     pub fn from_statuses<'a>(statuses: impl Iterator<Item = &'a BeamStatus>) -> Self {
         let mut b = StatusBreakdown {
             success: 0,
@@ -198,7 +201,7 @@ impl StatusBreakdown {
                 BeamStatus::Failed { .. } => b.failed += 1,
                 BeamStatus::Skipped { .. } => b.skipped += 1,
                 BeamStatus::Cancelled => b.cancelled += 1,
-                BeamStatus::Pending | BeamStatus::Running => {}
+                BeamStatus::Pending | BeamStatus::Running | BeamStatus::Stopping => {}
             }
         }
         b
@@ -317,6 +320,16 @@ pub fn watch_status_label(armed: bool, pending: bool) -> Option<&'static str> {
     }
 }
 
+/// The status-bar label: the active profile, when one is, ahead of the
+/// watch (or replay) label.
+pub fn status_label(profile: Option<&str>, watch: Option<&str>) -> Option<String> {
+    match (profile, watch) {
+        (Some(profile), Some(watch)) => Some(format!("profile {profile} · {watch}")),
+        (Some(profile), None) => Some(format!("profile {profile}")),
+        (None, watch) => watch.map(str::to_string),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -418,7 +431,7 @@ mod tests {
 
 #[cfg(test)]
 mod watch_label_tests {
-    use super::watch_status_label;
+    use super::{status_label, watch_status_label};
 
     #[test]
     fn label_reflects_watch_state() {
@@ -429,4 +442,21 @@ mod watch_label_tests {
             Some("change detected, waiting for run to finish")
         );
     }
+
+    #[test]
+    fn label_leads_with_the_active_profile() {
+        assert_eq!(status_label(None, None), None);
+        assert_eq!(
+            status_label(None, Some("watching")).as_deref(),
+            Some("watching")
+        );
+        assert_eq!(
+            status_label(Some("ci"), None).as_deref(),
+            Some("profile ci")
+        );
+        assert_eq!(
+            status_label(Some("ci"), Some("watching")).as_deref(),
+            Some("profile ci · watching")
+        );
+    }
 }
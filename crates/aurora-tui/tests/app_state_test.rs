@@ -1,5 +1,6 @@
 use aurora_tui::app::{
-    ExecutionAction, ExecutionState, FocusPanel, PickerAction, PickerBeam, PickerState,
+    ExecutionAction, ExecutionState, FocusPanel, PickerAction, PickerBeam, PickerLaunch,
+    PickerState,
 };
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
@@ -13,7 +14,7 @@ fn beam(name: &str, description: Option<&str>, depends_on: Vec<&str>) -> PickerB
         description: description.map(str::to_string),
         depends_on: depends_on.into_iter().map(str::to_string).collect(),
         signature: name.to_string(),
-        requires_args: false,
+        params: vec![],
     }
 }
 
@@ -26,7 +27,10 @@ fn picker_enter_with_selection_returns_beam_name() {
     let result = picker.handle_key(key(KeyCode::Enter));
     assert_eq!(
         result,
-        Some(PickerAction::Launch(vec!["build".to_string()]))
+        Some(PickerAction::Launch(PickerLaunch {
+            beams: vec!["build".to_string()],
+            ..PickerLaunch::default()
+        }))
     );
 }
 
@@ -0,0 +1,98 @@
+use aurora_core::events::CancelRequest;
+use aurora_core::scheduler::{BeamStatus, SchedulerEvent};
+use aurora_tui::app::ExecutionState;
+use aurora_tui::widgets::confirm_popup::render_confirm_popup;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{backend::TestBackend, Terminal};
+
+fn key(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+fn running(names: &[&str]) -> ExecutionState {
+    let mut state = ExecutionState::new(names.iter().map(|n| (n.to_string(), vec![])).collect());
+    for name in names {
+        state.apply_event(SchedulerEvent::BeamStarted {
+            name: name.to_string(),
+        });
+    }
+    state
+}
+
+#[test]
+fn confirming_returns_the_cancellation_to_send() {
+    for confirm in [KeyCode::Char('y'), KeyCode::Enter] {
+        let mut state = running(&["build"]);
+        state.request_cancel_selected();
+        assert_eq!(
+            state.handle_confirm_key(key(confirm)),
+            Some(CancelRequest::Beam("build".to_string()))
+        );
+        assert_eq!(state.confirm_cancel, None, "the popup closes");
+    }
+}
+
+#[test]
+fn declining_sends_nothing_and_other_keys_keep_the_question() {
+    let mut state = running(&["build"]);
+    state.request_cancel_run();
+
+    assert_eq!(state.handle_confirm_key(key(KeyCode::Char('j'))), None);
+    assert_eq!(
+        state.confirm_cancel,
+        Some(CancelRequest::Run),
+        "still asking"
+    );
+
+    assert_eq!(state.handle_confirm_key(key(KeyCode::Esc)), None);
+    assert_eq!(state.confirm_cancel, None, "Esc dismisses the popup");
+}
+
+/// The beam may end while the question is open: confirming would then cancel
+/// nothing, so the popup closes by itself.
+#[test]
+fn the_question_is_dropped_when_its_beam_ends() {
+    let mut state = running(&["build", "lint"]);
+    state.request_cancel_selected();
+
+    state.apply_event(SchedulerEvent::BeamCompleted {
+        name: "lint".to_string(),
+        status: BeamStatus::Cancelled,
+    });
+    assert!(
+        state.confirm_cancel.is_some(),
+        "another beam ending changes nothing"
+    );
+
+    state.apply_event(SchedulerEvent::BeamCompleted {
+        name: "build".to_string(),
+        status: BeamStatus::Cancelled,
+    });
+    assert_eq!(state.confirm_cancel, None);
+}
+
+#[test]
+fn the_popup_names_what_is_about_to_be_cancelled() {
+    let text = |request: &CancelRequest| {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        terminal
+            .draw(|f| render_confirm_popup(f, f.area(), request))
+            .unwrap();
+        let buf = terminal.backend().buffer().clone();
+        (0..buf.area.height)
+            .map(|y| {
+                (0..buf.area.width)
+                    .map(|x| buf[(x, y)].symbol())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let beam = text(&CancelRequest::Beam("deploy".to_string()));
+    assert!(beam.contains("Cancel 'deploy'?"), "{beam}");
+    assert!(beam.contains("y / Enter"), "{beam}");
+
+    let run = text(&CancelRequest::Run);
+    assert!(run.contains("Cancel the whole run?"), "{run}");
+}
@@ -0,0 +1,78 @@
+//! Colours written by a `tty = true` beam reach the log panel as styles, not
+//! as raw escape codes, and survive wrapping and search highlighting.
+
+use aurora_tui::app::{BeamView, LogSearch, LogViewState};
+use aurora_tui::execution::log_panel::render_log_panel;
+use ratatui::buffer::Buffer;
+use ratatui::style::{Color, Modifier};
+use ratatui::{backend::TestBackend, Terminal};
+
+fn render(beam: &BeamView, search: Option<&LogSearch>, width: u16) -> Buffer {
+    let mut terminal = Terminal::new(TestBackend::new(width, 10)).unwrap();
+    let log_state = LogViewState::new(0);
+    terminal
+        .draw(|f| render_log_panel(f, beam, &log_state, search, f.area(), false))
+        .unwrap();
+    terminal.backend().buffer().clone()
+}
+
+/// Text of row `y` inside the panel border.
+fn row(buf: &Buffer, y: u16) -> String {
+    (1..buf.area.width - 1)
+        .map(|x| buf[(x, y)].symbol())
+        .collect()
+}
+
+#[test]
+fn sgr_colours_are_rendered_as_styles() {
+    let mut beam = BeamView::new("b".to_string(), vec![]);
+    beam.push_log_line("\x1b[32mok\x1b[0m \x1b[1mdone\x1b[0m", false);
+
+    let buf = render(&beam, None, 40);
+    assert!(row(&buf, 1).starts_with("ok done"), "{:?}", row(&buf, 1));
+    assert_eq!(buf[(1, 1)].fg, Color::Indexed(2), "`ok` is green");
+    assert_eq!(buf[(3, 1)].fg, Color::Reset, "the space is unstyled");
+    assert!(
+        buf[(4, 1)].modifier.contains(Modifier::BOLD),
+        "`done` is bold"
+    );
+}
+
+#[test]
+fn colours_follow_the_text_across_a_wrap() {
+    let mut beam = BeamView::new("b".to_string(), vec![]);
+    // Inner width 4: "abcd" on the first row, "ef" on the second.
+    beam.push_log_line("abc\x1b[31mdef\x1b[0m", false);
+
+    let buf = render(&beam, None, 6);
+    assert_eq!(row(&buf, 1), "abcd");
+    assert_eq!(buf[(3, 1)].fg, Color::Reset, "`c` is unstyled");
+    assert_eq!(buf[(4, 1)].fg, Color::Indexed(1), "`d` is red");
+    assert_eq!(
+        buf[(1, 2)].fg,
+        Color::Indexed(1),
+        "`e` is red after the wrap"
+    );
+}
+
+#[test]
+fn a_search_match_stands_out_on_a_coloured_line() {
+    let mut beam = BeamView::new("b".to_string(), vec![]);
+    beam.push_log_line("\x1b[34mfound an error here\x1b[0m", false);
+    let mut search = LogSearch::new();
+    search.query = "error".to_string();
+    search.recompute(&beam);
+
+    let buf = render(&beam, Some(&search), 40);
+    // "found an " is 9 chars: the match starts at column 10.
+    assert_eq!(
+        buf[(1, 1)].fg,
+        Color::Indexed(4),
+        "the line keeps its colour"
+    );
+    assert_eq!(
+        buf[(10, 1)].bg,
+        Color::Yellow,
+        "the current match is highlighted"
+    );
+}
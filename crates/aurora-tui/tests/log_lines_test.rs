@@ -62,11 +62,41 @@ fn sanitize_strips_ansi_color_codes() {
 }
 
 #[test]
-fn sanitize_strips_cursor_moves_and_carriage_return() {
+fn sanitize_interprets_erase_line_and_carriage_return() {
     use aurora_tui::app::sanitize_log_line;
-    // Cursor movement (ESC[2K clears the line) + carriage return.
+    // ESC[2K clears the line, the carriage return rewrites it from the start:
+    // a terminal would only show the last frame.
     let raw = "ab\x1b[2K\rcd";
-    assert_eq!(sanitize_log_line(raw), "abcd");
+    assert_eq!(sanitize_log_line(raw), "cd");
+}
+
+#[test]
+fn sanitize_shows_the_last_frame_of_a_progress_bar() {
+    use aurora_tui::app::sanitize_log_line;
+    let raw = "[=   ]  25%\r[==  ]  50%\r[====] 100%";
+    assert_eq!(sanitize_log_line(raw), "[====] 100%");
+}
+
+#[test]
+fn push_log_line_keeps_colours_apart_from_the_text() {
+    let mut beam = BeamView::new("b".to_string(), vec![]);
+    beam.push_log_line("\x1b[32mok\x1b[0m done", false);
+    beam.push_log_line("plain", false);
+    beam.push_log_line("\x1b[1;31merror\x1b[0m", true);
+
+    // Search, wrap and copy work on the text alone.
+    assert_eq!(beam.stdout, vec!["ok done", "plain"]);
+    assert_eq!(beam.stderr, vec!["error"]);
+
+    let green = beam.log_line_runs(0);
+    assert_eq!(green.len(), 1);
+    assert_eq!((green[0].start, green[0].end), (0, 2));
+    assert!(beam.log_line_runs(1).is_empty());
+    // Index 2 is the `── stderr ──` separator, 3 the first stderr line.
+    assert!(beam.log_line_runs(2).is_empty());
+    let red = beam.log_line_runs(3);
+    assert_eq!((red[0].start, red[0].end), (0, 5));
+    assert!(red[0].style.bold);
 }
 
 #[test]
@@ -11,7 +11,7 @@ fn beam(name: &str, description: Option<&str>, depends_on: Vec<&str>) -> PickerB
         description: description.map(str::to_string),
         depends_on: depends_on.into_iter().map(str::to_string).collect(),
         signature: name.to_string(),
-        requires_args: false,
+        params: vec![],
     }
 }
 
@@ -1,5 +1,27 @@
-use aurora_tui::app::{PickerAction, PickerBeam, PickerState};
-use crossterm::event::{KeyCode, KeyEvent};
+use aurora_core::ast::Constraints;
+use aurora_tui::app::{
+    PickerAction, PickerBeam, PickerLaunch, PickerParam, PickerState, PickerVariable,
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::BTreeMap;
+
+fn press(state: &mut PickerState, code: KeyCode) -> Option<PickerAction> {
+    state.handle_key(KeyEvent::from(code))
+}
+
+fn type_text(state: &mut PickerState, text: &str) {
+    for c in text.chars() {
+        press(state, KeyCode::Char(c));
+    }
+}
+
+fn param(name: &str, default: Option<&str>) -> PickerParam {
+    PickerParam {
+        name: name.to_string(),
+        default: default.map(str::to_string),
+        ..PickerParam::default()
+    }
+}
 
 fn beams() -> Vec<PickerBeam> {
     vec![
@@ -8,44 +30,216 @@ fn beams() -> Vec<PickerBeam> {
             description: None,
             depends_on: vec![],
             signature: "fmt".to_string(),
-            requires_args: false,
+            params: vec![],
         },
         PickerBeam {
             name: "deploy".to_string(),
             description: None,
             depends_on: vec![],
             signature: "deploy <version> [env=staging]".to_string(),
-            requires_args: true,
+            params: vec![param("version", None), param("env", Some("staging"))],
         },
     ]
 }
 
+fn variables() -> Vec<PickerVariable> {
+    vec![PickerVariable {
+        name: "region".to_string(),
+        value: "eu".to_string(),
+        description: None,
+        sensitive: false,
+        constraints: Constraints::default(),
+    }]
+}
+
 #[test]
-fn enter_on_a_parameterized_beam_sets_a_notice_instead_of_launching() {
+fn enter_on_a_parameterized_beam_opens_the_params_form() {
     let mut state = PickerState::new(beams());
     state.selected = 1;
-    let action = state.handle_key(KeyEvent::from(KeyCode::Enter));
-    assert_eq!(action, None);
-    let notice = state.notice.as_deref().unwrap();
-    assert!(
-        notice.contains("aurora deploy <version> [env=staging]"),
-        "got: {notice}"
+    assert_eq!(press(&mut state, KeyCode::Enter), None);
+    let form = state.form.as_ref().expect("the params form is open");
+    let values: Vec<(&str, &str)> = form
+        .fields
+        .iter()
+        .map(|f| (f.name.as_str(), f.value.as_str()))
+        .collect();
+    assert_eq!(values, vec![("version", ""), ("env", "staging")]);
+}
+
+#[test]
+fn submitting_the_form_launches_with_the_entered_values() {
+    let mut state = PickerState::new(beams());
+    state.selected = 1;
+    press(&mut state, KeyCode::Enter);
+    type_text(&mut state, "1.2.0");
+    press(&mut state, KeyCode::Tab);
+    state.handle_key(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::CONTROL));
+    type_text(&mut state, "prod");
+
+    let action = press(&mut state, KeyCode::Enter);
+    assert_eq!(
+        action,
+        Some(PickerAction::Launch(PickerLaunch {
+            beams: vec!["deploy".to_string()],
+            args: BTreeMap::from([(
+                "deploy".to_string(),
+                vec!["version=1.2.0".to_string(), "env=prod".to_string()]
+            )]),
+            var_overrides: vec![],
+        }))
     );
 }
 
 #[test]
-fn space_cannot_check_a_parameterized_beam() {
+fn a_required_param_left_empty_keeps_the_form_open() {
     let mut state = PickerState::new(beams());
     state.selected = 1;
-    state.handle_key(KeyEvent::from(KeyCode::Char(' ')));
-    assert!(state.checked.iter().all(|&c| !c));
-    assert!(state.notice.is_some());
+    press(&mut state, KeyCode::Enter);
+    press(&mut state, KeyCode::Tab);
+
+    assert_eq!(press(&mut state, KeyCode::Enter), None);
+    let form = state.form.as_ref().expect("the form stays open");
+    assert_eq!(form.focused, 0, "the focus moves to the missing value");
+    let notice = state.notice.as_deref().unwrap();
+    assert!(notice.contains("'version'"), "got: {notice}");
+}
+
+#[test]
+fn esc_closes_the_form_without_launching() {
+    let mut state = PickerState::new(beams());
+    state.selected = 1;
+    press(&mut state, KeyCode::Enter);
+    assert_eq!(press(&mut state, KeyCode::Esc), None);
+    assert!(state.form.is_none());
+}
+
+#[test]
+fn the_last_value_prefills_the_form_ahead_of_the_default() {
+    let mut beams = beams();
+    beams[1].params[0].last = Some("1.1.0".to_string());
+    beams[1].params[1].last = Some("prod".to_string());
+    let mut state = PickerState::new(beams);
+    state.selected = 1;
+    press(&mut state, KeyCode::Enter);
+    let values: Vec<&str> = state
+        .form
+        .as_ref()
+        .unwrap()
+        .fields
+        .iter()
+        .map(|f| f.value.as_str())
+        .collect();
+    assert_eq!(values, vec!["1.1.0", "prod"]);
+}
+
+#[test]
+fn multi_select_asks_the_params_of_every_checked_beam() {
+    let mut state = PickerState::new(beams());
+    press(&mut state, KeyCode::Char(' '));
+    state.selected = 1;
+    press(&mut state, KeyCode::Char(' '));
+    assert!(state.checked.iter().all(|&c| c));
+
+    press(&mut state, KeyCode::Enter);
+    type_text(&mut state, "2.0.0");
+    let action = press(&mut state, KeyCode::Enter);
+    assert_eq!(
+        action,
+        Some(PickerAction::Launch(PickerLaunch {
+            beams: vec!["fmt".to_string(), "deploy".to_string()],
+            args: BTreeMap::from([(
+                "deploy".to_string(),
+                vec!["version=2.0.0".to_string(), "env=staging".to_string()]
+            )]),
+            var_overrides: vec![],
+        }))
+    );
 }
 
 #[test]
 fn plain_beams_still_launch() {
     let mut state = PickerState::new(beams());
     state.selected = 0;
-    let action = state.handle_key(KeyEvent::from(KeyCode::Enter));
-    assert_eq!(action, Some(PickerAction::Launch(vec!["fmt".to_string()])));
+    let action = press(&mut state, KeyCode::Enter);
+    assert_eq!(
+        action,
+        Some(PickerAction::Launch(PickerLaunch {
+            beams: vec!["fmt".to_string()],
+            ..PickerLaunch::default()
+        }))
+    );
+}
+
+#[test]
+fn changed_variables_are_launched_as_overrides() {
+    let mut state = PickerState::new(beams()).with_variables(variables());
+    press(&mut state, KeyCode::Char('v'));
+    assert!(state.form.is_some());
+    state.handle_key(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::CONTROL));
+    type_text(&mut state, "us");
+    assert_eq!(press(&mut state, KeyCode::Enter), None);
+    assert!(state.form.is_none());
+
+    let action = press(&mut state, KeyCode::Enter);
+    assert_eq!(
+        action,
+        Some(PickerAction::Launch(PickerLaunch {
+            beams: vec!["fmt".to_string()],
+            args: BTreeMap::new(),
+            var_overrides: vec!["region=us".to_string()],
+        }))
+    );
+}
+
+#[test]
+fn unchanged_variables_are_not_overridden() {
+    let mut state = PickerState::new(beams()).with_variables(variables());
+    press(&mut state, KeyCode::Char('v'));
+    press(&mut state, KeyCode::Enter);
+    let Some(PickerAction::Launch(launch)) = press(&mut state, KeyCode::Enter) else {
+        panic!("expected a launch");
+    };
+    assert!(launch.var_overrides.is_empty());
+}
+
+#[test]
+fn v_without_variables_only_sets_a_notice() {
+    let mut state = PickerState::new(beams());
+    press(&mut state, KeyCode::Char('v'));
+    assert!(state.form.is_none());
+    assert!(state.notice.is_some());
+}
+
+#[test]
+fn a_value_its_constraints_reject_keeps_the_form_open() {
+    let mut beams = beams();
+    beams[1].params[1].constraints = Constraints {
+        choices: vec!["staging".to_string(), "prod".to_string()],
+        ..Constraints::default()
+    };
+    let mut state = PickerState::new(beams);
+    state.selected = 1;
+    press(&mut state, KeyCode::Enter);
+    type_text(&mut state, "1.2.0");
+    press(&mut state, KeyCode::Tab);
+    state.handle_key(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::CONTROL));
+    type_text(&mut state, "prd");
+
+    assert_eq!(press(&mut state, KeyCode::Enter), None);
+    let form = state.form.as_ref().expect("the form stays open");
+    assert_eq!(form.focused, 1, "the focus moves to the rejected value");
+    assert_eq!(
+        state.notice.as_deref(),
+        Some(
+            "invalid value for param 'env' of 'deploy': expected one of staging, prod; \
+             did you mean 'prod'?"
+        )
+    );
+
+    press(&mut state, KeyCode::Backspace);
+    type_text(&mut state, "od");
+    assert!(matches!(
+        press(&mut state, KeyCode::Enter),
+        Some(PickerAction::Launch(_))
+    ));
 }
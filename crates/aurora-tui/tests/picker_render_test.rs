@@ -1,5 +1,7 @@
-use aurora_tui::app::{PickerBeam, PickerState};
+use aurora_core::ast::{Constraints, ValueType};
+use aurora_tui::app::{PickerBeam, PickerParam, PickerState};
 use aurora_tui::picker::view::render_picker;
+use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{backend::TestBackend, style::Color, Terminal};
 
 fn beam(name: &str, description: Option<&str>, depends_on: Vec<&str>) -> PickerBeam {
@@ -8,7 +10,7 @@ fn beam(name: &str, description: Option<&str>, depends_on: Vec<&str>) -> PickerB
         description: description.map(str::to_string),
         depends_on: depends_on.into_iter().map(str::to_string).collect(),
         signature: name.to_string(),
-        requires_args: false,
+        params: vec![],
     }
 }
 
@@ -45,85 +47,144 @@ fn render_picker_deps_open() {
     terminal.draw(|f| render_picker(f, &st)).unwrap();
 }
 
+fn deploy() -> PickerBeam {
+    PickerBeam {
+        name: "deploy".to_string(),
+        description: None,
+        depends_on: vec![],
+        signature: "deploy <version> [env=staging]".to_string(),
+        params: vec![
+            PickerParam {
+                name: "version".to_string(),
+                description: Some("release tag".to_string()),
+                ..PickerParam::default()
+            },
+            PickerParam {
+                name: "env".to_string(),
+                default: Some("staging".to_string()),
+                ..PickerParam::default()
+            },
+        ],
+    }
+}
+
 /// A row with a param signature and a pending notice must render without
-/// panicking: covers the dimmed/signature row and the footer notice line
-/// added for parameterized beams.
+/// panicking: covers the signature suffix and the footer notice line.
 #[test]
 fn render_picker_with_parameterized_beam_and_notice() {
     let backend = TestBackend::new(80, 24);
     let mut terminal = Terminal::new(backend).unwrap();
-    let mut st = PickerState::new(vec![
-        beam("fmt", None, vec![]),
-        PickerBeam {
-            name: "deploy".to_string(),
-            description: None,
-            depends_on: vec![],
-            signature: "deploy <version> [env=staging]".to_string(),
-            requires_args: true,
-        },
-    ]);
+    let mut st = PickerState::new(vec![beam("fmt", None, vec![]), deploy()]);
     st.selected = 1;
-    st.notice = Some(
-        "'deploy' requires arguments: run `aurora deploy <version> [env=staging]`".to_string(),
-    );
+    st.notice = Some("'deploy' needs a value for param 'version'".to_string());
     terminal.draw(|f| render_picker(f, &st)).unwrap();
 }
 
-/// Regression: a `requires_args` beam must stay dimmed even while the fuzzy
-/// filter is active. The search branch runs `highlight_name`, which used to
-/// have no dimmed case and rendered the matched name in Gray/White + Yellow,
-/// leaving the row's checkbox/prefix DarkGray but its name undimmed. Asserts
-/// on the produced buffer: every cell of the matched "deploy" name is
-/// DarkGray, so the whole row reads as uniformly dimmed.
+/// The params form is drawn over the picker: each field with its value, its
+/// description and whether it is required or defaulted.
 #[test]
-fn parameterized_beam_stays_dimmed_under_the_filter() {
+fn params_form_renders_fields_with_defaults_and_descriptions() {
     let backend = TestBackend::new(80, 24);
     let mut terminal = Terminal::new(backend).unwrap();
-    let mut st = PickerState::new(vec![
-        beam("fmt", None, vec![]),
-        PickerBeam {
-            name: "deploy".to_string(),
-            description: None,
-            depends_on: vec![],
-            signature: "deploy <version> [env=staging]".to_string(),
-            requires_args: true,
-        },
-    ]);
-    // A non-empty query matching the param beam drives the search branch.
-    st.search = "dep".to_string();
+    let mut st = PickerState::new(vec![beam("fmt", None, vec![]), deploy()]);
+    st.selected = 1;
+    st.handle_key(KeyEvent::from(KeyCode::Enter));
+    assert!(st.form.is_some());
+    terminal.draw(|f| render_picker(f, &st)).unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let area = *buffer.area();
+    let text: String = (0..area.height)
+        .map(|y| {
+            (0..area.width)
+                .map(|x| buffer.cell((x, y)).unwrap().symbol().to_string())
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    for expected in [
+        "Run 'deploy'",
+        "version = ",
+        "release tag · required",
+        "env     = staging",
+        "default: staging",
+    ] {
+        assert!(text.contains(expected), "missing {expected:?} in:\n{text}");
+    }
+}
+
+/// A typed field shows what it accepts; a sensitive one is masked, default
+/// included.
+#[test]
+fn params_form_shows_types_and_masks_secrets() {
+    let backend = TestBackend::new(80, 24);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let scale = PickerBeam {
+        name: "scale".to_string(),
+        description: None,
+        depends_on: vec![],
+        signature: "scale [n:int=2] [token=***]".to_string(),
+        params: vec![
+            PickerParam {
+                name: "n".to_string(),
+                default: Some("2".to_string()),
+                constraints: Constraints {
+                    value_type: ValueType::Int,
+                    ..Constraints::default()
+                },
+                ..PickerParam::default()
+            },
+            PickerParam {
+                name: "token".to_string(),
+                default: Some("s3cr3t".to_string()),
+                sensitive: true,
+                ..PickerParam::default()
+            },
+        ],
+    };
+    let mut st = PickerState::new(vec![scale]);
+    st.handle_key(KeyEvent::from(KeyCode::Enter));
     terminal.draw(|f| render_picker(f, &st)).unwrap();
 
     let buffer = terminal.backend().buffer();
-    let name: Vec<char> = "deploy".chars().collect();
     let area = *buffer.area();
-    // Locate the contiguous run of cells spelling "deploy" (the beam name span)
-    // and assert each of its cells is DarkGray.
-    let mut found = false;
-    'rows: for y in 0..area.height {
-        for x in 0..area.width.saturating_sub(name.len() as u16) {
-            let matches_here = name.iter().enumerate().all(|(k, ch)| {
-                buffer
-                    .cell((x + k as u16, y))
-                    .map(|c| c.symbol() == ch.to_string())
-                    .unwrap_or(false)
-            });
-            if matches_here {
-                for k in 0..name.len() as u16 {
-                    let cell = buffer.cell((x + k, y)).unwrap();
-                    assert_eq!(
-                        cell.fg,
-                        Color::DarkGray,
-                        "filtered param-beam name cell '{}' should be DarkGray",
-                        cell.symbol()
-                    );
-                }
-                found = true;
-                break 'rows;
-            }
-        }
+    let text: String = (0..area.height)
+        .map(|y| {
+            (0..area.width)
+                .map(|x| buffer.cell((x, y)).unwrap().symbol().to_string())
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    for expected in [
+        "type: int · default: 2",
+        "token = ******",
+        "default: hidden",
+    ] {
+        assert!(text.contains(expected), "missing {expected:?} in:\n{text}");
     }
-    assert!(
-        found,
-        "the 'deploy' row must be present in the rendered buffer"
-    );
+    assert!(!text.contains("s3cr3t"), "the secret is shown:\n{text}");
+}
+
+/// A filtered name keeps its match highlight: a param beam is launchable like
+/// any other, so its row is no longer dimmed.
+#[test]
+fn parameterized_beam_is_highlighted_under_the_filter() {
+    let backend = TestBackend::new(80, 24);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut st = PickerState::new(vec![beam("fmt", None, vec![]), deploy()]);
+    st.search = "dep".to_string();
+    terminal.draw(|f| render_picker(f, &st)).unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let area = *buffer.area();
+    let found = (0..area.height).any(|y| {
+        (0..area.width.saturating_sub(3)).any(|x| {
+            "dep".chars().enumerate().all(|(k, ch)| {
+                let cell = buffer.cell((x + k as u16, y)).unwrap();
+                cell.symbol() == ch.to_string() && cell.fg == Color::Yellow
+            })
+        })
+    });
+    assert!(found, "the matched 'dep' must be highlighted");
 }
@@ -0,0 +1,53 @@
+//! A replayed run (`aurora replay`) shows a recording: nothing runs, so the
+//! keys that would start or stop beams answer with a notice instead.
+
+use aurora_core::events::{BeamStatus, SchedulerEvent};
+use aurora_tui::app::ExecutionState;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::time::Duration;
+
+fn key(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+fn replay() -> ExecutionState {
+    let mut state = ExecutionState::new(vec![
+        ("build".to_string(), vec![]),
+        ("test".to_string(), vec!["build".to_string()]),
+    ]);
+    state.set_replay();
+    state
+}
+
+#[test]
+fn a_replay_refuses_to_cancel_a_beam_or_the_run() {
+    let mut state = replay();
+    state.apply_event(SchedulerEvent::BeamStarted {
+        name: "build".to_string(),
+    });
+
+    state.request_cancel_selected();
+    assert!(state.confirm_cancel.is_none());
+    assert!(state.notice.as_deref().unwrap().contains("replay"));
+
+    state.request_cancel_run();
+    assert!(state.confirm_cancel.is_none());
+    assert!(state.notice.is_some());
+}
+
+#[test]
+fn a_replay_refuses_to_rerun() {
+    let mut state = replay();
+    state.apply_event(SchedulerEvent::BeamCompleted {
+        name: "build".to_string(),
+        status: BeamStatus::Failed {
+            exit_code: 1,
+            duration: Duration::from_secs(1),
+        },
+    });
+    state.apply_event(SchedulerEvent::AllDone { success: false });
+
+    assert_eq!(state.handle_key(key(KeyCode::Char('r'))), None);
+    assert!(state.notice.as_deref().unwrap().contains("replay"));
+    assert!(matches!(state.beams[0].status, BeamStatus::Failed { .. }));
+}
@@ -0,0 +1,32 @@
+use aurora_core::scheduler::{BeamStatus, SchedulerEvent};
+use aurora_tui::app::ExecutionState;
+
+/// A cancelled beam shows as stopping while its command handles SIGTERM, then
+/// settles as cancelled. The elapsed time keeps running meanwhile.
+#[test]
+fn beam_stopping_is_a_transient_state_before_cancelled() {
+    let mut state = ExecutionState::new(vec![("apply".to_string(), vec![])]);
+
+    state.apply_event(SchedulerEvent::BeamStarted {
+        name: "apply".to_string(),
+    });
+    state.apply_event(SchedulerEvent::BeamStopping {
+        name: "apply".to_string(),
+    });
+    let beam = &state.beams[0];
+    assert!(matches!(beam.status, BeamStatus::Stopping));
+    assert!(beam.started_at.is_some(), "the start time is kept");
+
+    state.apply_event(SchedulerEvent::BeamOutput {
+        name: "apply".to_string(),
+        line: "releasing state lock".to_string(),
+        is_stderr: false,
+    });
+    state.apply_event(SchedulerEvent::BeamCompleted {
+        name: "apply".to_string(),
+        status: BeamStatus::Cancelled,
+    });
+    let beam = &state.beams[0];
+    assert!(matches!(beam.status, BeamStatus::Cancelled));
+    assert_eq!(beam.stdout, vec!["releasing state lock".to_string()]);
+}
@@ -0,0 +1,129 @@
+use aurora_core::events::{BeamStatus, SkipReason};
+use aurora_tui::app::{ExecutionState, LogSearch, LogViewState};
+use aurora_tui::execution::split_layout::render_execution;
+use aurora_tui::execution::timeline::{critical_path, timeline_bars};
+use ratatui::{backend::TestBackend, Terminal};
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+fn secs(s: u64) -> Duration {
+    Duration::from_secs(s)
+}
+
+/// fmt (0–2s) feeds lint (2–3s) and test (queued 2–4s, then 4–9s), which
+/// both feed build (9–10s, cached). test is what build waited for last.
+fn finished_run() -> (ExecutionState, Instant) {
+    let deps = |d: &[&str]| d.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    let mut exec = ExecutionState::new(vec![
+        ("fmt".to_string(), deps(&[])),
+        ("lint".to_string(), deps(&["fmt"])),
+        ("test".to_string(), deps(&["fmt"])),
+        ("build".to_string(), deps(&["lint", "test"])),
+    ]);
+    let t0 = exec.run_started_at;
+    let done = BeamStatus::Success {
+        duration: secs(1),
+        cached: false,
+    };
+    let times = [(0, 2), (2, 3), (4, 9), (9, 10)];
+    for (beam, (start, end)) in exec.beams.iter_mut().zip(times) {
+        beam.started_at = Some(t0 + secs(start));
+        beam.finished_at = Some(t0 + secs(end));
+        beam.status = done.clone();
+    }
+    exec.beams[3].status = BeamStatus::Skipped {
+        reason: SkipReason::Cached,
+    };
+    exec.done = Some(true);
+    (exec, t0 + secs(10))
+}
+
+#[test]
+fn bars_split_the_queued_and_active_time() {
+    let (exec, now) = finished_run();
+    let (bars, span) = timeline_bars(&exec, now);
+    assert_eq!(span, secs(10));
+    assert_eq!(bars.len(), 4);
+
+    assert_eq!(bars[0].queued, None, "fmt started with the run");
+    assert_eq!(bars[0].active, Some((secs(0), secs(2))));
+    assert_eq!(
+        bars[2].queued,
+        Some((secs(2), secs(4))),
+        "test waited for a slot"
+    );
+    assert_eq!(bars[2].active, Some((secs(4), secs(9))));
+    assert!(bars[3].cached && !bars[0].cached);
+}
+
+#[test]
+fn the_critical_path_follows_the_dependency_waited_for_last() {
+    let (exec, now) = finished_run();
+    assert_eq!(critical_path(&exec, now), HashSet::from([0, 2, 3]));
+    let (bars, _) = timeline_bars(&exec, now);
+    let critical: Vec<bool> = bars.iter().map(|b| b.critical).collect();
+    assert_eq!(critical, vec![true, false, true, true]);
+}
+
+#[test]
+fn a_running_beam_extends_to_now() {
+    let (mut exec, _) = finished_run();
+    let t0 = exec.run_started_at;
+    exec.beams[3].status = BeamStatus::Running;
+    exec.beams[3].finished_at = None;
+    exec.done = None;
+    let (bars, span) = timeline_bars(&exec, t0 + secs(12));
+    assert_eq!(bars[3].active, Some((secs(9), secs(12))));
+    assert_eq!(span, secs(12));
+}
+
+#[test]
+fn a_beam_waiting_on_its_dependencies_has_no_bar_yet() {
+    let deps = vec!["fmt".to_string()];
+    let mut exec = ExecutionState::new(vec![
+        ("fmt".to_string(), vec![]),
+        ("lint".to_string(), deps),
+    ]);
+    let t0 = exec.run_started_at;
+    exec.beams[0].status = BeamStatus::Running;
+    exec.beams[0].started_at = Some(t0);
+    let (bars, _) = timeline_bars(&exec, t0 + secs(1));
+    assert_eq!(bars[1].queued, None);
+    assert_eq!(bars[1].active, None);
+    assert!(critical_path(&exec, t0 + secs(1)).contains(&0));
+}
+
+/// A beam kept from the previous run on a rerun started before the rerun:
+/// it has no bar, and the rerun's beams are measured from the rerun.
+#[test]
+fn a_rerun_restarts_the_timeline() {
+    let (mut exec, end) = finished_run();
+    exec.reset_for_rerun(&["build".to_string()]);
+    // The fixture's times are synthetic: place the rerun after them.
+    exec.run_started_at = end + secs(5);
+    let (bars, _) = timeline_bars(&exec, exec.run_started_at + secs(1));
+    assert_eq!(bars[0].active, None);
+    assert_eq!(bars[3].active, None);
+    assert_eq!(bars[3].queued, Some((secs(0), secs(1))));
+}
+
+#[test]
+fn the_timeline_replaces_the_logs_panel() {
+    let (mut exec, _) = finished_run();
+    exec.show_timeline = true;
+    let backend = TestBackend::new(100, 24);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let log_state = LogViewState::new(0);
+    let search = LogSearch::new();
+    terminal
+        .draw(|f| render_execution(f, &exec, &log_state, &search, 0, false, None, None))
+        .unwrap();
+    let buf = terminal.backend().buffer().clone();
+    let text: String = (0..buf.area.height)
+        .flat_map(|y| (0..buf.area.width).map(move |x| (x, y)))
+        .map(|(x, y)| buf[(x, y)].symbol().to_string())
+        .collect();
+    assert!(text.contains("Timeline"), "{text}");
+    assert!(text.contains("critical path"));
+    assert!(text.contains('░') && text.contains('█') && text.contains('▒'));
+}
@@ -0,0 +1,590 @@
+//! Local control socket (`.aurora/aurora.sock`) and its client, `aurora ctl`.
+//!
+//! While Aurora runs (headless, TUI or watch), it serves JSON-RPC 2.0 on a
+//! Unix domain socket, one request or response per line. The server sees the
+//! same [`SchedulerEvent`] stream as the front end and holds the run's
+//! cancellation sender, so a client can do what a user at the TUI does:
+//!
+//! - `status`: the run's target, whether it is over, and each beam's status;
+//! - `cancel` (`{"beam": name}`, or no params for the whole run);
+//! - `rerun` (`{"beam": name}`, or the target): only once the run is over,
+//!   and only where a run can follow another (the TUI, `--watch`);
+//! - `tail` (`{"beam": name}`, or every beam): acknowledged with a `null`
+//!   result, then `output` notifications (`{beam, stream, line}`) and a
+//!   `completed` one (`{beam, status}`) per beam, until the tailed beam, or
+//!   the run, completes; the server then closes the connection.
+//!
+//! One socket per project: a second Aurora started in the same project runs
+//! without one.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{bail, Context, Result};
+use aurora_core::ansi::plain_text;
+use aurora_core::events::{BeamStatus, CancelRequest, RerunRequest, SchedulerEvent, SkipReason};
+use serde_json::{json, Value};
+use tokio::sync::{broadcast, mpsc, oneshot};
+
+use crate::headless::fmt_duration;
+
+/// The socket, relative to the project directory.
+pub const SOCKET: &str = ".aurora/aurora.sock";
+
+/// JSON-RPC error codes: the protocol's own, then the application's.
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const REFUSED: i64 = -32000;
+
+/// The first wait before accepting again after running out of descriptors,
+/// doubled on each failure in a row up to [`MAX_ACCEPT_BACKOFF`].
+#[cfg(unix)]
+const ACCEPT_BACKOFF: std::time::Duration = std::time::Duration::from_millis(50);
+#[cfg(unix)]
+const MAX_ACCEPT_BACKOFF: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// What the server knows of the current run.
+#[derive(Default)]
+struct RunState {
+    /// Runs observed so far: the first run is 1, each rerun or watch cycle
+    /// the next.
+    generation: u64,
+    target: String,
+    /// The beams of the run, with their current status.
+    beams: BTreeMap<String, BeamStatus>,
+    /// The outcome, once `AllDone` is seen.
+    success: Option<bool>,
+    cancel: Option<mpsc::UnboundedSender<CancelRequest>>,
+}
+
+/// The shared side of the control socket: fed by [`Control::observe`] for
+/// every run, read by the connections.
+#[derive(Clone)]
+pub struct Control {
+    state: Arc<Mutex<RunState>>,
+    events: broadcast::Sender<SchedulerEvent>,
+    rerun: Option<mpsc::UnboundedSender<RerunRequest>>,
+    /// Whether the server may warn on stderr that it stopped.
+    warn: bool,
+}
+
+impl Default for Control {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Control {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::default(),
+            events: broadcast::channel(1024).0,
+            rerun: None,
+            warn: false,
+        }
+    }
+
+    /// Where `rerun` requests go. Without it (a one-shot headless run),
+    /// `rerun` is refused.
+    pub fn with_rerun(mut self, rerun: mpsc::UnboundedSender<RerunRequest>) -> Self {
+        self.rerun = Some(rerun);
+        self
+    }
+
+    /// Lets the server say on stderr why it stopped accepting clients
+    /// (headless, outside `--json`).
+    pub fn with_warnings(mut self, warn: bool) -> Self {
+        self.warn = warn;
+        self
+    }
+
+    /// Starts tracking a new run of `target` over `beams`, and passes its
+    /// events on to the returned receiver after taking note of them.
+    /// `cancel` is the run's cancellation channel.
+    pub fn observe(
+        &self,
+        target: &str,
+        beams: &[String],
+        rx: mpsc::Receiver<SchedulerEvent>,
+        cancel: mpsc::UnboundedSender<CancelRequest>,
+    ) -> mpsc::Receiver<SchedulerEvent> {
+        {
+            let mut state = self.state.lock().unwrap();
+            state.generation += 1;
+            state.target = target.to_string();
+            state.beams = beams
+                .iter()
+                .map(|name| (name.clone(), BeamStatus::Pending))
+                .collect();
+            state.success = None;
+            state.cancel = Some(cancel);
+        }
+        let (tx, out) = mpsc::channel(128);
+        let control = self.clone();
+        let mut rx = rx;
+        tokio::spawn(async move {
+            while let Some(event) = rx.recv().await {
+                control.apply(&event);
+                // No client tailing is the common case, not an error.
+                let _ = control.events.send(event.clone());
+                if tx.send(event).await.is_err() {
+                    break;
+                }
+            }
+        });
+        out
+    }
+
+    fn apply(&self, event: &SchedulerEvent) {
+        let mut state = self.state.lock().unwrap();
+        let (name, status) = match event {
+            SchedulerEvent::BeamStarted { name } => (name, BeamStatus::Running),
+            SchedulerEvent::BeamStopping { name } => (name, BeamStatus::Stopping),
+            SchedulerEvent::BeamCompleted { name, status } => (name, status.clone()),
+            SchedulerEvent::AllDone { success } => {
+                state.success = Some(*success);
+                return;
+            }
+            _ => return,
+        };
+        if let Some(slot) = state.beams.get_mut(name) {
+            *slot = status;
+        }
+    }
+
+    /// Binds the socket at `path` and serves it until the returned guard is
+    /// dropped, which also removes the socket file. A socket left behind by
+    /// an Aurora that died is replaced; one a live Aurora answers on is not.
+    #[cfg(unix)]
+    pub fn serve(&self, path: &Path) -> std::io::Result<ControlSocket> {
+        if path.exists() {
+            if std::os::unix::net::UnixStream::connect(path).is_ok() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::AddrInUse,
+                    "another Aurora run of this project owns it",
+                ));
+            }
+            std::fs::remove_file(path)?;
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let listener = tokio::net::UnixListener::bind(path)?;
+        let control = self.clone();
+        let (stop_tx, mut stop_rx) = oneshot::channel::<()>();
+        tokio::spawn(async move {
+            let mut backoff = ACCEPT_BACKOFF;
+            loop {
+                tokio::select! {
+                    _ = &mut stop_rx => break,
+                    accepted = listener.accept() => match accepted {
+                        Ok((stream, _)) => {
+                            backoff = ACCEPT_BACKOFF;
+                            let control = control.clone();
+                            tokio::spawn(async move {
+                                let _ = control.connection(stream).await;
+                            });
+                        }
+                        // Out of descriptors or memory: retrying at once would
+                        // fail again, in a loop, until a client or a beam
+                        // lets go of some.
+                        Err(e) if exhausted(&e) => {
+                            tokio::select! {
+                                _ = &mut stop_rx => break,
+                                _ = tokio::time::sleep(backoff) => {}
+                            }
+                            backoff = (backoff * 2).min(MAX_ACCEPT_BACKOFF);
+                        }
+                        Err(e) if e.kind() == std::io::ErrorKind::ConnectionAborted
+                            || e.kind() == std::io::ErrorKind::Interrupted => {}
+                        Err(e) => {
+                            if control.warn {
+                                eprintln!("aurora: warning: the control socket stopped: {e}");
+                            }
+                            break;
+                        }
+                    },
+                }
+            }
+        });
+        Ok(ControlSocket {
+            path: path.to_path_buf(),
+            _stop: stop_tx,
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub fn serve(&self, _path: &Path) -> std::io::Result<ControlSocket> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "the control socket needs Unix domain sockets",
+        ))
+    }
+
+    /// Answers the requests of one client, in order.
+    #[cfg(unix)]
+    async fn connection(&self, stream: tokio::net::UnixStream) -> std::io::Result<()> {
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+        let (read, mut write) = stream.into_split();
+        let mut lines = BufReader::new(read).lines();
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let request: Value = match serde_json::from_str(&line) {
+                Ok(request) => request,
+                Err(e) => {
+                    let reply = error(Value::Null, PARSE_ERROR, &e.to_string());
+                    write.write_all(format!("{reply}\n").as_bytes()).await?;
+                    continue;
+                }
+            };
+            let id = request.get("id").cloned().unwrap_or(Value::Null);
+            let method = request.get("method").and_then(Value::as_str).unwrap_or("");
+            let beam = match request.pointer("/params/beam") {
+                None | Some(Value::Null) => None,
+                Some(Value::String(beam)) => Some(beam.clone()),
+                Some(_) => {
+                    let reply = error(id, INVALID_PARAMS, "`beam` must be a string");
+                    write.write_all(format!("{reply}\n").as_bytes()).await?;
+                    continue;
+                }
+            };
+
+            if method == "tail" {
+                // Subscribed before answering, so no event falls in between.
+                let events = self.events.subscribe();
+                match self.check_tail(beam.as_deref()) {
+                    Ok(()) => {
+                        let reply = json!({ "jsonrpc": "2.0", "id": id, "result": null });
+                        write.write_all(format!("{reply}\n").as_bytes()).await?;
+                        tail(events, beam.as_deref(), &mut write).await?;
+                        return write.shutdown().await;
+                    }
+                    Err(message) => {
+                        let reply = error(id, REFUSED, &message);
+                        write.write_all(format!("{reply}\n").as_bytes()).await?;
+                        continue;
+                    }
+                }
+            }
+
+            let result = match method {
+                "status" => Ok(self.status()),
+                "cancel" => self.cancel(beam).map(|()| Value::Null),
+                "rerun" => self.rerun(beam).await.map(|()| Value::Null),
+                _ => {
+                    let reply = error(id, METHOD_NOT_FOUND, &format!("unknown method `{method}`"));
+                    write.write_all(format!("{reply}\n").as_bytes()).await?;
+                    continue;
+                }
+            };
+            let reply = match result {
+                Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                Err(message) => error(id, REFUSED, &message),
+            };
+            write.write_all(format!("{reply}\n").as_bytes()).await?;
+        }
+        Ok(())
+    }
+
+    fn status(&self) -> Value {
+        let state = self.state.lock().unwrap();
+        let beams: Vec<Value> = state
+            .beams
+            .iter()
+            .map(|(name, status)| {
+                let mut beam = status_json(status);
+                beam["name"] = json!(name);
+                beam
+            })
+            .collect();
+        json!({
+            "run": state.generation,
+            "target": state.target,
+            "done": state.success.is_some(),
+            "success": state.success,
+            "beams": beams,
+        })
+    }
+
+    fn cancel(&self, beam: Option<String>) -> Result<(), String> {
+        let state = self.state.lock().unwrap();
+        if state.success.is_some() {
+            return Err("the run is over".to_string());
+        }
+        let request = match beam {
+            None => CancelRequest::Run,
+            Some(beam) => match state.beams.get(&beam) {
+                None => return Err(format!("`{beam}` is not part of the run")),
+                Some(BeamStatus::Pending | BeamStatus::Running | BeamStatus::Stopping) => {
+                    CancelRequest::Beam(beam)
+                }
+                Some(_) => return Err(format!("`{beam}` has already completed")),
+            },
+        };
+        state
+            .cancel
+            .as_ref()
+            .and_then(|cancel| cancel.send(request).ok())
+            .ok_or_else(|| "the run no longer takes cancellations".to_string())
+    }
+
+    async fn rerun(&self, beam: Option<String>) -> Result<(), String> {
+        let Some(rerun) = &self.rerun else {
+            return Err(
+                "this Aurora runs its target once; rerun needs the TUI or --watch".to_string(),
+            );
+        };
+        if self.state.lock().unwrap().success.is_none() {
+            return Err("a run is in progress; wait for it or cancel it first".to_string());
+        }
+        let (reply, answer) = oneshot::channel();
+        rerun
+            .send(RerunRequest { beam, reply })
+            .map_err(|_| "Aurora is shutting down".to_string())?;
+        answer
+            .await
+            .unwrap_or_else(|_| Err("Aurora is shutting down".to_string()))
+    }
+
+    fn check_tail(&self, beam: Option<&str>) -> Result<(), String> {
+        let state = self.state.lock().unwrap();
+        if state.success.is_some() {
+            return Err("the run is over; see `aurora logs`".to_string());
+        }
+        match beam.map(|beam| (beam, state.beams.get(beam))) {
+            None => Ok(()),
+            Some((beam, None)) => Err(format!("`{beam}` is not part of the run")),
+            Some((_, Some(BeamStatus::Pending | BeamStatus::Running | BeamStatus::Stopping))) => {
+                Ok(())
+            }
+            Some((beam, Some(_))) => Err(format!(
+                "`{beam}` has already completed; see `aurora logs {beam}`"
+            )),
+        }
+    }
+}
+
+/// Streams the run's events as notifications, until `beam` (or the run)
+/// completes. A client too slow for the stream misses lines, not the end.
+#[cfg(unix)]
+async fn tail(
+    mut events: broadcast::Receiver<SchedulerEvent>,
+    beam: Option<&str>,
+    write: &mut (impl tokio::io::AsyncWrite + Unpin),
+) -> std::io::Result<()> {
+    use tokio::io::AsyncWriteExt;
+
+    let wanted = |name: &str| beam.is_none_or(|beam| beam == name);
+    loop {
+        let event = match events.recv().await {
+            Ok(event) => event,
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => return Ok(()),
+        };
+        let (notification, last) = match &event {
+            SchedulerEvent::BeamOutput {
+                name,
+                line,
+                is_stderr,
+            } if wanted(name) => (
+                json!({
+                    "jsonrpc": "2.0", "method": "output",
+                    "params": {
+                        "beam": name,
+                        "stream": if *is_stderr { "stderr" } else { "stdout" },
+                        "line": plain_text(line),
+                    },
+                }),
+                false,
+            ),
+            SchedulerEvent::BeamCompleted { name, status } if wanted(name) => (
+                json!({
+                    "jsonrpc": "2.0", "method": "completed",
+                    "params": { "beam": name, "status": status_json(status) },
+                }),
+                beam.is_some(),
+            ),
+            SchedulerEvent::AllDone { .. } => return Ok(()),
+            _ => continue,
+        };
+        write
+            .write_all(format!("{notification}\n").as_bytes())
+            .await?;
+        if last {
+            return Ok(());
+        }
+    }
+}
+
+/// An `accept` that failed for want of descriptors or memory, which a closed
+/// connection or a finished beam gives back.
+#[cfg(unix)]
+fn exhausted(e: &std::io::Error) -> bool {
+    matches!(
+        e.raw_os_error(),
+        Some(libc::EMFILE | libc::ENFILE | libc::ENOBUFS | libc::ENOMEM)
+    )
+}
+
+fn error(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+/// A beam status on the wire: `{"status": ...}` plus its details.
+fn status_json(status: &BeamStatus) -> Value {
+    match status {
+        BeamStatus::Pending => json!({ "status": "pending" }),
+        BeamStatus::Running => json!({ "status": "running" }),
+        BeamStatus::Stopping => json!({ "status": "stopping" }),
+        BeamStatus::Success { duration, cached } => json!({
+            "status": "success", "cached": cached, "duration_ms": duration.as_millis() as u64,
+        }),
+        BeamStatus::Skipped { reason } => json!({
+            "status": "skipped",
+            "reason": match reason {
+                SkipReason::Cached => "cached",
+                SkipReason::SkipIf => "skip_if",
+                SkipReason::ConditionNotMet => "condition_not_met",
+            },
+        }),
+        BeamStatus::Failed {
+            exit_code,
+            duration,
+        } => json!({
+            "status": "failed", "exit_code": exit_code, "duration_ms": duration.as_millis() as u64,
+        }),
+        BeamStatus::FailedAllowed {
+            exit_code,
+            duration,
+        } => json!({
+            "status": "failed_allowed", "exit_code": exit_code,
+            "duration_ms": duration.as_millis() as u64,
+        }),
+        BeamStatus::Cancelled => json!({ "status": "cancelled" }),
+    }
+}
+
+/// The served socket. Dropping it stops the server and removes the file.
+pub struct ControlSocket {
+    path: PathBuf,
+    _stop: oneshot::Sender<()>,
+}
+
+impl Drop for ControlSocket {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// `aurora ctl <method> [BEAM]`: sends one request to the Aurora running in
+/// the project and prints its answer (and, for `tail`, the stream).
+#[cfg(unix)]
+pub async fn ctl_command(
+    socket: &Path,
+    method: &str,
+    beam: Option<&str>,
+    out: &mut impl std::io::Write,
+) -> Result<()> {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    let stream = tokio::net::UnixStream::connect(socket)
+        .await
+        .with_context(|| format!("no Aurora run is in progress here ({})", socket.display()))?;
+    let (read, mut write) = stream.into_split();
+    let params = beam.map_or(Value::Null, |beam| json!({ "beam": beam }));
+    let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+    write.write_all(format!("{request}\n").as_bytes()).await?;
+
+    let mut lines = BufReader::new(read).lines();
+    let Some(reply) = lines.next_line().await? else {
+        bail!("Aurora closed the control socket without answering");
+    };
+    let reply: Value = serde_json::from_str(&reply)?;
+    if let Some(message) = reply.pointer("/error/message").and_then(Value::as_str) {
+        bail!("{message}");
+    }
+    match method {
+        "status" => print_status(&reply["result"], out)?,
+        "tail" => {
+            while let Some(line) = lines.next_line().await? {
+                let notification: Value = serde_json::from_str(&line)?;
+                let params = &notification["params"];
+                let beam = params["beam"].as_str().unwrap_or("");
+                match notification["method"].as_str() {
+                    Some("output") => {
+                        writeln!(out, "[{beam}] {}", params["line"].as_str().unwrap_or(""))?
+                    }
+                    Some("completed") => {
+                        writeln!(out, "[{beam}] {}", status_label(&params["status"]))?
+                    }
+                    _ => {}
+                }
+                out.flush()?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub async fn ctl_command(
+    _socket: &Path,
+    _method: &str,
+    _beam: Option<&str>,
+    _out: &mut impl std::io::Write,
+) -> Result<()> {
+    bail!("`aurora ctl` needs Unix domain sockets, unavailable on this platform")
+}
+
+fn print_status(status: &Value, out: &mut impl std::io::Write) -> std::io::Result<()> {
+    let outcome = match status["success"].as_bool() {
+        None => "running",
+        Some(true) => "passed",
+        Some(false) => "failed",
+    };
+    writeln!(
+        out,
+        "run {}  target {}  {outcome}",
+        status["run"],
+        status["target"].as_str().unwrap_or("")
+    )?;
+    let beams = status["beams"].as_array().cloned().unwrap_or_default();
+    let width = beams
+        .iter()
+        .filter_map(|b| b["name"].as_str())
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    for beam in &beams {
+        let name = beam["name"].as_str().unwrap_or("");
+        writeln!(out, "  {name:<width$}  {}", status_label(beam))?;
+    }
+    Ok(())
+}
+
+/// A wire status, read back for a human in the words of `aurora logs`.
+fn status_label(status: &Value) -> String {
+    let exit = status["exit_code"].as_i64().unwrap_or_default();
+    let duration = status["duration_ms"]
+        .as_u64()
+        .map(|ms| fmt_duration(std::time::Duration::from_millis(ms)))
+        .unwrap_or_default();
+    match status["status"].as_str().unwrap_or("") {
+        "success" if status["cached"] == json!(true) => "cached".to_string(),
+        "success" => format!("passed {duration}"),
+        "skipped" => match status["reason"].as_str() {
+            Some("cached") => "cached".to_string(),
+            Some("skip_if") => "skipped (skip_if)".to_string(),
+            _ => "skipped (condition not met)".to_string(),
+        },
+        "failed" => format!("failed (exit {exit}) {duration}"),
+        "failed_allowed" => format!("failed, allowed (exit {exit}) {duration}"),
+        other => other.to_string(),
+    }
+}
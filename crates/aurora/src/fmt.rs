@@ -0,0 +1,36 @@
+//! `aurora fmt`: rewrites a Beamfile in the canonical style (see
+//! [`aurora_core::parser::format`]).
+
+use std::io::{Read, Write};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use aurora_core::parser::format::format;
+
+/// `aurora fmt [--check] [FILE]`. Rewrites `path` in place, or with `check`
+/// only reports whether it is formatted. `-` reads the Beamfile from stdin and
+/// writes the formatted one to `out`. Returns whether `path` was already
+/// formatted.
+pub fn fmt_command(path: &Path, check: bool, out: &mut impl Write) -> Result<bool> {
+    let stdin = path == Path::new("-");
+    let source = if stdin {
+        let mut source = String::new();
+        std::io::stdin().read_to_string(&mut source)?;
+        source
+    } else {
+        std::fs::read_to_string(path).with_context(|| format!("cannot read {}", path.display()))?
+    };
+    let formatted = format(&source).with_context(|| format!("cannot format {}", path.display()))?;
+    let unchanged = formatted == source;
+    if check {
+        if !unchanged {
+            writeln!(out, "{} is not formatted; run `aurora fmt`", path.display())?;
+        }
+    } else if stdin {
+        out.write_all(formatted.as_bytes())?;
+    } else if !unchanged {
+        std::fs::write(path, formatted)
+            .with_context(|| format!("cannot write {}", path.display()))?;
+    }
+    Ok(unchanged)
+}
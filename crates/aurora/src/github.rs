@@ -0,0 +1,284 @@
+//! GitHub Actions rendering for headless mode (`--ci github`, or `--ci auto`
+//! under `GITHUB_ACTIONS=true`).
+//!
+//! A workflow log has no room for the interleaved `[beam] line` prefixes of
+//! the plain renderer: each beam's output is held until the beam completes,
+//! then printed as one collapsible `::group::` titled with its outcome. A
+//! failed beam also gets an `::error::` annotation carrying the tail of its
+//! output, a warning a `::warning::` one, and the recap is written as a
+//! Markdown table to `$GITHUB_STEP_SUMMARY`. The values of the `sensitive`
+//! variables are registered with `::add-mask::` before anything is printed.
+//!
+//! Workflow commands are only read from stdout, so everything goes there,
+//! stderr lines included; within a group the lines keep their order.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use aurora_core::ansi::plain_text;
+use aurora_core::events::{BeamStatus, SchedulerEvent, SkipReason};
+use tokio::sync::mpsc;
+
+use crate::headless::{fmt_duration, recap_line, write_recap};
+use crate::reporter::Reporter;
+
+/// Lines of output carried by a failure annotation.
+const ANNOTATION_TAIL: usize = 10;
+
+/// Whether the GitHub Actions rendering applies, from the `--ci` value and the
+/// `GITHUB_ACTIONS` variable the runner sets. An explicit layout (`-i`,
+/// `--output`) wins over detection.
+pub fn github_actions_enabled(ci: &str, github_actions: Option<&str>, overridden: bool) -> bool {
+    match ci {
+        "github" => true,
+        "auto" => !overridden && github_actions == Some("true"),
+        _ => false,
+    }
+}
+
+/// Output held for one running beam.
+#[derive(Default)]
+struct Buffered {
+    lines: Vec<String>,
+    /// The last lines of stderr, and of stdout, for the annotation.
+    stderr_tail: Vec<String>,
+    stdout_tail: Vec<String>,
+}
+
+impl Buffered {
+    fn push(&mut self, line: String, is_stderr: bool) {
+        let tail = if is_stderr {
+            &mut self.stderr_tail
+        } else {
+            &mut self.stdout_tail
+        };
+        if tail.len() == ANNOTATION_TAIL {
+            tail.remove(0);
+        }
+        tail.push(line.clone());
+        self.lines.push(line);
+    }
+
+    /// The tail of stderr, or of stdout when the beam wrote nothing on stderr.
+    fn failure_tail(&self) -> &[String] {
+        if self.stderr_tail.is_empty() {
+            &self.stdout_tail
+        } else {
+            &self.stderr_tail
+        }
+    }
+}
+
+pub struct GithubReporter<'a, O: std::io::Write> {
+    target: String,
+    beam_names: Vec<String>,
+    secrets: Vec<String>,
+    /// `$GITHUB_STEP_SUMMARY`, appended to once the run is over.
+    summary: Option<PathBuf>,
+    out: &'a mut O,
+}
+
+impl<'a, O: std::io::Write> GithubReporter<'a, O> {
+    pub fn new(target: String, beam_names: Vec<String>, out: &'a mut O) -> Self {
+        Self {
+            target,
+            beam_names,
+            secrets: vec![],
+            summary: None,
+            out,
+        }
+    }
+
+    /// Values to mask in the log (see [`secret_values`](crate::secret_values)).
+    pub fn with_secrets(mut self, secrets: Vec<String>) -> Self {
+        self.secrets = secrets;
+        self
+    }
+
+    pub fn with_step_summary(mut self, path: Option<PathBuf>) -> Self {
+        self.summary = path;
+        self
+    }
+}
+
+#[async_trait]
+impl<O: std::io::Write + Send> Reporter for GithubReporter<'_, O> {
+    async fn run(&mut self, mut rx: mpsc::Receiver<SchedulerEvent>) -> std::io::Result<bool> {
+        let out = &mut *self.out;
+        // A mask only covers what is printed after it.
+        for secret in &self.secrets {
+            for line in secret.lines().filter(|l| !l.trim().is_empty()) {
+                writeln!(out, "::add-mask::{}", escape_data(line))?;
+            }
+        }
+
+        let width = self.beam_names.iter().map(|n| n.len()).max().unwrap_or(0);
+        let mut buffers: HashMap<String, Buffered> = HashMap::new();
+        let mut recap: Vec<(String, BeamStatus)> = vec![];
+        let mut overall = true;
+        while let Some(event) = rx.recv().await {
+            match event {
+                SchedulerEvent::BeamOutput {
+                    name,
+                    line,
+                    is_stderr,
+                } => buffers
+                    .entry(name)
+                    .or_default()
+                    .push(plain_text(&line), is_stderr),
+                SchedulerEvent::BeamStopping { name } => buffers
+                    .entry(name)
+                    .or_default()
+                    .lines
+                    .push("stopping…".to_string()),
+                SchedulerEvent::Warning { name, message } => writeln!(
+                    out,
+                    "::warning title={}::{}",
+                    escape_property(&format!("aurora: {name}")),
+                    escape_data(&message)
+                )?,
+                SchedulerEvent::BeamCompleted { name, status } => {
+                    let buffered = buffers.remove(&name).unwrap_or_default();
+                    write_group(out, &name, &status, &buffered)?;
+                    recap.push((name, status));
+                }
+                SchedulerEvent::BeamStarted { .. } | SchedulerEvent::BeamPhase { .. } => {}
+                SchedulerEvent::AllDone { success } => {
+                    overall = success;
+                    break;
+                }
+            }
+        }
+
+        writeln!(out)?;
+        write_recap(out, &recap, width, false)?;
+        if let Some(path) = &self.summary {
+            let summary = step_summary(&self.target, &recap);
+            let written = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .and_then(|mut file| file.write_all(summary.as_bytes()));
+            if let Err(e) = written {
+                eprintln!(
+                    "aurora: warning: cannot write the step summary {}: {e}",
+                    path.display()
+                );
+            }
+        }
+        Ok(overall)
+    }
+}
+
+/// Prints a completed beam: its output as a group titled with its outcome
+/// (nothing for a beam that printed nothing), then its annotation.
+fn write_group(
+    out: &mut impl Write,
+    name: &str,
+    status: &BeamStatus,
+    buffered: &Buffered,
+) -> std::io::Result<()> {
+    if !buffered.lines.is_empty() {
+        let title = recap_line(name, status, 0, false).unwrap_or_else(|| name.to_string());
+        writeln!(out, "::group::{}", escape_data(&title))?;
+        for line in &buffered.lines {
+            writeln!(out, "{line}")?;
+        }
+        writeln!(out, "::endgroup::")?;
+    }
+    let title = escape_property(&format!("aurora: {name}"));
+    match status {
+        BeamStatus::Failed { exit_code, .. } => {
+            let mut message = format!("{name} failed with exit code {exit_code}");
+            for line in buffered.failure_tail() {
+                message.push('\n');
+                message.push_str(line);
+            }
+            writeln!(out, "::error title={title}::{}", escape_data(&message))?;
+        }
+        BeamStatus::FailedAllowed { exit_code, .. } => writeln!(
+            out,
+            "::warning title={title}::{}",
+            escape_data(&format!(
+                "{name} failed with exit code {exit_code} (allowed)"
+            ))
+        )?,
+        _ => {}
+    }
+    Ok(())
+}
+
+/// The Markdown recap appended to `$GITHUB_STEP_SUMMARY`.
+pub fn step_summary(target: &str, recap: &[(String, BeamStatus)]) -> String {
+    let mut md = format!("### aurora {}\n\n", escape_markdown(target));
+    md.push_str("| Beam | Status | Duration |\n| --- | --- | --- |\n");
+    let (mut ok, mut failed, mut cancelled) = (0, 0, 0);
+    for (name, status) in recap {
+        let (label, duration) = match status {
+            BeamStatus::Success {
+                duration,
+                cached: false,
+            } => ("✅ passed".to_string(), fmt_duration(*duration)),
+            BeamStatus::Success { cached: true, .. }
+            | BeamStatus::Skipped {
+                reason: SkipReason::Cached,
+            } => ("✅ cached".to_string(), String::new()),
+            BeamStatus::Skipped {
+                reason: SkipReason::SkipIf,
+            } => ("⏭️ skipped (skip_if)".to_string(), String::new()),
+            BeamStatus::Skipped {
+                reason: SkipReason::ConditionNotMet,
+            } => ("⏭️ skipped (condition not met)".to_string(), String::new()),
+            BeamStatus::Failed {
+                exit_code,
+                duration,
+            } => (
+                format!("❌ failed (exit {exit_code})"),
+                fmt_duration(*duration),
+            ),
+            BeamStatus::FailedAllowed {
+                exit_code,
+                duration,
+            } => (
+                format!("⚠️ failed, allowed (exit {exit_code})"),
+                fmt_duration(*duration),
+            ),
+            BeamStatus::Cancelled => ("⛔ cancelled".to_string(), String::new()),
+            BeamStatus::Pending | BeamStatus::Running | BeamStatus::Stopping => continue,
+        };
+        match status {
+            BeamStatus::Failed { .. } => failed += 1,
+            BeamStatus::Cancelled => cancelled += 1,
+            _ => ok += 1,
+        }
+        md.push_str(&format!(
+            "| `{}` | {label} | {duration} |\n",
+            escape_markdown(name)
+        ));
+    }
+    md.push_str(&format!("\n**{ok} ok, {failed} failed"));
+    if cancelled > 0 {
+        md.push_str(&format!(", {cancelled} cancelled"));
+    }
+    md.push_str("**\n\n");
+    md
+}
+
+/// Escapes the message of a workflow command.
+fn escape_data(text: &str) -> String {
+    text.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escapes a workflow command property (`title=...`).
+fn escape_property(text: &str) -> String {
+    escape_data(text).replace(':', "%3A").replace(',', "%2C")
+}
+
+/// Keeps a table cell in its cell.
+fn escape_markdown(text: &str) -> String {
+    text.replace('|', "\\|")
+}
@@ -1,20 +1,42 @@
 //! Text rendering for headless mode: drains the scheduler's event stream,
 //! displays it as lines prefixed by beam (stdout/stderr separated), then prints
 //! a final recap. Returns the overall success, which drives the exit code.
+//!
+//! `--output grouped` and `--output failed-only` trade the live interleaving
+//! for readability: a beam's output is held until it completes, then printed
+//! as one block (see [`OutputMode`]).
 
+use std::collections::{HashMap, HashSet};
 use std::io::Write;
 
+use aurora_core::ansi::plain_text;
 use aurora_core::events::{BeamStatus, SchedulerEvent, SkipReason};
 use tokio::sync::mpsc;
 
 use crate::reporter::Reporter;
 use async_trait::async_trait;
 
+/// How the output of the beams is laid out (`--output`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum OutputMode {
+    /// Each line as it arrives, prefixed with its beam.
+    #[default]
+    Interleaved,
+    /// Each beam's output as one block once the beam completes; the blocks
+    /// of failed beams come last, right above the recap.
+    Grouped,
+    /// Only the blocks of the beams that failed.
+    FailedOnly,
+}
+
 /// Human-oriented text renderer: prefixed per-beam lines then an ASCII recap.
 pub struct HeadlessReporter<'a, O: std::io::Write, E: std::io::Write> {
     beam_names: Vec<String>,
     out_color: bool,
     err_color: bool,
+    output: OutputMode,
+    status_line: bool,
+    tty_beams: HashSet<String>,
     out: &'a mut O,
     err: &'a mut E,
 }
@@ -31,17 +53,56 @@ impl<'a, O: std::io::Write, E: std::io::Write> HeadlessReporter<'a, O, E> {
             beam_names,
             out_color,
             err_color,
+            output: OutputMode::Interleaved,
+            status_line: false,
+            tty_beams: HashSet::new(),
             out,
             err,
         }
     }
+
+    pub fn with_output(mut self, output: OutputMode) -> Self {
+        self.output = output;
+        self
+    }
+
+    /// Shows which beams are running on a line of stderr, redrawn in place,
+    /// while their output is held back. Only meant for a terminal stderr,
+    /// and ignored by the interleaved layout, whose lines already show it.
+    pub fn with_status_line(mut self, status_line: bool) -> Self {
+        self.status_line = status_line;
+        self
+    }
+
+    /// The `tty = true` beams, whose lines are printed as they would finally
+    /// read on a terminal, without its control codes. Every other beam's
+    /// output is printed as written, colours it forces included.
+    pub fn with_tty_beams(mut self, tty_beams: impl IntoIterator<Item = String>) -> Self {
+        self.tty_beams = tty_beams.into_iter().collect();
+        self
+    }
 }
 
 #[async_trait]
 impl<O: std::io::Write + Send, E: std::io::Write + Send> Reporter for HeadlessReporter<'_, O, E> {
     async fn run(&mut self, rx: mpsc::Receiver<SchedulerEvent>) -> std::io::Result<bool> {
+        if self.output != OutputMode::Interleaved {
+            return render_grouped(
+                &self.beam_names,
+                self.output,
+                self.status_line,
+                &self.tty_beams,
+                self.out_color,
+                self.err_color,
+                rx,
+                self.out,
+                self.err,
+            )
+            .await;
+        }
         render_headless(
             &self.beam_names,
+            &self.tty_beams,
             self.out_color,
             self.err_color,
             rx,
@@ -62,13 +123,19 @@ fn paint(text: &str, code: &str, use_color: bool) -> String {
 }
 
 /// Formats a duration in seconds with one decimal place (e.g. "4.2s").
-fn fmt_duration(d: std::time::Duration) -> String {
+pub(crate) fn fmt_duration(d: std::time::Duration) -> String {
     format!("{:.1}s", d.as_secs_f64())
 }
 
 /// Builds the recap line for a completed beam.
-/// Returns `None` for non-terminal statuses (Pending/Running), never emitted here.
-fn recap_line(name: &str, status: &BeamStatus, width: usize, use_color: bool) -> Option<String> {
+/// Returns `None` for non-terminal statuses (Pending/Running/Stopping), never
+/// emitted here.
+pub(crate) fn recap_line(
+    name: &str,
+    status: &BeamStatus,
+    width: usize,
+    use_color: bool,
+) -> Option<String> {
     let (marker, color, detail) = match status {
         BeamStatus::Success {
             duration,
@@ -100,7 +167,7 @@ fn recap_line(name: &str, status: &BeamStatus, width: usize, use_color: bool) ->
             format!("exit {exit_code} (allowed) {}", fmt_duration(*duration)),
         ),
         BeamStatus::Cancelled => ("CANC", "35", "cancelled".to_string()),
-        BeamStatus::Pending | BeamStatus::Running => return None,
+        BeamStatus::Pending | BeamStatus::Running | BeamStatus::Stopping => return None,
     };
     // Marker padded to 6 characters ("[FAIL]") before coloring, so the columns
     // stay aligned without the ANSI codes (zero width) shifting them.
@@ -117,6 +184,7 @@ fn recap_line(name: &str, status: &BeamStatus, width: usize, use_color: bool) ->
 /// inherit the color decided for stdout (and vice versa).
 async fn render_headless(
     beam_names: &[String],
+    tty_beams: &HashSet<String>,
     out_color: bool,
     err_color: bool,
     mut rx: mpsc::Receiver<SchedulerEvent>,
@@ -136,6 +204,10 @@ async fn render_headless(
             } => {
                 let color = if is_stderr { err_color } else { out_color };
                 let prefix = paint(&format!("[{name:<width$}]"), "90", color);
+                // A `tty = true` beam writes terminal control codes (colours,
+                // `\r` progress redraws): print the line as it would finally
+                // read, never the raw codes, which would garble a log file.
+                let line = terminal_line(tty_beams, &name, line);
                 if is_stderr {
                     writeln!(err, "{prefix} {line}")?;
                 } else {
@@ -147,8 +219,12 @@ async fn render_headless(
                 let tag = paint("warning:", "33", err_color);
                 writeln!(err, "{prefix} {tag} {message}")?;
             }
+            SchedulerEvent::BeamStopping { name } => {
+                let prefix = paint(&format!("[{name:<width$}]"), "90", err_color);
+                writeln!(err, "{prefix} {}", paint("stopping…", "35", err_color))?;
+            }
             SchedulerEvent::BeamCompleted { name, status } => recap.push((name, status)),
-            SchedulerEvent::BeamStarted { .. } => {}
+            SchedulerEvent::BeamStarted { .. } | SchedulerEvent::BeamPhase { .. } => {}
             SchedulerEvent::AllDone { success } => {
                 overall = success;
                 break;
@@ -157,10 +233,200 @@ async fn render_headless(
     }
 
     writeln!(out)?;
+    write_recap(out, &recap, width, out_color)?;
+
+    Ok(overall)
+}
+
+/// A line of `name`'s output as printed: resolved to plain text for a
+/// `tty = true` beam, as written otherwise.
+fn terminal_line(tty_beams: &HashSet<String>, name: &str, line: String) -> String {
+    if tty_beams.contains(name) {
+        plain_text(&line)
+    } else {
+        line
+    }
+}
+
+/// Width past which the status line is cut: a line that wraps can no longer
+/// be erased with a carriage return.
+const STATUS_WIDTH: usize = 79;
+
+/// Erases the status line (the cursor is left at its start).
+const CLEAR_LINE: &str = "\r\u{1b}[2K";
+
+/// The live status line: the beams running and how many are done.
+struct StatusLine {
+    enabled: bool,
+    running: Vec<String>,
+    done: usize,
+    shown: bool,
+}
+
+impl StatusLine {
+    fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            running: vec![],
+            done: 0,
+            shown: false,
+        }
+    }
+
+    /// Erases the line before something else is printed.
+    fn clear(&mut self, err: &mut impl Write) -> std::io::Result<()> {
+        if self.shown {
+            write!(err, "{CLEAR_LINE}")?;
+            err.flush()?;
+            self.shown = false;
+        }
+        Ok(())
+    }
+
+    fn draw(&mut self, err: &mut impl Write) -> std::io::Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let mut text = format!("[{} done] ", self.done);
+        if self.running.is_empty() {
+            text.push_str("waiting…");
+        } else {
+            text.push_str("running: ");
+            text.push_str(&self.running.join(", "));
+        }
+        if text.chars().count() > STATUS_WIDTH {
+            text = text.chars().take(STATUS_WIDTH - 1).collect();
+            text.push('…');
+        }
+        write!(err, "{CLEAR_LINE}{text}")?;
+        err.flush()?;
+        self.shown = true;
+        Ok(())
+    }
+}
+
+/// Prints a completed beam's held output as one block headed by its recap
+/// line. Stderr lines are part of the block, in their original order: the
+/// point is that a beam reads in one piece.
+fn write_block(
+    out: &mut impl Write,
+    name: &str,
+    status: &BeamStatus,
+    lines: &[String],
+    out_color: bool,
+) -> std::io::Result<()> {
+    let header = recap_line(name, status, 0, out_color).unwrap_or_else(|| name.to_string());
+    writeln!(out, "{header}")?;
+    for line in lines {
+        writeln!(out, "{line}")?;
+    }
+    writeln!(out)?;
+    out.flush()
+}
+
+/// The grouped and failed-only layouts: output is held per beam and printed
+/// as a block when the beam completes (a beam that printed nothing gets no
+/// block). Warnings and stops are still reported on stderr as they happen.
+#[allow(clippy::too_many_arguments)]
+async fn render_grouped(
+    beam_names: &[String],
+    output: OutputMode,
+    status_line: bool,
+    tty_beams: &HashSet<String>,
+    out_color: bool,
+    err_color: bool,
+    mut rx: mpsc::Receiver<SchedulerEvent>,
+    out: &mut impl Write,
+    err: &mut impl Write,
+) -> std::io::Result<bool> {
+    let width = beam_names.iter().map(|n| n.len()).max().unwrap_or(0);
+    let mut recap: Vec<(String, BeamStatus)> = Vec::new();
+    let mut held: HashMap<String, Vec<String>> = HashMap::new();
+    // Failed beams' blocks, printed after every other one.
+    let mut deferred: Vec<(String, BeamStatus, Vec<String>)> = vec![];
+    let mut blocks = 0usize;
+    let mut status = StatusLine::new(status_line);
+    let mut overall = true;
+
+    while let Some(event) = rx.recv().await {
+        match event {
+            SchedulerEvent::BeamOutput { name, line, .. } => {
+                let line = terminal_line(tty_beams, &name, line);
+                held.entry(name).or_default().push(line);
+            }
+            SchedulerEvent::Warning { name, message } => {
+                status.clear(err)?;
+                let prefix = paint(&format!("[{name:<width$}]"), "90", err_color);
+                let tag = paint("warning:", "33", err_color);
+                writeln!(err, "{prefix} {tag} {message}")?;
+                status.draw(err)?;
+            }
+            SchedulerEvent::BeamStopping { name } => {
+                status.clear(err)?;
+                let prefix = paint(&format!("[{name:<width$}]"), "90", err_color);
+                writeln!(err, "{prefix} {}", paint("stopping…", "35", err_color))?;
+                status.draw(err)?;
+            }
+            SchedulerEvent::BeamStarted { name } => {
+                status.running.push(name);
+                status.draw(err)?;
+            }
+            SchedulerEvent::BeamCompleted { name, status: beam } => {
+                status.running.retain(|n| n != &name);
+                status.done += 1;
+                let lines = held.remove(&name).unwrap_or_default();
+                let failed = matches!(beam, BeamStatus::Failed { .. });
+                let show = match output {
+                    OutputMode::FailedOnly => {
+                        failed || matches!(beam, BeamStatus::FailedAllowed { .. })
+                    }
+                    _ => true,
+                };
+                if show && !lines.is_empty() {
+                    if failed && output == OutputMode::Grouped {
+                        deferred.push((name.clone(), beam.clone(), lines));
+                    } else {
+                        status.clear(err)?;
+                        write_block(out, &name, &beam, &lines, out_color)?;
+                        blocks += 1;
+                    }
+                }
+                status.draw(err)?;
+                recap.push((name, beam));
+            }
+            SchedulerEvent::BeamPhase { .. } => {}
+            SchedulerEvent::AllDone { success } => {
+                overall = success;
+                break;
+            }
+        }
+    }
+
+    status.clear(err)?;
+    for (name, beam, lines) in &deferred {
+        write_block(out, name, beam, lines, out_color)?;
+        blocks += 1;
+    }
+    // A block already ends with a blank line.
+    if blocks == 0 {
+        writeln!(out)?;
+    }
+    write_recap(out, &recap, width, out_color)?;
+
+    Ok(overall)
+}
+
+/// Prints one recap line per completed beam, then the `Done:` summary.
+pub(crate) fn write_recap(
+    out: &mut impl Write,
+    recap: &[(String, BeamStatus)],
+    width: usize,
+    out_color: bool,
+) -> std::io::Result<()> {
     let mut ok = 0usize;
     let mut failed = 0usize;
     let mut cancelled = 0usize;
-    for (name, status) in &recap {
+    for (name, status) in recap {
         if let Some(line) = recap_line(name, status, width, out_color) {
             writeln!(out, "{line}")?;
         }
@@ -173,7 +439,7 @@ async fn render_headless(
             | BeamStatus::FailedAllowed { .. } => ok += 1,
             BeamStatus::Failed { .. } => failed += 1,
             BeamStatus::Cancelled => cancelled += 1,
-            BeamStatus::Pending | BeamStatus::Running => {}
+            BeamStatus::Pending | BeamStatus::Running | BeamStatus::Stopping => {}
         }
     }
     // The `cancelled` category only appears when it is nonzero, to
@@ -182,7 +448,5 @@ async fn render_headless(
     if cancelled > 0 {
         summary.push_str(&format!(", {cancelled} cancelled"));
     }
-    writeln!(out, "{summary}")?;
-
-    Ok(overall)
+    writeln!(out, "{summary}")
 }
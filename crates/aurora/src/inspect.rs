@@ -0,0 +1,650 @@
+//! `aurora inspect <beam>`: a beam's definition as the parser resolved it,
+//! with the template it extends merged in, written back as a Beamfile block.
+//! With `--json`, the whole parsed Beamfile as one JSON document for tooling
+//! (and with `--expanded`, the instances a run would schedule), versioned by
+//! `schema` like the `--json` event stream and described by
+//! `schemas/inspect.v1.schema.json`.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::time::Duration;
+
+use anyhow::Result;
+use aurora_core::ast::{
+    Beam, BeamFile, Condition, ConditionClause, ConditionOp, Constraints, Dependency, EnvValue,
+    Environment, Run, ValueOrigin, ValueType,
+};
+use aurora_core::expand::Expansion;
+use aurora_core::parser::format::format;
+use serde::Serialize;
+
+use crate::{ensure_beam_exists, secret_values};
+
+/// The schema version of the `inspect --json` document, described by
+/// `schemas/inspect.v<N>.schema.json`; versioned apart from the `--json`
+/// event stream.
+pub const INSPECT_SCHEMA: u32 = 1;
+
+/// `aurora inspect <beam>`. Prints the merged definition of `beam` in the
+/// canonical style. Variables are not interpolated yet: `${var.x}` and
+/// `${param.x}` show as written.
+pub fn inspect_command(beam_file: &BeamFile, beam: &str, out: &mut impl Write) -> Result<()> {
+    ensure_beam_exists(beam_file, beam)?;
+    let beam = beam_file
+        .beams
+        .iter()
+        .find(|b| b.name == beam)
+        .expect("checked above");
+    out.write_all(render(beam)?.as_bytes())?;
+    Ok(())
+}
+
+/// `beam` as a `beam "name" { ... }` block, with a leading comment naming the
+/// template it extends.
+pub fn render(beam: &Beam) -> Result<String> {
+    let mut source = String::new();
+    if let Some(template) = &beam.extends {
+        source.push_str(&format!("# extends {}\n", quote(template)));
+    }
+    source.push_str(&format!("beam {} {{\n", quote(&beam.name)));
+    if let Some(description) = &beam.description {
+        source.push_str(&format!("description = {}\n", quote(description)));
+    }
+    if !beam.tags.is_empty() {
+        source.push_str(&format!("tags = {}\n", list(&beam.tags)));
+    }
+    for param in &beam.params {
+        source.push_str(&format!("param {} {{\n", quote(&param.name)));
+        if let Some(default) = &param.default {
+            source.push_str(&format!("default = {}\n", quote(default)));
+        }
+        if let Some(description) = &param.description {
+            source.push_str(&format!("description = {}\n", quote(description)));
+        }
+        let constraints = &param.constraints;
+        if constraints.value_type != ValueType::String {
+            source.push_str(&format!("type = \"{}\"\n", constraints.value_type.name()));
+        }
+        if !constraints.choices.is_empty() {
+            source.push_str(&format!("choices = {}\n", list(&constraints.choices)));
+        }
+        if let Some(pattern) = &constraints.pattern {
+            source.push_str(&format!("pattern = {}\n", quote(pattern)));
+        }
+        if param.sensitive {
+            source.push_str("sensitive = true\n");
+        }
+        source.push_str("}\n");
+    }
+    if !beam.depends_on.is_empty() {
+        let deps: Vec<String> = beam
+            .depends_on
+            .iter()
+            .map(|dep| {
+                if dep.params.is_empty() {
+                    return quote(&dep.beam);
+                }
+                let bindings: Vec<String> = dep
+                    .params
+                    .iter()
+                    .map(|(name, value)| format!("{name} = {}", quote(value)))
+                    .collect();
+                format!(
+                    "{{ beam = {}, params = {{ {} }} }}",
+                    quote(&dep.beam),
+                    bindings.join(", ")
+                )
+            })
+            .collect();
+        source.push_str(&format!("depends_on = [{}]\n", deps.join(", ")));
+    }
+    if let Some(dir) = &beam.dir {
+        source.push_str(&format!("dir = {}\n", quote(dir)));
+    }
+    if !beam.inputs.is_empty() {
+        source.push_str(&format!("inputs = {}\n", list(&beam.inputs)));
+    }
+    if !beam.outputs.is_empty() {
+        source.push_str(&format!("outputs = {}\n", list(&beam.outputs)));
+    }
+    if let Some(skip_if) = &beam.skip_if {
+        source.push_str(&format!("skip_if = {}\n", quote(skip_if)));
+    }
+    if beam.allow_failure {
+        source.push_str("allow_failure = true\n");
+    }
+    if beam.tty {
+        source.push_str("tty = true\n");
+    }
+    if let Some(timeout) = beam.kill_timeout {
+        source.push_str(&format!("kill_timeout = \"{}\"\n", duration(timeout)));
+    }
+    if let Some(condition) = &beam.condition {
+        let op = match condition.op {
+            ConditionOp::Any => "any",
+            ConditionOp::All => "all",
+        };
+        let clauses: Vec<String> = condition
+            .clauses
+            .iter()
+            .map(|ConditionClause::Shell(shell)| format!("{{ shell = {} }}", quote(shell)))
+            .collect();
+        source.push_str(&format!("condition {{ {op} = [{}] }}\n", clauses.join(" ")));
+    }
+    if !beam.inherit_env.is_empty() {
+        source.push_str(&format!("inherit_env = {}\n", list(&beam.inherit_env)));
+    }
+    if let Some(environment) = &beam.environment {
+        source.push_str(&environment_block(environment));
+    }
+    if let Some(run) = &beam.run {
+        source.push_str("run {\n");
+        source.push_str(&format!("commands = {}\n", list(&run.commands)));
+        if let Some(executor) = &run.executor {
+            source.push_str(&format!("executor {} {{\n", quote(&executor.name)));
+            let mut config: Vec<(&String, &String)> = executor.config.iter().collect();
+            config.sort();
+            for (key, value) in config {
+                // A whole-value `var.x` reference is written bare, as declared.
+                let value = match value.strip_prefix("var.") {
+                    Some(_) => value.clone(),
+                    None => quote(value),
+                };
+                source.push_str(&format!("{key} = {value}\n"));
+            }
+            source.push_str("}\n");
+        }
+        source.push_str("}\n");
+    }
+    source.push_str("}\n");
+    format(&source)
+}
+
+/// An `environment {}` block, each `load` back among the entries it was
+/// declared between.
+fn environment_block(environment: &Environment) -> String {
+    let mut block = String::from("environment {\n");
+    let load = |block: &mut String, position: usize| {
+        for load in environment.loads.iter().filter(|l| l.after == position) {
+            let files: Vec<String> = load
+                .files
+                .iter()
+                .map(|file| match file.optional {
+                    true => format!("{{ path = {}, optional = true }}", quote(&file.path)),
+                    false => quote(&file.path),
+                })
+                .collect();
+            block.push_str(&format!("load = [{}]\n", files.join(", ")));
+        }
+    };
+    for (position, var) in environment.vars.iter().enumerate() {
+        load(&mut block, position);
+        let value = match &var.value {
+            EnvValue::Literal(value) => quote(value),
+            EnvValue::Shell(command) => format!("shell({})", quote(command)),
+        };
+        block.push_str(&format!("{} = {value}\n", var.name));
+    }
+    load(&mut block, environment.vars.len());
+    block.push_str("}\n");
+    block
+}
+
+fn list(items: &[String]) -> String {
+    let items: Vec<String> = items.iter().map(|item| quote(item)).collect();
+    format!("[{}]", items.join(", "))
+}
+
+/// `value` as a Beamfile string literal: the escapes the parser decodes.
+fn quote(value: &str) -> String {
+    let mut out = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// A `kill_timeout` in the largest unit that writes it exactly.
+fn duration(timeout: Duration) -> String {
+    let millis = timeout.as_millis();
+    if millis.is_multiple_of(60_000) && millis > 0 {
+        format!("{}m", millis / 60_000)
+    } else if millis.is_multiple_of(1000) {
+        format!("{}s", millis / 1000)
+    } else {
+        format!("{millis}ms")
+    }
+}
+
+#[derive(Serialize)]
+struct JsonBeamFile<'a> {
+    schema: u32,
+    /// The profile applied, if any.
+    profile: Option<&'a str>,
+    config: Option<JsonConfig<'a>>,
+    variables: Vec<JsonVariable<'a>>,
+    environment: Option<JsonEnvironment<'a>>,
+    profiles: Vec<JsonProfile<'a>>,
+    beams: Vec<JsonBeam<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    instances: Option<Vec<JsonInstance<'a>>>,
+}
+
+#[derive(Serialize)]
+struct JsonConfig<'a> {
+    version: &'a str,
+    default: Option<&'a str>,
+    max_parallelism: Option<usize>,
+    log_retention: Option<usize>,
+    inherit_env: &'a [String],
+}
+
+#[derive(Serialize)]
+struct JsonVariable<'a> {
+    name: &'a str,
+    /// `***` when the variable is `sensitive`.
+    value: &'a str,
+    origin: &'static str,
+    description: Option<&'a str>,
+    #[serde(flatten)]
+    constraints: JsonConstraints<'a>,
+    sensitive: bool,
+    from_env: Option<&'a str>,
+    from_file: Option<&'a str>,
+    shell: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct JsonConstraints<'a> {
+    #[serde(rename = "type")]
+    value_type: &'static str,
+    choices: &'a [String],
+    pattern: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct JsonEnvironment<'a> {
+    vars: Vec<JsonEnvVar<'a>>,
+    loads: Vec<JsonEnvLoad<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonEnvVar<'a> {
+    name: &'a str,
+    /// `literal`, or `shell` when `value` is the command whose output it is.
+    kind: &'static str,
+    value: &'a str,
+}
+
+#[derive(Serialize)]
+struct JsonEnvLoad<'a> {
+    /// How many of `vars` are declared before it.
+    after: usize,
+    files: Vec<JsonEnvFile<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonEnvFile<'a> {
+    path: &'a str,
+    optional: bool,
+}
+
+#[derive(Serialize)]
+struct JsonProfile<'a> {
+    name: &'a str,
+    variables: BTreeMap<&'a str, &'a str>,
+    max_parallelism: Option<usize>,
+    environment: Option<JsonEnvironment<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonBeam<'a> {
+    name: &'a str,
+    extends: Option<&'a str>,
+    description: Option<&'a str>,
+    tags: &'a [String],
+    params: Vec<JsonParam<'a>>,
+    depends_on: Vec<JsonDependency<'a>>,
+    #[serde(flatten)]
+    body: JsonBody<'a>,
+}
+
+#[derive(Serialize)]
+struct JsonParam<'a> {
+    name: &'a str,
+    /// `***` when the param is `sensitive`.
+    default: Option<&'a str>,
+    description: Option<&'a str>,
+    #[serde(flatten)]
+    constraints: JsonConstraints<'a>,
+    sensitive: bool,
+}
+
+#[derive(Serialize)]
+struct JsonDependency<'a> {
+    beam: &'a str,
+    params: &'a BTreeMap<String, String>,
+}
+
+/// The fields a beam and its instances share.
+#[derive(Serialize)]
+struct JsonBody<'a> {
+    dir: Option<&'a str>,
+    inputs: &'a [String],
+    outputs: &'a [String],
+    skip_if: Option<&'a str>,
+    condition: Option<JsonCondition<'a>>,
+    allow_failure: bool,
+    tty: bool,
+    kill_timeout_ms: Option<u128>,
+    inherit_env: &'a [String],
+    environment: Option<JsonEnvironment<'a>>,
+    run: Option<JsonRun<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonCondition<'a> {
+    /// `any` or `all`.
+    op: &'static str,
+    shell: Vec<&'a str>,
+}
+
+#[derive(Serialize)]
+struct JsonRun<'a> {
+    commands: &'a [String],
+    executor: Option<JsonExecutor<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonExecutor<'a> {
+    name: &'a str,
+    config: BTreeMap<&'a str, &'a str>,
+}
+
+#[derive(Serialize)]
+struct JsonInstance<'a> {
+    id: &'a str,
+    /// The declared beam it was expanded from.
+    beam: &'a str,
+    bindings: BTreeMap<&'a str, &'a str>,
+    /// Instance ids.
+    depends_on: Vec<&'a str>,
+    #[serde(flatten)]
+    body: JsonBody<'a>,
+}
+
+/// `aurora inspect --json`: `beam_file` (variables evaluated when expanded,
+/// references as written) as a single JSON document, plus the instances of
+/// `expansion` when given. Sensitive values read `***`, in the instances too.
+pub fn inspect_json(
+    beam_file: &BeamFile,
+    profile: Option<&str>,
+    expansion: Option<&Expansion>,
+    out: &mut impl Write,
+) -> Result<()> {
+    // Masked in a copy of the instances, which the document borrows from.
+    let instances: Option<Vec<Beam>> = expansion.map(|expansion| {
+        let secrets = secret_values(beam_file, &expansion.instances);
+        expansion
+            .instances
+            .iter()
+            .map(|instance| mask_instance(instance, &secrets))
+            .collect()
+    });
+    let document = JsonBeamFile {
+        schema: INSPECT_SCHEMA,
+        profile,
+        config: beam_file.config.as_ref().map(|config| JsonConfig {
+            version: &config.version,
+            default: config.default.as_deref(),
+            max_parallelism: config.max_parallelism,
+            log_retention: config.log_retention,
+            inherit_env: &config.inherit_env,
+        }),
+        variables: beam_file
+            .variables
+            .iter()
+            .map(|variable| JsonVariable {
+                name: &variable.name,
+                value: match variable.sensitive {
+                    true => "***",
+                    false => &variable.default,
+                },
+                origin: origin(variable.origin),
+                description: variable.description.as_deref(),
+                constraints: constraints(&variable.constraints),
+                sensitive: variable.sensitive,
+                from_env: variable.sources.env.as_deref(),
+                from_file: variable.sources.file.as_deref(),
+                shell: variable.sources.shell.as_deref(),
+            })
+            .collect(),
+        environment: beam_file.environment.as_ref().map(environment),
+        profiles: beam_file
+            .profiles
+            .iter()
+            .map(|profile| JsonProfile {
+                name: &profile.name,
+                variables: profile
+                    .variables
+                    .iter()
+                    .map(|v| (v.name.as_str(), v.value.as_str()))
+                    .collect(),
+                max_parallelism: profile.max_parallelism,
+                environment: profile.environment.as_ref().map(environment),
+            })
+            .collect(),
+        beams: beam_file
+            .beams
+            .iter()
+            .map(|beam| JsonBeam {
+                name: &beam.name,
+                extends: beam.extends.as_deref(),
+                description: beam.description.as_deref(),
+                tags: &beam.tags,
+                params: beam
+                    .params
+                    .iter()
+                    .map(|param| JsonParam {
+                        name: &param.name,
+                        default: match param.sensitive {
+                            true => param.default.as_ref().map(|_| "***"),
+                            false => param.default.as_deref(),
+                        },
+                        description: param.description.as_deref(),
+                        constraints: constraints(&param.constraints),
+                        sensitive: param.sensitive,
+                    })
+                    .collect(),
+                depends_on: beam.depends_on.iter().map(dependency).collect(),
+                body: body(beam),
+            })
+            .collect(),
+        target: expansion.map(|expansion| expansion.target_id.as_str()),
+        instances: instances.as_ref().map(|instances| {
+            instances
+                .iter()
+                .map(|instance| JsonInstance {
+                    id: &instance.name,
+                    beam: beam_file
+                        .beams
+                        .iter()
+                        .find(|beam| beam.span == instance.span)
+                        .map_or(instance.name.as_str(), |beam| beam.name.as_str()),
+                    bindings: instance
+                        .bindings
+                        .iter()
+                        .map(|(name, value)| (name.as_str(), value.as_str()))
+                        .collect(),
+                    depends_on: instance
+                        .depends_on
+                        .iter()
+                        .map(|d| d.beam.as_str())
+                        .collect(),
+                    body: body(instance),
+                })
+                .collect()
+        }),
+    };
+    serde_json::to_writer(&mut *out, &document)?;
+    writeln!(out)?;
+    Ok(())
+}
+
+/// `instance` with every secret in its bindings and interpolated fields
+/// replaced by `***`.
+fn mask_instance(instance: &Beam, secrets: &[String]) -> Beam {
+    let mask = |value: &str| {
+        secrets.iter().fold(value.to_string(), |value, secret| {
+            value.replace(secret, "***")
+        })
+    };
+    let mask_all = |values: &[String]| values.iter().map(|v| mask(v)).collect::<Vec<_>>();
+    let mut masked = instance.clone();
+    masked.bindings = instance
+        .bindings
+        .iter()
+        .map(|(name, value)| (name.clone(), mask(value)))
+        .collect();
+    masked.dir = instance.dir.as_deref().map(mask);
+    masked.inputs = mask_all(&instance.inputs);
+    masked.outputs = mask_all(&instance.outputs);
+    masked.skip_if = instance.skip_if.as_deref().map(mask);
+    if let Some(condition) = &mut masked.condition {
+        for ConditionClause::Shell(shell) in &mut condition.clauses {
+            *shell = mask(shell);
+        }
+    }
+    if let Some(environment) = &mut masked.environment {
+        for var in &mut environment.vars {
+            match &mut var.value {
+                EnvValue::Literal(value) | EnvValue::Shell(value) => *value = mask(value),
+            }
+        }
+    }
+    if let Some(run) = &mut masked.run {
+        run.commands = mask_all(&run.commands);
+        if let Some(executor) = &mut run.executor {
+            for value in executor.config.values_mut() {
+                *value = mask(value);
+            }
+        }
+    }
+    masked
+}
+
+fn origin(origin: ValueOrigin) -> &'static str {
+    match origin {
+        ValueOrigin::Unset => "unset",
+        ValueOrigin::Literal => "default",
+        ValueOrigin::Shell => "shell",
+        ValueOrigin::File => "file",
+        ValueOrigin::Env => "env",
+        ValueOrigin::Profile => "profile",
+        ValueOrigin::Cli => "cli",
+    }
+}
+
+fn constraints(constraints: &Constraints) -> JsonConstraints<'_> {
+    JsonConstraints {
+        value_type: constraints.value_type.name(),
+        choices: &constraints.choices,
+        pattern: constraints.pattern.as_deref(),
+    }
+}
+
+fn environment(environment: &Environment) -> JsonEnvironment<'_> {
+    JsonEnvironment {
+        vars: environment
+            .vars
+            .iter()
+            .map(|var| match &var.value {
+                EnvValue::Literal(value) => JsonEnvVar {
+                    name: &var.name,
+                    kind: "literal",
+                    value,
+                },
+                EnvValue::Shell(command) => JsonEnvVar {
+                    name: &var.name,
+                    kind: "shell",
+                    value: command,
+                },
+            })
+            .collect(),
+        loads: environment
+            .loads
+            .iter()
+            .map(|load| JsonEnvLoad {
+                after: load.after,
+                files: load
+                    .files
+                    .iter()
+                    .map(|file| JsonEnvFile {
+                        path: &file.path,
+                        optional: file.optional,
+                    })
+                    .collect(),
+            })
+            .collect(),
+    }
+}
+
+fn dependency(dependency: &Dependency) -> JsonDependency<'_> {
+    JsonDependency {
+        beam: &dependency.beam,
+        params: &dependency.params,
+    }
+}
+
+fn body(beam: &Beam) -> JsonBody<'_> {
+    JsonBody {
+        dir: beam.dir.as_deref(),
+        inputs: &beam.inputs,
+        outputs: &beam.outputs,
+        skip_if: beam.skip_if.as_deref(),
+        condition: beam.condition.as_ref().map(condition),
+        allow_failure: beam.allow_failure,
+        tty: beam.tty,
+        kill_timeout_ms: beam.kill_timeout.map(|timeout| timeout.as_millis()),
+        inherit_env: &beam.inherit_env,
+        environment: beam.environment.as_ref().map(environment),
+        run: beam.run.as_ref().map(run),
+    }
+}
+
+fn condition(condition: &Condition) -> JsonCondition<'_> {
+    JsonCondition {
+        op: match condition.op {
+            ConditionOp::Any => "any",
+            ConditionOp::All => "all",
+        },
+        shell: condition
+            .clauses
+            .iter()
+            .map(|ConditionClause::Shell(shell)| shell.as_str())
+            .collect(),
+    }
+}
+
+fn run(run: &Run) -> JsonRun<'_> {
+    JsonRun {
+        commands: &run.commands,
+        executor: run.executor.as_ref().map(|executor| JsonExecutor {
+            name: &executor.name,
+            config: executor
+                .config
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.as_str()))
+                .collect(),
+        }),
+    }
+}
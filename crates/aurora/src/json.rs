@@ -1,28 +1,37 @@
 //! Machine-readable NDJSON renderer: one JSON object per line on stdout, in
 //! real time. The wire types here are the public contract (versioned by
 //! `schema`), deliberately separate from `aurora_core`'s `SchedulerEvent` so a
-//! refactor of the engine cannot silently break a consumer.
+//! refactor of the engine cannot silently break a consumer. They are read
+//! back by `aurora replay` (see [`crate::replay`]).
 
+use std::collections::BTreeMap;
 use std::io::Write;
 use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
+use aurora_core::ansi::plain_text;
+use aurora_core::ast::Span;
+use aurora_core::diagnostic::{self, Diagnostic};
 use aurora_core::events::{BeamStatus, SchedulerEvent, SkipReason};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
 
 use crate::reporter::Reporter;
 use crate::time::now_iso8601;
 
 /// The schema version stamped on every emitted line.
-const SCHEMA: u32 = 1;
+pub(crate) const SCHEMA: u32 = 1;
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 #[serde(tag = "event", rename_all = "snake_case")]
-enum WireEvent {
+pub(crate) enum WireEvent {
     RunStarted {
         target: String,
         beams: Vec<String>,
+        /// Each beam's direct dependencies within `beams`, for a replay to
+        /// draw the graph. Absent from recordings that predate it.
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        deps: BTreeMap<String, Vec<String>>,
         at: String,
     },
     BeamStarted {
@@ -31,20 +40,24 @@ enum WireEvent {
     },
     BeamOutput {
         beam: String,
-        stream: &'static str,
+        stream: String,
         line: String,
     },
+    BeamStopping {
+        beam: String,
+        at: String,
+    },
     BeamCompleted {
         beam: String,
         #[serde(flatten)]
         status: WireStatus,
         #[serde(skip_serializing_if = "Option::is_none")]
-        duration_ms: Option<u128>,
+        duration_ms: Option<u64>,
         at: String,
     },
     RunCompleted {
         success: bool,
-        duration_ms: u128,
+        duration_ms: u64,
         at: String,
     },
     Warning {
@@ -54,14 +67,48 @@ enum WireEvent {
     Error {
         kind: String,
         message: String,
+        /// Where in the Beamfile the error is, when it is about a place in it.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        span: Option<WireSpan>,
     },
 }
 
-#[derive(Serialize)]
+/// A range of the Beamfile: 1-based lines and columns (in characters), the end
+/// exclusive.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct WireSpan {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+impl WireSpan {
+    /// The span `diagnostic` points at in `source`, read from `file`. `None`
+    /// for a synthetic span.
+    pub fn locate(diagnostic: &Diagnostic, file: &str, source: &str) -> Option<Self> {
+        let span = diagnostic.locate(source);
+        if span.is_synthetic() {
+            return None;
+        }
+        let (line, column) = span.line_col(source);
+        let (end_line, end_column) = Span::new(span.end, span.end).line_col(source);
+        Some(Self {
+            file: file.to_string(),
+            line,
+            column,
+            end_line,
+            end_column,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
 #[serde(tag = "status", rename_all = "snake_case")]
-enum WireStatus {
+pub(crate) enum WireStatus {
     Success { cached: bool },
-    Skipped { reason: &'static str },
+    Skipped { reason: String },
     Failed { exit_code: i32 },
     FailedAllowed { exit_code: i32 },
     Cancelled,
@@ -69,13 +116,13 @@ enum WireStatus {
 
 /// Maps a scheduler status to its wire form plus the optional duration.
 /// A cached or skipped beam has no run to time, so its duration is `None`.
-fn map_status(status: BeamStatus) -> (WireStatus, Option<u128>) {
+fn map_status(status: BeamStatus) -> (WireStatus, Option<u64>) {
     match status {
         BeamStatus::Success { duration, cached } => {
             let duration_ms = if cached {
                 None
             } else {
-                Some(duration.as_millis())
+                Some(duration.as_millis() as u64)
             };
             (WireStatus::Success { cached }, duration_ms)
         }
@@ -85,34 +132,65 @@ fn map_status(status: BeamStatus) -> (WireStatus, Option<u128>) {
                 SkipReason::SkipIf => "skip_if",
                 SkipReason::ConditionNotMet => "condition_not_met",
             };
-            (WireStatus::Skipped { reason }, None)
+            (
+                WireStatus::Skipped {
+                    reason: reason.to_string(),
+                },
+                None,
+            )
         }
         BeamStatus::Failed {
             exit_code,
             duration,
-        } => (WireStatus::Failed { exit_code }, Some(duration.as_millis())),
+        } => (
+            WireStatus::Failed { exit_code },
+            Some(duration.as_millis() as u64),
+        ),
         BeamStatus::FailedAllowed {
             exit_code,
             duration,
         } => (
             WireStatus::FailedAllowed { exit_code },
-            Some(duration.as_millis()),
+            Some(duration.as_millis() as u64),
         ),
         BeamStatus::Cancelled => (WireStatus::Cancelled, None),
-        // Pending/Running are internal TUI states, never carried by BeamCompleted.
-        BeamStatus::Pending | BeamStatus::Running => (WireStatus::Cancelled, None),
+        // Pending/Running/Stopping are transient states, never carried by
+        // BeamCompleted.
+        BeamStatus::Pending | BeamStatus::Running | BeamStatus::Stopping => {
+            (WireStatus::Cancelled, None)
+        }
     }
 }
 
 pub struct JsonReporter<'a, W: Write> {
     target: String,
     beams: Vec<String>,
+    deps: BTreeMap<String, Vec<String>>,
     out: &'a mut W,
 }
 
 impl<'a, W: Write> JsonReporter<'a, W> {
     pub fn new(target: String, beams: Vec<String>, out: &'a mut W) -> Self {
-        Self { target, beams, out }
+        Self {
+            target,
+            beams,
+            deps: BTreeMap::new(),
+            out,
+        }
+    }
+
+    /// The dependencies of every beam (name, direct dependencies), carried by
+    /// `run_started` for the beams of the run.
+    pub fn with_deps(mut self, deps: Vec<(String, Vec<String>)>) -> Self {
+        self.deps = deps
+            .into_iter()
+            .filter(|(name, _)| self.beams.contains(name))
+            .map(|(name, deps)| {
+                let deps = deps.into_iter().filter(|d| self.beams.contains(d));
+                (name, deps.collect())
+            })
+            .collect();
+        self
     }
 
     /// Serializes one event as a single line and flushes immediately: unbuffered
@@ -142,6 +220,14 @@ struct Wire<'a> {
     event: &'a WireEvent,
 }
 
+/// One line of a recording, as read back.
+#[derive(Deserialize)]
+pub(crate) struct WireLine {
+    pub schema: u32,
+    #[serde(flatten)]
+    pub event: WireEvent,
+}
+
 /// A broken pipe means the consumer closed stdout (the common `... | head`
 /// pattern): a normal, expected end of the stream, not a failure. Returns
 /// `Ok(true)` to stop emitting cleanly, propagates any other I/O error, and
@@ -164,6 +250,7 @@ impl<W: Write + Send> Reporter for JsonReporter<'_, W> {
         let run_started = WireEvent::RunStarted {
             target: self.target.clone(),
             beams: self.beams.clone(),
+            deps: self.deps.clone(),
             at: now_iso8601(),
         };
         if stop_on_broken_pipe(self.emit(&run_started))? {
@@ -183,11 +270,19 @@ impl<W: Write + Send> Reporter for JsonReporter<'_, W> {
                     line,
                     is_stderr,
                 } => {
-                    let stream = if is_stderr { "stderr" } else { "stdout" };
+                    let stream = if is_stderr { "stderr" } else { "stdout" }.to_string();
+                    // Consumers get the text, never terminal control codes
+                    // (see `aurora_core::ansi`).
                     stop_on_broken_pipe(self.emit(&WireEvent::BeamOutput {
                         beam: name,
                         stream,
-                        line,
+                        line: plain_text(&line),
+                    }))?
+                }
+                SchedulerEvent::BeamStopping { name } => {
+                    stop_on_broken_pipe(self.emit(&WireEvent::BeamStopping {
+                        beam: name,
+                        at: now_iso8601(),
                     }))?
                 }
                 SchedulerEvent::BeamCompleted { name, status } => {
@@ -205,12 +300,14 @@ impl<W: Write + Send> Reporter for JsonReporter<'_, W> {
                         message,
                     }))?
                 }
+                // Not part of the wire contract: the phases are for `--trace`.
+                SchedulerEvent::BeamPhase { .. } => false,
                 SchedulerEvent::AllDone { success } => {
                     overall = success;
                     let elapsed: Duration = started.elapsed();
                     let _ = stop_on_broken_pipe(self.emit(&WireEvent::RunCompleted {
                         success,
-                        duration_ms: elapsed.as_millis(),
+                        duration_ms: elapsed.as_millis() as u64,
                         at: now_iso8601(),
                     }))?;
                     break;
@@ -231,6 +328,24 @@ pub fn write_error(out: &mut impl Write, kind: &str, message: &str) -> std::io::
     let event = WireEvent::Error {
         kind: kind.to_string(),
         message: message.to_string(),
+        span: None,
+    };
+    write_line(out, &event)
+}
+
+/// Like [`write_error`] for `err`, with the span of the [`Diagnostic`] in its
+/// chain, if any, located in `source` (the Beamfile at `file`).
+pub fn write_located_error(
+    out: &mut impl Write,
+    kind: &str,
+    err: &anyhow::Error,
+    file: &str,
+    source: &str,
+) -> std::io::Result<()> {
+    let event = WireEvent::Error {
+        kind: kind.to_string(),
+        message: err.to_string(),
+        span: diagnostic::find(err).and_then(|d| WireSpan::locate(d, file, source)),
     };
     write_line(out, &event)
 }
@@ -0,0 +1,217 @@
+//! JUnit XML report (`--report junit=path.xml`): one testcase per beam
+//! instance, for the CI dashboards that render JUnit natively (Jenkins,
+//! GitLab).
+//!
+//! The mapping: a failed beam is a `<failure>` carrying its exit code and the
+//! tail of its stderr; a cancelled one is an `<error>`; a cached, `skip_if` or
+//! condition skip is `<skipped>`. A failure tolerated by `allow_failure`
+//! passes, as it does for the exit code, with the failure noted in its
+//! `<system-out>`.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use aurora_core::ansi::plain_text;
+use aurora_core::events::{BeamStatus, SchedulerEvent, SkipReason};
+use tokio::sync::mpsc;
+
+use crate::reporter::Reporter;
+use crate::time::now_iso8601;
+
+/// Lines of output kept per beam for a failure message.
+const TAIL_LINES: usize = 20;
+
+/// Output kept for one beam: the last lines of each stream.
+#[derive(Default)]
+struct Tail {
+    stdout: Vec<String>,
+    stderr: Vec<String>,
+}
+
+impl Tail {
+    fn push(&mut self, line: String, is_stderr: bool) {
+        let lines = if is_stderr {
+            &mut self.stderr
+        } else {
+            &mut self.stdout
+        };
+        if lines.len() == TAIL_LINES {
+            lines.remove(0);
+        }
+        lines.push(line);
+    }
+
+    /// The tail of stderr, or of stdout when the beam wrote nothing on
+    /// stderr (a `tty = true` beam has a single stream, reported as stdout).
+    fn failure_output(&self) -> String {
+        if self.stderr.is_empty() {
+            self.stdout.join("\n")
+        } else {
+            self.stderr.join("\n")
+        }
+    }
+}
+
+/// One completed beam, in completion order.
+struct Case {
+    name: String,
+    status: BeamStatus,
+    output: String,
+}
+
+/// Collects the run and writes the report once it is over (or the stream
+/// ends without `AllDone`, in which case the beams completed so far are
+/// reported).
+pub struct JunitReporter {
+    suite: String,
+    path: PathBuf,
+}
+
+impl JunitReporter {
+    /// `suite` names the `<testsuite>` (the run's target).
+    pub fn new(suite: String, path: PathBuf) -> Self {
+        Self { suite, path }
+    }
+}
+
+#[async_trait]
+impl Reporter for JunitReporter {
+    async fn run(&mut self, mut rx: mpsc::Receiver<SchedulerEvent>) -> std::io::Result<bool> {
+        let timestamp = now_iso8601();
+        let started = std::time::Instant::now();
+        let mut tails: HashMap<String, Tail> = HashMap::new();
+        let mut cases = vec![];
+        let mut success = false;
+
+        while let Some(event) = rx.recv().await {
+            match event {
+                SchedulerEvent::BeamOutput {
+                    name,
+                    line,
+                    is_stderr,
+                } => tails
+                    .entry(name)
+                    .or_default()
+                    .push(plain_text(&line), is_stderr),
+                SchedulerEvent::BeamCompleted { name, status } => {
+                    let output = tails
+                        .remove(&name)
+                        .map(|tail| tail.failure_output())
+                        .unwrap_or_default();
+                    cases.push(Case {
+                        name,
+                        status,
+                        output,
+                    });
+                }
+                SchedulerEvent::AllDone { success: s } => {
+                    success = s;
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        let xml = render(&self.suite, &timestamp, started.elapsed(), &cases);
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = std::fs::File::create(&self.path)?;
+        file.write_all(xml.as_bytes())?;
+        Ok(success)
+    }
+}
+
+/// Renders the whole document.
+fn render(suite: &str, timestamp: &str, elapsed: Duration, cases: &[Case]) -> String {
+    let count = |pred: fn(&BeamStatus) -> bool| cases.iter().filter(|c| pred(&c.status)).count();
+    let failures = count(|s| matches!(s, BeamStatus::Failed { .. }));
+    let errors = count(|s| matches!(s, BeamStatus::Cancelled));
+    let skipped = count(|s| matches!(s, BeamStatus::Skipped { .. }));
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let suite_attrs = format!(
+        "name=\"{}\" tests=\"{}\" failures=\"{failures}\" errors=\"{errors}\" skipped=\"{skipped}\" time=\"{}\"",
+        escape(suite),
+        cases.len(),
+        seconds(elapsed),
+    );
+    xml.push_str(&format!("<testsuites {suite_attrs}>\n"));
+    xml.push_str(&format!(
+        "  <testsuite {suite_attrs} timestamp=\"{}\">\n",
+        escape(timestamp)
+    ));
+    for case in cases {
+        render_case(&mut xml, case);
+    }
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+fn render_case(xml: &mut String, case: &Case) {
+    let time = match &case.status {
+        BeamStatus::Success { duration, .. }
+        | BeamStatus::Failed { duration, .. }
+        | BeamStatus::FailedAllowed { duration, .. } => *duration,
+        _ => Duration::ZERO,
+    };
+    let open = format!(
+        "    <testcase name=\"{}\" classname=\"aurora\" time=\"{}\"",
+        escape(&case.name),
+        seconds(time)
+    );
+    let body = match &case.status {
+        BeamStatus::Failed { exit_code, .. } => format!(
+            "      <failure message=\"exit code {exit_code}\" type=\"exit_code\">{}</failure>\n",
+            escape(&case.output)
+        ),
+        BeamStatus::Cancelled => {
+            "      <error message=\"cancelled\" type=\"cancelled\"/>\n".to_string()
+        }
+        BeamStatus::Skipped { reason } => {
+            let message = match reason {
+                SkipReason::Cached => "cached",
+                SkipReason::SkipIf => "skip_if",
+                SkipReason::ConditionNotMet => "condition not met",
+            };
+            format!("      <skipped message=\"{message}\"/>\n")
+        }
+        BeamStatus::FailedAllowed { exit_code, .. } => format!(
+            "      <system-out>failed with exit code {exit_code} (allowed)\n{}</system-out>\n",
+            escape(&case.output)
+        ),
+        _ => String::new(),
+    };
+    if body.is_empty() {
+        xml.push_str(&format!("{open}/>\n"));
+    } else {
+        xml.push_str(&format!("{open}>\n{body}    </testcase>\n"));
+    }
+}
+
+/// JUnit times are in seconds, as a decimal.
+fn seconds(d: Duration) -> String {
+    format!("{:.3}", d.as_secs_f64())
+}
+
+/// Escapes `text` for an attribute or text node. Control characters XML 1.0
+/// cannot carry at all (anything below 0x20 but tab and newlines) are dropped.
+pub fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            '\t' | '\n' | '\r' => out.push(c),
+            c if (c as u32) < 0x20 => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
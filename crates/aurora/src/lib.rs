@@ -1,15 +1,30 @@
 //! Internal library of the `aurora` binary: exposes the components
 //! that are testable independently of the TUI (headless mode).
 
+pub mod control;
+pub mod fmt;
+pub mod github;
 pub mod headless;
+pub mod inspect;
 pub mod json;
+pub mod junit;
+pub mod lint;
+pub mod logs;
+pub mod lsp;
+#[cfg(feature = "otlp")]
+pub mod otlp;
+pub mod picker_memory;
 pub mod plugins;
+pub mod replay;
 pub mod reporter;
+pub mod telemetry;
 pub mod time;
+pub mod trace;
 pub mod watch;
 
 use anyhow::{bail, Result};
 use aurora_core::ast::{Beam, BeamFile};
+use aurora_core::diagnostic::closest;
 use aurora_core::events::SchedulerEvent;
 use aurora_core::scheduler::Scheduler;
 use aurora_executor_api::Executor;
@@ -23,10 +38,33 @@ use tokio::sync::mpsc;
 /// The command-line interface. Defined here rather than in `main`, so the
 /// completion and man-page generators describe the very CLI that runs.
 pub fn cli() -> Command {
+    run_cli()
+        .subcommands(subcommands())
+        .subcommand(
+            Command::new(BUILTIN)
+                .about("Run a subcommand even when a beam has its name (`aurora builtin fmt`)")
+                .subcommand_required(true)
+                .subcommands(subcommands()),
+        )
+        .after_help("A beam named like a subcommand runs instead of it: `aurora fmt` runs a beam \"fmt\" when the Beamfile declares one, and the formatter stays `aurora builtin fmt`.")
+}
+
+/// The namespace every subcommand stays reachable under when a beam of the
+/// Beamfile takes its name.
+pub const BUILTIN: &str = "builtin";
+
+/// The command line of a run, without the subcommands: `aurora <name>` parses
+/// with it when the Beamfile declares a beam named like a subcommand, which
+/// then runs as it did before the subcommand existed.
+pub fn run_cli() -> Command {
     Command::new("aurora")
         .version(env!("CARGO_PKG_VERSION"))
         .about("Aurora: task runner with HCL-inspired Beamfile DSL")
-        .arg(Arg::new("beam").help("Beam to run").index(1))
+        .arg(
+            Arg::new("beam")
+                .help("Beam to run; more targets can follow, each after a -- (`aurora deploy 1.2.3 -- test`)")
+                .index(1),
+        )
         .arg(
             Arg::new("no-cache")
                 .long("no-cache")
@@ -46,12 +84,69 @@ pub fn cli() -> Command {
                 .action(clap::ArgAction::SetTrue)
                 .help("List the available beams with their descriptions"),
         )
+        .arg(
+            Arg::new("print-vars")
+                .long("print-vars")
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with_all(["json", "list", "dry-run"])
+                .help("Print each variable's value and where it came from, run nothing"),
+        )
+        .arg(
+            Arg::new("verbose")
+                .long("verbose")
+                .short('v')
+                .action(clap::ArgAction::SetTrue)
+                .requires("dry-run")
+                .help("With --dry-run, also list the environment variables each beam receives"),
+        )
+        .arg(
+            Arg::new("inherit-env")
+                .long("inherit-env")
+                .value_name("PATTERN")
+                .action(clap::ArgAction::Append)
+                .help("Forward ambient variables matching PATTERN (e.g. SSH_AUTH_SOCK, AWS_*) to every beam (repeatable)"),
+        )
+        .arg(
+            Arg::new("profile")
+                .long("profile")
+                .value_name("NAME")
+                .env("AURORA_PROFILE")
+                .help("Apply a Beamfile profile: its variables, environment and parallelism overlay the globals"),
+        )
         .arg(
             Arg::new("var")
                 .long("var")
                 .action(clap::ArgAction::Append)
                 .help("Override a Beamfile variable: --var key=value"),
         )
+        .arg(
+            Arg::new("tag")
+                .long("tag")
+                .value_name("TAG")
+                .action(clap::ArgAction::Append)
+                .help("Also run every beam tagged TAG in its `tags` (repeatable)"),
+        )
+        .arg(
+            Arg::new("only")
+                .long("only")
+                .action(clap::ArgAction::SetTrue)
+                .help("Run the targets without their dependencies, taken as already satisfied"),
+        )
+        .arg(
+            Arg::new("exclude")
+                .long("exclude")
+                .value_name("BEAM")
+                .action(clap::ArgAction::Append)
+                .help("Leave BEAM (a beam or an instance id) out of the run, with the dependencies only it needs (repeatable)"),
+        )
+        .arg(
+            Arg::new("jobs")
+                .long("jobs")
+                .short('j')
+                .value_name("N")
+                .value_parser(parse_jobs)
+                .help("Run at most N beams at once, over `max_parallelism` and the profile's"),
+        )
         .arg(
             Arg::new("no-tui")
                 .long("no-tui")
@@ -81,6 +176,40 @@ pub fn cli() -> Command {
                 .conflicts_with_all(["interactive", "list", "dry-run"])
                 .help("Stream the run as newline-delimited JSON events on stdout"),
         )
+        .arg(
+            Arg::new("ci")
+                .long("ci")
+                .value_name("MODE")
+                .value_parser(["auto", "github", "off"])
+                .default_value("auto")
+                .help("CI-native headless output: github (groups, annotations, step summary), off, or auto (github under GITHUB_ACTIONS=true)"),
+        )
+        .arg(
+            Arg::new("output")
+                .long("output")
+                .value_name("MODE")
+                .value_parser(clap::value_parser!(headless::OutputMode))
+                .conflicts_with_all(["json", "interactive", "list", "dry-run"])
+                .help("Headless output layout: interleaved (the default), grouped (one block per beam, failures last) or failed-only"),
+        )
+        .arg(
+            Arg::new("report")
+                .long("report")
+                .value_name("FORMAT=PATH")
+                .action(clap::ArgAction::Append)
+                .value_parser(|raw: &str| reporter::ReportSpec::parse(raw))
+                .conflicts_with_all(["interactive", "list", "dry-run"])
+                .help("Also write a report of the run to a file: junit=report.xml or trace=trace.json (repeatable); one that cannot be written fails the run, or is only reported under --watch"),
+        )
+        .arg(
+            Arg::new("trace")
+                .long("trace")
+                .value_name("PATH")
+                .value_parser(clap::value_parser!(std::path::PathBuf))
+                .conflicts_with_all(["interactive", "list", "dry-run"])
+                .help("Write a Chrome trace of the run (chrome://tracing, Perfetto); same as --report trace=PATH"),
+        )
+        .args(otlp_args())
         .arg(
             Arg::new("completions")
                 .long("completions")
@@ -96,12 +225,147 @@ pub fn cli() -> Command {
         )
         .arg(
             Arg::new("args")
-                .help("Positional arguments for the target beam (use -- before hyphen-leading values)")
+                .help("Positional arguments for the target beam, until a -- and the next beam name (use -- before hyphen-leading values)")
                 .index(2)
                 .num_args(0..),
         )
 }
 
+/// The subcommands, offered at the top level and under [`BUILTIN`].
+fn subcommands() -> [Command; 7] {
+    [
+        Command::new("logs")
+            .about("Print the logs of a past or running run")
+            .arg(Arg::new("beam").help("Beam or instance whose log to print; omitted, the run's beams and their status"))
+            .arg(
+                Arg::new("run")
+                    .long("run")
+                    .value_name("N")
+                    .value_parser(clap::value_parser!(u64))
+                    .help("The run to read (the newest by default)"),
+            )
+            .arg(
+                Arg::new("follow")
+                    .long("follow")
+                    .short('f')
+                    .action(clap::ArgAction::SetTrue)
+                    .requires("beam")
+                    .help("Keep printing the beam's log as it is written, until the beam completes"),
+            ),
+        Command::new("replay")
+            .about("Open a run recorded with --json in the TUI")
+            .arg(
+                Arg::new("file")
+                    .required(true)
+                    .value_parser(clap::value_parser!(PathBuf))
+                    .help("The NDJSON of the run (`aurora <beam> --json > run.ndjson`)"),
+            )
+            .arg(
+                Arg::new("speed")
+                    .long("speed")
+                    .value_name("X")
+                    .value_parser(parse_speed)
+                    .help("Play the run back at X times its recorded pace (1 for real time); without it, show it all at once"),
+            ),
+        Command::new("lint")
+            .about("Check the Beamfile for likely mistakes without running anything")
+            .arg(
+                Arg::new("json")
+                    .long("json")
+                    .action(clap::ArgAction::SetTrue)
+                    .help("Print the diagnostics as a single JSON document"),
+            )
+            .arg(
+                Arg::new("deny-warnings")
+                    .long("deny-warnings")
+                    .action(clap::ArgAction::SetTrue)
+                    .help("Fail on warnings too, not only on errors"),
+            ),
+        Command::new("inspect")
+            .about("Print a beam's definition with the template it extends merged in")
+            .arg(
+                Arg::new("beam")
+                    .required_unless_present("json")
+                    .help("The beam to print; with --expanded, the target (the default beam if omitted)"),
+            )
+            .arg(
+                Arg::new("args")
+                    .help("Positional arguments for the target beam, with --expanded")
+                    .num_args(0..)
+                    .requires("expanded"),
+            )
+            .arg(
+                Arg::new("json")
+                    .long("json")
+                    .action(clap::ArgAction::SetTrue)
+                    .help("Print the whole parsed Beamfile as a single JSON document"),
+            )
+            .arg(
+                Arg::new("expanded")
+                    .long("expanded")
+                    .action(clap::ArgAction::SetTrue)
+                    .requires("json")
+                    .help("Also print the instances a run of the target would schedule"),
+            ),
+        Command::new("fmt")
+            .about("Rewrite the Beamfile in the canonical style")
+            .arg(
+                Arg::new("file")
+                    .value_parser(clap::value_parser!(PathBuf))
+                    .help("The Beamfile to format (`-` for stdin to stdout); the project's by default"),
+            )
+            .arg(
+                Arg::new("check")
+                    .long("check")
+                    .action(clap::ArgAction::SetTrue)
+                    .help("Change nothing; fail when the Beamfile is not formatted"),
+            ),
+        Command::new("ctl")
+            .about("Talk to the Aurora running in this project, through its control socket")
+            .subcommand_required(true)
+            .subcommand(Command::new("status").about("Print the run's target and each beam's status"))
+            .subcommand(
+                Command::new("cancel")
+                    .about("Cancel a beam and its dependents, or the whole run")
+                    .arg(Arg::new("beam").help("Beam or instance to cancel; omitted, the whole run")),
+            )
+            .subcommand(
+                Command::new("rerun")
+                    .about("Run the target, or one beam, again once the run is over (TUI and --watch only)")
+                    .arg(Arg::new("beam").help("Beam or instance to rerun; omitted, the target")),
+            )
+            .subcommand(
+                Command::new("tail")
+                    .about("Stream the output of a running beam, or of every beam, until it completes")
+                    .arg(Arg::new("beam").help("Beam or instance to follow; omitted, every beam")),
+            ),
+        Command::new("lsp")
+            .about("Serve the Language Server Protocol for Beamfiles, for editors")
+            .arg(
+                Arg::new("stdio")
+                    .long("stdio")
+                    .action(clap::ArgAction::SetTrue)
+                    .help("Speak over stdin and stdout (the only transport, accepted for the clients that pass it)"),
+            ),
+    ]
+}
+
+/// `--speed`: a positive, finite factor.
+fn parse_speed(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(speed) if speed.is_finite() && speed > 0.0 => Ok(speed),
+        _ => Err(format!("`{value}` is not a positive number")),
+    }
+}
+
+/// `--jobs`: at least one beam at a time.
+fn parse_jobs(value: &str) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(jobs) if jobs > 0 => Ok(jobs),
+        _ => Err(format!("`{value}` is not a positive integer")),
+    }
+}
+
 /// Writes the completion script for `shell` to `out`.
 pub fn print_completions(shell: clap_complete::Shell, out: &mut impl Write) {
     clap_complete::generate(shell, &mut cli(), "aurora", out);
@@ -112,20 +376,6 @@ pub fn print_man_page(out: &mut impl Write) -> std::io::Result<()> {
     clap_mangen::Man::new(cli()).render(out)
 }
 
-/// Similarity above which a candidate is offered as a "did you mean". Jaro-Winkler
-/// rewards a shared prefix, which is what a typo usually preserves.
-const SUGGESTION_THRESHOLD: f64 = 0.8;
-
-/// The candidate closest to `input`, when one is close enough to be worth
-/// suggesting. Returns `None` rather than a distant, misleading guess.
-fn closest<'a>(input: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
-    candidates
-        .map(|candidate| (candidate, strsim::jaro_winkler(input, candidate)))
-        .filter(|(_, score)| *score >= SUGGESTION_THRESHOLD)
-        .max_by(|(_, a), (_, b)| a.total_cmp(b))
-        .map(|(candidate, _)| candidate)
-}
-
 /// Resolves the beam to run: the explicitly invoked one, otherwise the
 /// `aurora { default = ... }` beam. The resolved name is checked against the
 /// declared beams, because a target that does not exist schedules nothing: left
@@ -196,6 +446,265 @@ pub fn ensure_beam_exists(beam_file: &BeamFile, target: &str) -> Result<()> {
     }
 }
 
+/// The virtual beam a run of several targets is rooted at: it depends on each
+/// target, bound with its arguments, and runs nothing itself. Its events are
+/// dropped before anything shows them (see [`hide_multi_beam`]).
+pub const MULTI_BEAM: &str = "__multi__";
+
+/// A target named on the command line, with the positional arguments that
+/// follow it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Target {
+    pub beam: String,
+    pub args: Vec<String>,
+}
+
+impl Target {
+    pub fn named(beam: impl Into<String>) -> Self {
+        Self {
+            beam: beam.into(),
+            args: vec![],
+        }
+    }
+}
+
+/// What a run covers: the targets named on the command line (or picked) and
+/// the beams `--tag` selects, narrowed by `--only` and `--exclude`. Kept whole
+/// so a reload under watch selects the same instances again.
+#[derive(Debug, Clone, Default)]
+pub struct Selection {
+    pub targets: Vec<Target>,
+    /// `--tag`: every beam carrying one of these is a target too.
+    pub tags: Vec<String>,
+    /// `--only`: the targets run without their dependencies, taken as
+    /// already satisfied.
+    pub only: bool,
+    /// `--exclude`: beams (or instance ids) left out of the run, with the
+    /// dependencies only they need.
+    pub exclude: Vec<String>,
+}
+
+impl Selection {
+    /// True when no target is named nor tagged: the picker or the default beam
+    /// decides.
+    pub fn is_empty(&self) -> bool {
+        self.targets.is_empty() && self.tags.is_empty()
+    }
+}
+
+/// Splits the positionals of `aurora` into targets, each followed by its
+/// arguments. A `--` ends a target's arguments: the value after it names the
+/// next target (`aurora deploy 1.2.3 -- test`), unless it is no beam's name and
+/// the arguments go on (`aurora test -- --nocapture`). Without `--`, a beam's
+/// name starts another target only after one that takes no arguments
+/// (`aurora fmt test docs`); after a beam with params it is an argument like
+/// any other value (`aurora deploy test` binds `env=test`).
+pub fn split_targets(beam_file: &BeamFile, positionals: &[String]) -> Vec<Target> {
+    let declared = |name: &str| beam_file.beams.iter().find(|b| b.name == name);
+    let mut targets: Vec<Target> = vec![];
+    let mut separated = false;
+    for value in positionals {
+        if value == "--" {
+            separated = true;
+            continue;
+        }
+        let takes_no_arguments =
+            |target: &Target| declared(&target.beam).is_some_and(|beam| beam.params.is_empty());
+        match targets.last_mut() {
+            Some(target)
+                if declared(value).is_none() || !(separated || takes_no_arguments(target)) =>
+            {
+                target.args.push(value.clone())
+            }
+            _ => targets.push(Target::named(value.clone())),
+        }
+        separated = false;
+    }
+    targets
+}
+
+/// The targets of `selection`, each checked against the declared beams: the
+/// named ones, then the beams tagged with one of its tags in declaration
+/// order, once each. With neither, the `aurora { default = ... }` beam.
+///
+/// A tag no beam carries is an error, like an unknown beam: a typo in CI would
+/// otherwise select nothing more and still pass.
+pub fn selected_targets(beam_file: &BeamFile, selection: &Selection) -> Result<Vec<Target>> {
+    let mut targets: Vec<Target> = vec![];
+    for target in &selection.targets {
+        ensure_beam_exists(beam_file, &target.beam)?;
+        if !targets.contains(target) {
+            targets.push(target.clone());
+        }
+    }
+    for tag in &selection.tags {
+        if beam_file.beams.iter().any(|b| b.tags.contains(tag)) {
+            continue;
+        }
+        let tags = beam_file.beams.iter().flat_map(|b| &b.tags);
+        match closest(tag, tags.map(String::as_str)) {
+            Some(suggestion) => bail!("No beam is tagged '{tag}'. Did you mean '{suggestion}'?"),
+            None => bail!("No beam is tagged '{tag}'. Run `aurora --list` to see the beams' tags."),
+        }
+    }
+    for beam in &beam_file.beams {
+        let tagged = beam.tags.iter().any(|t| selection.tags.contains(t));
+        if tagged && !targets.iter().any(|t| t.beam == beam.name) {
+            targets.push(Target::named(beam.name.clone()));
+        }
+    }
+    if targets.is_empty() {
+        targets.push(Target::named(resolve_target(beam_file, None)?));
+    }
+    Ok(targets)
+}
+
+/// The beam to expand a run of `targets` from, with its arguments: the target
+/// itself when there is one, otherwise [`MULTI_BEAM`], added to `beam_file`
+/// with a dependency on each target bound with its arguments.
+pub fn root_target(beam_file: &mut BeamFile, targets: &[Target]) -> Result<(String, Vec<String>)> {
+    if let [target] = targets {
+        return Ok((target.beam.clone(), target.args.clone()));
+    }
+    let mut depends_on = Vec::with_capacity(targets.len());
+    for target in targets {
+        ensure_beam_exists(beam_file, &target.beam)?;
+        let beam = beam_file
+            .beams
+            .iter()
+            .find(|b| b.name == target.beam)
+            .expect("checked above");
+        depends_on.push(aurora_core::ast::Dependency {
+            params: aurora_core::expand::bind_cli_args(beam, &target.args)?,
+            ..aurora_core::ast::Dependency::named(target.beam.clone())
+        });
+    }
+    beam_file.beams.push(Beam {
+        name: MULTI_BEAM.to_string(),
+        description: Some("Multi-beam run".to_string()),
+        depends_on,
+        ..Beam::default()
+    });
+    Ok((MULTI_BEAM.to_string(), vec![]))
+}
+
+/// The instance ids a run of `target_id` was asked for: the target, or each
+/// target of a [`MULTI_BEAM`] run.
+fn target_ids(instances: &[Beam], target_id: &str) -> Vec<String> {
+    match instances.iter().find(|b| b.name == target_id) {
+        Some(multi) if target_id == MULTI_BEAM => multi.dependency_names(),
+        _ => vec![target_id.to_string()],
+    }
+}
+
+/// Applies `--exclude` then `--only` to the expanded `instances` of a run of
+/// `target_id`.
+///
+/// An excluded beam's instances leave the run along with every edge to them,
+/// so the dependencies nothing else needs leave with them. With `only`, a
+/// target keeps no dependency but on another target: those run in order,
+/// everything else is taken as already satisfied.
+pub fn prune(
+    beam_file: &BeamFile,
+    instances: &mut Vec<Beam>,
+    target_id: &str,
+    selection: &Selection,
+) -> Result<()> {
+    let targets = target_ids(instances, target_id);
+    for name in &selection.exclude {
+        let known = beam_file.beams.iter().any(|b| &b.name == name)
+            || instances.iter().any(|b| &b.name == name);
+        if !known {
+            match closest(name, beam_file.beams.iter().map(|b| b.name.as_str())) {
+                Some(suggestion) => bail!(
+                    "Unknown beam '{name}' passed with --exclude. Did you mean '{suggestion}'?"
+                ),
+                None => bail!(
+                    "Unknown beam '{name}' passed with --exclude. \
+                     Run `aurora --list` to see the available beams."
+                ),
+            }
+        }
+        if let Some(target) = targets.iter().find(|id| excludes(name, id)) {
+            bail!("--exclude {name} would exclude the target '{target}'");
+        }
+    }
+    let excluded = |id: &str| selection.exclude.iter().any(|name| excludes(name, id));
+    instances.retain(|b| !excluded(&b.name));
+    for instance in instances.iter_mut() {
+        instance.depends_on.retain(|d| !excluded(&d.beam));
+    }
+    if selection.only {
+        for instance in instances.iter_mut().filter(|b| targets.contains(&b.name)) {
+            instance.depends_on.retain(|d| targets.contains(&d.beam));
+        }
+    }
+    Ok(())
+}
+
+/// Whether `--exclude name` covers the instance `id`: it is that instance, or
+/// one of the beam `name` (an id is the beam's name up to the first `[`).
+fn excludes(name: &str, id: &str) -> bool {
+    id == name || id.split('[').next() == Some(name)
+}
+
+/// The name of a run of `target_id` for the user (the logs, `aurora ctl`, the
+/// reports): the target's id, or the targets' of a [`MULTI_BEAM`] run.
+pub fn run_label(instances: &[Beam], target_id: &str) -> String {
+    target_ids(instances, target_id).join(" ")
+}
+
+/// Forwards the events of `rx` but those of [`MULTI_BEAM`]: it is no beam the
+/// user asked for, so no output, log or report shows it.
+pub fn hide_multi_beam(mut rx: mpsc::Receiver<SchedulerEvent>) -> mpsc::Receiver<SchedulerEvent> {
+    let (tx, out) = mpsc::channel(128);
+    tokio::spawn(async move {
+        while let Some(event) = rx.recv().await {
+            let name = match &event {
+                SchedulerEvent::BeamStarted { name }
+                | SchedulerEvent::BeamCompleted { name, .. }
+                | SchedulerEvent::BeamStopping { name }
+                | SchedulerEvent::BeamOutput { name, .. }
+                | SchedulerEvent::BeamPhase { name, .. }
+                | SchedulerEvent::Warning { name, .. } => Some(name.as_str()),
+                SchedulerEvent::AllDone { .. } => None,
+            };
+            if name == Some(MULTI_BEAM) {
+                continue;
+            }
+            if tx.send(event).await.is_err() {
+                break;
+            }
+        }
+    });
+    out
+}
+
+/// The values of the `sensitive` variables, as overridden, and of the
+/// `sensitive` params of `instances`, as bound, for masking. Empty values mask
+/// nothing and are left out.
+pub fn secret_values(beam_file: &BeamFile, instances: &[Beam]) -> Vec<String> {
+    let variables = beam_file
+        .variables
+        .iter()
+        .filter(|v| v.sensitive)
+        .map(|v| v.default.clone());
+    let params = instances.iter().flat_map(|instance| {
+        instance
+            .params
+            .iter()
+            .filter(|p| p.sensitive)
+            .filter_map(|p| instance.bindings.get(&p.name).cloned())
+    });
+    let mut secrets: Vec<String> = vec![];
+    for secret in variables.chain(params) {
+        if !secret.is_empty() && !secrets.contains(&secret) {
+            secrets.push(secret);
+        }
+    }
+    secrets
+}
+
 /// Applies `--var key=value` overrides to the Beamfile's global variables.
 ///
 /// An unknown key is an error: silently dropping it means the run proceeds with
@@ -223,12 +732,69 @@ pub fn apply_var_overrides<'a>(
         }
 
         for variable in beam_file.variables.iter_mut().filter(|v| v.name == key) {
+            if let Err(e) = variable.constraints.check(value) {
+                bail!("Invalid value for variable '{key}' passed with --var {raw}: {e}");
+            }
             variable.default = value.to_string();
+            variable.origin = aurora_core::ast::ValueOrigin::Cli;
+        }
+    }
+    Ok(())
+}
+
+/// Overlays the profile `name` on the globals: its variables replace their
+/// defaults and sources (a `--var`, applied after, still wins), its
+/// `environment {}` is declared after the global block, and its
+/// `max_parallelism` replaces the `aurora {}` one.
+///
+/// Only values change: the profile's name reaches no cache key, so a beam
+/// whose inputs the profile leaves alone stays cached across profiles.
+pub fn apply_profile(beam_file: &mut BeamFile, name: &str) -> Result<()> {
+    let Some(profile) = beam_file.profiles.iter().find(|p| p.name == name).cloned() else {
+        match closest(name, beam_file.profiles.iter().map(|p| p.name.as_str())) {
+            Some(suggestion) => bail!("Unknown profile '{name}'. Did you mean '{suggestion}'?"),
+            None => bail!(
+                "Unknown profile '{name}'. Run `aurora --list` to see the available profiles."
+            ),
         }
+    };
+    for binding in &profile.variables {
+        for variable in beam_file
+            .variables
+            .iter_mut()
+            .filter(|v| v.name == binding.name)
+        {
+            variable.default = binding.value.clone();
+            variable.origin = aurora_core::ast::ValueOrigin::Profile;
+        }
+    }
+    if let Some(overlay) = profile.environment {
+        beam_file
+            .environment
+            .get_or_insert_with(Default::default)
+            .extend(overlay);
+    }
+    if let Some(max_parallelism) = profile.max_parallelism {
+        set_max_parallelism(beam_file, max_parallelism);
     }
     Ok(())
 }
 
+/// Replaces the `aurora {}` block's `max_parallelism`, declaring the block if
+/// the Beamfile has none: what a profile's value and `--jobs` do.
+pub fn set_max_parallelism(beam_file: &mut BeamFile, max_parallelism: usize) {
+    beam_file
+        .config
+        .get_or_insert_with(|| aurora_core::ast::AuroraConfig {
+            version: "1".to_string(),
+            default: None,
+            max_parallelism: None,
+            log_retention: None,
+            inherit_env: vec![],
+        })
+        .max_parallelism = Some(max_parallelism);
+}
+
 /// Resolves once the process is asked to terminate: Ctrl-C anywhere, and also
 /// SIGTERM on Unix (what a CI runner or an orchestrator sends to stop a job).
 ///
@@ -274,6 +840,22 @@ pub fn resolve_max_parallelism(configured: Option<usize>) -> Option<usize> {
     }))
 }
 
+/// `--otlp`, when the binary is built with the `otlp` feature.
+fn otlp_args() -> Vec<Arg> {
+    #[cfg(feature = "otlp")]
+    {
+        vec![Arg::new("otlp")
+            .long("otlp")
+            .value_name("ENDPOINT")
+            .conflicts_with_all(["interactive", "list", "dry-run"])
+            .help("Export the run as OpenTelemetry spans to an OTLP/HTTP collector, e.g. http://localhost:4318")]
+    }
+    #[cfg(not(feature = "otlp"))]
+    {
+        vec![]
+    }
+}
+
 /// Builds a [`Scheduler`] from the shared run parameters, applying the cache
 /// setting and the default parallelism cap in one place. Centralizes the
 /// wiring shared by the initial run and the TUI rerun path so it cannot drift
@@ -324,21 +906,39 @@ pub fn phantom_beams(
 }
 
 /// Evaluates each instance's `environment {}` block (params already
-/// interpolated by expansion) against the global environment. One `shell()`
-/// there runs once per instance: the instance is the unit of execution.
+/// interpolated by expansion) against the global environment, plus the
+/// ambient variables its own `inherit_env` forwards. One `shell()` there runs
+/// once per instance: the instance is the unit of execution.
 pub fn apply_env_overlays(
     beams: &mut [Beam],
     env: &HashMap<String, String>,
     working_dir: &Path,
 ) -> Result<()> {
     for beam in beams.iter_mut() {
+        beam.inherited_env = aurora_core::env::inherited(&beam.inherit_env);
         if let Some(block) = &beam.environment {
-            beam.env_overlay = aurora_core::env::evaluate_overlay(block, env, working_dir)?;
+            let mut visible = env.clone();
+            for (name, value) in &beam.inherited_env {
+                visible.entry(name.clone()).or_insert_with(|| value.clone());
+            }
+            beam.env_overlay = aurora_core::env::evaluate_overlay(block, &visible, working_dir)?;
         }
     }
     Ok(())
 }
 
+/// The ambient variables forwarded to every beam: the Beamfile's global
+/// `inherit_env`, then the `--inherit-env` patterns.
+pub fn inherit_patterns(beam_file: &BeamFile, cli: &[String]) -> Vec<String> {
+    beam_file
+        .config
+        .iter()
+        .flat_map(|c| c.inherit_env.iter())
+        .chain(cli)
+        .cloned()
+        .collect()
+}
+
 /// The per-cycle run data produced by re-parsing a Beamfile: the beams (with
 /// variables and arguments already interpolated into their commands), the full
 /// evaluated environment, the declared subset that feeds cache keys, and the
@@ -354,12 +954,17 @@ pub struct RunInputs {
     /// Sidebar rows for declared beams with no runnable instance (a required
     /// param). Listed but non-launchable; see [`phantom_beams`].
     pub phantom_beams: Vec<(String, Vec<String>)>,
+    /// The values to mask in CI output; see [`secret_values`].
+    pub secrets: Vec<String>,
 }
 
 /// Re-reads and re-parses the Beamfile at `beamfile_path` into a [`RunInputs`],
-/// applying `--var` overrides, resolving `var.*` references and positional
-/// arguments for `target`, then evaluating the `environment {}` block (falling
-/// back to the allowlisted base environment when none is declared).
+/// applying the selected `profile`, `--jobs` then the `--var` overrides, evaluating the variables' sources, resolving
+/// `var.*` references, expanding the targets of `selection` with their
+/// arguments, then evaluating
+/// the `environment {}` block on top of the allowlisted and inherited ambient
+/// variables (`inherit_env` in the Beamfile, `inherit_env` here from
+/// `--inherit-env`).
 ///
 /// This is the reload path: it composes the same steps `main` runs at startup,
 /// so a Beamfile edited while watching is picked up identically to a fresh run.
@@ -369,29 +974,41 @@ pub struct RunInputs {
 pub fn resolve_run_inputs(
     beamfile_path: &Path,
     working_dir: &Path,
+    profile: Option<&str>,
     var_overrides: &[String],
-    target: &str,
-    args: &[String],
+    inherit_env: &[String],
+    selection: &Selection,
+    jobs: Option<usize>,
 ) -> Result<RunInputs> {
     let content = std::fs::read_to_string(beamfile_path)?;
     let mut beam_file = aurora_core::parser::parse(&content)?;
+    aurora_core::dag::validate(&beam_file)?;
+    if let Some(profile) = profile {
+        apply_profile(&mut beam_file, profile)?;
+    }
+    if let Some(jobs) = jobs {
+        set_max_parallelism(&mut beam_file, jobs);
+    }
     apply_var_overrides(&mut beam_file, var_overrides.iter())?;
+    aurora_core::env::evaluate_variables(&mut beam_file, working_dir)?;
     aurora_core::parser::resolve_variables(&mut beam_file)?;
-    let expansion = aurora_core::expand::expand(&beam_file, target, args)?;
+    let targets = selected_targets(&beam_file, selection)?;
+    let (target, args) = root_target(&mut beam_file, &targets)?;
+    let expansion = aurora_core::expand::expand(&beam_file, &target, &args)?;
 
-    let env = match &beam_file.environment {
-        Some(env_block) => aurora_core::env::evaluate(env_block, working_dir)?,
-        None => aurora_core::env::base_env(),
-    };
-    let declared_env = aurora_core::env::declared_only(beam_file.environment.as_ref(), &env);
+    let ambient = aurora_core::env::ambient_env(&inherit_patterns(&beam_file, inherit_env));
+    let aurora_core::env::EvaluatedEnv {
+        env,
+        declared: declared_env,
+    } = aurora_core::env::evaluate_declared(beam_file.environment.as_ref(), ambient, working_dir)?;
     let max_parallelism = beam_file.config.as_ref().and_then(|c| c.max_parallelism);
 
     let mut instances = expansion.instances;
+    prune(&beam_file, &mut instances, &expansion.target_id, selection)?;
     apply_env_overlays(&mut instances, &env, working_dir)?;
 
-    // A reloaded Beamfile carries no virtual multi-beam sentinel (watch runs a
-    // single beam), so nothing is excluded.
-    let phantom_beams = phantom_beams(&beam_file.beams, &instances, "");
+    let phantom_beams = phantom_beams(&beam_file.beams, &instances, MULTI_BEAM);
+    let secrets = secret_values(&beam_file, &instances);
 
     Ok(RunInputs {
         beams: instances,
@@ -400,5 +1017,6 @@ pub fn resolve_run_inputs(
         max_parallelism,
         target_id: expansion.target_id,
         phantom_beams,
+        secrets,
     })
 }
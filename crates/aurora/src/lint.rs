@@ -0,0 +1,686 @@
+//! `aurora lint`: static checks of a Beamfile, run without executing anything.
+//!
+//! Each check is a rule with a stable ID and a severity. Errors are mistakes
+//! the run itself would fail on (or silently misbehave with); warnings are
+//! likely mistakes. A rule is silenced for one beam or variable with a
+//! suppression comment inside its block, or on the lines just above it:
+//!
+//! ```text
+//! # aurora-lint: allow(dead-input, unreachable-beam)
+//! beam "generated" { ... }
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use aurora_core::ast::{Beam, BeamFile, ConditionClause, EnvValue};
+use aurora_core::dag::BeamGraph;
+use serde::Serialize;
+
+/// The schema version of the `lint --json` document, versioned apart from the
+/// `--json` event stream.
+pub const LINT_SCHEMA: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl Severity {
+    fn label(self) -> &'static str {
+        match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+/// A lint rule: its ID (as used in suppressions and in the output) and its
+/// severity.
+pub struct Rule {
+    pub id: &'static str,
+    pub severity: Severity,
+    pub summary: &'static str,
+}
+
+/// Every rule, in reporting order.
+pub const RULES: &[Rule] = &[
+    Rule {
+        id: "parse-error",
+        severity: Severity::Error,
+        summary: "the Beamfile does not parse; nothing else is checked",
+    },
+    Rule {
+        id: "unknown-dependency",
+        severity: Severity::Error,
+        summary: "`depends_on` names a beam that is not declared",
+    },
+    Rule {
+        id: "dependency-cycle",
+        severity: Severity::Error,
+        summary: "the beams depend on each other in a cycle",
+    },
+    Rule {
+        id: "missing-dir",
+        severity: Severity::Error,
+        summary: "a beam's `dir` does not exist",
+    },
+    Rule {
+        id: "unknown-variable",
+        severity: Severity::Error,
+        summary: "`${var.x}` references a variable that is not declared",
+    },
+    Rule {
+        id: "undeclared-param",
+        severity: Severity::Error,
+        summary: "`${param.x}` references a param the beam does not declare",
+    },
+    Rule {
+        id: "docker-without-image",
+        severity: Severity::Error,
+        summary: "a docker executor has no `image`",
+    },
+    Rule {
+        id: "dead-input",
+        severity: Severity::Warning,
+        summary: "an `inputs` pattern matches no file, so it keys the cache on nothing",
+    },
+    Rule {
+        id: "output-overlaps-input",
+        severity: Severity::Warning,
+        summary: "an output matches an `inputs` pattern, so each run invalidates the next",
+    },
+    Rule {
+        id: "unused-variable",
+        severity: Severity::Warning,
+        summary: "a variable is declared but never referenced",
+    },
+    Rule {
+        id: "unreachable-beam",
+        severity: Severity::Warning,
+        summary: "a beam has no description, is not the default and no target depends on it",
+    },
+    Rule {
+        id: "unknown-rule",
+        severity: Severity::Warning,
+        summary: "a suppression comment names a rule that does not exist",
+    },
+];
+
+fn rule(id: &str) -> &'static Rule {
+    RULES
+        .iter()
+        .find(|rule| rule.id == id)
+        .unwrap_or_else(|| panic!("undeclared lint rule `{id}`"))
+}
+
+/// What a diagnostic is about: the suppression scope.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Subject {
+    File,
+    Beam(String),
+    Variable(String),
+}
+
+impl std::fmt::Display for Subject {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Subject::File => write!(f, "Beamfile"),
+            Subject::Beam(name) => write!(f, "beam '{name}'"),
+            Subject::Variable(name) => write!(f, "variable '{name}'"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub subject: Subject,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn new(id: &str, subject: Subject, message: String) -> Self {
+        let rule = rule(id);
+        Self {
+            rule: rule.id,
+            severity: rule.severity,
+            subject,
+            message,
+        }
+    }
+}
+
+/// Lints the Beamfile `source`, whose directory is `project_dir`, and drops
+/// the diagnostics its suppression comments allow. Diagnostics come out in
+/// [`RULES`] order, then in declaration order.
+pub fn lint(source: &str, project_dir: &Path) -> Vec<Diagnostic> {
+    let beam_file = match aurora_core::parser::parse(source) {
+        Ok(beam_file) => beam_file,
+        Err(e) => {
+            let mut message = e.to_string();
+            if let Some(diagnostic) = aurora_core::diagnostic::find(&e) {
+                let (line, column) = diagnostic.locate(source).line_col(source);
+                message.push_str(&format!(" (line {line}, column {column})"));
+            }
+            return vec![Diagnostic::new("parse-error", Subject::File, message)];
+        }
+    };
+    let (allowed, mut diagnostics) = suppressions(source);
+    diagnostics.extend(check(&beam_file, project_dir).into_iter().filter(|d| {
+        !allowed
+            .get(&d.subject)
+            .is_some_and(|ids| ids.contains(d.rule))
+    }));
+    let order = |d: &Diagnostic| RULES.iter().position(|r| r.id == d.rule);
+    diagnostics.sort_by_key(order);
+    diagnostics
+}
+
+fn check(beam_file: &BeamFile, project_dir: &Path) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    let beams = &beam_file.beams;
+    let declared: HashSet<&str> = beams.iter().map(|b| b.name.as_str()).collect();
+    let variables: HashMap<&str, &str> = beam_file
+        .variables
+        .iter()
+        .map(|v| (v.name.as_str(), v.default.as_str()))
+        .collect();
+    let mut referenced: HashSet<String> = HashSet::new();
+
+    for beam in beams {
+        let subject = || Subject::Beam(beam.name.clone());
+
+        for dep in &beam.depends_on {
+            if !declared.contains(dep.beam.as_str()) {
+                diagnostics.push(Diagnostic::new(
+                    "unknown-dependency",
+                    subject(),
+                    format!(
+                        "depends on '{}', which is not declared{}",
+                        dep.beam,
+                        suggestion(&dep.beam, declared.iter().copied())
+                    ),
+                ));
+            }
+        }
+
+        for name in variable_references(beam) {
+            if !variables.contains_key(name.as_str()) {
+                diagnostics.push(Diagnostic::new(
+                    "unknown-variable",
+                    subject(),
+                    format!(
+                        "references variable '{name}', which is not declared{}",
+                        suggestion(&name, variables.keys().copied())
+                    ),
+                ));
+            }
+            referenced.insert(name);
+        }
+
+        let params: HashSet<&str> = beam.params.iter().map(|p| p.name.as_str()).collect();
+        let mut reported = HashSet::new();
+        for name in param_references(beam) {
+            if !params.contains(name.as_str()) && reported.insert(name.clone()) {
+                diagnostics.push(Diagnostic::new(
+                    "undeclared-param",
+                    subject(),
+                    format!(
+                        "references '${{param.{name}}}', but declares no param '{name}'{}",
+                        suggestion(&name, params.iter().copied())
+                    ),
+                ));
+            }
+        }
+
+        if let Some(executor) = beam.run.as_ref().and_then(|run| run.executor.as_ref()) {
+            if executor.name == "docker" && !executor.config.contains_key("image") {
+                diagnostics.push(Diagnostic::new(
+                    "docker-without-image",
+                    subject(),
+                    "runs on the docker executor without an `image`".to_string(),
+                ));
+            }
+        }
+
+        // The directory the beam resolves its inputs against. A `dir` that
+        // still holds a `${...}` token after the variables (a param, a shell
+        // expansion) is only known at run time: neither it nor the inputs
+        // under it are checked.
+        let base = match &beam.dir {
+            None => Some(project_dir.to_path_buf()),
+            Some(dir) => {
+                let resolved = substitute_variables(dir, &variables);
+                if resolved.contains("${") {
+                    None
+                } else if project_dir.join(&resolved).is_dir() {
+                    Some(project_dir.join(&resolved))
+                } else {
+                    diagnostics.push(Diagnostic::new(
+                        "missing-dir",
+                        subject(),
+                        format!("dir '{resolved}' does not exist"),
+                    ));
+                    None
+                }
+            }
+        };
+        if let Some(base) = base {
+            for pattern in &beam.inputs {
+                if !matches_any(&base, pattern) {
+                    diagnostics.push(Diagnostic::new(
+                        "dead-input",
+                        subject(),
+                        format!("input '{pattern}' matches no file"),
+                    ));
+                }
+            }
+        }
+    }
+
+    let all: HashSet<String> = beams.iter().map(|b| b.name.clone()).collect();
+    for (beam, output) in crate::watch::output_input_overlaps(beams, &all) {
+        diagnostics.push(Diagnostic::new(
+            "output-overlaps-input",
+            Subject::Beam(beam),
+            format!(
+                "output '{output}' matches an input; each run invalidates the cache \
+                 and re-triggers a watch"
+            ),
+        ));
+    }
+
+    for variable in &beam_file.variables {
+        if !referenced.contains(&variable.name) {
+            diagnostics.push(Diagnostic::new(
+                "unused-variable",
+                Subject::Variable(variable.name.clone()),
+                "is declared but never referenced".to_string(),
+            ));
+        }
+    }
+
+    // Unknown dependencies are reported above; the rest of the graph is
+    // still checked without them.
+    let deps: Vec<(String, Vec<String>)> = beams
+        .iter()
+        .map(|b| {
+            let known = b
+                .dependency_names()
+                .into_iter()
+                .filter(|dep| declared.contains(dep.as_str()))
+                .collect();
+            (b.name.clone(), known)
+        })
+        .collect();
+    match BeamGraph::from_deps(deps) {
+        Ok(graph) => diagnostics.extend(unreachable(beam_file, &graph)),
+        Err(e) => diagnostics.push(Diagnostic::new(
+            "dependency-cycle",
+            Subject::File,
+            e.to_string(),
+        )),
+    }
+    diagnostics
+}
+
+/// The beams no target reaches. The targets are the entry points a Beamfile
+/// advertises: the default beam and every beam with a `description` (what
+/// `--list` and the picker present). A Beamfile that advertises none treats
+/// every beam as a target, so nothing is unreachable.
+fn unreachable(beam_file: &BeamFile, graph: &BeamGraph) -> Vec<Diagnostic> {
+    let default = beam_file.config.as_ref().and_then(|c| c.default.as_deref());
+    let targets: Vec<&Beam> = beam_file
+        .beams
+        .iter()
+        .filter(|b| b.description.is_some() || Some(b.name.as_str()) == default)
+        .collect();
+    if targets.is_empty() {
+        return vec![];
+    }
+    let reached: HashSet<String> = targets
+        .iter()
+        .flat_map(|b| graph.transitive_deps(&b.name))
+        .collect();
+    beam_file
+        .beams
+        .iter()
+        .filter(|b| !reached.contains(&b.name))
+        .map(|b| {
+            Diagnostic::new(
+                "unreachable-beam",
+                Subject::Beam(b.name.clone()),
+                "is not the default, has no description and no target depends on it".to_string(),
+            )
+        })
+        .collect()
+}
+
+/// `"; did you mean 'x'?"` when a candidate is close to `input`.
+fn suggestion<'a>(input: &str, candidates: impl Iterator<Item = &'a str>) -> String {
+    aurora_core::diagnostic::closest(input, candidates)
+        .map(|s| format!("; did you mean '{s}'?"))
+        .unwrap_or_default()
+}
+
+/// The `${<prefix><name>}` tokens of `s`, as names.
+fn tokens(s: &str, prefix: &str) -> Vec<String> {
+    let mut names = vec![];
+    let mut rest = s;
+    while let Some(start) = rest.find("${") {
+        rest = &rest[start + 2..];
+        let Some(end) = rest.find('}') else { break };
+        if let Some(name) = rest[..end].strip_prefix(prefix) {
+            names.push(name.to_string());
+        }
+        rest = &rest[end + 1..];
+    }
+    names
+}
+
+/// The strings of `beam` that take `${...}` interpolation: `dir`, the gates,
+/// the commands, the executor config and the `environment {}` values.
+fn interpolated(beam: &Beam) -> Vec<&str> {
+    let mut fields: Vec<&str> = vec![];
+    fields.extend(beam.dir.as_deref());
+    fields.extend(beam.skip_if.as_deref());
+    if let Some(condition) = &beam.condition {
+        fields.extend(
+            condition
+                .clauses
+                .iter()
+                .map(|ConditionClause::Shell(clause)| clause.as_str()),
+        );
+    }
+    if let Some(run) = &beam.run {
+        fields.extend(run.commands.iter().map(String::as_str));
+        if let Some(executor) = &run.executor {
+            fields.extend(executor.config.values().map(String::as_str));
+        }
+    }
+    if let Some(environment) = &beam.environment {
+        fields.extend(environment.vars.iter().map(|var| match &var.value {
+            EnvValue::Literal(s) | EnvValue::Shell(s) => s.as_str(),
+        }));
+        fields.extend(
+            environment
+                .loads
+                .iter()
+                .flat_map(|load| &load.files)
+                .map(|file| file.path.as_str()),
+        );
+    }
+    fields
+}
+
+/// The values a beam binds in its `depends_on` entries. They are interpolated
+/// in the beam's own context, like its other fields.
+fn bound_values(beam: &Beam) -> impl Iterator<Item = &str> {
+    beam.depends_on
+        .iter()
+        .flat_map(|dep| dep.params.values().map(String::as_str))
+}
+
+/// The variables `beam` references: `${var.x}` anywhere it is interpolated,
+/// and an executor field whose whole value is `var.x`.
+fn variable_references(beam: &Beam) -> Vec<String> {
+    let mut names: Vec<String> = interpolated(beam)
+        .into_iter()
+        .chain(bound_values(beam))
+        .flat_map(|s| tokens(s, "var."))
+        .collect();
+    if let Some(executor) = beam.run.as_ref().and_then(|run| run.executor.as_ref()) {
+        let mut keys: Vec<&String> = executor.config.keys().collect();
+        keys.sort();
+        names.extend(
+            keys.into_iter()
+                .filter_map(|key| executor.config[key].strip_prefix("var."))
+                .map(str::to_string),
+        );
+    }
+    names
+}
+
+/// The params `beam` references with `${param.x}`.
+fn param_references(beam: &Beam) -> Vec<String> {
+    interpolated(beam)
+        .into_iter()
+        .chain(bound_values(beam))
+        .flat_map(|s| tokens(s, "param."))
+        .collect()
+}
+
+/// `s` with its `${var.x}` tokens replaced by the variables' defaults; an
+/// unknown variable is left as is.
+fn substitute_variables(s: &str, variables: &HashMap<&str, &str>) -> String {
+    let mut out = s.to_string();
+    for (name, value) in variables {
+        out = out.replace(&format!("${{var.{name}}}"), value);
+    }
+    out
+}
+
+/// Whether the input `pattern`, relative to `base`, matches anything. An
+/// invalid pattern counts as matching nothing.
+fn matches_any(base: &Path, pattern: &str) -> bool {
+    let full = base.join(pattern).to_string_lossy().to_string();
+    glob::glob(&full).is_ok_and(|mut paths| paths.any(|path| path.is_ok()))
+}
+
+/// The rules each beam and variable allows, read from the suppression
+/// comments of `source`, and an `unknown-rule` diagnostic for each allowed
+/// ID that is no rule.
+///
+/// A comment inside a `beam` or `variable` block applies to that block; a
+/// comment between blocks applies to the next one. `source` must parse, so
+/// the scan only has to track strings, comments and braces.
+fn suppressions(source: &str) -> (HashMap<Subject, HashSet<String>>, Vec<Diagnostic>) {
+    let mut allowed: HashMap<Subject, HashSet<String>> = HashMap::new();
+    let mut diagnostics = vec![];
+    let mut pending: Vec<String> = vec![];
+    let mut current: Option<Subject> = None;
+    // The keyword of the top-level block being opened, until its name.
+    let mut opening: Option<&str> = None;
+    let mut depth = 0usize;
+
+    let bytes = source.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'#' => {
+                let end = source[i..].find('\n').map_or(source.len(), |n| i + n);
+                let ids = allow_directive(&source[i + 1..end]);
+                match (&current, depth) {
+                    (Some(subject), 1..) => allowed
+                        .entry(subject.clone())
+                        .or_default()
+                        .extend(ids.iter().cloned()),
+                    _ => pending.extend(ids.iter().cloned()),
+                }
+                for id in ids {
+                    if !RULES.iter().any(|rule| rule.id == id) {
+                        diagnostics.push(Diagnostic::new(
+                            "unknown-rule",
+                            current.clone().unwrap_or(Subject::File),
+                            format!(
+                                "allows '{id}', which is no lint rule{}",
+                                suggestion(&id, RULES.iter().map(|rule| rule.id))
+                            ),
+                        ));
+                    }
+                }
+                i = end;
+            }
+            b'"' => {
+                let start = i + 1;
+                i = start;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+                if let Some(keyword) = opening.take() {
+                    let name = source[start..i.min(source.len())].to_string();
+                    let subject = match keyword {
+                        "beam" => Subject::Beam(name),
+                        _ => Subject::Variable(name),
+                    };
+                    allowed
+                        .entry(subject.clone())
+                        .or_default()
+                        .extend(pending.drain(..));
+                    current = Some(subject);
+                }
+                i += 1;
+            }
+            b'{' => {
+                depth += 1;
+                i += 1;
+            }
+            b'}' => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    current = None;
+                }
+                i += 1;
+            }
+            b if depth == 0 && b.is_ascii_alphabetic() => {
+                let end = source[i..]
+                    .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                    .map_or(source.len(), |n| i + n);
+                opening = match &source[i..end] {
+                    "beam" => Some("beam"),
+                    "variable" => Some("variable"),
+                    // `aurora {}` and `environment {}` take no suppression:
+                    // what is pending is dropped.
+                    _ => {
+                        pending.clear();
+                        None
+                    }
+                };
+                i = end;
+            }
+            _ => i += 1,
+        }
+    }
+    (allowed, diagnostics)
+}
+
+/// The rule IDs of an `aurora-lint: allow(a, b)` comment (without its `#`).
+fn allow_directive(comment: &str) -> Vec<String> {
+    let Some(rest) = comment.trim().strip_prefix("aurora-lint:") else {
+        return vec![];
+    };
+    let Some(list) = rest
+        .trim()
+        .strip_prefix("allow(")
+        .and_then(|rest| rest.split_once(')'))
+        .map(|(list, _)| list)
+    else {
+        return vec![];
+    };
+    list.split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    schema: u32,
+    errors: usize,
+    warnings: usize,
+    diagnostics: Vec<JsonDiagnostic<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonDiagnostic<'a> {
+    rule: &'a str,
+    severity: Severity,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    beam: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    variable: Option<&'a str>,
+    message: &'a str,
+}
+
+/// Writes `diagnostics` to `out`: one line each then a count, or with `json`
+/// a single JSON document.
+pub fn report(diagnostics: &[Diagnostic], json: bool, out: &mut impl Write) -> Result<()> {
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .count();
+    let warnings = diagnostics.len() - errors;
+    if json {
+        let report = JsonReport {
+            schema: LINT_SCHEMA,
+            errors,
+            warnings,
+            diagnostics: diagnostics
+                .iter()
+                .map(|d| JsonDiagnostic {
+                    rule: d.rule,
+                    severity: d.severity,
+                    beam: match &d.subject {
+                        Subject::Beam(name) => Some(name),
+                        _ => None,
+                    },
+                    variable: match &d.subject {
+                        Subject::Variable(name) => Some(name),
+                        _ => None,
+                    },
+                    message: &d.message,
+                })
+                .collect(),
+        };
+        serde_json::to_writer(&mut *out, &report)?;
+        writeln!(out)?;
+        return Ok(());
+    }
+    for d in diagnostics {
+        writeln!(
+            out,
+            "{}[{}]: {}: {}",
+            d.severity.label(),
+            d.rule,
+            d.subject,
+            d.message
+        )?;
+    }
+    if diagnostics.is_empty() {
+        writeln!(out, "no issues found")?;
+    } else {
+        let plural = |n: usize, word: &str| format!("{n} {word}{}", if n == 1 { "" } else { "s" });
+        writeln!(
+            out,
+            "{}, {}",
+            plural(errors, "error"),
+            plural(warnings, "warning")
+        )?;
+    }
+    Ok(())
+}
+
+/// `aurora lint [--json] [--deny-warnings]`. Returns whether the Beamfile
+/// passes: no error, and no warning either with `deny_warnings`.
+pub fn lint_command(
+    beamfile: &Path,
+    json: bool,
+    deny_warnings: bool,
+    out: &mut impl Write,
+) -> Result<bool> {
+    let source = std::fs::read_to_string(beamfile)
+        .with_context(|| format!("cannot read {}", beamfile.display()))?;
+    let project_dir = beamfile.parent().unwrap_or_else(|| Path::new("."));
+    let diagnostics = lint(&source, project_dir);
+    report(&diagnostics, json, out)?;
+    Ok(!diagnostics
+        .iter()
+        .any(|d| d.severity == Severity::Error || deny_warnings))
+}
@@ -0,0 +1,474 @@
+//! Per-run log files and `aurora logs`.
+//!
+//! Every run (headless, `--json`, the TUI, a rerun from the TUI, a watch
+//! cycle) gets a numbered directory under `.aurora/logs`, and each beam
+//! instance a `<instance>.log` file in it, written line by line as the events
+//! arrive so that another process can follow it. A line reads
+//! `<timestamp> <tag> <text>`: the tag is `out` or `err` for the command's
+//! streams and `---` for Aurora's own notes (start, stop, warnings). A file
+//! ends with a `--- done: <status>` line once its beam completes. `run.info`
+//! records the run's target, the process writing it and, once it is over, its
+//! outcome.
+//!
+//! Only the newest runs are kept: `log_retention` in the `aurora {}` block,
+//! [`DEFAULT_RETENTION`] when unset, `0` to write no logs at all.
+
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use aurora_core::ansi::plain_text;
+use aurora_core::events::{BeamStatus, SchedulerEvent, SkipReason};
+use tokio::sync::mpsc;
+
+use crate::headless::fmt_duration;
+use crate::time::now_iso8601;
+
+/// Runs kept when the Beamfile sets no `log_retention`.
+pub const DEFAULT_RETENTION: usize = 20;
+
+/// Where the run directories live, relative to the project directory.
+const LOGS_DIR: &str = ".aurora/logs";
+
+/// The run's metadata file, next to the beam logs.
+const RUN_INFO: &str = "run.info";
+
+/// The tag of Aurora's own lines, and the note that closes a beam's file.
+const NOTE: &str = "---";
+const DONE: &str = "done: ";
+
+/// How often `--follow` looks for new lines.
+const FOLLOW_POLL: Duration = Duration::from_millis(200);
+
+/// The log directory of a project.
+#[derive(Debug, Clone)]
+pub struct LogStore {
+    root: PathBuf,
+    retention: usize,
+}
+
+impl LogStore {
+    pub fn new(project_dir: &Path) -> Self {
+        Self {
+            root: project_dir.join(LOGS_DIR),
+            retention: DEFAULT_RETENTION,
+        }
+    }
+
+    /// Applies the Beamfile's `log_retention`, when it sets one.
+    pub fn with_retention(mut self, retention: Option<usize>) -> Self {
+        if let Some(retention) = retention {
+            self.retention = retention;
+        }
+        self
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.retention > 0
+    }
+
+    /// The ids of the runs on disk, oldest first.
+    pub fn runs(&self) -> std::io::Result<Vec<u64>> {
+        let entries = match fs::read_dir(&self.root) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e),
+        };
+        let mut runs: Vec<u64> = entries
+            .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse().ok())
+            .collect();
+        runs.sort_unstable();
+        Ok(runs)
+    }
+
+    pub fn run_dir(&self, id: u64) -> PathBuf {
+        self.root.join(id.to_string())
+    }
+
+    /// Creates the directory of a new run of `target`, numbered after the
+    /// newest one, then drops the runs beyond the retention. Creating the
+    /// directory claims its number, so concurrent Aurora processes never
+    /// share a run.
+    pub fn open_run(&self, target: &str) -> std::io::Result<RunLog> {
+        fs::create_dir_all(&self.root)?;
+        let mut id = self.runs()?.last().map_or(1, |last| last + 1);
+        let dir = loop {
+            let dir = self.run_dir(id);
+            match fs::create_dir(&dir) {
+                Ok(()) => break dir,
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => id += 1,
+                Err(e) => return Err(e),
+            }
+        };
+        fs::write(
+            dir.join(RUN_INFO),
+            format!(
+                "target = {target}\nstarted = {}\npid = {}\n",
+                now_iso8601(),
+                std::process::id()
+            ),
+        )?;
+
+        let runs = self.runs()?;
+        let excess = runs.len().saturating_sub(self.retention.max(1));
+        for old in &runs[..excess] {
+            // A run another process still writes to goes too: its files
+            // are unlinked, not truncated, so that process carries on.
+            let _ = fs::remove_dir_all(self.run_dir(*old));
+        }
+        Ok(RunLog {
+            id,
+            dir,
+            files: HashMap::new(),
+        })
+    }
+}
+
+/// The log files of one run, being written.
+pub struct RunLog {
+    id: u64,
+    dir: PathBuf,
+    files: HashMap<String, File>,
+}
+
+impl RunLog {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Appends what `event` says to the log of its beam.
+    pub fn record(&mut self, event: &SchedulerEvent) -> std::io::Result<()> {
+        match event {
+            SchedulerEvent::BeamStarted { name } => self.write(name, NOTE, "started"),
+            SchedulerEvent::BeamOutput {
+                name,
+                line,
+                is_stderr,
+            } => {
+                let tag = if *is_stderr { "err" } else { "out" };
+                self.write(name, tag, &plain_text(line))
+            }
+            SchedulerEvent::BeamStopping { name } => self.write(name, NOTE, "stopping"),
+            SchedulerEvent::Warning { name, message } => {
+                self.write(name, NOTE, &format!("warning: {message}"))
+            }
+            SchedulerEvent::BeamCompleted { name, status } => {
+                self.write(name, NOTE, &format!("{DONE}{}", status_text(status)))?;
+                self.files.remove(name);
+                Ok(())
+            }
+            SchedulerEvent::BeamPhase { .. } => Ok(()),
+            SchedulerEvent::AllDone { success } => {
+                let mut info = OpenOptions::new()
+                    .append(true)
+                    .open(self.dir.join(RUN_INFO))?;
+                let outcome = if *success { "passed" } else { "failed" };
+                write!(info, "finished = {}\noutcome = {outcome}\n", now_iso8601())
+            }
+        }
+    }
+
+    /// Writes one line in a single call, so a reader never sees half of it.
+    fn write(&mut self, beam: &str, tag: &str, text: &str) -> std::io::Result<()> {
+        let file = match self.files.get_mut(beam) {
+            Some(file) => file,
+            None => {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(self.dir.join(file_name(beam)))?;
+                self.files.entry(beam.to_string()).or_insert(file)
+            }
+        };
+        file.write_all(format!("{} {tag} {text}\n", now_iso8601()).as_bytes())
+    }
+
+    /// Records every event of `rx` on its way to the returned receiver.
+    /// Logs are best-effort: a write that fails (a full disk) never holds
+    /// the run up.
+    pub fn tee(mut self, mut rx: mpsc::Receiver<SchedulerEvent>) -> mpsc::Receiver<SchedulerEvent> {
+        let (tx, out) = mpsc::channel(128);
+        tokio::spawn(async move {
+            while let Some(event) = rx.recv().await {
+                let _ = self.record(&event);
+                if tx.send(event).await.is_err() {
+                    break;
+                }
+            }
+        });
+        out
+    }
+}
+
+/// The log file of an instance: its id, with anything a file name cannot
+/// safely carry (a `/` in a bound param value) replaced by `_`.
+pub fn file_name(instance: &str) -> String {
+    let safe: String = instance
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || "-_.[]=,+@".contains(c) {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{safe}.log")
+}
+
+/// A completed beam's status, as its closing line puts it.
+fn status_text(status: &BeamStatus) -> String {
+    match status {
+        BeamStatus::Success {
+            duration,
+            cached: false,
+        } => format!("passed {}", fmt_duration(*duration)),
+        BeamStatus::Success { cached: true, .. }
+        | BeamStatus::Skipped {
+            reason: SkipReason::Cached,
+        } => "cached".to_string(),
+        BeamStatus::Skipped {
+            reason: SkipReason::SkipIf,
+        } => "skipped (skip_if)".to_string(),
+        BeamStatus::Skipped {
+            reason: SkipReason::ConditionNotMet,
+        } => "skipped (condition not met)".to_string(),
+        BeamStatus::Failed {
+            exit_code,
+            duration,
+        } => format!("failed (exit {exit_code}) {}", fmt_duration(*duration)),
+        BeamStatus::FailedAllowed {
+            exit_code,
+            duration,
+        } => format!(
+            "failed, allowed (exit {exit_code}) {}",
+            fmt_duration(*duration)
+        ),
+        BeamStatus::Cancelled => "cancelled".to_string(),
+        BeamStatus::Pending | BeamStatus::Running | BeamStatus::Stopping => "running".to_string(),
+    }
+}
+
+/// The status a log line closes its file with, if it is the closing line.
+fn done_status(line: &str) -> Option<&str> {
+    let (_, rest) = line.split_once(' ')?;
+    rest.strip_prefix(NOTE)?
+        .strip_prefix(' ')?
+        .strip_prefix(DONE)
+}
+
+/// `key = value` lines of a run's `run.info`.
+fn run_info(dir: &Path) -> HashMap<String, String> {
+    fs::read_to_string(dir.join(RUN_INFO))
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| line.split_once(" = "))
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+fn run_finished(dir: &Path) -> bool {
+    run_over(&run_info(dir))
+}
+
+/// Whether a run is over: it recorded its outcome, or the process writing it
+/// is gone without doing so (killed).
+fn run_over(info: &HashMap<String, String>) -> bool {
+    info.contains_key("outcome") || writer_gone(info)
+}
+
+fn writer_gone(info: &HashMap<String, String>) -> bool {
+    info.get("pid")
+        .and_then(|pid| pid.parse::<u32>().ok())
+        .is_some_and(|pid| !process_alive(pid))
+}
+
+#[cfg(unix)]
+fn process_alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // SAFETY: signal 0 sends nothing; `kill(2)` only checks that the process
+    // exists (EPERM: it does, owned by someone else).
+    let found = unsafe { libc::kill(pid, 0) } == 0;
+    found || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+/// Without a portable check, a writer is taken as alive until it records the
+/// run's outcome.
+#[cfg(not(unix))]
+fn process_alive(_pid: u32) -> bool {
+    true
+}
+
+/// `aurora logs [BEAM] [--run N] [--follow]`: without a beam, the selected
+/// run (the newest by default) and the status of each of its beams; with a
+/// beam, its log, followed until the beam completes under `--follow`.
+pub async fn logs_command(
+    store: &LogStore,
+    beam: Option<&str>,
+    run: Option<u64>,
+    follow: bool,
+    out: &mut impl Write,
+) -> Result<()> {
+    let runs = store.runs()?;
+    let Some(&newest) = runs.last() else {
+        bail!("no run logs yet under {}", store.root().display());
+    };
+    let id = run.unwrap_or(newest);
+    if !runs.contains(&id) {
+        bail!(
+            "no run {id} under {} (runs {} to {newest} are kept)",
+            store.root().display(),
+            runs[0]
+        );
+    }
+    let dir = store.run_dir(id);
+
+    let Some(beam) = beam else {
+        return print_run(id, &dir, out);
+    };
+
+    let logs = loop {
+        let logs = beam_logs(&dir, beam)?;
+        // A beam of a live run may not have started yet.
+        if !logs.is_empty() || !follow || run_finished(&dir) {
+            break logs;
+        }
+        tokio::time::sleep(FOLLOW_POLL).await;
+    };
+    match logs.as_slice() {
+        [] => bail!("no log for `{beam}` in run {id}"),
+        [path] if follow => follow_log(path, &dir, out).await,
+        [_, ..] if follow => bail!(
+            "`{beam}` ran as several instances in run {id}; follow one of them: {}",
+            logs.iter()
+                .map(|p| instance_of(p))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        [path] => {
+            out.write_all(&fs::read(path)?)?;
+            Ok(())
+        }
+        _ => {
+            for (i, path) in logs.iter().enumerate() {
+                if i > 0 {
+                    writeln!(out)?;
+                }
+                writeln!(out, "==> {} <==", instance_of(path))?;
+                out.write_all(&fs::read(path)?)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// The logs of `beam` in a run: the instance of that exact id, or else
+/// every instance of the beam (`beam[...]`).
+fn beam_logs(dir: &Path, beam: &str) -> Result<Vec<PathBuf>> {
+    let exact = dir.join(file_name(beam));
+    if exact.exists() {
+        return Ok(vec![exact]);
+    }
+    let stem = file_name(beam);
+    let prefix = format!("{}[", stem.trim_end_matches(".log"));
+    let mut logs: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let name = path.file_name()?.to_str()?;
+            (name.starts_with(&prefix) && name.ends_with(".log")).then_some(path)
+        })
+        .collect();
+    logs.sort();
+    Ok(logs)
+}
+
+fn instance_of(path: &Path) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// The run header, then one line per beam log with the status it closed
+/// with (`running` while it has not, `unfinished` if the run ended first).
+fn print_run(id: u64, dir: &Path, out: &mut impl Write) -> Result<()> {
+    let info = run_info(dir);
+    let field = |key: &str| info.get(key).map(String::as_str).unwrap_or("?");
+    let outcome = match info.get("outcome") {
+        Some(outcome) => outcome.as_str(),
+        None if writer_gone(&info) => "interrupted",
+        None => "running",
+    };
+    writeln!(
+        out,
+        "run {id}  target {}  started {}  {outcome}",
+        field("target"),
+        field("started")
+    )?;
+
+    let mut logs: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            (path.extension()? == "log").then_some(path)
+        })
+        .collect();
+    logs.sort();
+    let names: Vec<String> = logs.iter().map(|p| instance_of(p)).collect();
+    let width = names.iter().map(|n| n.len()).max().unwrap_or(0);
+    for (path, name) in logs.iter().zip(&names) {
+        let content = fs::read_to_string(path).unwrap_or_default();
+        let status = content
+            .lines()
+            .last()
+            .and_then(done_status)
+            .unwrap_or(if run_over(&info) {
+                "unfinished"
+            } else {
+                "running"
+            });
+        writeln!(out, "  {name:<width$}  {status}")?;
+    }
+    Ok(())
+}
+
+/// Prints the log at `path`, then its new lines as they are written, until
+/// its closing line (or the end of the run, should the beam never close it).
+/// Only whole lines are printed: a read can end inside a character.
+async fn follow_log(path: &Path, run_dir: &Path, out: &mut impl Write) -> Result<()> {
+    let mut offset = 0u64;
+    let mut pending: Vec<u8> = vec![];
+    loop {
+        // Read once more after the run is seen finished: its last lines
+        // were written before `run.info` said so.
+        let finished = run_finished(run_dir);
+        let mut file = File::open(path)?;
+        file.seek(SeekFrom::Start(offset))?;
+        offset += file.read_to_end(&mut pending)? as u64;
+        while let Some(end) = pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = pending.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            out.write_all(line.as_bytes())?;
+            if done_status(line.trim_end_matches('\n')).is_some() {
+                out.flush()?;
+                return Ok(());
+            }
+        }
+        out.flush()?;
+        if finished {
+            return Ok(());
+        }
+        tokio::time::sleep(FOLLOW_POLL).await;
+    }
+}
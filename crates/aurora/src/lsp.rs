@@ -0,0 +1,959 @@
+//! `aurora lsp`: a language server for Beamfiles, speaking the Language
+//! Server Protocol over stdio.
+//!
+//! The editor sends the whole document on every change (full sync), and each
+//! request is answered from a parse of it:
+//!
+//! - diagnostics: the first error the runner itself would stop on (syntax,
+//!   dependency graph, variables, params), then `aurora lint`'s findings;
+//! - completion: beam names in `depends_on` and `default`, `${var.*}`,
+//!   `${param.*}` and executor names, discovered plugins included;
+//! - go to definition and find references, for beams, variables and params;
+//! - hover: a beam's signature and description, a variable's or a param's
+//!   default;
+//! - document symbols: beams with their params, variables and environment
+//!   variables.
+//!
+//! Messages are JSON-RPC 2.0 framed by a `Content-Length` header. Positions
+//! count UTF-16 code units, the protocol's default encoding. Completion keeps
+//! working while the document does not parse (a string being typed), from
+//! the last version of it that did.
+
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+use anyhow::Result;
+use aurora_core::ast::{Beam, BeamFile, Span};
+use aurora_core::expand::{has_required_params, signature};
+use aurora_core::parser::parse;
+use aurora_executor_api::Executor;
+use serde_json::{json, Value};
+
+use crate::lint::{self, Severity, Subject};
+
+/// JSON-RPC error code for a request the server does not implement.
+const METHOD_NOT_FOUND: i64 = -32601;
+/// JSON-RPC error code for a message that cannot be read as JSON.
+const PARSE_ERROR: i64 = -32700;
+
+/// `DiagnosticSeverity`, `CompletionItemKind` and `SymbolKind` values of the
+/// protocol.
+const SEVERITY_ERROR: u8 = 1;
+const SEVERITY_WARNING: u8 = 2;
+const COMPLETION_FUNCTION: u8 = 3;
+const COMPLETION_VARIABLE: u8 = 6;
+const COMPLETION_MODULE: u8 = 9;
+const COMPLETION_PROPERTY: u8 = 10;
+const SYMBOL_MODULE: u8 = 2;
+const SYMBOL_PROPERTY: u8 = 7;
+const SYMBOL_FUNCTION: u8 = 12;
+const SYMBOL_VARIABLE: u8 = 13;
+const SYMBOL_CONSTANT: u8 = 14;
+
+/// The executors a Beamfile can name: the built-in ones, then the plugins
+/// under `~/.aurora/plugins` (a plugin cannot shadow a built-in).
+pub fn executor_names() -> Vec<String> {
+    let mut names = vec![
+        aurora_executor_local::LocalExecutor::new()
+            .name()
+            .to_string(),
+        aurora_executor_docker::DockerExecutor::new()
+            .name()
+            .to_string(),
+    ];
+    for (name, _) in crate::plugins::discover_plugins() {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// Serves the protocol on `input` and `output` until the client sends `exit`
+/// or closes the input.
+pub fn serve(
+    mut input: impl BufRead,
+    mut output: impl Write,
+    executors: Vec<String>,
+) -> Result<()> {
+    let mut server = Server {
+        documents: HashMap::new(),
+        executors,
+    };
+    while let Some(message) = read_message(&mut input)? {
+        // A malformed message is answered, and the session goes on.
+        let message = match message {
+            Ok(message) => message,
+            Err(error) => {
+                write_message(
+                    &mut output,
+                    &json!({
+                        "jsonrpc": "2.0",
+                        "id": Value::Null,
+                        "error": { "code": PARSE_ERROR, "message": error },
+                    }),
+                )?;
+                continue;
+            }
+        };
+        if !server.handle(&message, &mut output)? {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Reads one message; `None` at the end of the input, `Some(Err)` for a
+/// message whose framing or body is invalid, its bytes skipped. Only a
+/// failure to read is an error.
+fn read_message(input: &mut impl BufRead) -> std::io::Result<Option<Result<Value, String>>> {
+    let mut length = Err("a message without a Content-Length header".to_string());
+    loop {
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value
+                    .trim()
+                    .parse::<usize>()
+                    .map_err(|_| format!("invalid Content-Length `{}`", value.trim()));
+            }
+        }
+    }
+    let length = match length {
+        Ok(length) => length,
+        Err(error) => return Ok(Some(Err(error))),
+    };
+    let mut body = vec![0; length];
+    input.read_exact(&mut body)?;
+    Ok(Some(
+        serde_json::from_slice(&body).map_err(|e| format!("a message that is not JSON: {e}")),
+    ))
+}
+
+fn write_message(output: &mut impl Write, message: &Value) -> Result<()> {
+    let body = serde_json::to_string(message)?;
+    write!(output, "Content-Length: {}\r\n\r\n{body}", body.len())?;
+    output.flush()?;
+    Ok(())
+}
+
+/// An open document.
+struct Document {
+    text: String,
+    /// The last version of the document that parsed, which may be older than
+    /// `text`.
+    beam_file: Option<BeamFile>,
+    /// Whether `beam_file` is a parse of `text`: its spans point into it.
+    current: bool,
+}
+
+impl Document {
+    fn new(text: String, previous: Option<BeamFile>) -> Self {
+        match parse(&text) {
+            Ok(beam_file) => Self {
+                text,
+                beam_file: Some(beam_file),
+                current: true,
+            },
+            Err(_) => Self {
+                text,
+                beam_file: previous,
+                current: false,
+            },
+        }
+    }
+
+    /// The parse of the current text, for the requests that need positions.
+    fn parsed(&self) -> Option<&BeamFile> {
+        self.beam_file.as_ref().filter(|_| self.current)
+    }
+}
+
+struct Server {
+    documents: HashMap<String, Document>,
+    executors: Vec<String>,
+}
+
+impl Server {
+    /// Handles one message, answering it when it is a request. Returns
+    /// `false` on `exit`.
+    fn handle(&mut self, message: &Value, output: &mut impl Write) -> Result<bool> {
+        // A response to a request of ours: the server sends none.
+        let Some(method) = message["method"].as_str() else {
+            return Ok(true);
+        };
+        let params = &message["params"];
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        let result = match method {
+            "initialize" => json!({
+                "capabilities": {
+                    "textDocumentSync": 1,
+                    "completionProvider": { "triggerCharacters": [".", "\"", "{"] },
+                    "definitionProvider": true,
+                    "referencesProvider": true,
+                    "hoverProvider": true,
+                    "documentSymbolProvider": true,
+                },
+                "serverInfo": { "name": "aurora", "version": env!("CARGO_PKG_VERSION") },
+            }),
+            "shutdown" => Value::Null,
+            "exit" => return Ok(false),
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                self.documents
+                    .insert(uri.to_string(), Document::new(text.to_string(), None));
+                self.publish(uri, output)?;
+                return Ok(true);
+            }
+            "textDocument/didChange" => {
+                let change = params["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str());
+                if let Some(text) = change {
+                    let previous = self.documents.remove(uri).and_then(|d| d.beam_file);
+                    self.documents
+                        .insert(uri.to_string(), Document::new(text.to_string(), previous));
+                    self.publish(uri, output)?;
+                }
+                return Ok(true);
+            }
+            // The file system may have changed (a `dir`, an input).
+            "textDocument/didSave" => {
+                self.publish(uri, output)?;
+                return Ok(true);
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(uri);
+                write_message(
+                    output,
+                    &json!({
+                        "jsonrpc": "2.0",
+                        "method": "textDocument/publishDiagnostics",
+                        "params": { "uri": uri, "diagnostics": [] },
+                    }),
+                )?;
+                return Ok(true);
+            }
+            "textDocument/completion"
+            | "textDocument/definition"
+            | "textDocument/references"
+            | "textDocument/hover"
+            | "textDocument/documentSymbol" => match self.documents.get(uri) {
+                Some(document) => {
+                    let offset = offset(&document.text, &params["position"]);
+                    match method {
+                        "textDocument/completion" => {
+                            Value::Array(completion(document, offset, &self.executors))
+                        }
+                        "textDocument/definition" => definition(document, uri, offset),
+                        "textDocument/references" => Value::Array(references(
+                            document,
+                            uri,
+                            offset,
+                            params["context"]["includeDeclaration"]
+                                .as_bool()
+                                .unwrap_or(true),
+                        )),
+                        "textDocument/hover" => hover(document, offset),
+                        _ => Value::Array(symbols(document)),
+                    }
+                }
+                None => Value::Null,
+            },
+            _ => {
+                if let Some(id) = message.get("id") {
+                    write_message(
+                        output,
+                        &json!({
+                            "jsonrpc": "2.0",
+                            "id": id,
+                            "error": {
+                                "code": METHOD_NOT_FOUND,
+                                "message": format!("unsupported method `{method}`"),
+                            },
+                        }),
+                    )?;
+                }
+                return Ok(true);
+            }
+        };
+        if let Some(id) = message.get("id") {
+            write_message(
+                output,
+                &json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            )?;
+        }
+        Ok(true)
+    }
+
+    fn publish(&self, uri: &str, output: &mut impl Write) -> Result<()> {
+        let Some(document) = self.documents.get(uri) else {
+            return Ok(());
+        };
+        write_message(
+            output,
+            &json!({
+                "jsonrpc": "2.0",
+                "method": "textDocument/publishDiagnostics",
+                "params": { "uri": uri, "diagnostics": diagnostics(document, uri) },
+            }),
+        )
+    }
+}
+
+/// The checks the runner makes before running anything, in its order.
+fn check(text: &str) -> Result<()> {
+    let mut beam_file = parse(text)?;
+    aurora_core::dag::validate(&beam_file)?;
+    aurora_core::parser::resolve_variables(&mut beam_file)?;
+    // Expanding any beam also instantiates every beam that needs no argument.
+    if let Some(target) = beam_file.beams.iter().find(|b| !has_required_params(b)) {
+        aurora_core::expand::expand(&beam_file, &target.name, &[])?;
+    }
+    Ok(())
+}
+
+/// The error the runner would stop on, then the lint findings. The lint needs
+/// the project directory, so a document that is not a file gets none; the lint
+/// errors that repeat the runner's are dropped while the runner has one.
+fn diagnostics(document: &Document, uri: &str) -> Vec<Value> {
+    let text = &document.text;
+    let mut out = vec![];
+    let blocking = check(text).err();
+    if let Some(err) = &blocking {
+        let span = aurora_core::diagnostic::find(err)
+            .map(|d| d.locate(text))
+            .unwrap_or_default();
+        out.push(json!({
+            "range": range(text, span),
+            "severity": SEVERITY_ERROR,
+            "source": "aurora",
+            "message": err.to_string(),
+        }));
+    }
+    let (Some(beam_file), Some(path)) = (document.parsed(), file_path(uri)) else {
+        return out;
+    };
+    let project_dir = path.parent().unwrap_or(&path);
+    for diagnostic in lint::lint(text, project_dir) {
+        if blocking.is_some() && diagnostic.severity == Severity::Error {
+            continue;
+        }
+        let span = match &diagnostic.subject {
+            Subject::File => Span::default(),
+            Subject::Beam(name) => beam(beam_file, name).map_or_else(Span::default, |b| b.span),
+            Subject::Variable(name) => beam_file
+                .variables
+                .iter()
+                .find(|v| &v.name == name)
+                .map_or_else(Span::default, |v| v.span),
+        };
+        out.push(json!({
+            "range": range(text, span),
+            "severity": match diagnostic.severity {
+                Severity::Error => SEVERITY_ERROR,
+                Severity::Warning => SEVERITY_WARNING,
+            },
+            "source": "aurora lint",
+            "code": diagnostic.rule,
+            "message": diagnostic.message,
+        }));
+    }
+    out
+}
+
+/// What the cursor can complete.
+enum Completion {
+    Beams,
+    Variables,
+    Params,
+    /// Right after `${`: `var.` and `param.` references.
+    References,
+    Executors,
+}
+
+/// What the text before `offset` asks for, if anything.
+fn completion_context(text: &str, offset: usize) -> Option<Completion> {
+    let line = &text[line_start(text, offset)..offset];
+    if let Some(open) = line.rfind("${") {
+        let inner = &line[open + 2..];
+        if !inner.contains('}') {
+            return Some(if inner.starts_with("var.") {
+                Completion::Variables
+            } else if inner.starts_with("param.") {
+                Completion::Params
+            } else {
+                Completion::References
+            });
+        }
+    }
+    let quote = open_quote(line)?;
+    let head = line[..quote].trim_end();
+    if head.ends_with("executor") {
+        return Some(Completion::Executors);
+    }
+    if let Some(key) = head.strip_suffix('=') {
+        let key = key.trim_end();
+        // `{ beam = "` in `depends_on`, or the `default` of `aurora {}`.
+        let aurora_default = key.ends_with("default") && block_keyword(text, offset) == "aurora";
+        return (key.ends_with("beam") || aurora_default).then_some(Completion::Beams);
+    }
+    if head.ends_with('[') || head.ends_with(',') {
+        let in_depends_on = text[..offset]
+            .rfind("depends_on")
+            .is_some_and(|at| !text[at..offset].contains(']'));
+        return in_depends_on.then_some(Completion::Beams);
+    }
+    None
+}
+
+/// The byte offset of the quote that opens the string `line` ends in, if it
+/// ends in one.
+fn open_quote(line: &str) -> Option<usize> {
+    let mut open = None;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if open.is_some() => escaped = true,
+            '"' => open = if open.is_some() { None } else { Some(i) },
+            '#' if open.is_none() => return None,
+            _ => {}
+        }
+    }
+    open
+}
+
+/// The keyword of the top-level block `offset` is in (`aurora`, `variable`,
+/// `environment`, `profile`, `template`, `beam`): the last unindented line before it that opens one.
+fn block_keyword(text: &str, offset: usize) -> &str {
+    text[..offset]
+        .lines()
+        .rev()
+        .filter(|line| !line.starts_with(char::is_whitespace))
+        .find_map(|line| {
+            [
+                "aurora",
+                "variable",
+                "environment",
+                "profile",
+                "template",
+                "beam",
+            ]
+            .into_iter()
+            .find(|keyword| line.starts_with(keyword))
+        })
+        .unwrap_or("")
+}
+
+/// The name of the beam whose block `offset` is in: the last line before it
+/// that declares a beam.
+fn enclosing_beam(text: &str, offset: usize) -> Option<String> {
+    text[..offset].lines().rev().find_map(|line| {
+        let rest = line.trim_start().strip_prefix("beam")?;
+        let rest = rest.trim_start().strip_prefix('"')?;
+        Some(rest[..rest.find('"')?].to_string())
+    })
+}
+
+fn completion(document: &Document, offset: usize, executors: &[String]) -> Vec<Value> {
+    let Some(context) = completion_context(&document.text, offset) else {
+        return vec![];
+    };
+    let empty = BeamFile {
+        config: None,
+        variables: vec![],
+        environment: None,
+        profiles: vec![],
+        beams: vec![],
+    };
+    let beam_file = document.beam_file.as_ref().unwrap_or(&empty);
+    let variable = |name: String, v: &aurora_core::ast::Variable| {
+        json!({
+            "label": name,
+            "kind": COMPLETION_VARIABLE,
+            "detail": variable_value(v),
+            "documentation": v.description,
+        })
+    };
+    let params = || {
+        enclosing_beam(&document.text, offset)
+            .and_then(|name| beam(beam_file, &name))
+            .map(|b| b.params.clone())
+            .unwrap_or_default()
+    };
+    let param = |name: String, p: &aurora_core::ast::Param| {
+        json!({
+            "label": name,
+            "kind": COMPLETION_PROPERTY,
+            "detail": param_value(p),
+            "documentation": p.description,
+        })
+    };
+    match context {
+        Completion::Beams => beam_file
+            .beams
+            .iter()
+            .map(|b| {
+                json!({
+                    "label": b.name,
+                    "kind": COMPLETION_FUNCTION,
+                    "detail": signature(b),
+                    "documentation": b.description,
+                })
+            })
+            .collect(),
+        Completion::Variables => beam_file
+            .variables
+            .iter()
+            .map(|v| variable(v.name.clone(), v))
+            .collect(),
+        Completion::Params => params().iter().map(|p| param(p.name.clone(), p)).collect(),
+        Completion::References => beam_file
+            .variables
+            .iter()
+            .map(|v| variable(format!("var.{}", v.name), v))
+            .chain(
+                params()
+                    .iter()
+                    .map(|p| param(format!("param.{}", p.name), p)),
+            )
+            .collect(),
+        Completion::Executors => executors
+            .iter()
+            .map(|name| json!({ "label": name, "kind": COMPLETION_MODULE }))
+            .collect(),
+    }
+}
+
+/// What the cursor is on.
+#[derive(Debug, PartialEq)]
+enum Symbol {
+    Beam(String),
+    Variable(String),
+    Param { beam: String, name: String },
+}
+
+/// The beam, variable or param under `offset`: a `${var.x}` or `${param.x}`
+/// reference, a `var.x` executor value, or a quoted name.
+fn symbol_at(text: &str, offset: usize, beam_file: &BeamFile) -> Option<Symbol> {
+    let start = line_start(text, offset);
+    let line = &text[start..text[start..].find('\n').map_or(text.len(), |i| start + i)];
+    let column = offset - start;
+    let reference = |inner: &str| {
+        if let Some(name) = inner.strip_prefix("var.") {
+            return Some(Symbol::Variable(name.to_string()));
+        }
+        let name = inner.strip_prefix("param.")?;
+        Some(Symbol::Param {
+            beam: enclosing_beam(text, offset)?,
+            name: name.to_string(),
+        })
+    };
+    if let Some(open) = line[..column].rfind("${") {
+        if let Some(close) = line[open..].find('}') {
+            if open + close >= column {
+                return reference(&line[open + 2..open + close]);
+            }
+        }
+    }
+    let is_word = |c: char| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.');
+    let word_start = line[..column]
+        .rfind(|c: char| !is_word(c))
+        .map_or(0, |i| i + 1);
+    let word_end = line[column..]
+        .find(|c: char| !is_word(c))
+        .map_or(line.len(), |i| column + i);
+    if line[word_start..word_end].starts_with("var.") {
+        return reference(&line[word_start..word_end]);
+    }
+    let open = open_quote(&line[..column])?;
+    let close = column + line[column..].find('"')?;
+    let name = &line[open + 1..close];
+    let head = line[..open].trim();
+    if head.ends_with("variable") {
+        Some(Symbol::Variable(name.to_string()))
+    } else if head.ends_with("param") {
+        Some(Symbol::Param {
+            beam: enclosing_beam(text, offset)?,
+            name: name.to_string(),
+        })
+    } else {
+        beam(beam_file, name).map(|_| Symbol::Beam(name.to_string()))
+    }
+}
+
+fn beam<'a>(beam_file: &'a BeamFile, name: &str) -> Option<&'a Beam> {
+    beam_file.beams.iter().find(|b| b.name == name)
+}
+
+/// Where `symbol` is declared.
+fn declaration(beam_file: &BeamFile, symbol: &Symbol) -> Option<Span> {
+    match symbol {
+        Symbol::Beam(name) => beam(beam_file, name).map(|b| b.span),
+        Symbol::Variable(name) => beam_file
+            .variables
+            .iter()
+            .find(|v| &v.name == name)
+            .map(|v| v.span),
+        Symbol::Param { beam: owner, name } => beam(beam_file, owner)?
+            .params
+            .iter()
+            .find(|p| &p.name == name)
+            .map(|p| p.span),
+    }
+}
+
+fn location(uri: &str, text: &str, span: Span) -> Value {
+    json!({ "uri": uri, "range": range(text, span) })
+}
+
+fn definition(document: &Document, uri: &str, offset: usize) -> Value {
+    let text = &document.text;
+    document
+        .parsed()
+        .and_then(|beam_file| declaration(beam_file, &symbol_at(text, offset, beam_file)?))
+        .map_or(Value::Null, |span| location(uri, text, span))
+}
+
+fn references(
+    document: &Document,
+    uri: &str,
+    offset: usize,
+    include_declaration: bool,
+) -> Vec<Value> {
+    let text = &document.text;
+    let Some(beam_file) = document.parsed() else {
+        return vec![];
+    };
+    let Some(symbol) = symbol_at(text, offset, beam_file) else {
+        return vec![];
+    };
+    let mut spans = vec![];
+    if include_declaration {
+        spans.extend(declaration(beam_file, &symbol));
+    }
+    match &symbol {
+        Symbol::Beam(name) => {
+            let quoted = format!("\"{name}\"");
+            for dep in beam_file.beams.iter().flat_map(|b| &b.depends_on) {
+                if &dep.beam == name {
+                    // The name inside the entry, not the whole `{ beam = ... }`.
+                    let at = text[dep.span.start..dep.span.end]
+                        .find(&quoted)
+                        .map_or(dep.span.start, |i| dep.span.start + i);
+                    spans.push(Span::new(at, at + quoted.len()));
+                }
+            }
+        }
+        Symbol::Variable(name) => {
+            spans.extend(occurrences(text, &format!("var.{name}"), 0, text.len()));
+        }
+        Symbol::Param { beam: owner, name } => {
+            // A param is only visible in its own beam's block.
+            if let Some(owner) = beam(beam_file, owner) {
+                let end = beam_file
+                    .beams
+                    .iter()
+                    .map(|b| b.span.start)
+                    .filter(|&start| start > owner.span.start)
+                    .min()
+                    .unwrap_or(text.len());
+                spans.extend(occurrences(
+                    text,
+                    &format!("${{param.{name}}}"),
+                    owner.span.start,
+                    end,
+                ));
+            }
+        }
+    }
+    spans
+        .into_iter()
+        .map(|span| location(uri, text, span))
+        .collect()
+}
+
+/// The occurrences of `needle` in `text[from..to]` that are not the start of
+/// a longer name.
+fn occurrences(text: &str, needle: &str, from: usize, to: usize) -> Vec<Span> {
+    text[from..to]
+        .match_indices(needle)
+        .map(|(i, _)| from + i)
+        .filter(|&at| {
+            !text[at + needle.len()..]
+                .starts_with(|c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        })
+        .map(|at| Span::new(at, at + needle.len()))
+        .collect()
+}
+
+/// A variable's value as shown to the user; a `sensitive` one is not.
+/// The value as declared: sources are shown, never evaluated, as the server
+/// runs no command.
+fn variable_value(variable: &aurora_core::ast::Variable) -> String {
+    let sources = &variable.sources;
+    let value = if variable.sensitive {
+        "(sensitive)".to_string()
+    } else if let Some(cmd) = &sources.shell {
+        format!("= shell(\"{cmd}\")")
+    } else {
+        format!("= \"{}\"", variable.default)
+    };
+    let overrides: Vec<String> = [("from_env", &sources.env), ("from_file", &sources.file)]
+        .into_iter()
+        .filter_map(|(key, source)| Some(format!("{key} \"{}\"", source.as_ref()?)))
+        .collect();
+    if overrides.is_empty() {
+        value
+    } else {
+        format!("{value} ({})", overrides.join(", "))
+    }
+}
+
+fn param_value(param: &aurora_core::ast::Param) -> String {
+    match &param.default {
+        Some(_) if param.sensitive => "(sensitive)".to_string(),
+        Some(default) => format!("= \"{default}\""),
+        None => "(required)".to_string(),
+    }
+}
+
+fn hover(document: &Document, offset: usize) -> Value {
+    let Some(beam_file) = document.parsed() else {
+        return Value::Null;
+    };
+    let Some(symbol) = symbol_at(&document.text, offset, beam_file) else {
+        return Value::Null;
+    };
+    let (code, description) = match &symbol {
+        Symbol::Beam(name) => {
+            let Some(b) = beam(beam_file, name) else {
+                return Value::Null;
+            };
+            (format!("beam {}", signature(b)), b.description.clone())
+        }
+        Symbol::Variable(name) => {
+            let Some(v) = beam_file.variables.iter().find(|v| &v.name == name) else {
+                return Value::Null;
+            };
+            (
+                format!("variable \"{name}\" {}", variable_value(v)),
+                v.description.clone(),
+            )
+        }
+        Symbol::Param { beam: owner, name } => {
+            let Some(p) =
+                beam(beam_file, owner).and_then(|b| b.params.iter().find(|p| &p.name == name))
+            else {
+                return Value::Null;
+            };
+            (
+                format!("param \"{name}\" {} (beam '{owner}')", param_value(p)),
+                p.description.clone(),
+            )
+        }
+    };
+    let constraints = match &symbol {
+        Symbol::Variable(name) => beam_file
+            .variables
+            .iter()
+            .find(|v| &v.name == name)
+            .map(|v| &v.constraints),
+        Symbol::Param { beam: owner, name } => beam(beam_file, owner)
+            .and_then(|b| b.params.iter().find(|p| &p.name == name))
+            .map(|p| &p.constraints),
+        Symbol::Beam(_) => None,
+    };
+    let mut value = format!("```\n{code}\n```");
+    if let Some(hint) = constraints.and_then(|c| c.hint()) {
+        value.push_str(&format!("\n\ntype: `{hint}`"));
+    }
+    if let Some(pattern) = constraints.and_then(|c| c.pattern.as_ref()) {
+        value.push_str(&format!("\n\nmatching `{pattern}`"));
+    }
+    if let Some(description) = description {
+        value.push_str(&format!("\n\n{description}"));
+    }
+    json!({ "contents": { "kind": "markdown", "value": value } })
+}
+
+fn symbols(document: &Document) -> Vec<Value> {
+    let text = &document.text;
+    let Some(beam_file) = document.parsed() else {
+        return vec![];
+    };
+    let symbol = |name: &str, detail: Option<&str>, kind: u8, span: Span, children: Vec<Value>| {
+        json!({
+            "name": name,
+            "detail": detail,
+            "kind": kind,
+            "range": range(text, Span::new(line_start(text, span.start), block_end(text, span.end))),
+            "selectionRange": range(text, span),
+            "children": children,
+        })
+    };
+    let mut out: Vec<Value> = beam_file
+        .variables
+        .iter()
+        .map(|v| {
+            symbol(
+                &v.name,
+                v.description.as_deref(),
+                SYMBOL_VARIABLE,
+                v.span,
+                vec![],
+            )
+        })
+        .collect();
+    for var in beam_file.environment.iter().flat_map(|e| &e.vars) {
+        out.push(json!({
+            "name": var.name,
+            "kind": SYMBOL_CONSTANT,
+            "range": range(text, var.span),
+            "selectionRange": range(text, var.span),
+        }));
+    }
+    for profile in &beam_file.profiles {
+        let bindings = profile
+            .variables
+            .iter()
+            .map(|v| {
+                json!({
+                    "name": v.name,
+                    "kind": SYMBOL_PROPERTY,
+                    "range": range(text, v.span),
+                    "selectionRange": range(text, v.span),
+                })
+            })
+            .collect();
+        out.push(symbol(
+            &profile.name,
+            None,
+            SYMBOL_MODULE,
+            profile.span,
+            bindings,
+        ));
+    }
+    for b in &beam_file.beams {
+        let params = b
+            .params
+            .iter()
+            .map(|p| {
+                symbol(
+                    &p.name,
+                    p.description.as_deref(),
+                    SYMBOL_PROPERTY,
+                    p.span,
+                    vec![],
+                )
+            })
+            .collect();
+        let detail = signature(b);
+        out.push(symbol(
+            &b.name,
+            Some(b.description.as_deref().unwrap_or(&detail)),
+            SYMBOL_FUNCTION,
+            b.span,
+            params,
+        ));
+    }
+    out
+}
+
+/// The end of the `{ ... }` block that follows `from`: past its closing brace,
+/// skipping strings and comments. The end of the text if it is not closed.
+fn block_end(text: &str, from: usize) -> usize {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut in_comment = false;
+    for (i, c) in text[from..].char_indices() {
+        match c {
+            '\n' if in_comment => in_comment = false,
+            _ if in_comment => {}
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            _ if in_string => {}
+            '#' => in_comment = true,
+            '{' => depth += 1,
+            '}' => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    return from + i + 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    text.len()
+}
+
+/// The path of a `file://` URI.
+fn file_path(uri: &str) -> Option<PathBuf> {
+    let path = uri.strip_prefix("file://")?;
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escape = (bytes[i] == b'%')
+            .then(|| path.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escape {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    Some(PathBuf::from(String::from_utf8(decoded).ok()?))
+}
+
+fn line_start(text: &str, offset: usize) -> usize {
+    text[..offset].rfind('\n').map_or(0, |i| i + 1)
+}
+
+/// The protocol position of the byte `offset`.
+fn position(text: &str, offset: usize) -> Value {
+    let offset = offset.min(text.len());
+    let start = line_start(text, offset);
+    json!({
+        "line": text[..start].matches('\n').count(),
+        "character": text[start..offset].encode_utf16().count(),
+    })
+}
+
+fn range(text: &str, span: Span) -> Value {
+    json!({ "start": position(text, span.start), "end": position(text, span.end) })
+}
+
+/// The byte offset of a protocol position, clamped to its line.
+fn offset(text: &str, position: &Value) -> usize {
+    let line = position["line"].as_u64().unwrap_or(0) as usize;
+    let character = position["character"].as_u64().unwrap_or(0) as usize;
+    let start: usize = text.split_inclusive('\n').take(line).map(str::len).sum();
+    let content = text[start..].split('\n').next().unwrap_or("");
+    let mut units = 0;
+    for (i, c) in content.char_indices() {
+        if units >= character {
+            return start + i;
+        }
+        units += c.len_utf16();
+    }
+    start + content.len()
+}
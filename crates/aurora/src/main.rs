@@ -1,9 +1,15 @@
 use anyhow::{bail, Result};
-use aurora::headless;
-use aurora_core::{env::evaluate, events::SchedulerEvent, parser::parse};
+use aurora::{headless, MULTI_BEAM};
+use aurora_core::{
+    ast::{BeamFile, Profile, ValueOrigin, Variable},
+    env::{evaluate_declared, EvaluatedEnv},
+    events::{CancelRequest, RerunRequest, SchedulerEvent},
+    parser::parse,
+};
 use aurora_executor_api::Executor;
 use aurora_executor_docker::DockerExecutor;
 use aurora_executor_local::LocalExecutor;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
 use std::io::IsTerminal;
 use std::path::PathBuf;
@@ -11,10 +17,14 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::sync::mpsc;
 
-/// Synthetic beam injected when several beams are selected at once in the
-/// picker: it depends on every selected beam and is filtered out of any
-/// user-facing listing.
-const MULTI_BEAM: &str = "__multi__";
+/// The Beamfile of this run, as read: what [`fail_prerun`] points into.
+struct Source {
+    /// Relative to the current directory when it is under it.
+    path: String,
+    text: String,
+}
+
+static BEAMFILE: std::sync::OnceLock<Source> = std::sync::OnceLock::new();
 
 /// Result of the `start_watch` closure handed to the TUI: an opaque watcher
 /// guard (kept alive while armed), its trigger receiver, and the advisory
@@ -28,7 +38,12 @@ type WatchArm = anyhow::Result<(
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let matches = aurora::cli().get_matches();
+    let matches = command_line();
+    // `aurora builtin <subcommand>` is `aurora <subcommand>`, never shadowed.
+    let subcommand = match matches.subcommand() {
+        Some((aurora::BUILTIN, builtin)) => builtin.subcommand(),
+        other => other,
+    };
 
     // Pure emitters: a packager runs them from an arbitrary directory, so they
     // must not depend on a Beamfile being present.
@@ -41,7 +56,85 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
-    let json = matches.get_flag("json");
+    if let Some(("logs", sub)) = subcommand {
+        let beamfile_path = find_beamfile(false)?;
+        let project_dir = beamfile_path
+            .parent()
+            .unwrap_or_else(|| std::path::Path::new("."));
+        let store = aurora::logs::LogStore::new(project_dir);
+        return aurora::logs::logs_command(
+            &store,
+            sub.get_one::<String>("beam").map(String::as_str),
+            sub.get_one::<u64>("run").copied(),
+            sub.get_flag("follow"),
+            &mut std::io::stdout(),
+        )
+        .await;
+    }
+
+    if let Some(("replay", sub)) = subcommand {
+        let path = sub.get_one::<PathBuf>("file").expect("required");
+        return aurora::replay::replay_command(path, sub.get_one::<f64>("speed").copied()).await;
+    }
+
+    if let Some(("lint", sub)) = subcommand {
+        let beamfile_path = find_beamfile(sub.get_flag("json"))?;
+        let passed = aurora::lint::lint_command(
+            &beamfile_path,
+            sub.get_flag("json"),
+            sub.get_flag("deny-warnings"),
+            &mut std::io::stdout(),
+        )?;
+        if !passed {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if let Some(("fmt", sub)) = subcommand {
+        let path = match sub.get_one::<PathBuf>("file") {
+            Some(path) => path.clone(),
+            None => find_beamfile(false)?,
+        };
+        let formatted =
+            aurora::fmt::fmt_command(&path, sub.get_flag("check"), &mut std::io::stdout())?;
+        if sub.get_flag("check") && !formatted {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if let Some(("ctl", sub)) = subcommand {
+        let (method, args) = sub.subcommand().expect("a ctl subcommand is required");
+        let beamfile_path = find_beamfile(false)?;
+        let project_dir = beamfile_path
+            .parent()
+            .unwrap_or_else(|| std::path::Path::new("."));
+        return aurora::control::ctl_command(
+            &project_dir.join(aurora::control::SOCKET),
+            method,
+            // `status` takes no beam.
+            args.try_get_one::<String>("beam")
+                .ok()
+                .flatten()
+                .map(String::as_str),
+            &mut std::io::stdout(),
+        )
+        .await;
+    }
+
+    // The editor sends the Beamfiles it opens: no project lookup.
+    if let Some(("lsp", _)) = subcommand {
+        return aurora::lsp::serve(
+            std::io::stdin().lock(),
+            std::io::stdout().lock(),
+            aurora::lsp::executor_names(),
+        );
+    }
+
+    // `aurora inspect --json` reports a failure as the run's `--json` does.
+    let json = matches.get_flag("json")
+        || matches!(subcommand, Some(("inspect", sub)) if sub.get_flag("json"));
 
     let beamfile_path = match find_beamfile(json) {
         Ok(path) => path,
@@ -51,16 +144,70 @@ async fn main() -> Result<()> {
         Ok(c) => c,
         Err(e) => fail_prerun(json, "beamfile", &anyhow::Error::from(e)),
     };
+    let _ = BEAMFILE.set(Source {
+        path: std::env::current_dir()
+            .ok()
+            .and_then(|cwd| beamfile_path.strip_prefix(cwd).ok().map(PathBuf::from))
+            .unwrap_or_else(|| beamfile_path.clone())
+            .display()
+            .to_string(),
+        text: content.clone(),
+    });
     let mut beam_file = match parse(&content) {
         Ok(bf) => bf,
         Err(e) => fail_prerun(json, "beamfile", &e),
     };
 
-    if let Some(vars) = matches.get_many::<String>("var") {
-        if let Err(e) = aurora::apply_var_overrides(&mut beam_file, vars) {
+    // `beamfile_path` always ends with the `Beamfile` component, so it has a
+    // parent; fall back to the current directory rather than panic if not.
+    let working_dir = beamfile_path
+        .parent()
+        .unwrap_or_else(|| std::path::Path::new("."))
+        .to_path_buf();
+
+    let inherit_env: Vec<String> = matches
+        .get_many::<String>("inherit-env")
+        .map(|values| values.cloned().collect())
+        .unwrap_or_default();
+    // An empty `AURORA_PROFILE=` selects no profile.
+    let profile: Option<String> = matches
+        .get_one::<String>("profile")
+        .filter(|name| !name.is_empty())
+        .cloned();
+    let mut var_overrides: Vec<String> = matches
+        .get_many::<String>("var")
+        .map(|values| values.cloned().collect())
+        .unwrap_or_default();
+    let jobs = matches.get_one::<usize>("jobs").copied();
+    // The profile goes first, so a `--var` still wins over it, and so does
+    // `--jobs` over its `max_parallelism`.
+    if let Some(profile) = &profile {
+        if let Err(e) = aurora::apply_profile(&mut beam_file, profile) {
+            fail_prerun(json, "variable", &e);
+        }
+    }
+    if let Some(jobs) = jobs {
+        aurora::set_max_parallelism(&mut beam_file, jobs);
+    }
+    if let Err(e) = aurora::apply_var_overrides(&mut beam_file, var_overrides.iter()) {
+        fail_prerun(json, "variable", &e);
+    }
+    // A variable's sources run commands and read files: only for what uses
+    // the values, a run (dry or not), `--print-vars` and `inspect --expanded`,
+    // never to list or describe the Beamfile.
+    let needs_values = match subcommand {
+        Some(("inspect", sub)) => sub.get_flag("expanded"),
+        _ => !matches.get_flag("list"),
+    };
+    if needs_values {
+        if let Err(e) = aurora_core::env::evaluate_variables(&mut beam_file, &working_dir) {
             fail_prerun(json, "variable", &e);
         }
     }
+    // The variables as profiled, overridden on the command line and evaluated, before
+    // their references are resolved: the picker's variables form may still change
+    // them, which takes a fresh resolution from this state.
+    let unresolved = beam_file.clone();
 
     // Resolve `var.<name>` references now that any --var override has been
     // applied, so the overrides actually take effect.
@@ -68,120 +215,330 @@ async fn main() -> Result<()> {
         fail_prerun(json, "variable", &e);
     }
 
+    if matches.get_flag("print-vars") {
+        let rows: Vec<(&str, &str, String)> = beam_file
+            .variables
+            .iter()
+            .map(|v| {
+                let value = if v.sensitive {
+                    "***"
+                } else {
+                    v.default.as_str()
+                };
+                (v.name.as_str(), value, v.origin_label())
+            })
+            .collect();
+        let name_width = rows.iter().map(|r| r.0.len()).max().unwrap_or(0);
+        let value_width = rows.iter().map(|r| r.1.chars().count()).max().unwrap_or(0);
+        for (name, value, origin) in rows {
+            println!("{name:<name_width$} = {value:<value_width$}  ({origin})");
+        }
+        return Ok(());
+    }
+
     if matches.get_flag("list") {
         println!("Available beams:");
         for beam in &beam_file.beams {
-            let desc = beam.description.as_deref().unwrap_or("");
-            println!("  {:<28}  {}", aurora_core::expand::signature(beam), desc);
+            let tags = (!beam.tags.is_empty()).then(|| format!("[{}]", beam.tags.join(", ")));
+            let desc: Vec<&str> = beam
+                .description
+                .as_deref()
+                .into_iter()
+                .chain(tags.as_deref())
+                .collect();
+            println!(
+                "  {:<28}  {}",
+                aurora_core::expand::signature(beam),
+                desc.join(" ")
+            );
+        }
+        if !beam_file.variables.is_empty() {
+            println!("\nVariables:");
+            for variable in &beam_file.variables {
+                let name = match variable.constraints.hint() {
+                    Some(hint) => format!("{}:{hint}", variable.name),
+                    None => variable.name.clone(),
+                };
+                let value = if variable.sensitive {
+                    "***"
+                } else {
+                    &variable.default
+                };
+                let desc: Vec<String> = variable_sources(variable)
+                    .into_iter()
+                    .chain(variable.description.clone())
+                    .collect();
+                println!("  {:<28}  {}", format!("{name}={value}"), desc.join(" "));
+            }
+        }
+        if !beam_file.profiles.is_empty() {
+            println!("\nProfiles:");
+            for p in &beam_file.profiles {
+                let name = match profile.as_deref() == Some(p.name.as_str()) {
+                    true => format!("{} (active)", p.name),
+                    false => p.name.clone(),
+                };
+                println!("  {name:<28}  {}", profile_summary(&beam_file, p));
+            }
         }
         return Ok(());
     }
 
+    if let Some(("inspect", sub)) = subcommand {
+        let beam = sub.get_one::<String>("beam").map(String::as_str);
+        if !sub.get_flag("json") {
+            let beam = beam.expect("required without --json");
+            if let Err(e) =
+                aurora::inspect::inspect_command(&unresolved, beam, &mut std::io::stdout())
+            {
+                fail_prerun(json, "target", &e);
+            }
+            return Ok(());
+        }
+        let expansion = match sub.get_flag("expanded") {
+            true => {
+                if let Err(e) = aurora_core::dag::validate(&beam_file) {
+                    fail_prerun(json, "beamfile", &e);
+                }
+                let target = match aurora::resolve_target(&beam_file, beam) {
+                    Ok(target) => target,
+                    Err(e) => fail_prerun(json, "target", &e),
+                };
+                let args: Vec<String> = sub
+                    .get_many::<String>("args")
+                    .map(|values| values.cloned().collect())
+                    .unwrap_or_default();
+                match aurora_core::expand::expand(&beam_file, &target, &args) {
+                    Ok(expansion) => Some(expansion),
+                    Err(e) => fail_prerun(json, "argument", &e),
+                }
+            }
+            false => None,
+        };
+        return aurora::inspect::inspect_json(
+            &unresolved,
+            profile.as_deref(),
+            expansion.as_ref(),
+            &mut std::io::stdout(),
+        );
+    }
+
+    // Unknown dependencies and cycles, checked on the declared beams where
+    // they can still be pointed at in the Beamfile.
+    if let Err(e) = aurora_core::dag::validate(&beam_file) {
+        fail_prerun(json, "beamfile", &e);
+    }
+
+    // The positionals name the targets, each followed by its arguments. A
+    // beam launched from the picker gets the values entered in its params form
+    // instead. A value that must reach a dependency is a global variable or a
+    // bound param, not a positional argument.
+    let positionals = positionals(&matches);
+    let mut selection = aurora::Selection {
+        targets: aurora::split_targets(&beam_file, &positionals),
+        tags: matches
+            .get_many::<String>("tag")
+            .map(|values| values.cloned().collect())
+            .unwrap_or_default(),
+        only: matches.get_flag("only"),
+        exclude: matches
+            .get_many::<String>("exclude")
+            .map(|values| values.cloned().collect())
+            .unwrap_or_default(),
+    };
+
     if matches.get_flag("dry-run") {
-        let target = aurora::resolve_target(
+        let targets = aurora::selected_targets(&beam_file, &selection)?;
+        let (target, args) = aurora::root_target(&mut beam_file, &targets)?;
+        let mut expansion = aurora_core::expand::expand(&beam_file, &target, &args)?;
+        aurora::prune(
             &beam_file,
-            matches.get_one::<String>("beam").map(|s| s.as_str()),
+            &mut expansion.instances,
+            &expansion.target_id,
+            &selection,
         )?;
-        let args: Vec<String> = matches
-            .get_many::<String>("args")
-            .map(|values| values.cloned().collect())
-            .unwrap_or_default();
-        let expansion = aurora_core::expand::expand(&beam_file, &target, &args)?;
         print_execution_plan(&expansion)?;
+        if matches.get_flag("verbose") {
+            // What each beam would receive takes evaluating the environment,
+            // `shell(...)` commands included, exactly as a run would.
+            let inherited =
+                aurora_core::env::inherited(&aurora::inherit_patterns(&beam_file, &inherit_env));
+            let mut ambient = aurora_core::env::base_env();
+            ambient.extend(inherited.clone());
+            let evaluated = evaluate_declared(
+                beam_file.environment.as_ref(),
+                ambient,
+                &working_dir,
+            )
+            .and_then(|evaluated| {
+                aurora::apply_env_overlays(&mut expansion.instances, &evaluated.env, &working_dir)?;
+                Ok(evaluated)
+            });
+            match evaluated {
+                Ok(evaluated) => print_beam_environments(&expansion, &evaluated, &inherited)?,
+                Err(e) => fail_prerun(json, "beamfile", &e),
+            }
+        }
         return Ok(());
     }
 
+    let mut reports: Vec<aurora::reporter::ReportSpec> = matches
+        .get_many::<aurora::reporter::ReportSpec>("report")
+        .map(|values| values.cloned().collect())
+        .unwrap_or_default();
+    if let Some(path) = matches.get_one::<PathBuf>("trace") {
+        reports.push(aurora::reporter::ReportSpec::Trace(path.clone()));
+    }
+    let telemetry = aurora::telemetry::Telemetry::from_matches(&matches);
+    let ci = matches
+        .get_one::<String>("ci")
+        .map(String::as_str)
+        .unwrap_or("auto");
+    let output = matches.get_one::<headless::OutputMode>("output").copied();
+    if ci == "github" && output.is_some() {
+        aurora::cli()
+            .error(
+                clap::error::ErrorKind::ArgumentConflict,
+                "--output cannot be used with --ci github, whose layout groups each beam's output",
+            )
+            .exit();
+    }
+    let github = aurora::github::github_actions_enabled(
+        ci,
+        std::env::var("GITHUB_ACTIONS").ok().as_deref(),
+        matches.get_flag("interactive") || output.is_some(),
+    );
+
+    // A file report or a telemetry export is a CI feature: it runs alongside
+    // the headless or JSON output, never the TUI. So is a headless layout.
     let interactive = !json
+        && output.is_none()
+        && reports.is_empty()
+        && !telemetry.is_enabled()
+        && !github
         && (matches.get_flag("interactive")
             || (std::io::stdout().is_terminal() && !matches.get_flag("no-tui")));
 
-    // Target resolution: picker in interactive mode, `default` beam in headless mode
-    // (the picker is inherently interactive and does not exist outside a TTY).
-    let target = if interactive {
-        if let Some(beam_name) = matches.get_one::<String>("beam") {
-            // The picker can only ever yield beams that exist; an explicitly
-            // named one must be validated exactly as in headless mode.
-            aurora::ensure_beam_exists(&beam_file, beam_name)?;
-            beam_name.clone()
-        } else if let Some(picker_results) = aurora_tui::run_picker(
-            beam_file
+    // Target resolution: the picker in interactive mode when no target is
+    // named nor tagged, otherwise the selection or the `default` beam (the
+    // picker is inherently interactive and does not exist outside a TTY).
+    if interactive && selection.is_empty() {
+        let project_dir = beamfile_path
+            .parent()
+            .unwrap_or_else(|| std::path::Path::new("."));
+        let mut memory = aurora::picker_memory::PickerMemory::load(project_dir);
+        let picker_beams = beam_file
+            .beams
+            .iter()
+            .map(|b| aurora_tui::PickerBeam {
+                name: b.name.clone(),
+                description: b.description.clone(),
+                depends_on: b.dependency_names(),
+                signature: aurora_core::expand::signature(b),
+                params: b
+                    .params
+                    .iter()
+                    .map(|p| aurora_tui::PickerParam {
+                        name: p.name.clone(),
+                        default: p.default.clone(),
+                        description: p.description.clone(),
+                        last: memory.last(&b.name, &p.name).map(str::to_string),
+                        sensitive: p.sensitive,
+                        constraints: p.constraints.clone(),
+                    })
+                    .collect(),
+            })
+            .collect();
+        let picker_variables = unresolved
+            .variables
+            .iter()
+            .map(|v| aurora_tui::PickerVariable {
+                name: v.name.clone(),
+                value: v.default.clone(),
+                description: v.description.clone(),
+                sensitive: v.sensitive,
+                constraints: v.constraints.clone(),
+            })
+            .collect();
+        let Some(launch) = aurora_tui::run_picker(picker_beams, picker_variables)? else {
+            return Ok(());
+        };
+
+        // A sensitive param's value is not written to disk.
+        for (beam, values) in &launch.args {
+            let sensitive: Vec<&str> = beam_file
                 .beams
                 .iter()
-                .map(|b| aurora_tui::PickerBeam {
-                    name: b.name.clone(),
-                    description: b.description.clone(),
-                    depends_on: b.dependency_names(),
-                    signature: aurora_core::expand::signature(b),
-                    requires_args: aurora_core::expand::has_required_params(b),
+                .filter(|b| &b.name == beam)
+                .flat_map(|b| &b.params)
+                .filter(|p| p.sensitive)
+                .map(|p| p.name.as_str())
+                .collect();
+            let values: Vec<String> = values
+                .iter()
+                .filter(|v| {
+                    v.split_once('=')
+                        .is_none_or(|(k, _)| !sensitive.contains(&k))
                 })
-                .collect(),
-        )? {
-            if picker_results.len() == 1 {
-                picker_results.into_iter().next().unwrap()
-            } else {
-                // A multi-select run cannot bind CLI arguments to each selected
-                // beam individually, so a beam with a required param has no way
-                // to receive a value here: reject it up front with the usage
-                // signature, rather than let expansion fail with a less
-                // actionable "missing required param" further down.
-                for name in &picker_results {
-                    if let Some(beam) = beam_file.beams.iter().find(|b| &b.name == name) {
-                        if aurora_core::expand::has_required_params(beam) {
-                            fail_prerun(
-                                json,
-                                "target",
-                                &anyhow::anyhow!(
-                                    "beam '{}' requires arguments: run `aurora {}`",
-                                    name,
-                                    aurora_core::expand::signature(beam)
-                                ),
-                            );
-                        }
-                    }
-                }
-                // Multi-select: virtual beam __multi__ depending on the selected beams
-                let virtual_beam = aurora_core::ast::Beam {
-                    name: MULTI_BEAM.to_string(),
-                    description: Some("Multi-beam run".to_string()),
-                    depends_on: picker_results
-                        .into_iter()
-                        .map(aurora_core::ast::Dependency::named)
-                        .collect(),
-                    ..aurora_core::ast::Beam::default()
-                };
-                beam_file.beams.push(virtual_beam);
-                MULTI_BEAM.to_string()
-            }
-        } else {
-            return Ok(());
+                .cloned()
+                .collect();
+            memory.remember(beam, &values);
         }
-    } else {
-        match aurora::resolve_target(
-            &beam_file,
-            matches.get_one::<String>("beam").map(|s| s.as_str()),
-        ) {
-            Ok(target) => target,
-            Err(e) => fail_prerun(json, "target", &e),
+        if !launch.args.is_empty() {
+            memory.save(project_dir);
         }
+
+        // Variables changed in the picker apply on top of the `--var`
+        // overrides, and are kept with them for a reload under watch.
+        if !launch.var_overrides.is_empty() {
+            beam_file = unresolved.clone();
+            if let Err(e) = aurora::apply_var_overrides(&mut beam_file, launch.var_overrides.iter())
+                .and_then(|()| aurora_core::parser::resolve_variables(&mut beam_file))
+            {
+                fail_prerun(json, "variable", &e);
+            }
+            var_overrides.extend(launch.var_overrides.iter().cloned());
+        }
+
+        selection.targets = launch
+            .beams
+            .iter()
+            .map(|name| aurora::Target {
+                beam: name.clone(),
+                args: launch.args.get(name).cloned().unwrap_or_default(),
+            })
+            .collect();
+    }
+    let targets = match aurora::selected_targets(&beam_file, &selection) {
+        Ok(targets) => targets,
+        Err(e) => fail_prerun(json, "target", &e),
+    };
+    // Several targets run as the dependencies of the virtual __multi__ beam.
+    let (target, args) = match aurora::root_target(&mut beam_file, &targets) {
+        Ok(root) => root,
+        Err(e) => fail_prerun(json, "argument", &e),
     };
 
-    // Positional arguments belong to the explicitly invoked target. Expand
-    // `target` (bound with `args`) and its transitive dependencies into
-    // instances now that the target is known; a value that must reach a
-    // dependency is a global variable or a bound param, not a positional
-    // argument.
-    let args: Vec<String> = matches
-        .get_many::<String>("args")
-        .map(|values| values.cloned().collect())
-        .unwrap_or_default();
-    let expansion = match aurora_core::expand::expand(&beam_file, &target, &args) {
+    // Expand `target` (bound with `args`) and its transitive dependencies
+    // into instances now that the target is known, then leave out what
+    // `--exclude` and `--only` do.
+    let mut expansion = match aurora_core::expand::expand(&beam_file, &target, &args) {
         Ok(expansion) => expansion,
         Err(e) => fail_prerun(json, "argument", &e),
     };
-    // `target` (the beam name) stays only for user-facing messages from here
-    // on; every scheduler, TUI and reporter root is the instance id.
+    if let Err(e) = aurora::prune(
+        &beam_file,
+        &mut expansion.instances,
+        &expansion.target_id,
+        &selection,
+    ) {
+        fail_prerun(json, "target", &e);
+    }
+    // Every scheduler, TUI and reporter root is the instance id; what the
+    // user reads (logs, reports, `aurora ctl`) is the label, the targets' ids.
     let instances = expansion.instances;
     let target_id = expansion.target_id;
+    let label = aurora::run_label(&instances, &target_id);
 
     // Register each executor under the name it reports, so the registry key and
     // Executor::name() cannot drift apart.
@@ -207,31 +564,25 @@ async fn main() -> Result<()> {
         }
     }
 
-    // `beamfile_path` always ends with the `Beamfile` component, so it has a
-    // parent; fall back to the current directory rather than panic if not.
-    let working_dir = beamfile_path
-        .parent()
-        .unwrap_or_else(|| std::path::Path::new("."))
-        .to_path_buf();
-
     // Evaluate environment variables (shell(...)) sequentially. When no
     // `environment { }` block is declared, fall back to the allowlisted base
     // environment, never to the full process environment: a Beamfile is
     // untrusted and must not inherit ambient secrets (CI tokens, AWS_*, ...).
-    let env = match &beam_file.environment {
-        Some(env_block) => match evaluate(env_block, &working_dir) {
-            Ok(env) => env,
-            Err(e) => fail_prerun(json, "beamfile", &e),
-        },
-        None => aurora_core::env::base_env(),
+    //
+    // The declared half of the environment takes part in every beam's cache
+    // key: a `shell(...)` value that changes (a commit sha, a branch) changes
+    // what the beams produce without changing any of their input files. The
+    // ambient half stays out of the key (see `env::evaluate_declared`).
+    let ambient =
+        aurora_core::env::ambient_env(&aurora::inherit_patterns(&beam_file, &inherit_env));
+    let EvaluatedEnv {
+        env,
+        declared: declared_env,
+    } = match evaluate_declared(beam_file.environment.as_ref(), ambient, &working_dir) {
+        Ok(evaluated) => evaluated,
+        Err(e) => fail_prerun(json, "beamfile", &e),
     };
 
-    // The declared half of the environment takes part in every beam's cache key:
-    // a `shell(...)` value that changes (a commit sha, a branch) changes what the
-    // beams produce without changing any of their input files. The ambient half
-    // stays out of the key (see `env::declared_only`).
-    let declared_env = aurora_core::env::declared_only(beam_file.environment.as_ref(), &env);
-
     // Evaluate each instance's own `environment {}` block (params already
     // interpolated by expansion) against the global environment, before it
     // feeds the sidebar listing, the run set, or the scheduler below.
@@ -240,7 +591,38 @@ async fn main() -> Result<()> {
         fail_prerun(json, "beamfile", &e);
     }
 
+    let log_store = aurora::logs::LogStore::new(&working_dir)
+        .with_retention(beam_file.config.as_ref().and_then(|c| c.log_retention));
+    let no_cache = matches.get_flag("no-cache");
+    let watch = matches.get_flag("watch");
+    // Headless watch builds a scheduler per cycle: this first one only runs
+    // in the other modes.
+    let first_run = interactive || !watch;
+
+    // The control socket (`aurora ctl`) lives as long as this process runs
+    // beams. A run can follow another in the TUI and under watch, so only
+    // those take `rerun` requests.
+    let (rerun_tx, mut rerun_rx) = mpsc::unbounded_channel::<RerunRequest>();
+    let mut control = aurora::control::Control::new().with_warnings(!json && !interactive);
+    if interactive || watch {
+        control = control.with_rerun(rerun_tx);
+    }
+    let socket_path = working_dir.join(aurora::control::SOCKET);
+    let control_socket = match control.serve(&socket_path) {
+        Ok(socket) => Some(socket),
+        Err(e) => {
+            if !json && !interactive {
+                eprintln!(
+                    "aurora: warning: no control socket at {}: {e}",
+                    socket_path.display()
+                );
+            }
+            None
+        }
+    };
+
     let (tx, rx) = mpsc::channel(128);
+    let (cancel_tx, cancel_rx) = mpsc::unbounded_channel::<CancelRequest>();
     // The sidebar lists every declared beam (minus the virtual __multi__): it
     // doubles as a launcher, so a run of one target must still let you reach the
     // others. Beams with a required param have no runnable instance, so they are
@@ -265,15 +647,15 @@ async fn main() -> Result<()> {
             .collect();
         aurora::run_closure_names(&all, &target_id, MULTI_BEAM)
     };
+    let rx = if first_run {
+        let rx = aurora::hide_multi_beam(rx);
+        let rx = record_run(&log_store, &label, rx, !json && !interactive);
+        control.observe(&label, &run_set, rx, cancel_tx.clone())
+    } else {
+        rx
+    };
 
-    let no_cache = matches.get_flag("no-cache");
-    let watch = matches.get_flag("watch");
     let max_parallelism = beam_file.config.as_ref().and_then(|c| c.max_parallelism);
-    let var_overrides: Vec<String> = matches
-        .get_many::<String>("var")
-        .map(|values| values.cloned().collect())
-        .unwrap_or_default();
-
     let beams = instances.clone();
     let scheduler = aurora::build_scheduler(
         beams,
@@ -287,7 +669,6 @@ async fn main() -> Result<()> {
     );
 
     if interactive {
-        let (cancel_tx, cancel_rx) = mpsc::unbounded_channel::<String>();
         let target_clone = target_id.clone();
         tokio::spawn(async move {
             if let Err(e) = scheduler
@@ -308,15 +689,26 @@ async fn main() -> Result<()> {
         let rerun_working_dir = working_dir.clone();
         let rerun_env = env.clone();
         let rerun_declared_env = declared_env.clone();
+        let rerun_logs = log_store.clone();
+        let rerun_control = control.clone();
 
         let rerun = move |root: String,
                           pre_success: Vec<String>|
               -> (
             mpsc::Receiver<SchedulerEvent>,
-            mpsc::UnboundedSender<String>,
+            mpsc::UnboundedSender<CancelRequest>,
         ) {
             let (tx, rx) = mpsc::channel(128);
-            let (cancel_tx, cancel_rx) = mpsc::unbounded_channel::<String>();
+            let rx = record_run(&rerun_logs, &root, aurora::hide_multi_beam(rx), false);
+            let (cancel_tx, cancel_rx) = mpsc::unbounded_channel::<CancelRequest>();
+            // The beams that already passed are not run again.
+            let all: Vec<(String, Vec<String>)> = rerun_beams
+                .iter()
+                .map(|b| (b.name.clone(), b.dependency_names()))
+                .collect();
+            let mut names = aurora::run_closure_names(&all, &root, MULTI_BEAM);
+            names.retain(|name| !pre_success.contains(name));
+            let rx = rerun_control.observe(&root, &names, rx, cancel_tx.clone());
             let scheduler = aurora::build_scheduler(
                 rerun_beams.clone(),
                 rerun_executors.clone(),
@@ -365,17 +757,22 @@ async fn main() -> Result<()> {
         let rl_executors = executors.clone();
         let rl_working_dir = working_dir.clone();
         let rl_beamfile = beamfile_path.clone();
-        let rl_target = target.clone();
-        let rl_args = args.clone();
+        let rl_selection = selection.clone();
+        let rl_profile = profile.clone();
         let rl_var_overrides = var_overrides.clone();
+        let rl_inherit_env = inherit_env.clone();
         let rl_no_cache = no_cache;
+        let rl_logs = log_store.clone();
+        let rl_control = control.clone();
         let reload = move || -> anyhow::Result<aurora_tui::ReloadResult> {
             let loaded = aurora::resolve_run_inputs(
                 &rl_beamfile,
                 &rl_working_dir,
+                rl_profile.as_deref(),
                 &rl_var_overrides,
-                &rl_target,
-                &rl_args,
+                &rl_inherit_env,
+                &rl_selection,
+                jobs,
             )?;
             let non_launchable: Vec<String> = loaded
                 .phantom_beams
@@ -388,8 +785,17 @@ async fn main() -> Result<()> {
                 .map(|b| (b.name.clone(), b.dependency_names()))
                 .chain(loaded.phantom_beams.iter().cloned())
                 .collect();
+            let label = aurora::run_label(&loaded.beams, &loaded.target_id);
             let (tx, rx) = mpsc::channel(128);
-            let (cancel_tx, cancel_rx) = mpsc::unbounded_channel::<String>();
+            let rx = record_run(&rl_logs, &label, aurora::hide_multi_beam(rx), false);
+            let (cancel_tx, cancel_rx) = mpsc::unbounded_channel::<CancelRequest>();
+            let all: Vec<(String, Vec<String>)> = loaded
+                .beams
+                .iter()
+                .map(|b| (b.name.clone(), b.dependency_names()))
+                .collect();
+            let names = aurora::run_closure_names(&all, &loaded.target_id, MULTI_BEAM);
+            let rx = rl_control.observe(&label, &names, rx, cancel_tx.clone());
             let scheduler = aurora::build_scheduler(
                 loaded.beams.clone(),
                 rl_executors.clone(),
@@ -434,6 +840,8 @@ async fn main() -> Result<()> {
             rerun,
             start_watch,
             reload,
+            rerun_rx,
+            profile,
         )
         .await?;
     } else {
@@ -449,6 +857,8 @@ async fn main() -> Result<()> {
             // (pre-reload) id would run against beams that no longer exist in
             // `beams`, silently scheduling nothing for a parameterized target.
             let mut target_id = target_id;
+            let mut label = label;
+            let mut secrets = aurora::secret_values(&beam_file, &beams);
 
             let mut closure = aurora::watch::closure_of(&beams, &target_id);
             let set =
@@ -469,6 +879,10 @@ async fn main() -> Result<()> {
                     beam_info.iter().map(|(name, _)| name.clone()).collect();
 
                 let (tx, rx) = mpsc::channel(128);
+                let rx = record_run(&log_store, &label, aurora::hide_multi_beam(rx), true);
+                let (cancel_tx, cancel_rx) = mpsc::unbounded_channel::<CancelRequest>();
+                let names = aurora::run_closure_names(&beam_info, &target_id, MULTI_BEAM);
+                let rx = control.observe(&label, &names, rx, cancel_tx);
                 let scheduler = aurora::build_scheduler(
                     beams.clone(),
                     executors.clone(),
@@ -491,8 +905,13 @@ async fn main() -> Result<()> {
                 });
 
                 let scheduler = scheduler.with_shutdown(sd_rx);
+                let (scheduler, run_reports) = telemetry.arm(scheduler, &beams, &reports);
                 let target_clone = target_id.clone();
-                let handle = tokio::spawn(async move { scheduler.run(&target_clone, &[]).await });
+                let handle = tokio::spawn(async move {
+                    scheduler
+                        .run_cancellable(&target_clone, &[], cancel_rx)
+                        .await
+                });
 
                 let out_color =
                     std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
@@ -501,15 +920,45 @@ async fn main() -> Result<()> {
                 let mut stdout = std::io::stdout();
                 let mut stderr = std::io::stderr();
                 let stdout_closed = {
+                    // Each cycle renders as a one-shot run would.
                     use aurora::reporter::Reporter;
-                    let mut reporter = headless::HeadlessReporter::new(
-                        beam_names.clone(),
-                        out_color,
-                        err_color,
-                        &mut stdout,
-                        &mut stderr,
-                    );
-                    reporter.run(rx).await.is_err()
+                    let mut reporter: Box<dyn Reporter> = if github {
+                        Box::new(
+                            aurora::github::GithubReporter::new(
+                                label.clone(),
+                                beam_names.clone(),
+                                &mut stdout,
+                            )
+                            .with_secrets(secrets.clone())
+                            .with_step_summary(
+                                std::env::var_os("GITHUB_STEP_SUMMARY").map(PathBuf::from),
+                            ),
+                        )
+                    } else {
+                        Box::new(
+                            headless::HeadlessReporter::new(
+                                beam_names.clone(),
+                                out_color,
+                                err_color,
+                                &mut stdout,
+                                &mut stderr,
+                            )
+                            .with_output(output.unwrap_or_default())
+                            .with_status_line(std::io::stderr().is_terminal())
+                            .with_tty_beams(beams.iter().filter(|b| b.tty).map(|b| b.name.clone())),
+                        )
+                    };
+                    let (result, report_errors) = aurora::reporter::run_with_reports(
+                        reporter.as_mut(),
+                        &run_reports,
+                        &label,
+                        rx,
+                    )
+                    .await;
+                    for error in report_errors {
+                        eprintln!("aurora: {error}");
+                    }
+                    result.is_err()
                 };
                 let _ = handle.await;
                 if stdout_closed {
@@ -527,10 +976,28 @@ async fn main() -> Result<()> {
 
                 eprintln!("aurora: watching for changes (Ctrl-C to stop)");
 
-                // ---- wait for a change or Ctrl-C ----
-                let trigger = tokio::select! {
-                    _ = aurora::wait_for_termination_signal() => None,
-                    trig = trig_rx.recv() => trig,
+                // ---- wait for a change, a rerun of the target, or Ctrl-C ----
+                let trigger = loop {
+                    tokio::select! {
+                        _ = aurora::wait_for_termination_signal() => break None,
+                        trig = trig_rx.recv() => break trig,
+                        Some(request) = rerun_rx.recv() => {
+                            // Only the target has a watch cycle to run.
+                            match request.beam {
+                                Some(beam) if beam != target && beam != target_id => {
+                                    let _ = request.reply.send(Err(format!(
+                                        "watch mode reruns `{label}` only, not `{beam}`"
+                                    )));
+                                }
+                                _ => {
+                                    let _ = request.reply.send(Ok(()));
+                                    break Some(aurora_core::events::WatchTrigger {
+                                        beamfile_changed: false,
+                                    });
+                                }
+                            }
+                        }
+                    }
                 };
                 let Some(trigger) = trigger else {
                     break; // Ctrl-C while waiting, or the watcher went away.
@@ -540,16 +1007,20 @@ async fn main() -> Result<()> {
                     match aurora::resolve_run_inputs(
                         &beamfile_path,
                         &working_dir,
+                        profile.as_deref(),
                         &var_overrides,
-                        &target,
-                        &args,
+                        &inherit_env,
+                        &selection,
+                        jobs,
                     ) {
                         Ok(loaded) => {
+                            label = aurora::run_label(&loaded.beams, &loaded.target_id);
                             beams = loaded.beams;
                             env = loaded.env;
                             declared_env = loaded.declared_env;
                             max_parallelism = loaded.max_parallelism;
                             target_id = loaded.target_id;
+                            secrets = loaded.secrets;
                             closure = aurora::watch::closure_of(&beams, &target_id);
                             let set = aurora::watch::build_watch_set(
                                 &beams,
@@ -581,8 +1052,8 @@ async fn main() -> Result<()> {
             return Ok(());
         }
 
-        // Headless mode: no interactive cancellation, `run` manages its own
-        // channel. Ctrl-C and SIGTERM tear the run down instead of killing
+        // Headless mode: cancellation only comes through the control socket.
+        // Ctrl-C and SIGTERM tear the run down instead of killing
         // Aurora outright, which would orphan the beams' process subtrees.
         let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
         let interrupted = Arc::new(AtomicBool::new(false));
@@ -594,18 +1065,30 @@ async fn main() -> Result<()> {
         });
 
         let scheduler = scheduler.with_shutdown(shutdown_rx);
+        let (scheduler, run_reports) = telemetry.arm(scheduler, &instances, &reports);
         let target_clone = target_id.clone();
-        let handle = tokio::spawn(async move { scheduler.run(&target_clone, &[]).await });
+        let handle = tokio::spawn(async move {
+            scheduler
+                .run_cancellable(&target_clone, &[], cancel_rx)
+                .await
+        });
 
         let beam_names: Vec<String> = beam_info.iter().map(|(name, _)| name.clone()).collect();
         // run_started.beams is the target's dependency closure, not every declared
         // beam. Fall back to the full list if the graph cannot be built (a cycle):
         // the scheduler then surfaces that error as an event.
         let json_beams: Vec<String> = if json {
-            aurora_core::dag::BeamGraph::from_deps(beam_info.clone())
+            let all: Vec<(String, Vec<String>)> = instances
+                .iter()
+                .map(|b| (b.name.clone(), b.dependency_names()))
+                .collect();
+            aurora_core::dag::BeamGraph::from_deps(all)
                 .ok()
                 .and_then(|graph| graph.execution_levels(&target_id).ok())
-                .map(|levels| levels.into_iter().flatten().collect())
+                .map(|levels| {
+                    let names = levels.into_iter().flatten();
+                    names.filter(|name| name != MULTI_BEAM).collect()
+                })
                 .unwrap_or_else(|| beam_names.clone())
         } else {
             beam_names.clone()
@@ -618,21 +1101,44 @@ async fn main() -> Result<()> {
         let mut stderr = std::io::stderr();
         use aurora::reporter::Reporter;
         let mut reporter: Box<dyn Reporter> = if json {
-            Box::new(aurora::json::JsonReporter::new(
-                target_id.clone(),
-                json_beams,
-                &mut stdout,
-            ))
+            Box::new(
+                aurora::json::JsonReporter::new(label.clone(), json_beams, &mut stdout)
+                    .with_deps(beam_info.clone()),
+            )
+        } else if github {
+            Box::new(
+                aurora::github::GithubReporter::new(label.clone(), beam_names.clone(), &mut stdout)
+                    .with_secrets(aurora::secret_values(&beam_file, &instances))
+                    .with_step_summary(std::env::var_os("GITHUB_STEP_SUMMARY").map(PathBuf::from)),
+            )
         } else {
-            Box::new(headless::HeadlessReporter::new(
-                beam_names.clone(),
-                out_color,
-                err_color,
-                &mut stdout,
-                &mut stderr,
-            ))
+            Box::new(
+                headless::HeadlessReporter::new(
+                    beam_names.clone(),
+                    out_color,
+                    err_color,
+                    &mut stdout,
+                    &mut stderr,
+                )
+                .with_output(output.unwrap_or_default())
+                .with_status_line(std::io::stderr().is_terminal())
+                .with_tty_beams(instances.iter().filter(|b| b.tty).map(|b| b.name.clone())),
+            )
         };
-        let success = reporter.run(rx).await?;
+        let (result, report_errors) =
+            aurora::reporter::run_with_reports(reporter.as_mut(), &run_reports, &label, rx).await;
+        let success = result?;
+        // A report that could not be written fails the run: a CI job relying on
+        // it would otherwise go green without its test results.
+        let reports_ok = report_errors.is_empty();
+        for error in report_errors {
+            if json {
+                let mut stdout = std::io::stdout();
+                let _ = aurora::json::write_error(&mut stdout, "report", &error);
+            } else {
+                eprintln!("aurora: {error}");
+            }
+        }
 
         // The scheduler can fail before emitting AllDone (DAG construction error:
         // cycle, unknown dependency). We join its task to propagate
@@ -658,6 +1164,8 @@ async fn main() -> Result<()> {
                 false
             }
         };
+        // `exit` skips destructors: remove the socket first.
+        drop(control_socket);
         // 128 + SIGINT(2): the shell convention for "terminated by interrupt",
         // and distinct from the plain 1 of a beam that failed on its own.
         if interrupted.load(Ordering::SeqCst) {
@@ -666,7 +1174,7 @@ async fn main() -> Result<()> {
             }
             std::process::exit(130);
         }
-        if !success || !scheduler_ok {
+        if !success || !scheduler_ok || !reports_ok {
             std::process::exit(1);
         }
     }
@@ -681,10 +1189,27 @@ async fn main() -> Result<()> {
 /// nothing on stderr, so a consumer parsing NDJSON never has to also watch
 /// stderr for a pre-run failure. Outside `--json`, behavior is unchanged:
 /// the message goes to stderr, exactly as the former `anyhow` bail did.
+///
+/// An error about a place in the Beamfile (a [`Diagnostic`] in the chain) is
+/// shown with its snippet on stderr, and carries its `span` in the event.
+///
+/// [`Diagnostic`]: aurora_core::diagnostic::Diagnostic
 fn fail_prerun(json: bool, kind: &str, err: &anyhow::Error) -> ! {
+    let source = BEAMFILE.get();
     if json {
         let mut stdout = std::io::stdout();
-        let _ = aurora::json::write_error(&mut stdout, kind, &err.to_string());
+        let _ = match source {
+            Some(source) => aurora::json::write_located_error(
+                &mut stdout,
+                kind,
+                err,
+                &source.path,
+                &source.text,
+            ),
+            None => aurora::json::write_error(&mut stdout, kind, &err.to_string()),
+        };
+    } else if let (Some(source), Some(diagnostic)) = (source, aurora_core::diagnostic::find(err)) {
+        eprint!("{}", diagnostic.render(&source.text, &source.path));
     } else {
         // Matches the format the default `Result<(), E: Debug>` process
         // termination used before this function took over: `{err:?}`, not
@@ -695,6 +1220,125 @@ fn fail_prerun(json: bool, kind: &str, err: &anyhow::Error) -> ! {
     std::process::exit(1);
 }
 
+/// What `--list` shows of a variable's sources, which it does not evaluate:
+/// `from env X, file Y`, unless `--var` or the profile set the value.
+fn variable_sources(variable: &Variable) -> Option<String> {
+    if matches!(variable.origin, ValueOrigin::Cli | ValueOrigin::Profile) {
+        return None;
+    }
+    let sources = &variable.sources;
+    let labels: Vec<String> = [
+        sources.env.as_ref().map(|name| format!("env {name}")),
+        sources.file.as_ref().map(|file| format!("file {file}")),
+        sources.shell.as_ref().map(|cmd| format!("shell `{cmd}`")),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    (!labels.is_empty()).then(|| format!("from {}", labels.join(", ")))
+}
+
+/// What `--list` shows of a profile: the values it sets, sensitive ones
+/// masked, then the names its `environment {}` declares.
+fn profile_summary(beam_file: &BeamFile, profile: &Profile) -> String {
+    let mut parts: Vec<String> = profile
+        .variables
+        .iter()
+        .map(|binding| {
+            let sensitive = beam_file
+                .variables
+                .iter()
+                .any(|v| v.name == binding.name && v.sensitive);
+            let value = if sensitive { "***" } else { &binding.value };
+            format!("{}={value}", binding.name)
+        })
+        .collect();
+    if let Some(max_parallelism) = profile.max_parallelism {
+        parts.push(format!("max_parallelism={max_parallelism}"));
+    }
+    if let Some(environment) = &profile.environment {
+        parts.extend(environment.vars.iter().map(|var| format!("${}", var.name)));
+    }
+    parts.join(", ")
+}
+
+/// Tees a run's events into a new run directory of `logs`. Logs are
+/// best-effort: a run whose directory cannot be created still runs, with a
+/// warning on stderr when `warn` (headless, outside `--json`).
+fn record_run(
+    logs: &aurora::logs::LogStore,
+    target: &str,
+    rx: mpsc::Receiver<SchedulerEvent>,
+    warn: bool,
+) -> mpsc::Receiver<SchedulerEvent> {
+    if !logs.is_enabled() {
+        return rx;
+    }
+    match logs.open_run(target) {
+        Ok(run) => run.tee(rx),
+        Err(e) => {
+            if warn {
+                eprintln!(
+                    "aurora: warning: cannot write the run logs under {}: {e}",
+                    logs.root().display()
+                );
+            }
+            rx
+        }
+    }
+}
+
+/// The positionals of a run, with the `--` clap takes as the end of the flags
+/// put back in its place: every value after it is one, so its place is known
+/// from the raw arguments. Later `--` are values already.
+fn positionals(matches: &clap::ArgMatches) -> Vec<String> {
+    let mut positionals: Vec<String> = matches
+        .get_one::<String>("beam")
+        .into_iter()
+        .chain(matches.get_many::<String>("args").into_iter().flatten())
+        .cloned()
+        .collect();
+    let raw: Vec<std::ffi::OsString> = std::env::args_os().skip(1).collect();
+    if let Some(escape) = raw.iter().position(|arg| arg == "--") {
+        let after = raw.len() - escape - 1;
+        positionals.insert(positionals.len().saturating_sub(after), "--".to_string());
+    }
+    positionals
+}
+
+/// Parses the command line. A beam the Beamfile declares under a subcommand's
+/// name wins over the subcommand, so a Beamfile written before it existed keeps
+/// its meaning: the arguments are then read as a run's, with a hint on stderr
+/// (unless `--json`). A missing or broken Beamfile leaves the subcommand be.
+fn command_line() -> clap::ArgMatches {
+    // Read leniently first: the beam's arguments need not suit the subcommand.
+    let name = aurora::run_cli()
+        .ignore_errors(true)
+        .try_get_matches()
+        .ok()
+        .and_then(|matches| matches.get_one::<String>("beam").cloned())
+        .filter(|name| name != aurora::BUILTIN && aurora::cli().find_subcommand(name).is_some());
+    let Some(name) = name else {
+        return aurora::cli().get_matches();
+    };
+    let declared = find_beamfile(true)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| parse(&content).ok())
+        .is_some_and(|beam_file| beam_file.beams.iter().any(|beam| beam.name == name));
+    if !declared {
+        return aurora::cli().get_matches();
+    }
+    let matches = aurora::run_cli().get_matches();
+    if !matches.get_flag("json") {
+        eprintln!(
+            "aurora: running the beam '{name}'; the `{name}` subcommand is `aurora {} {name}`",
+            aurora::BUILTIN
+        );
+    }
+    matches
+}
+
 fn find_beamfile(json: bool) -> Result<PathBuf> {
     let start = std::env::current_dir()?;
     let mut dir = start.clone();
@@ -720,6 +1364,66 @@ fn find_beamfile(json: bool) -> Result<PathBuf> {
     }
 }
 
+/// Lists, for `--dry-run --verbose`, the names of the environment variables
+/// each instance of the plan would receive, by where they come from. Values
+/// stay out: they may be secrets.
+fn print_beam_environments(
+    expansion: &aurora_core::expand::Expansion,
+    evaluated: &EvaluatedEnv,
+    global_inherited: &BTreeMap<String, String>,
+) -> Result<()> {
+    let deps: Vec<(String, Vec<String>)> = expansion
+        .instances
+        .iter()
+        .map(|b| (b.name.clone(), b.dependency_names()))
+        .collect();
+    let graph = aurora_core::dag::BeamGraph::from_deps(deps)?;
+    let mut planned: Vec<String> = graph
+        .execution_levels(&expansion.target_id)?
+        .into_iter()
+        .flatten()
+        .collect();
+    planned.sort();
+
+    println!("\nEnvironment:");
+    for beam in expansion
+        .instances
+        .iter()
+        .filter(|b| b.name != MULTI_BEAM && planned.contains(&b.name))
+    {
+        let declared: BTreeSet<&str> = evaluated
+            .declared
+            .keys()
+            .chain(beam.env_overlay.keys())
+            .map(String::as_str)
+            .collect();
+        let inherited: BTreeSet<&str> = global_inherited
+            .keys()
+            .chain(beam.inherited_env.keys())
+            .map(String::as_str)
+            .filter(|name| !declared.contains(name))
+            .collect();
+        let ambient: BTreeSet<&str> = evaluated
+            .env
+            .keys()
+            .map(String::as_str)
+            .filter(|name| !declared.contains(name) && !inherited.contains(name))
+            .collect();
+        println!("  {}", beam.name);
+        for (origin, names) in [
+            ("declared", declared),
+            ("inherited", inherited),
+            ("ambient", ambient),
+        ] {
+            if !names.is_empty() {
+                let names: Vec<&str> = names.into_iter().collect();
+                println!("    {origin:<10} {}", names.join(", "));
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Prints, without running anything, the instances a target would execute
 /// grouped by dependency level (level 0 runs first). Instances are identified
 /// by their instance id (e.g. `deploy[env=staging,version=1.2.3]`), the
@@ -732,9 +1436,23 @@ fn print_execution_plan(expansion: &aurora_core::expand::Expansion) -> Result<()
         .map(|b| (b.name.clone(), b.dependency_names()))
         .collect();
     let graph = aurora_core::dag::BeamGraph::from_deps(deps)?;
-    let levels = graph.execution_levels(&expansion.target_id)?;
+    // The virtual __multi__ root of several targets runs nothing.
+    let levels: Vec<Vec<String>> = graph
+        .execution_levels(&expansion.target_id)?
+        .into_iter()
+        .map(|level| {
+            level
+                .into_iter()
+                .filter(|name| name != MULTI_BEAM)
+                .collect()
+        })
+        .filter(|level: &Vec<String>| !level.is_empty())
+        .collect();
 
-    println!("Execution plan for '{}':", expansion.target_id);
+    println!(
+        "Execution plan for '{}':",
+        aurora::run_label(&expansion.instances, &expansion.target_id)
+    );
     if levels.is_empty() {
         println!("  (nothing to run)");
     }
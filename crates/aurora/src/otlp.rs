@@ -0,0 +1,419 @@
+//! OpenTelemetry export over OTLP/HTTP (`--otlp <endpoint>`, cargo feature
+//! `otlp`): each run is one trace, sent to a collector once the run is over.
+//!
+//! The trace has a root span for the run, a child span per beam, and under
+//! each beam a span per phase (gate, input hash, cache lookup, execute). The
+//! beam spans carry their outcome as attributes: status, cache hit, exit
+//! code, executor and instance bindings. Their ids are the ones the scheduler
+//! handed to the beams as `TRACEPARENT` (see [`TraceContext`]), so spans
+//! recorded by the tools inside a beam land under it.
+//!
+//! The payload is OTLP's JSON encoding, which every collector accepts on
+//! `/v1/traces`. Telemetry never decides a build: an export that fails is a
+//! warning on stderr, not a failed run.
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use aurora_core::ast::Beam;
+use aurora_core::events::{BeamPhase, BeamStatus, SchedulerEvent, SkipReason};
+use aurora_core::trace_context::{random_span_id, TraceContext};
+use serde_json::{json, Value};
+use tokio::sync::mpsc;
+
+use crate::reporter::Reporter;
+
+/// Bounds the whole export, so an unreachable collector delays the end of the
+/// run by at most this much.
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Where and how to send the traces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OtlpConfig {
+    pub endpoint: String,
+    /// Extra request headers (authentication, tenant).
+    pub headers: Vec<(String, String)>,
+    pub service_name: String,
+}
+
+impl OtlpConfig {
+    /// The config for `endpoint`, completed from the standard OpenTelemetry
+    /// variables: `OTEL_EXPORTER_OTLP_HEADERS` (`key=value,key=value`) and
+    /// `OTEL_SERVICE_NAME` (`aurora` by default).
+    pub fn from_env(endpoint: &str) -> Self {
+        let headers = std::env::var("OTEL_EXPORTER_OTLP_HEADERS")
+            .map(|raw| parse_headers(&raw))
+            .unwrap_or_default();
+        let service_name = std::env::var("OTEL_SERVICE_NAME")
+            .ok()
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| "aurora".to_string());
+        Self {
+            endpoint: endpoint.to_string(),
+            headers,
+            service_name,
+        }
+    }
+
+    /// The traces URL: `endpoint` is the collector's base URL
+    /// (`http://localhost:4318`), unless it already names `/v1/traces`.
+    pub fn traces_url(&self) -> String {
+        if self.endpoint.ends_with("/v1/traces") {
+            self.endpoint.clone()
+        } else {
+            format!("{}/v1/traces", self.endpoint.trim_end_matches('/'))
+        }
+    }
+}
+
+/// Parses `OTEL_EXPORTER_OTLP_HEADERS`; malformed entries are skipped.
+fn parse_headers(raw: &str) -> Vec<(String, String)> {
+    raw.split(',')
+        .filter_map(|entry| entry.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .filter(|(key, _)| !key.is_empty())
+        .collect()
+}
+
+/// What the events do not say about a beam, taken from its definition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BeamMeta {
+    pub executor: String,
+    pub bindings: BTreeMap<String, String>,
+}
+
+/// The metadata of the beams of a run, by instance name.
+pub fn beam_meta(beams: &[Beam]) -> HashMap<String, BeamMeta> {
+    beams
+        .iter()
+        .map(|beam| {
+            let executor = beam
+                .run
+                .as_ref()
+                .and_then(|run| run.executor.as_ref())
+                .map_or("local", |executor| executor.name.as_str());
+            let meta = BeamMeta {
+                executor: executor.to_string(),
+                bindings: beam.bindings.clone(),
+            };
+            (beam.name.clone(), meta)
+        })
+        .collect()
+}
+
+/// The export of one run: the trace the scheduler was armed with (see
+/// [`aurora_core::scheduler::Scheduler::with_trace_context`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OtlpExport {
+    pub config: OtlpConfig,
+    pub context: TraceContext,
+    pub beams: HashMap<String, BeamMeta>,
+}
+
+/// Records the run and exports it once it is over.
+pub struct OtlpReporter {
+    target: String,
+    export: OtlpExport,
+}
+
+impl OtlpReporter {
+    pub fn new(target: String, export: OtlpExport) -> Self {
+        Self { target, export }
+    }
+}
+
+#[async_trait]
+impl Reporter for OtlpReporter {
+    async fn run(&mut self, mut rx: mpsc::Receiver<SchedulerEvent>) -> std::io::Result<bool> {
+        let clock = Clock::now();
+        let mut recorded = vec![];
+        let mut success = false;
+        while let Some(event) = rx.recv().await {
+            let done = match event {
+                SchedulerEvent::AllDone { success: s } => {
+                    success = s;
+                    true
+                }
+                _ => false,
+            };
+            recorded.push((Instant::now(), event));
+            if done {
+                break;
+            }
+        }
+
+        let payload = payload(&self.target, &self.export, &clock, &recorded);
+        let config = self.export.config.clone();
+        let sent = tokio::task::spawn_blocking(move || send(&config, &payload)).await;
+        let error = match sent {
+            Ok(Ok(())) => None,
+            Ok(Err(e)) => Some(e.to_string()),
+            Err(e) => Some(e.to_string()),
+        };
+        if let Some(error) = error {
+            eprintln!(
+                "aurora: warning: cannot export the run to {}: {error}",
+                self.export.config.traces_url()
+            );
+        }
+        Ok(success)
+    }
+}
+
+fn send(config: &OtlpConfig, payload: &Value) -> Result<(), ureq::Error> {
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(EXPORT_TIMEOUT))
+        .build()
+        .into();
+    let mut request = agent
+        .post(config.traces_url())
+        .header("content-type", "application/json");
+    for (key, value) in &config.headers {
+        request = request.header(key, value);
+    }
+    request.send(payload.to_string())?;
+    Ok(())
+}
+
+/// Maps the monotonic instants of the events to wall-clock time, which OTLP
+/// timestamps are in.
+pub struct Clock {
+    instant: Instant,
+    unix: Duration,
+}
+
+impl Clock {
+    pub fn now() -> Self {
+        Self {
+            instant: Instant::now(),
+            unix: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Nanoseconds since the Unix epoch, as OTLP's JSON encoding wants them
+    /// (a 64-bit integer, so a string).
+    fn unix_nanos(&self, at: Instant) -> String {
+        let unix = if at >= self.instant {
+            self.unix + (at - self.instant)
+        } else {
+            self.unix.saturating_sub(self.instant - at)
+        };
+        unix.as_nanos().to_string()
+    }
+}
+
+/// One beam as recorded.
+struct BeamRecord<'a> {
+    name: &'a str,
+    start: Option<Instant>,
+    end: Option<Instant>,
+    status: Option<&'a BeamStatus>,
+    phases: Vec<(BeamPhase, Instant, Instant)>,
+}
+
+/// Builds the OTLP `ExportTraceServiceRequest` of a run from its events and
+/// their arrival times.
+pub fn payload(
+    target: &str,
+    export: &OtlpExport,
+    clock: &Clock,
+    recorded: &[(Instant, SchedulerEvent)],
+) -> Value {
+    let context = &export.context;
+    let mut beams: Vec<BeamRecord> = vec![];
+    let mut index: HashMap<&str, usize> = HashMap::new();
+    let mut success = false;
+    for (at, event) in recorded {
+        let name = match event {
+            SchedulerEvent::BeamStarted { name }
+            | SchedulerEvent::BeamCompleted { name, .. }
+            | SchedulerEvent::BeamPhase { name, .. } => name.as_str(),
+            SchedulerEvent::AllDone { success: s } => {
+                success = *s;
+                continue;
+            }
+            _ => continue,
+        };
+        let i = *index.entry(name).or_insert_with(|| {
+            beams.push(BeamRecord {
+                name,
+                start: None,
+                end: None,
+                status: None,
+                phases: vec![],
+            });
+            beams.len() - 1
+        });
+        let beam = &mut beams[i];
+        match event {
+            SchedulerEvent::BeamStarted { .. } => beam.start = Some(*at),
+            SchedulerEvent::BeamCompleted { status, .. } => {
+                beam.end = Some(*at);
+                beam.status = Some(status);
+            }
+            SchedulerEvent::BeamPhase {
+                phase, start, end, ..
+            } => beam.phases.push((*phase, *start, *end)),
+            _ => {}
+        }
+    }
+
+    let run_start = recorded
+        .iter()
+        .filter_map(|(_, event)| match event {
+            SchedulerEvent::BeamPhase { start, .. } => Some(*start),
+            _ => None,
+        })
+        .fold(clock.instant, Instant::min);
+    let run_end = recorded.last().map_or(run_start, |(at, _)| *at);
+
+    let mut root = span(
+        context,
+        &context.root_span_id,
+        context.parent_span_id.as_deref(),
+        &format!("aurora {target}"),
+        clock,
+        (run_start, run_end),
+        vec![
+            attribute("aurora.target", json!({ "stringValue": target })),
+            attribute("aurora.success", json!({ "boolValue": success })),
+        ],
+    );
+    root["status"] = if success {
+        json!({ "code": 1 })
+    } else {
+        json!({ "code": 2, "message": "the run failed" })
+    };
+
+    let mut spans = vec![root];
+    for beam in &beams {
+        let span_id = context.beam_span_id(beam.name);
+        // Widened to its phases: `BeamStarted` arrives a moment after the
+        // scheduler sent it. A beam that never started spans no time.
+        let end = beam
+            .phases
+            .iter()
+            .map(|(_, _, e)| *e)
+            .fold(beam.end.unwrap_or(run_end), Instant::max);
+        let start = beam
+            .phases
+            .iter()
+            .map(|(_, s, _)| *s)
+            .fold(beam.start.unwrap_or(end), Instant::min);
+        let mut beam_span = span(
+            context,
+            &span_id,
+            Some(&context.root_span_id),
+            beam.name,
+            clock,
+            (start, end),
+            beam_attributes(beam.name, beam.status, export.beams.get(beam.name)),
+        );
+        if let Some(BeamStatus::Failed { exit_code, .. }) = beam.status {
+            beam_span["status"] = json!({ "code": 2, "message": format!("exit code {exit_code}") });
+        }
+        spans.push(beam_span);
+        for (phase, start, end) in &beam.phases {
+            spans.push(span(
+                context,
+                &random_span_id(),
+                Some(&span_id),
+                phase.label(),
+                clock,
+                (*start, *end),
+                vec![attribute(
+                    "aurora.beam.name",
+                    json!({ "stringValue": beam.name }),
+                )],
+            ));
+        }
+    }
+
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [
+                    attribute("service.name", json!({ "stringValue": export.config.service_name })),
+                ],
+            },
+            "scopeSpans": [{
+                "scope": { "name": "aurora", "version": env!("CARGO_PKG_VERSION") },
+                "spans": spans,
+            }],
+        }],
+    })
+}
+
+fn span(
+    context: &TraceContext,
+    span_id: &str,
+    parent: Option<&str>,
+    name: &str,
+    clock: &Clock,
+    (start, end): (Instant, Instant),
+    attributes: Vec<Value>,
+) -> Value {
+    let mut span = json!({
+        "traceId": context.trace_id,
+        "spanId": span_id,
+        "name": name,
+        // SPAN_KIND_INTERNAL
+        "kind": 1,
+        "startTimeUnixNano": clock.unix_nanos(start),
+        "endTimeUnixNano": clock.unix_nanos(end.max(start)),
+        "attributes": attributes,
+    });
+    if let Some(parent) = parent {
+        span["parentSpanId"] = json!(parent);
+    }
+    span
+}
+
+fn attribute(key: &str, value: Value) -> Value {
+    json!({ "key": key, "value": value })
+}
+
+fn beam_attributes(name: &str, status: Option<&BeamStatus>, meta: Option<&BeamMeta>) -> Vec<Value> {
+    let (label, cache_hit, exit_code) = match status {
+        Some(BeamStatus::Success { cached, .. }) => {
+            (if *cached { "cached" } else { "success" }, *cached, None)
+        }
+        Some(BeamStatus::Skipped {
+            reason: SkipReason::Cached,
+        }) => ("cached", true, None),
+        Some(BeamStatus::Skipped { .. }) => ("skipped", false, None),
+        Some(BeamStatus::Failed { exit_code, .. }) => ("failed", false, Some(*exit_code)),
+        Some(BeamStatus::FailedAllowed { exit_code, .. }) => {
+            ("failed_allowed", false, Some(*exit_code))
+        }
+        Some(BeamStatus::Cancelled) => ("cancelled", false, None),
+        _ => ("unfinished", false, None),
+    };
+    let mut attributes = vec![
+        attribute("aurora.beam.name", json!({ "stringValue": name })),
+        attribute("aurora.beam.status", json!({ "stringValue": label })),
+        attribute("aurora.beam.cache_hit", json!({ "boolValue": cache_hit })),
+    ];
+    if let Some(exit_code) = exit_code {
+        attributes.push(attribute(
+            "aurora.beam.exit_code",
+            json!({ "intValue": exit_code.to_string() }),
+        ));
+    }
+    if let Some(meta) = meta {
+        attributes.push(attribute(
+            "aurora.beam.executor",
+            json!({ "stringValue": meta.executor }),
+        ));
+        for (param, value) in &meta.bindings {
+            attributes.push(attribute(
+                &format!("aurora.beam.binding.{param}"),
+                json!({ "stringValue": value }),
+            ));
+        }
+    }
+    attributes
+}
@@ -0,0 +1,63 @@
+//! The param values last entered in the picker, remembered per beam so the
+//! next launch of the same beam offers them again.
+//!
+//! Stored as JSON under the Beamfile's `.aurora/` directory, next to the
+//! cache. Both reading and writing are best-effort: a missing or unreadable
+//! file is an empty memory, and a failure to save never fails the run.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PickerMemory {
+    /// Beam name → param name → last value entered.
+    #[serde(default)]
+    pub params: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl PickerMemory {
+    /// Where the memory of the project rooted at `working_dir` lives.
+    pub fn path(working_dir: &Path) -> PathBuf {
+        working_dir.join(".aurora/picker.json")
+    }
+
+    /// The remembered values, or an empty memory when there are none (or the
+    /// file cannot be read or parsed).
+    pub fn load(working_dir: &Path) -> Self {
+        std::fs::read_to_string(Self::path(working_dir))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Writes the memory back, ignoring any failure (a read-only checkout
+    /// simply does not remember).
+    pub fn save(&self, working_dir: &Path) {
+        let path = Self::path(working_dir);
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if let Ok(content) = serde_json::to_string_pretty(self) {
+            let _ = std::fs::write(path, content);
+        }
+    }
+
+    /// The last value entered for `beam`'s `param`.
+    pub fn last(&self, beam: &str, param: &str) -> Option<&str> {
+        self.params.get(beam)?.get(param).map(String::as_str)
+    }
+
+    /// Records the `name=value` arguments entered for `beam`. A param left
+    /// empty is absent from `args` and keeps its previous memory.
+    pub fn remember(&mut self, beam: &str, args: &[String]) {
+        for arg in args {
+            if let Some((name, value)) = arg.split_once('=') {
+                self.params
+                    .entry(beam.to_string())
+                    .or_default()
+                    .insert(name.to_string(), value.to_string());
+            }
+        }
+    }
+}
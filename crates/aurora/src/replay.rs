@@ -0,0 +1,252 @@
+//! `aurora replay`: a run recorded with `--json`, read back into
+//! [`SchedulerEvent`]s and played in the execution TUI, so a CI run can be
+//! inspected like a local one (beam list, logs, search, dependencies).
+//!
+//! Only the lifecycle events carry a timestamp (`at`); the output lines of a
+//! beam are spread evenly between the timestamped events around them, which
+//! is as close as the recording gets to their real pace.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use aurora_core::events::{BeamStatus, SchedulerEvent, SkipReason};
+use tokio::sync::mpsc;
+
+use crate::json::{WireEvent, WireLine, WireStatus, SCHEMA};
+
+/// A recorded run.
+#[derive(Debug)]
+pub struct Recording {
+    pub target: String,
+    /// The beams of the run, in the recorded order, with their dependencies
+    /// (none for a recording without `deps`).
+    pub beams: Vec<(String, Vec<String>)>,
+    /// Each event with its time since the start of the run.
+    pub events: Vec<(Duration, SchedulerEvent)>,
+}
+
+/// What `run_started` says of the run.
+struct Started {
+    target: String,
+    beams: Vec<String>,
+    deps: BTreeMap<String, Vec<String>>,
+    /// In milliseconds since the Unix epoch.
+    at: i64,
+}
+
+/// Parses the NDJSON a `--json` run wrote. A recording cut short (no
+/// `run_completed`) ends as a failed run.
+pub fn parse_recording(text: &str) -> Result<Recording> {
+    let mut run: Option<Started> = None;
+    let mut events: Vec<(Option<i64>, SchedulerEvent)> = vec![];
+    let mut completed = false;
+    for (index, line) in text.lines().enumerate() {
+        let number = index + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let wire: WireLine = serde_json::from_str(line)
+            .with_context(|| format!("line {number}: not an `aurora --json` event"))?;
+        if wire.schema != SCHEMA {
+            bail!(
+                "line {number}: schema {} is not supported (this Aurora reads schema {SCHEMA})",
+                wire.schema
+            );
+        }
+        let at = |at: &str| -> Result<i64> {
+            at.parse::<jiff::Timestamp>()
+                .map(|t| t.as_millisecond())
+                .with_context(|| format!("line {number}: invalid timestamp `{at}`"))
+        };
+        if let WireEvent::RunStarted {
+            target,
+            beams,
+            deps,
+            at: started,
+        } = wire.event
+        {
+            if run.is_some() {
+                bail!("line {number}: a second run_started; replay one run per file");
+            }
+            run = Some(Started {
+                target,
+                beams,
+                deps,
+                at: at(&started)?,
+            });
+            continue;
+        }
+        let Some(Started { target, .. }) = &run else {
+            match wire.event {
+                WireEvent::Error { message, .. } => {
+                    bail!("the recorded run failed before it started: {message}")
+                }
+                _ => bail!("line {number}: an event before run_started"),
+            }
+        };
+        let (time, event) = match wire.event {
+            WireEvent::RunStarted { .. } => unreachable!("handled above"),
+            WireEvent::BeamStarted { beam, at: time } => {
+                (Some(at(&time)?), SchedulerEvent::BeamStarted { name: beam })
+            }
+            WireEvent::BeamOutput { beam, stream, line } => (
+                None,
+                SchedulerEvent::BeamOutput {
+                    name: beam,
+                    line,
+                    is_stderr: stream == "stderr",
+                },
+            ),
+            WireEvent::BeamStopping { beam, at: time } => (
+                Some(at(&time)?),
+                SchedulerEvent::BeamStopping { name: beam },
+            ),
+            WireEvent::BeamCompleted {
+                beam,
+                status,
+                duration_ms,
+                at: time,
+            } => {
+                let duration = Duration::from_millis(duration_ms.unwrap_or(0));
+                let status = match status {
+                    WireStatus::Success { cached } => BeamStatus::Success { duration, cached },
+                    WireStatus::Skipped { reason } => BeamStatus::Skipped {
+                        reason: match reason.as_str() {
+                            "cached" => SkipReason::Cached,
+                            "skip_if" => SkipReason::SkipIf,
+                            _ => SkipReason::ConditionNotMet,
+                        },
+                    },
+                    WireStatus::Failed { exit_code } => BeamStatus::Failed {
+                        exit_code,
+                        duration,
+                    },
+                    WireStatus::FailedAllowed { exit_code } => BeamStatus::FailedAllowed {
+                        exit_code,
+                        duration,
+                    },
+                    WireStatus::Cancelled => BeamStatus::Cancelled,
+                };
+                (
+                    Some(at(&time)?),
+                    SchedulerEvent::BeamCompleted { name: beam, status },
+                )
+            }
+            WireEvent::Warning { beam, message } => (
+                None,
+                SchedulerEvent::Warning {
+                    name: beam,
+                    message,
+                },
+            ),
+            // An error after the start (a dependency cycle) is shown on the
+            // target, the closest the view has to a run-level message.
+            WireEvent::Error { kind, message, .. } => (
+                None,
+                SchedulerEvent::Warning {
+                    name: target.clone(),
+                    message: format!("{kind} error: {message}"),
+                },
+            ),
+            WireEvent::RunCompleted {
+                success, at: time, ..
+            } => {
+                completed = true;
+                (Some(at(&time)?), SchedulerEvent::AllDone { success })
+            }
+        };
+        events.push((time, event));
+        if completed {
+            break;
+        }
+    }
+
+    let Some(Started {
+        target,
+        beams: names,
+        mut deps,
+        at: started,
+    }) = run
+    else {
+        bail!("no run_started event: not a recording of `aurora --json`");
+    };
+    if !completed {
+        events.push((None, SchedulerEvent::AllDone { success: false }));
+    }
+    let beams = names
+        .into_iter()
+        .map(|name| {
+            let deps = deps.remove(&name).unwrap_or_default();
+            (name, deps)
+        })
+        .collect();
+    Ok(Recording {
+        target,
+        beams,
+        events: spread(started, events),
+    })
+}
+
+/// Gives every event a time since `started`: an event without a timestamp
+/// sits evenly between the timestamped events around it, or at the last one
+/// when none follows.
+fn spread(
+    started: i64,
+    events: Vec<(Option<i64>, SchedulerEvent)>,
+) -> Vec<(Duration, SchedulerEvent)> {
+    let mut times = vec![0i64; events.len()];
+    let (mut last, mut last_index) = (started, None::<usize>);
+    for (index, (time, _)) in events.iter().enumerate() {
+        let Some(time) = *time else { continue };
+        // Clocks only move forward in a replay.
+        let time = time.max(last);
+        let from = last_index.map_or(0, |i| i + 1);
+        let steps = (index - from + 1) as i64;
+        for (k, slot) in times[from..index].iter_mut().enumerate() {
+            *slot = last + (time - last) * (k as i64 + 1) / steps;
+        }
+        times[index] = time;
+        (last, last_index) = (time, Some(index));
+    }
+    let from = last_index.map_or(0, |i| i + 1);
+    times[from..].fill(last);
+    events
+        .into_iter()
+        .zip(times)
+        .map(|((_, event), time)| (Duration::from_millis((time - started).max(0) as u64), event))
+        .collect()
+}
+
+/// Sends the events to `tx`: all at once without `speed`, otherwise at
+/// `speed` times their recorded pace. Stops when the receiver goes away.
+pub async fn play(
+    events: Vec<(Duration, SchedulerEvent)>,
+    speed: Option<f64>,
+    tx: mpsc::Sender<SchedulerEvent>,
+) {
+    let start = tokio::time::Instant::now();
+    for (offset, event) in events {
+        if let Some(speed) = speed {
+            tokio::time::sleep_until(start + offset.div_f64(speed)).await;
+        }
+        if tx.send(event).await.is_err() {
+            return;
+        }
+    }
+}
+
+/// `aurora replay FILE [--speed X]`.
+pub async fn replay_command(path: &Path, speed: Option<f64>) -> Result<()> {
+    let text =
+        std::fs::read_to_string(path).with_context(|| format!("cannot read {}", path.display()))?;
+    let recording =
+        parse_recording(&text).with_context(|| format!("cannot replay {}", path.display()))?;
+    if !std::io::IsTerminal::is_terminal(&std::io::stdout()) {
+        bail!("`aurora replay` opens the TUI and needs a terminal");
+    }
+    let (tx, rx) = mpsc::channel(128);
+    tokio::spawn(play(recording.events, speed, tx));
+    aurora_tui::run_replay_tui(recording.beams, recording.target, rx).await
+}
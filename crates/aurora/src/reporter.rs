@@ -2,13 +2,16 @@
 //!
 //! A `Reporter` drains the scheduler's event stream and renders it to some
 //! sink, returning the overall success that drives the exit code. Headless
-//! (human-oriented text) and JSON (a machine contract) are the two concrete
-//! renderers today; JUnit XML or CI annotations would be further ones. The TUI
-//! is deliberately not a `Reporter`: it owns the terminal, the cancellation
-//! channel and the rerun closure, and would not fit behind this interface.
+//! (human-oriented text) and JSON (a machine contract) are the two renderers
+//! of the console; file reports (`--report junit=...`, `--trace`) run alongside either
+//! one, on a copy of the same stream. The TUI is deliberately not a
+//! `Reporter`: it owns the terminal, the cancellation channel and the rerun
+//! closure, and would not fit behind this interface.
 
+use anyhow::{bail, Result};
 use async_trait::async_trait;
 use aurora_core::events::SchedulerEvent;
+use std::path::PathBuf;
 use tokio::sync::mpsc;
 
 #[async_trait]
@@ -17,3 +20,120 @@ pub trait Reporter: Send {
     /// overall success carried by `AllDone`, which drives the process exit code.
     async fn run(&mut self, rx: mpsc::Receiver<SchedulerEvent>) -> std::io::Result<bool>;
 }
+
+/// A report run alongside the console: a file requested with `--report
+/// <format>=<path>` (or `--trace`), or the OTLP export of the run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReportSpec {
+    /// JUnit XML, as rendered natively by Jenkins and GitLab.
+    Junit(PathBuf),
+    /// Chrome Trace Event Format, for `chrome://tracing` and Perfetto.
+    Trace(PathBuf),
+    /// OpenTelemetry spans sent to a collector (see [`crate::otlp`]).
+    #[cfg(feature = "otlp")]
+    Otlp(Box<crate::otlp::OtlpExport>),
+}
+
+impl ReportSpec {
+    /// Parses the `--report` value.
+    pub fn parse(raw: &str) -> Result<Self> {
+        let Some((format, path)) = raw.split_once('=') else {
+            bail!("invalid --report '{raw}': expected <format>=<path>, e.g. junit=report.xml");
+        };
+        if path.is_empty() {
+            bail!("invalid --report '{raw}': the path is empty");
+        }
+        match format {
+            "junit" => Ok(ReportSpec::Junit(PathBuf::from(path))),
+            "trace" => Ok(ReportSpec::Trace(PathBuf::from(path))),
+            other => bail!("unknown report format '{other}': expected junit or trace"),
+        }
+    }
+
+    /// Where the report goes, for error messages.
+    pub fn destination(&self) -> String {
+        match self {
+            ReportSpec::Junit(path) | ReportSpec::Trace(path) => path.display().to_string(),
+            #[cfg(feature = "otlp")]
+            ReportSpec::Otlp(export) => export.config.traces_url(),
+        }
+    }
+}
+
+/// Copies every event of `rx` to `n` receivers, in order. A receiver dropped
+/// early (a console whose consumer went away) does not stop the others.
+pub fn fan_out(
+    mut rx: mpsc::Receiver<SchedulerEvent>,
+    n: usize,
+) -> Vec<mpsc::Receiver<SchedulerEvent>> {
+    let (txs, rxs): (Vec<_>, Vec<_>) = (0..n).map(|_| mpsc::channel(128)).unzip();
+    tokio::spawn(async move {
+        while let Some(event) = rx.recv().await {
+            for tx in &txs {
+                let _ = tx.send(event.clone()).await;
+            }
+        }
+    });
+    rxs
+}
+
+/// Runs `console` (headless or JSON) on the event stream, with the file
+/// `reports` alongside it on copies of the stream. Returns the console's
+/// result, plus one message per report that could not be written: a report
+/// never changes what the console shows, and the caller decides how to
+/// surface its failure.
+pub async fn run_with_reports(
+    console: &mut dyn Reporter,
+    reports: &[ReportSpec],
+    target: &str,
+    rx: mpsc::Receiver<SchedulerEvent>,
+) -> (std::io::Result<bool>, Vec<String>) {
+    if reports.is_empty() {
+        return (console.run(rx).await, vec![]);
+    }
+    let mut rxs = fan_out(rx, reports.len() + 1);
+    let console_rx = rxs.remove(0);
+    // Each report drains its own copy in a task of its own: awaited in turn
+    // from here, a report would stall the fan-out once its channel filled.
+    let report_tasks: Vec<_> = reports
+        .iter()
+        .zip(rxs)
+        .map(|(spec, rx)| {
+            let spec = spec.clone();
+            let target = target.to_string();
+            tokio::spawn(async move {
+                let result = match &spec {
+                    ReportSpec::Junit(path) => {
+                        crate::junit::JunitReporter::new(target, path.clone())
+                            .run(rx)
+                            .await
+                    }
+                    ReportSpec::Trace(path) => {
+                        crate::trace::TraceReporter::new(target, path.clone())
+                            .run(rx)
+                            .await
+                    }
+                    #[cfg(feature = "otlp")]
+                    ReportSpec::Otlp(export) => {
+                        crate::otlp::OtlpReporter::new(target, (**export).clone())
+                            .run(rx)
+                            .await
+                    }
+                };
+                result
+                    .err()
+                    .map(|e| format!("cannot write the report {}: {e}", spec.destination()))
+            })
+        })
+        .collect();
+    let console_result = console.run(console_rx).await;
+    let mut report_errors = vec![];
+    for task in report_tasks {
+        match task.await {
+            Ok(Some(error)) => report_errors.push(error),
+            Ok(None) => {}
+            Err(e) => report_errors.push(format!("report task failed: {e}")),
+        }
+    }
+    (console_result, report_errors)
+}
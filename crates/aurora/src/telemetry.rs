@@ -0,0 +1,70 @@
+//! Telemetry of non-interactive runs: the OTLP export (`--otlp`, behind the
+//! `otlp` cargo feature), armed afresh on every run so that each run, watch
+//! cycles included, is a trace of its own.
+//!
+//! Without the feature this is inert, which keeps the wiring in `main` free
+//! of `cfg` attributes.
+
+use aurora_core::ast::Beam;
+use aurora_core::scheduler::Scheduler;
+
+use crate::reporter::ReportSpec;
+
+pub struct Telemetry {
+    #[cfg(feature = "otlp")]
+    otlp: Option<crate::otlp::OtlpConfig>,
+}
+
+impl Telemetry {
+    pub fn from_matches(matches: &clap::ArgMatches) -> Self {
+        #[cfg(feature = "otlp")]
+        {
+            Self {
+                otlp: matches
+                    .get_one::<String>("otlp")
+                    .map(|endpoint| crate::otlp::OtlpConfig::from_env(endpoint)),
+            }
+        }
+        #[cfg(not(feature = "otlp"))]
+        {
+            let _ = matches;
+            Self {}
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        #[cfg(feature = "otlp")]
+        {
+            self.otlp.is_some()
+        }
+        #[cfg(not(feature = "otlp"))]
+        {
+            false
+        }
+    }
+
+    /// Arms one run of `beams`: starts its trace (continuing the `TRACEPARENT`
+    /// Aurora itself was given, if any), hands it to the scheduler and adds its
+    /// export to the file `reports`.
+    pub fn arm(
+        &self,
+        scheduler: Scheduler,
+        beams: &[Beam],
+        reports: &[ReportSpec],
+    ) -> (Scheduler, Vec<ReportSpec>) {
+        #[cfg(feature = "otlp")]
+        if let Some(config) = &self.otlp {
+            use aurora_core::trace_context::{TraceContext, TRACEPARENT};
+            let context = TraceContext::new(std::env::var(TRACEPARENT).ok().as_deref());
+            let mut reports = reports.to_vec();
+            reports.push(ReportSpec::Otlp(Box::new(crate::otlp::OtlpExport {
+                config: config.clone(),
+                context: context.clone(),
+                beams: crate::otlp::beam_meta(beams),
+            })));
+            return (scheduler.with_trace_context(context), reports);
+        }
+        let _ = beams;
+        (scheduler, reports.to_vec())
+    }
+}
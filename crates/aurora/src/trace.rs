@@ -0,0 +1,286 @@
+//! Chrome trace export (`--trace out.json`): the run in the Trace Event
+//! Format, for `chrome://tracing`, Perfetto or speedscope.
+//!
+//! Every scheduler event is recorded with the monotonic time it arrived, and
+//! the trace is written once the run is over. Each parallelism slot is a
+//! track (a "thread"): a beam takes the lowest free slot when it starts and
+//! gives it back when it completes, so the tracks show how the slots were
+//! used. A beam is a span on its track, with its phases (gate, input hash,
+//! cache lookup, execute) nested inside; stops, output lines and warnings are
+//! instant events. Beams that never started (skipped or cancelled while
+//! waiting) are instants on the scheduler track.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use async_trait::async_trait;
+use aurora_core::ansi::plain_text;
+use aurora_core::events::{BeamPhase, BeamStatus, SchedulerEvent, SkipReason};
+use serde_json::{json, Value};
+use tokio::sync::mpsc;
+
+use crate::reporter::Reporter;
+
+/// The single process of the trace.
+const PID: u32 = 1;
+/// The track of the events that belong to no slot.
+const SCHEDULER_TID: usize = 0;
+
+/// Records the run and writes the trace once it is over (or the stream ends
+/// without `AllDone`, in which case what was recorded so far is written).
+pub struct TraceReporter {
+    target: String,
+    path: PathBuf,
+}
+
+impl TraceReporter {
+    /// `target` names the trace's process.
+    pub fn new(target: String, path: PathBuf) -> Self {
+        Self { target, path }
+    }
+}
+
+#[async_trait]
+impl Reporter for TraceReporter {
+    async fn run(&mut self, mut rx: mpsc::Receiver<SchedulerEvent>) -> std::io::Result<bool> {
+        let origin = Instant::now();
+        let mut recorded = vec![];
+        let mut success = false;
+        while let Some(event) = rx.recv().await {
+            let done = match event {
+                SchedulerEvent::AllDone { success: s } => {
+                    success = s;
+                    true
+                }
+                _ => false,
+            };
+            recorded.push((Instant::now(), event));
+            if done {
+                break;
+            }
+        }
+
+        let trace = render(&self.target, origin, &recorded);
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = std::fs::File::create(&self.path)?;
+        serde_json::to_writer(&mut file, &trace)?;
+        file.write_all(b"\n")?;
+        Ok(success)
+    }
+}
+
+/// A started beam, on its slot.
+struct BeamSpan {
+    name: String,
+    tid: usize,
+    start: Instant,
+    end: Option<Instant>,
+    status: Option<BeamStatus>,
+    phases: Vec<(BeamPhase, Instant, Instant)>,
+}
+
+/// Renders `recorded` (events with their arrival time, in order) as a Trace
+/// Event Format document. Times are microseconds since the earliest instant
+/// seen, so no event lands before zero.
+pub fn render(target: &str, origin: Instant, recorded: &[(Instant, SchedulerEvent)]) -> Value {
+    let origin = recorded
+        .iter()
+        .filter_map(|(_, event)| match event {
+            SchedulerEvent::BeamPhase { start, .. } => Some(*start),
+            _ => None,
+        })
+        .fold(origin, Instant::min);
+    let ts = |t: Instant| t.saturating_duration_since(origin).as_micros() as u64;
+
+    let mut spans: Vec<BeamSpan> = vec![];
+    let mut running: HashMap<&str, usize> = HashMap::new();
+    let mut busy: Vec<bool> = vec![];
+    let mut events = vec![];
+    let instant = |name: &str, cat: &str, at: Instant, tid: usize, args: Value| {
+        json!({
+            "name": name, "cat": cat, "ph": "i", "s": "t",
+            "ts": ts(at), "pid": PID, "tid": tid, "args": args,
+        })
+    };
+
+    for (at, event) in recorded {
+        // The track of an event about a running beam: its slot.
+        let tid = match event {
+            SchedulerEvent::BeamStopping { name }
+            | SchedulerEvent::BeamOutput { name, .. }
+            | SchedulerEvent::Warning { name, .. } => running
+                .get(name.as_str())
+                .map_or(SCHEDULER_TID, |&i| spans[i].tid),
+            _ => SCHEDULER_TID,
+        };
+        match event {
+            SchedulerEvent::BeamStarted { name } => {
+                let slot = busy.iter().position(|b| !b).unwrap_or_else(|| {
+                    busy.push(false);
+                    busy.len() - 1
+                });
+                busy[slot] = true;
+                running.insert(name.as_str(), spans.len());
+                spans.push(BeamSpan {
+                    name: name.clone(),
+                    tid: slot + 1,
+                    start: *at,
+                    end: None,
+                    status: None,
+                    phases: vec![],
+                });
+            }
+            SchedulerEvent::BeamPhase {
+                name,
+                phase,
+                start,
+                end,
+            } => {
+                if let Some(&i) = running.get(name.as_str()) {
+                    spans[i].phases.push((*phase, *start, *end));
+                }
+            }
+            SchedulerEvent::BeamCompleted { name, status } => match running.remove(name.as_str()) {
+                Some(i) => {
+                    spans[i].end = Some(*at);
+                    spans[i].status = Some(status.clone());
+                    busy[spans[i].tid - 1] = false;
+                }
+                None => events.push(instant(
+                    name,
+                    "beam",
+                    *at,
+                    SCHEDULER_TID,
+                    status_args(status),
+                )),
+            },
+            SchedulerEvent::BeamStopping { name } => events.push(instant(
+                "stopping",
+                "beam",
+                *at,
+                tid,
+                json!({ "beam": name }),
+            )),
+            SchedulerEvent::BeamOutput {
+                name,
+                line,
+                is_stderr,
+            } => events.push(instant(
+                "output",
+                "output",
+                *at,
+                tid,
+                json!({
+                    "beam": name,
+                    "stream": if *is_stderr { "stderr" } else { "stdout" },
+                    "line": plain_text(line),
+                }),
+            )),
+            SchedulerEvent::Warning { name, message } => events.push(instant(
+                "warning",
+                "warning",
+                *at,
+                tid,
+                json!({ "beam": name, "message": message }),
+            )),
+            SchedulerEvent::AllDone { success } => events.push(json!({
+                "name": "run completed", "cat": "run", "ph": "i", "s": "g",
+                "ts": ts(*at), "pid": PID, "tid": SCHEDULER_TID,
+                "args": { "success": success },
+            })),
+        }
+    }
+
+    // Spans last, widened to their phases: `BeamStarted` arrives a moment
+    // after the scheduler sent it, and a viewer only nests a phase whose
+    // bounds fall inside its beam's.
+    let last = recorded.last().map(|(at, _)| *at).unwrap_or(origin);
+    for span in &spans {
+        let start = span
+            .phases
+            .iter()
+            .map(|(_, s, _)| *s)
+            .fold(span.start, Instant::min);
+        let end = span
+            .phases
+            .iter()
+            .map(|(_, _, e)| *e)
+            .fold(span.end.unwrap_or(last), Instant::max);
+        let args = span
+            .status
+            .as_ref()
+            .map(status_args)
+            .unwrap_or_else(|| json!({ "status": "unfinished" }));
+        events.push(complete(
+            &span.name,
+            "beam",
+            ts(start),
+            ts(end),
+            span.tid,
+            args,
+        ));
+        for (phase, s, e) in &span.phases {
+            events.push(complete(
+                phase.label(),
+                "phase",
+                ts(*s),
+                ts(*e),
+                span.tid,
+                json!({ "beam": span.name }),
+            ));
+        }
+    }
+
+    let mut metadata = vec![json!({
+        "name": "process_name", "ph": "M", "pid": PID,
+        "args": { "name": format!("aurora {target}") },
+    })];
+    metadata.extend(thread_name(SCHEDULER_TID, "scheduler"));
+    for tid in 1..=busy.len() {
+        metadata.extend(thread_name(tid, &format!("slot {tid}")));
+    }
+    metadata.extend(events);
+    json!({ "traceEvents": metadata, "displayTimeUnit": "ms" })
+}
+
+/// A span (`"X"` complete event) from `start` to `end`, in microseconds.
+fn complete(name: &str, cat: &str, start: u64, end: u64, tid: usize, args: Value) -> Value {
+    json!({
+        "name": name, "cat": cat, "ph": "X",
+        "ts": start, "dur": end.saturating_sub(start),
+        "pid": PID, "tid": tid, "args": args,
+    })
+}
+
+/// Names a track, and keeps the tracks in slot order.
+fn thread_name(tid: usize, name: &str) -> [Value; 2] {
+    [
+        json!({ "name": "thread_name", "ph": "M", "pid": PID, "tid": tid, "args": { "name": name } }),
+        json!({ "name": "thread_sort_index", "ph": "M", "pid": PID, "tid": tid, "args": { "sort_index": tid } }),
+    ]
+}
+
+fn status_args(status: &BeamStatus) -> Value {
+    match status {
+        BeamStatus::Success { cached: true, .. }
+        | BeamStatus::Skipped {
+            reason: SkipReason::Cached,
+        } => json!({ "status": "cached" }),
+        BeamStatus::Success { .. } => json!({ "status": "success" }),
+        BeamStatus::Skipped { .. } => json!({ "status": "skipped" }),
+        BeamStatus::Failed { exit_code, .. } => {
+            json!({ "status": "failed", "exit_code": exit_code })
+        }
+        BeamStatus::FailedAllowed { exit_code, .. } => {
+            json!({ "status": "failed_allowed", "exit_code": exit_code })
+        }
+        BeamStatus::Cancelled => json!({ "status": "cancelled" }),
+        BeamStatus::Pending | BeamStatus::Running | BeamStatus::Stopping => {
+            json!({ "status": "unfinished" })
+        }
+    }
+}
@@ -202,6 +202,20 @@ pub async fn debounce_loop(
 /// only warns; the overlap can be intentional and the cache usually stabilizes
 /// the loop on the second cycle.
 pub fn detect_output_input_overlap(beams: &[Beam], closure: &HashSet<String>) -> Vec<String> {
+    output_input_overlaps(beams, closure)
+        .into_iter()
+        .map(|(beam, output)| {
+            format!(
+                "beam '{beam}' output '{output}' matches a watched input; the watch may re-trigger itself"
+            )
+        })
+        .collect()
+}
+
+/// The `(beam, output)` pairs behind [`detect_output_input_overlap`]: each
+/// output of a closure beam that an `inputs` glob of the closure matches.
+/// `aurora lint` reports the same pairs over every beam.
+pub fn output_input_overlaps(beams: &[Beam], closure: &HashSet<String>) -> Vec<(String, String)> {
     let patterns: Vec<glob::Pattern> = beams
         .iter()
         .filter(|b| closure.contains(&b.name))
@@ -209,18 +223,15 @@ pub fn detect_output_input_overlap(beams: &[Beam], closure: &HashSet<String>) ->
         .filter_map(|p| glob::Pattern::new(p).ok())
         .collect();
 
-    let mut warnings = Vec::new();
+    let mut overlaps = Vec::new();
     for b in beams.iter().filter(|b| closure.contains(&b.name)) {
         for output in &b.outputs {
             if patterns.iter().any(|p| p.matches(output)) {
-                warnings.push(format!(
-                    "beam '{}' output '{}' matches a watched input; the watch may re-trigger itself",
-                    b.name, output
-                ));
+                overlaps.push((b.name.clone(), output.clone()));
             }
         }
     }
-    warnings
+    overlaps
 }
 
 /// The advisory warnings to surface once a watch is armed for `target`: the
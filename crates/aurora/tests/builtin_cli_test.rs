@@ -0,0 +1,91 @@
+//! A beam named like a subcommand runs instead of it, and the subcommand stays
+//! reachable under `aurora builtin`.
+
+use std::fs;
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+const BEAMFILE: &str = r#"
+beam "fmt" {
+    run { commands = ["echo formatting the code"] }
+}
+
+beam "lint" {
+  param "level" { default = "normal" }
+  run { commands = ["echo linting ${param.level}"] }
+}
+"#;
+
+fn project() -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("Beamfile"), BEAMFILE).unwrap();
+    dir
+}
+
+fn aurora(dir: &TempDir, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .env_remove("GITHUB_ACTIONS")
+        .args(args)
+        .current_dir(dir.path())
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn a_declared_beam_wins_over_the_subcommand() {
+    let dir = project();
+    let output = aurora(&dir, &["--no-tui", "fmt"]);
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("formatting the code"), "{stdout}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("the `fmt` subcommand is `aurora builtin fmt`"),
+        "{stderr}"
+    );
+    assert_eq!(
+        fs::read_to_string(dir.path().join("Beamfile")).unwrap(),
+        BEAMFILE,
+        "the formatter did not run"
+    );
+
+    // The beam's arguments bind as they did before `lint` was a subcommand.
+    let output = aurora(&dir, &["--no-tui", "lint", "strict"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("linting strict"), "{stdout}");
+}
+
+#[test]
+fn json_runs_the_beam_without_the_hint() {
+    let dir = project();
+    let output = aurora(&dir, &["--json", "fmt"]);
+    assert!(output.status.success(), "{output:?}");
+    assert!(output.stderr.is_empty(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("formatting the code"), "{stdout}");
+}
+
+#[test]
+fn builtin_reaches_a_shadowed_subcommand() {
+    let dir = project();
+    let output = aurora(&dir, &["builtin", "fmt", "--check"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("is not formatted"), "{stdout}");
+
+    let output = aurora(&dir, &["builtin", "fmt"]);
+    assert!(output.status.success(), "{output:?}");
+    assert!(fs::read_to_string(dir.path().join("Beamfile"))
+        .unwrap()
+        .contains("  run { commands = [\"echo formatting the code\"] }"));
+}
+
+#[test]
+fn a_subcommand_no_beam_shadows_runs_as_usual() {
+    let dir = project();
+    let output = aurora(&dir, &["inspect", "lint"]);
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("beam \"lint\" {"), "{stdout}");
+    assert!(output.stderr.is_empty(), "{output:?}");
+}
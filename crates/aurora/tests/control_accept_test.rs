@@ -0,0 +1,67 @@
+//! The control socket out of file descriptors. A test binary of its own: the
+//! limit it lowers is the whole process's.
+
+use aurora::control::Control;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::time::Duration;
+
+/// CPU time this process has used so far, user and system.
+fn cpu_time() -> Duration {
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    assert_eq!(unsafe { libc::getrusage(libc::RUSAGE_SELF, &mut usage) }, 0);
+    let time = |t: libc::timeval| {
+        Duration::from_secs(t.tv_sec as u64) + Duration::from_micros(t.tv_usec as u64)
+    };
+    time(usage.ru_utime) + time(usage.ru_stime)
+}
+
+fn set_fd_limit(limit: &libc::rlimit) {
+    assert_eq!(unsafe { libc::setrlimit(libc::RLIMIT_NOFILE, limit) }, 0);
+}
+
+#[test]
+fn the_server_backs_off_while_out_of_descriptors_and_recovers() {
+    let dir = tempfile::tempdir().unwrap();
+    let socket = dir.path().join("aurora.sock");
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let _guard = runtime.block_on(async { Control::new().serve(&socket).unwrap() });
+
+    // Every descriptor under the limit in use but `spare`, which the client
+    // takes: the server has none left to accept it with.
+    let mut original: libc::rlimit = unsafe { std::mem::zeroed() };
+    assert_eq!(
+        unsafe { libc::getrlimit(libc::RLIMIT_NOFILE, &mut original) },
+        0
+    );
+    let spare = unsafe { libc::dup(2) };
+    let next = unsafe { libc::dup(2) };
+    assert!(spare >= 0 && next > spare);
+    unsafe { libc::close(next) };
+    set_fd_limit(&libc::rlimit {
+        rlim_cur: next as libc::rlim_t,
+        rlim_max: original.rlim_max,
+    });
+    unsafe { libc::close(spare) };
+    let client = UnixStream::connect(&socket);
+    std::thread::sleep(Duration::from_millis(100));
+    let before = cpu_time();
+    std::thread::sleep(Duration::from_millis(500));
+    let spent = cpu_time() - before;
+    set_fd_limit(&original);
+
+    let mut client = client.unwrap();
+    assert!(
+        spent < Duration::from_millis(150),
+        "the accept loop spun: {spent:?} of CPU in 500ms"
+    );
+    client
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    client
+        .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"status\"}\n")
+        .unwrap();
+    let mut answer = String::new();
+    BufReader::new(&client).read_line(&mut answer).unwrap();
+    assert!(answer.contains("\"id\":1"), "{answer}");
+}
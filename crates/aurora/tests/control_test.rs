@@ -0,0 +1,248 @@
+#![cfg(unix)]
+
+use aurora::control::{ctl_command, Control};
+use aurora_core::events::{BeamStatus, CancelRequest, RerunRequest, SchedulerEvent};
+use std::fs;
+use std::process::Command;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+async fn ctl(socket: &std::path::Path, method: &str, beam: Option<&str>) -> Result<String, String> {
+    let mut out = vec![];
+    ctl_command(socket, method, beam, &mut out)
+        .await
+        .map(|()| String::from_utf8(out).unwrap())
+        .map_err(|e| e.to_string())
+}
+
+fn started(name: &str) -> SchedulerEvent {
+    SchedulerEvent::BeamStarted { name: name.into() }
+}
+
+fn passed(name: &str) -> SchedulerEvent {
+    SchedulerEvent::BeamCompleted {
+        name: name.into(),
+        status: BeamStatus::Success {
+            duration: Duration::from_millis(1500),
+            cached: false,
+        },
+    }
+}
+
+/// Sends `events` through the observed run and waits until they are seen.
+async fn feed(
+    tx: &mpsc::Sender<SchedulerEvent>,
+    rx: &mut mpsc::Receiver<SchedulerEvent>,
+    events: Vec<SchedulerEvent>,
+) {
+    for event in events {
+        tx.send(event).await.unwrap();
+        rx.recv().await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn status_and_cancel_reach_the_observed_run() {
+    let dir = tempfile::tempdir().unwrap();
+    let socket = dir.path().join(".aurora/aurora.sock");
+    let control = Control::new();
+    let guard = control.serve(&socket).unwrap();
+
+    let (tx, rx) = mpsc::channel(16);
+    let (cancel_tx, mut cancel_rx) = mpsc::unbounded_channel();
+    let names = ["build".to_string(), "test".to_string()];
+    let mut rx = control.observe("test", &names, rx, cancel_tx);
+    feed(
+        &tx,
+        &mut rx,
+        vec![started("build"), passed("build"), started("test")],
+    )
+    .await;
+
+    let status = ctl(&socket, "status", None).await.unwrap();
+    assert_eq!(
+        status,
+        "run 1  target test  running\n  build  passed 1.5s\n  test   running\n"
+    );
+
+    ctl(&socket, "cancel", Some("test")).await.unwrap();
+    assert_eq!(
+        cancel_rx.recv().await,
+        Some(CancelRequest::Beam("test".into()))
+    );
+    let err = ctl(&socket, "cancel", Some("build")).await.unwrap_err();
+    assert_eq!(err, "`build` has already completed");
+    let err = ctl(&socket, "cancel", Some("lint")).await.unwrap_err();
+    assert_eq!(err, "`lint` is not part of the run");
+    let err = ctl(&socket, "deploy", None).await.unwrap_err();
+    assert_eq!(err, "unknown method `deploy`");
+
+    ctl(&socket, "cancel", None).await.unwrap();
+    assert_eq!(cancel_rx.recv().await, Some(CancelRequest::Run));
+
+    feed(
+        &tx,
+        &mut rx,
+        vec![SchedulerEvent::AllDone { success: false }],
+    )
+    .await;
+    let err = ctl(&socket, "cancel", None).await.unwrap_err();
+    assert_eq!(err, "the run is over");
+
+    drop(guard);
+    assert!(!socket.exists(), "the guard removes the socket");
+    let err = ctl(&socket, "status", None).await.unwrap_err();
+    assert!(
+        err.starts_with("no Aurora run is in progress here"),
+        "{err}"
+    );
+}
+
+#[tokio::test]
+async fn tail_streams_a_beam_until_it_completes() {
+    let dir = tempfile::tempdir().unwrap();
+    let socket = dir.path().join("aurora.sock");
+    let control = Control::new();
+    let _guard = control.serve(&socket).unwrap();
+
+    let (tx, rx) = mpsc::channel(16);
+    let (cancel_tx, _cancel_rx) = mpsc::unbounded_channel();
+    let names = ["build".to_string(), "test".to_string()];
+    let mut rx = control.observe("test", &names, rx, cancel_tx);
+    feed(&tx, &mut rx, vec![started("build")]).await;
+
+    let tailing = {
+        let socket = socket.clone();
+        tokio::spawn(async move { ctl(&socket, "tail", Some("build")).await })
+    };
+    // Let the client subscribe before the beam prints.
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let output = |name: &str, line: &str| SchedulerEvent::BeamOutput {
+        name: name.into(),
+        line: line.into(),
+        is_stderr: false,
+    };
+    feed(
+        &tx,
+        &mut rx,
+        vec![
+            output("build", "\x1b[32mcompiling\x1b[0m"),
+            output("test", "not tailed"),
+            passed("build"),
+            output("build", "after the end"),
+        ],
+    )
+    .await;
+
+    let out = tokio::time::timeout(Duration::from_secs(10), tailing)
+        .await
+        .expect("tail must stop when the beam completes")
+        .unwrap()
+        .unwrap();
+    assert_eq!(out, "[build] compiling\n[build] passed 1.5s\n");
+
+    let err = ctl(&socket, "tail", Some("build")).await.unwrap_err();
+    assert_eq!(
+        err,
+        "`build` has already completed; see `aurora logs build`"
+    );
+}
+
+#[tokio::test]
+async fn rerun_waits_for_the_run_to_be_over_and_needs_a_front_end() {
+    let dir = tempfile::tempdir().unwrap();
+    let socket = dir.path().join("aurora.sock");
+    let (tx, rx) = mpsc::channel(16);
+    let (cancel_tx, _cancel_rx) = mpsc::unbounded_channel();
+    let names = ["build".to_string()];
+
+    {
+        let control = Control::new();
+        let _guard = control.serve(&socket).unwrap();
+        let err = ctl(&socket, "rerun", None).await.unwrap_err();
+        assert!(err.contains("rerun needs the TUI or --watch"), "{err}");
+    }
+
+    let (rerun_tx, mut rerun_rx) = mpsc::unbounded_channel::<RerunRequest>();
+    let control = Control::new().with_rerun(rerun_tx);
+    let _guard = control.serve(&socket).unwrap();
+    let mut rx = control.observe("build", &names, rx, cancel_tx);
+    feed(&tx, &mut rx, vec![started("build")]).await;
+    let err = ctl(&socket, "rerun", None).await.unwrap_err();
+    assert!(err.starts_with("a run is in progress"), "{err}");
+
+    feed(
+        &tx,
+        &mut rx,
+        vec![passed("build"), SchedulerEvent::AllDone { success: true }],
+    )
+    .await;
+    let front_end = tokio::spawn(async move {
+        let request = rerun_rx.recv().await.unwrap();
+        let beam = request.beam.clone();
+        request.reply.send(Err("no such beam".into())).unwrap();
+        beam
+    });
+    let err = ctl(&socket, "rerun", Some("lint")).await.unwrap_err();
+    assert_eq!(err, "no such beam");
+    assert_eq!(front_end.await.unwrap().as_deref(), Some("lint"));
+}
+
+#[test]
+fn a_second_aurora_does_not_take_over_a_live_socket() {
+    let dir = tempfile::tempdir().unwrap();
+    let socket = dir.path().join("aurora.sock");
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let _guard = runtime.block_on(async {
+        // A socket file left by an Aurora that died is replaced.
+        drop(std::os::unix::net::UnixListener::bind(&socket).unwrap());
+        Control::new().serve(&socket).unwrap()
+    });
+    let err = runtime
+        .block_on(async { Control::new().serve(&socket).map(|_| ()) })
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::AddrInUse);
+}
+
+#[test]
+fn aurora_ctl_cancels_a_headless_run() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("Beamfile"),
+        r#"beam "slow" { run { commands = ["sleep 30"] } }"#,
+    )
+    .unwrap();
+    let aurora = || {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_aurora"));
+        command.env_remove("GITHUB_ACTIONS").current_dir(dir.path());
+        command
+    };
+
+    let mut run = aurora()
+        .args(["slow", "--no-tui"])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    let deadline = Instant::now() + Duration::from_secs(20);
+    let status = loop {
+        let status = aurora().args(["ctl", "status"]).output().unwrap();
+        let stdout = String::from_utf8_lossy(&status.stdout).into_owned();
+        if stdout.contains("slow  running") || Instant::now() > deadline {
+            break stdout;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    };
+    assert!(
+        status.starts_with("run 1  target slow  running\n"),
+        "{status}"
+    );
+
+    let cancel = aurora().args(["ctl", "cancel"]).output().unwrap();
+    assert!(cancel.status.success(), "{cancel:?}");
+    let started = Instant::now();
+    let exit = run.wait().unwrap();
+    assert!(started.elapsed() < Duration::from_secs(15), "not cancelled");
+    assert!(!exit.success());
+    assert!(!dir.path().join(".aurora/aurora.sock").exists());
+}
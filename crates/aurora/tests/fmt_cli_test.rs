@@ -0,0 +1,75 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+const MESSY: &str = "beam \"build\" {\n    run { commands = [\"make\"] }\n  description = \"Build\" # shown in --list\n}\n";
+const FORMATTED: &str =
+    "beam \"build\" {\n  description = \"Build\"  # shown in --list\n  run { commands = [\"make\"] }\n}\n";
+
+fn aurora(dir: &Path, args: &[&str]) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_aurora"));
+    command
+        .env_remove("GITHUB_ACTIONS")
+        .current_dir(dir)
+        .arg("fmt")
+        .args(args);
+    command
+}
+
+#[test]
+fn check_reports_an_unformatted_beamfile_and_fmt_rewrites_it() {
+    let dir = tempfile::tempdir().unwrap();
+    let beamfile = dir.path().join("Beamfile");
+    fs::write(&beamfile, MESSY).unwrap();
+
+    let check = aurora(dir.path(), &["--check"]).output().unwrap();
+    assert_eq!(check.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&check.stdout).contains("is not formatted"));
+    assert_eq!(
+        fs::read_to_string(&beamfile).unwrap(),
+        MESSY,
+        "--check changes nothing"
+    );
+
+    let fmt = aurora(dir.path(), &[]).output().unwrap();
+    assert!(fmt.status.success(), "{fmt:?}");
+    assert_eq!(fs::read_to_string(&beamfile).unwrap(), FORMATTED);
+
+    let check = aurora(dir.path(), &["--check"]).output().unwrap();
+    assert!(check.status.success(), "{check:?}");
+    assert!(check.stdout.is_empty());
+}
+
+#[test]
+fn a_dash_formats_stdin_to_stdout() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut child = aurora(dir.path(), &["-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(MESSY.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(String::from_utf8_lossy(&output.stdout), FORMATTED);
+}
+
+#[test]
+fn a_beamfile_that_does_not_parse_is_left_alone() {
+    let dir = tempfile::tempdir().unwrap();
+    let beamfile = dir.path().join("Beamfile");
+    fs::write(&beamfile, "beam \"a\" { inputs = [ }").unwrap();
+    let fmt = aurora(dir.path(), &[]).output().unwrap();
+    assert!(!fmt.status.success());
+    assert!(String::from_utf8_lossy(&fmt.stderr).contains("cannot format"));
+    assert_eq!(
+        fs::read_to_string(&beamfile).unwrap(),
+        "beam \"a\" { inputs = [ }"
+    );
+}
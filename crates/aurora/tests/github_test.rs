@@ -0,0 +1,240 @@
+use aurora::github::{github_actions_enabled, step_summary, GithubReporter};
+use aurora::reporter::Reporter;
+use aurora_core::events::{BeamStatus, SchedulerEvent, SkipReason};
+use std::process::Command;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+fn output(name: &str, line: &str, is_stderr: bool) -> SchedulerEvent {
+    SchedulerEvent::BeamOutput {
+        name: name.to_string(),
+        line: line.to_string(),
+        is_stderr,
+    }
+}
+
+fn completed(name: &str, status: BeamStatus) -> SchedulerEvent {
+    SchedulerEvent::BeamCompleted {
+        name: name.to_string(),
+        status,
+    }
+}
+
+async fn render(events: Vec<SchedulerEvent>, secrets: Vec<String>) -> (bool, String) {
+    let (tx, rx) = mpsc::channel(64);
+    for event in events {
+        tx.send(event).await.unwrap();
+    }
+    drop(tx);
+    let mut out = Vec::new();
+    let names = vec!["build".to_string(), "test".to_string()];
+    let success = GithubReporter::new("ci".to_string(), names, &mut out)
+        .with_secrets(secrets)
+        .run(rx)
+        .await
+        .unwrap();
+    (success, String::from_utf8(out).unwrap())
+}
+
+#[test]
+fn detection_follows_the_runner_unless_overridden() {
+    assert!(github_actions_enabled("auto", Some("true"), false));
+    assert!(!github_actions_enabled("auto", None, false));
+    assert!(
+        !github_actions_enabled("auto", Some("true"), true),
+        "-i wins"
+    );
+    assert!(!github_actions_enabled("off", Some("true"), false));
+    assert!(github_actions_enabled("github", None, false));
+}
+
+#[tokio::test]
+async fn each_beam_is_a_group_in_completion_order() {
+    let (success, out) = render(
+        vec![
+            output("test", "running 3 tests", false),
+            output("build", "compiling", false),
+            output("test", "panicked", true),
+            completed(
+                "build",
+                BeamStatus::Success {
+                    duration: Duration::from_millis(4200),
+                    cached: false,
+                },
+            ),
+            completed(
+                "test",
+                BeamStatus::Failed {
+                    exit_code: 101,
+                    duration: Duration::from_millis(1800),
+                },
+            ),
+            SchedulerEvent::AllDone { success: false },
+        ],
+        vec![],
+    )
+    .await;
+
+    assert!(!success);
+    let build = out.find("::group::[PASS] build  4.2s\ncompiling\n::endgroup::\n");
+    let test =
+        out.find("::group::[FAIL] test  exit 101 1.8s\nrunning 3 tests\npanicked\n::endgroup::\n");
+    assert!(build.is_some() && test.is_some(), "{out}");
+    assert!(build < test, "{out}");
+    assert!(
+        out.contains("::error title=aurora%3A test::test failed with exit code 101%0Apanicked\n"),
+        "{out}"
+    );
+    assert!(out.contains("Done: 1 ok, 1 failed"), "{out}");
+}
+
+#[tokio::test]
+async fn secrets_are_masked_before_any_output() {
+    let (_, out) = render(
+        vec![
+            output("build", "token is s3cr%t", false),
+            SchedulerEvent::AllDone { success: true },
+        ],
+        vec!["s3cr%t".to_string()],
+    )
+    .await;
+    assert!(out.starts_with("::add-mask::s3cr%25t\n"), "{out}");
+}
+
+#[tokio::test]
+async fn warnings_and_allowed_failures_are_warning_annotations() {
+    let (_, out) = render(
+        vec![
+            SchedulerEvent::Warning {
+                name: "build".to_string(),
+                message: "input pattern matched no files: src/**".to_string(),
+            },
+            completed(
+                "test",
+                BeamStatus::FailedAllowed {
+                    exit_code: 1,
+                    duration: Duration::ZERO,
+                },
+            ),
+            SchedulerEvent::AllDone { success: true },
+        ],
+        vec![],
+    )
+    .await;
+    assert!(
+        out.contains("::warning title=aurora%3A build::input pattern matched no files: src/**\n")
+    );
+    assert!(
+        out.contains("::warning title=aurora%3A test::test failed with exit code 1 (allowed)\n")
+    );
+    // No output, no group.
+    assert!(!out.contains("::group::"), "{out}");
+}
+
+#[test]
+fn step_summary_is_a_markdown_table() {
+    let md = step_summary(
+        "ci",
+        &[
+            (
+                "build".to_string(),
+                BeamStatus::Success {
+                    duration: Duration::from_millis(4200),
+                    cached: false,
+                },
+            ),
+            (
+                "fmt".to_string(),
+                BeamStatus::Skipped {
+                    reason: SkipReason::Cached,
+                },
+            ),
+            (
+                "test[a|b]".to_string(),
+                BeamStatus::Failed {
+                    exit_code: 2,
+                    duration: Duration::from_millis(500),
+                },
+            ),
+        ],
+    );
+    assert!(md.starts_with("### aurora ci\n\n| Beam | Status | Duration |\n| --- | --- | --- |\n"));
+    assert!(md.contains("| `build` | ✅ passed | 4.2s |\n"), "{md}");
+    assert!(md.contains("| `fmt` | ✅ cached |  |\n"), "{md}");
+    assert!(
+        md.contains("| `test[a\\|b]` | ❌ failed (exit 2) | 0.5s |\n"),
+        "{md}"
+    );
+    assert!(md.contains("**2 ok, 1 failed**"), "{md}");
+}
+
+const BEAMFILE: &str = r#"
+aurora { version = "1" }
+variable "token" {
+  default   = "hunter2"
+  sensitive = true
+}
+beam "ok" { run { commands = ["echo hello"] } }
+"#;
+
+#[test]
+fn github_actions_is_detected_and_writes_the_step_summary() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("Beamfile"), BEAMFILE).unwrap();
+    let summary = dir.path().join("summary.md");
+    let output = Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .args(["ok", "--var", "token=s3cret"])
+        .env("GITHUB_ACTIONS", "true")
+        .env("GITHUB_STEP_SUMMARY", &summary)
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("::add-mask::s3cret\n"), "{stdout}");
+    assert!(stdout.contains("::group::[PASS] ok"), "{stdout}");
+    let md = std::fs::read_to_string(summary).unwrap();
+    assert!(md.contains("| `ok` | ✅ passed |"), "{md}");
+}
+
+#[test]
+fn a_sensitive_param_is_masked_as_bound() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("Beamfile"),
+        r#"beam "push" {
+  param "key" { sensitive = true }
+  run { commands = ["echo pushed"] }
+}
+"#,
+    )
+    .unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .args(["push", "k3y"])
+        .env("GITHUB_ACTIONS", "true")
+        .env_remove("GITHUB_STEP_SUMMARY")
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("::add-mask::k3y\n"), "{stdout}");
+}
+
+#[test]
+fn ci_off_keeps_the_plain_output() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("Beamfile"), BEAMFILE).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .args(["ok", "--ci", "off"])
+        .env("GITHUB_ACTIONS", "true")
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("::"), "{stdout}");
+    assert!(stdout.contains("[ok] hello"), "{stdout}");
+}
@@ -31,6 +31,7 @@ beam "boom" {
 fn passing_beam_streams_prefixed_output_and_exits_zero() {
     let dir = fixture_dir(BEAMFILE);
     let output = Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .env_remove("GITHUB_ACTIONS")
         .args(["ok", "--no-tui"])
         .current_dir(dir.path())
         .output()
@@ -60,6 +61,7 @@ beam "qa"   { depends_on = ["lint", "test"] }
 "#;
     let dir = fixture_dir(beamfile);
     let output = Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .env_remove("GITHUB_ACTIONS")
         .args(["qa", "--dry-run"])
         .current_dir(dir.path())
         .output()
@@ -84,6 +86,7 @@ fn warns_when_beamfile_comes_from_parent_directory() {
     fs::create_dir_all(&sub).unwrap();
 
     let output = Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .env_remove("GITHUB_ACTIONS")
         .args(["ok", "--no-tui"])
         .current_dir(&sub)
         .output()
@@ -117,6 +120,7 @@ beam "broken" {
 fn unknown_dependency_exits_one() {
     let dir = fixture_dir(BROKEN_BEAMFILE);
     let output = Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .env_remove("GITHUB_ACTIONS")
         .args(["broken", "--no-tui"])
         .current_dir(dir.path())
         .output()
@@ -151,6 +155,7 @@ beam "b" {
 fn dependency_cycle_exits_one() {
     let dir = fixture_dir(CYCLIC_BEAMFILE);
     let output = Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .env_remove("GITHUB_ACTIONS")
         .args(["a", "--no-tui"])
         .current_dir(dir.path())
         .output()
@@ -168,6 +173,7 @@ fn dependency_cycle_exits_one() {
 fn failing_beam_exits_one() {
     let dir = fixture_dir(BEAMFILE);
     let output = Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .env_remove("GITHUB_ACTIONS")
         .args(["boom", "--no-tui"])
         .current_dir(dir.path())
         .output()
@@ -208,6 +214,7 @@ fn cacheable_dir() -> TempDir {
 fn no_cache_writes_no_cache_directory() {
     let dir = cacheable_dir();
     let output = Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .env_remove("GITHUB_ACTIONS")
         .args(["cached", "--no-tui", "--no-cache"])
         .current_dir(dir.path())
         .output()
@@ -223,6 +230,7 @@ fn no_cache_writes_no_cache_directory() {
 fn default_run_writes_cache_directory() {
     let dir = cacheable_dir();
     let output = Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .env_remove("GITHUB_ACTIONS")
         .args(["cached", "--no-tui"])
         .current_dir(dir.path())
         .output()
@@ -233,3 +241,59 @@ fn default_run_writes_cache_directory() {
         "a normal run persists a cache directory (proving --no-cache matters)"
     );
 }
+
+#[test]
+fn grouped_output_prints_each_beam_in_one_block() {
+    let beamfile = r#"
+beam "a" { run { commands = ["echo a1", "echo a2"] } }
+beam "b" { run { commands = ["echo b1"] } }
+beam "all" { depends_on = ["a", "b"] }
+"#;
+    let dir = fixture_dir(beamfile);
+    let output = Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .env_remove("GITHUB_ACTIONS")
+        .args(["all", "--output", "grouped"])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout:\n{stdout}");
+    assert!(stdout.contains("a1\na2\n"), "one block:\n{stdout}");
+    assert!(stdout.contains("b1\n"), "{stdout}");
+    assert!(!stdout.contains("[a]"), "no per-line prefix:\n{stdout}");
+}
+
+#[test]
+fn an_explicit_output_wins_over_github_actions_detection() {
+    let beamfile = r#"
+beam "a" { run { commands = ["echo a1", "echo a2"] } }
+beam "all" { depends_on = ["a"] }
+"#;
+    let dir = fixture_dir(beamfile);
+    let output = Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .env("GITHUB_ACTIONS", "true")
+        .env_remove("GITHUB_STEP_SUMMARY")
+        .args(["all", "--output", "grouped"])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout:\n{stdout}");
+    assert!(stdout.contains("a1\na2\n"), "one block:\n{stdout}");
+    assert!(!stdout.contains("::group::"), "{stdout}");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .env("GITHUB_ACTIONS", "true")
+        .args(["all", "--ci", "github", "--output", "failed-only"])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("--output cannot be used with --ci github"),
+        "{stderr}"
+    );
+}
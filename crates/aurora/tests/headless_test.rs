@@ -1,4 +1,4 @@
-use aurora::headless::HeadlessReporter;
+use aurora::headless::{HeadlessReporter, OutputMode};
 use aurora::reporter::Reporter;
 use aurora_core::scheduler::{BeamStatus, SchedulerEvent, SkipReason};
 use std::time::Duration;
@@ -230,3 +230,200 @@ async fn warning_is_prefixed_and_routed_to_stderr() {
         "warning must not leak to stdout:\n{out}"
     );
 }
+
+#[tokio::test]
+async fn terminal_codes_are_resolved_to_plain_text() {
+    let (tx, rx) = mpsc::channel(16);
+    let beams = vec!["build".to_string()];
+
+    // What a `tty = true` beam writes: colours and a `\r`-redrawn progress bar.
+    tx.send(SchedulerEvent::BeamOutput {
+        name: "build".into(),
+        line: "\x1b[32mCompiling\x1b[0m 10%\r\x1b[32mCompiling\x1b[0m 99%".into(),
+        is_stderr: false,
+    })
+    .await
+    .unwrap();
+    tx.send(SchedulerEvent::AllDone { success: true })
+        .await
+        .unwrap();
+    drop(tx);
+
+    let mut out: Vec<u8> = Vec::new();
+    let mut err: Vec<u8> = Vec::new();
+    HeadlessReporter::new(beams, false, false, &mut out, &mut err)
+        .with_tty_beams(["build".to_string()])
+        .run(rx)
+        .await
+        .unwrap();
+    let out = String::from_utf8(out).unwrap();
+
+    assert!(
+        out.contains("[build] Compiling 99%\n"),
+        "only the last frame, as plain text:\n{out:?}"
+    );
+    assert!(!out.contains('\x1b') && !out.contains('\r'), "{out:?}");
+}
+
+#[tokio::test]
+async fn a_beam_without_tty_keeps_the_colours_it_forces() {
+    for output in [OutputMode::Interleaved, OutputMode::Grouped] {
+        let (tx, rx) = mpsc::channel(16);
+        // `cargo --color=always` through a pipe.
+        tx.send(SchedulerEvent::BeamOutput {
+            name: "build".into(),
+            line: "\x1b[32mCompiling\x1b[0m app".into(),
+            is_stderr: false,
+        })
+        .await
+        .unwrap();
+        tx.send(SchedulerEvent::BeamCompleted {
+            name: "build".into(),
+            status: BeamStatus::Success {
+                duration: Duration::from_millis(10),
+                cached: false,
+            },
+        })
+        .await
+        .unwrap();
+        tx.send(SchedulerEvent::AllDone { success: true })
+            .await
+            .unwrap();
+        drop(tx);
+
+        let mut out: Vec<u8> = Vec::new();
+        let mut err: Vec<u8> = Vec::new();
+        HeadlessReporter::new(vec!["build".to_string()], false, false, &mut out, &mut err)
+            .with_output(output)
+            .run(rx)
+            .await
+            .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("\x1b[32mCompiling\x1b[0m app\n"), "{out:?}");
+    }
+}
+
+/// Two beams whose lines interleave, `build` failing first and `test` passing.
+async fn interleaved_failure_events(tx: mpsc::Sender<SchedulerEvent>) {
+    for name in ["build", "test"] {
+        tx.send(SchedulerEvent::BeamStarted { name: name.into() })
+            .await
+            .unwrap();
+    }
+    for (name, line, is_stderr) in [
+        ("build", "b1", false),
+        ("test", "t1", false),
+        ("build", "b2", true),
+        ("test", "t2", false),
+    ] {
+        tx.send(SchedulerEvent::BeamOutput {
+            name: name.into(),
+            line: line.into(),
+            is_stderr,
+        })
+        .await
+        .unwrap();
+    }
+    tx.send(SchedulerEvent::BeamCompleted {
+        name: "build".into(),
+        status: BeamStatus::Failed {
+            exit_code: 2,
+            duration: Duration::from_millis(100),
+        },
+    })
+    .await
+    .unwrap();
+    tx.send(SchedulerEvent::BeamCompleted {
+        name: "test".into(),
+        status: BeamStatus::Success {
+            duration: Duration::from_millis(200),
+            cached: false,
+        },
+    })
+    .await
+    .unwrap();
+    tx.send(SchedulerEvent::AllDone { success: false })
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn grouped_output_prints_one_block_per_beam_with_failures_last() {
+    let (tx, rx) = mpsc::channel(16);
+    interleaved_failure_events(tx).await;
+
+    let mut out: Vec<u8> = Vec::new();
+    let mut err: Vec<u8> = Vec::new();
+    let beams = vec!["build".to_string(), "test".to_string()];
+    let success = HeadlessReporter::new(beams, false, false, &mut out, &mut err)
+        .with_output(OutputMode::Grouped)
+        .run(rx)
+        .await
+        .unwrap();
+    let out = String::from_utf8(out).unwrap();
+    let err = String::from_utf8(err).unwrap();
+
+    assert!(!success);
+    // `test` completed after `build`, but the failure comes last, in one
+    // piece with its stderr line, right above the recap.
+    assert!(
+        out.starts_with(
+            "[PASS] test  0.2s\nt1\nt2\n\n[FAIL] build  exit 2 0.1s\nb1\nb2\n\n[FAIL] build"
+        ),
+        "blocks:\n{out}"
+    );
+    assert!(out.ends_with("Done: 1 ok, 1 failed\n"), "recap:\n{out}");
+    assert!(err.is_empty(), "no status line without a terminal: {err:?}");
+}
+
+#[tokio::test]
+async fn failed_only_output_prints_only_the_failed_blocks() {
+    let (tx, rx) = mpsc::channel(16);
+    interleaved_failure_events(tx).await;
+
+    let mut out: Vec<u8> = Vec::new();
+    let mut err: Vec<u8> = Vec::new();
+    let beams = vec!["build".to_string(), "test".to_string()];
+    HeadlessReporter::new(beams, false, false, &mut out, &mut err)
+        .with_output(OutputMode::FailedOnly)
+        .run(rx)
+        .await
+        .unwrap();
+    let out = String::from_utf8(out).unwrap();
+
+    assert!(
+        out.starts_with("[FAIL] build  exit 2 0.1s\nb1\nb2\n\n"),
+        "failed block:\n{out}"
+    );
+    assert!(!out.contains("t1") && !out.contains("t2"), "{out}");
+    assert!(
+        out.contains("[PASS] test   0.2s"),
+        "recap still complete:\n{out}"
+    );
+}
+
+#[tokio::test]
+async fn status_line_tracks_running_beams_on_stderr_and_is_erased() {
+    let (tx, rx) = mpsc::channel(16);
+    interleaved_failure_events(tx).await;
+
+    let mut out: Vec<u8> = Vec::new();
+    let mut err: Vec<u8> = Vec::new();
+    let beams = vec!["build".to_string(), "test".to_string()];
+    HeadlessReporter::new(beams, false, false, &mut out, &mut err)
+        .with_output(OutputMode::Grouped)
+        .with_status_line(true)
+        .run(rx)
+        .await
+        .unwrap();
+    let out = String::from_utf8(out).unwrap();
+    let err = String::from_utf8(err).unwrap();
+
+    assert!(err.contains("[0 done] running: build, test"), "{err:?}");
+    assert!(err.contains("[1 done] running: test"), "{err:?}");
+    assert!(err.ends_with("\r\u{1b}[2K"), "erased at the end: {err:?}");
+    assert!(
+        !out.contains("running:"),
+        "status stays off stdout: {out:?}"
+    );
+}
@@ -0,0 +1,100 @@
+//! CLI tests for forwarding ambient variables past the allowlist:
+//! `inherit_env` in the `aurora {}` block and on a beam, `--inherit-env`,
+//! and `--dry-run --verbose`.
+
+use std::fs;
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+const BEAMFILE: &str = r#"
+aurora {
+  inherit_env = ["AURORA_FWD_GLOBAL_*"]
+}
+
+environment {
+  DECLARED = "d"
+}
+
+beam "deploy" {
+  inherit_env = ["AURORA_FWD_BEAM"]
+  inputs      = ["input.txt"]
+  run { commands = ["echo global=$AURORA_FWD_GLOBAL_A beam=$AURORA_FWD_BEAM cli=$AURORA_FWD_CLI"] }
+}
+
+beam "other" {
+  run { commands = ["echo other beam=[$AURORA_FWD_BEAM]"] }
+}
+"#;
+
+fn fixture_dir() -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("Beamfile"), BEAMFILE).unwrap();
+    fs::write(dir.path().join("input.txt"), "content").unwrap();
+    dir
+}
+
+fn aurora(dir: &TempDir, beam_value: &str, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .env_remove("GITHUB_ACTIONS")
+        .env("AURORA_FWD_GLOBAL_A", "g")
+        .env("AURORA_FWD_BEAM", beam_value)
+        .env("AURORA_FWD_CLI", "c")
+        .args(args)
+        .current_dir(dir.path())
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn inherited_variables_reach_only_the_beams_that_ask() {
+    let dir = fixture_dir();
+    let output = aurora(
+        &dir,
+        "b",
+        &["--no-tui", "--inherit-env", "AURORA_FWD_CLI", "deploy"],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    assert!(stdout.contains("global=g beam=b cli=c"), "{stdout}");
+
+    let output = aurora(&dir, "b", &["--no-tui", "other"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("other beam=[]"), "{stdout}");
+}
+
+#[test]
+fn an_inherited_value_stays_out_of_the_cache_key() {
+    let dir = fixture_dir();
+    let first = aurora(&dir, "one", &["--no-tui", "deploy"]);
+    assert!(String::from_utf8_lossy(&first.stdout).contains("beam=one"));
+
+    // Another machine, another socket path: still a cache hit.
+    let second = aurora(&dir, "two", &["--no-tui", "deploy"]);
+    let stdout = String::from_utf8_lossy(&second.stdout);
+    assert!(second.status.success(), "{stdout}");
+    assert!(!stdout.contains("beam=two"), "{stdout}");
+}
+
+#[test]
+fn dry_run_verbose_lists_each_beams_variables_by_origin() {
+    let dir = fixture_dir();
+    let output = aurora(&dir, "b", &["--dry-run", "--verbose", "deploy"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    assert!(stdout.contains("\nEnvironment:\n  deploy\n"), "{stdout}");
+    assert!(stdout.contains("    declared   DECLARED\n"), "{stdout}");
+    assert!(
+        stdout.contains("    inherited  AURORA_FWD_BEAM, AURORA_FWD_GLOBAL_A\n"),
+        "{stdout}"
+    );
+    let ambient = stdout
+        .lines()
+        .find(|l| l.starts_with("    ambient"))
+        .unwrap();
+    assert!(ambient.contains("PATH"), "{stdout}");
+    assert!(!ambient.contains("AURORA_FWD_CLI"), "{stdout}");
+
+    // Without --dry-run, --verbose is refused.
+    let output = aurora(&dir, "b", &["--verbose", "deploy"]);
+    assert!(!output.status.success());
+}
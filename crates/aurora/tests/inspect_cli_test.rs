@@ -0,0 +1,314 @@
+//! CLI tests for `aurora inspect`, which prints a beam with the template it
+//! extends merged in.
+
+use std::fs;
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+const BEAMFILE: &str = r#"
+template "rust-ci" {
+  description = "A Rust CI step"
+  inputs      = ["src/**/*.rs"]
+  environment {
+    RUST_LOG = "info"
+  }
+  run {
+    commands = ["cargo build"]
+    executor "docker" { image = "rust:1.80" }
+  }
+}
+
+beam "build" {
+  extends = "rust-ci"
+  inputs += ["build.rs"]
+  run { commands += ["cargo test"] }
+}
+"#;
+
+fn aurora(dir: &TempDir, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .env_remove("GITHUB_ACTIONS")
+        .args(args)
+        .current_dir(dir.path())
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn inspect_prints_the_merged_beam() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("Beamfile"), BEAMFILE).unwrap();
+
+    let output = aurora(&dir, &["inspect", "build"]);
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        r#"# extends "rust-ci"
+beam "build" {
+  description = "A Rust CI step"
+  inputs = ["src/**/*.rs", "build.rs"]
+
+  environment {
+    RUST_LOG = "info"
+  }
+
+  run {
+    commands = ["cargo build", "cargo test"]
+    executor "docker" { image = "rust:1.80" }
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn inspect_prints_a_beamfile_that_means_the_same() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("Beamfile"), BEAMFILE).unwrap();
+    let merged = aurora(&dir, &["inspect", "build"]).stdout;
+
+    // The merged beam, on its own, inspects to itself.
+    let standalone = tempfile::tempdir().unwrap();
+    fs::write(standalone.path().join("Beamfile"), &merged).unwrap();
+    let output = aurora(&standalone, &["inspect", "build"]);
+    assert!(output.status.success(), "{output:?}");
+    let expected = String::from_utf8_lossy(&merged).replace("# extends \"rust-ci\"\n", "");
+    assert_eq!(String::from_utf8_lossy(&output.stdout), expected);
+}
+
+#[test]
+fn inspect_rejects_an_unknown_beam() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("Beamfile"), BEAMFILE).unwrap();
+
+    let output = aurora(&dir, &["inspect", "rust-ci"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Unknown beam 'rust-ci'"), "{stderr}");
+}
+
+const JSON_BEAMFILE: &str = r#"
+aurora { default = "build" }
+
+variable "region" {
+  default = "us"
+  choices = ["us", "eu"]
+}
+
+variable "token" {
+  default   = "s3cret"
+  sensitive = true
+}
+
+environment {
+  MODE = "dev"
+  load = [{ path = ".env", optional = true }]
+}
+
+profile "ci" {
+  variables = { region = "eu" }
+}
+
+template "rust-ci" {
+  inputs = ["src/**"]
+  run { commands = ["cargo build --${var.region}"] }
+}
+
+beam "fetch" {
+  param "ref" { default = "main" }
+  condition { any = [{ shell = "true" }] }
+  run {
+    commands = ["git fetch ${param.ref} ${var.token}"]
+    executor "docker" { image = "alpine" }
+  }
+}
+
+beam "build" {
+  extends      = "rust-ci"
+  depends_on   = [{ beam = "fetch", params = { ref = "dev" } }]
+  kill_timeout = "5s"
+}
+"#;
+
+fn inspect_json(args: &[&str]) -> serde_json::Value {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("Beamfile"), JSON_BEAMFILE).unwrap();
+    let output = aurora(&dir, args);
+    assert!(output.status.success(), "{output:?}");
+    let document = serde_json::from_slice(&output.stdout).unwrap();
+    check_schema(&document);
+    document
+}
+
+/// Checks `document` against `schemas/inspect.v1.schema.json`, for the
+/// keywords the schema uses.
+fn check_schema(document: &serde_json::Value) {
+    let schema: serde_json::Value =
+        serde_json::from_str(include_str!("../../../schemas/inspect.v1.schema.json")).unwrap();
+    if let Err(e) = validate(document, &schema, &schema, "$") {
+        panic!("{e}\n{document:#}");
+    }
+}
+
+fn validate(
+    value: &serde_json::Value,
+    schema: &serde_json::Value,
+    root: &serde_json::Value,
+    path: &str,
+) -> Result<(), String> {
+    use serde_json::Value;
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        let name = reference.strip_prefix("#/$defs/").unwrap();
+        return validate(value, &root["$defs"][name], root, path);
+    }
+    if let Some(options) = schema.get("oneOf").and_then(Value::as_array) {
+        let matching = options
+            .iter()
+            .filter(|option| validate(value, option, root, path).is_ok())
+            .count();
+        if matching != 1 {
+            return Err(format!("{path}: matches {matching} of oneOf"));
+        }
+    }
+    if let Some(expected) = schema.get("const") {
+        if value != expected {
+            return Err(format!("{path}: expected {expected}"));
+        }
+    }
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            return Err(format!("{path}: {value} is not one of {allowed:?}"));
+        }
+    }
+    if let Some(types) = schema.get("type") {
+        let types: Vec<&str> = match types {
+            Value::String(name) => vec![name.as_str()],
+            names => names
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|n| n.as_str().unwrap())
+                .collect(),
+        };
+        let actual = match value {
+            Value::Null => "null",
+            Value::Bool(_) => "boolean",
+            Value::Number(n) if n.is_u64() || n.is_i64() => "integer",
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+        };
+        if !types.contains(&actual) {
+            return Err(format!("{path}: {actual} is not one of {types:?}"));
+        }
+    }
+    if let (Some(minimum), Some(n)) = (
+        schema.get("minimum").and_then(Value::as_u64),
+        value.as_u64(),
+    ) {
+        if n < minimum {
+            return Err(format!("{path}: {n} is below {minimum}"));
+        }
+    }
+    if let (Some(items), Some(array)) = (schema.get("items"), value.as_array()) {
+        for (index, item) in array.iter().enumerate() {
+            validate(item, items, root, &format!("{path}[{index}]"))?;
+        }
+    }
+    if let Some(object) = value.as_object() {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        for name in schema
+            .get("required")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            if !object.contains_key(name.as_str().unwrap()) {
+                return Err(format!("{path}: missing {name}"));
+            }
+        }
+        for (key, field) in object {
+            let field_path = format!("{path}.{key}");
+            match (
+                properties.and_then(|p| p.get(key)),
+                schema.get("additionalProperties"),
+            ) {
+                (Some(property), _) => validate(field, property, root, &field_path)?,
+                (None, Some(Value::Bool(false))) => {
+                    return Err(format!("{field_path}: not in the schema"))
+                }
+                (None, Some(additional)) => validate(field, additional, root, &field_path)?,
+                (None, None) => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn inspect_json_dumps_the_parsed_beamfile() {
+    let document = inspect_json(&["inspect", "--json"]);
+    assert_eq!(document["schema"], 1);
+    assert_eq!(document["profile"], serde_json::Value::Null);
+    assert_eq!(document["config"]["default"], "build");
+    assert_eq!(document["variables"][0]["value"], "us");
+    assert_eq!(
+        document["variables"][0]["choices"],
+        serde_json::json!(["us", "eu"])
+    );
+    assert_eq!(document["variables"][1]["value"], "***");
+    assert_eq!(document["environment"]["loads"][0]["after"], 1);
+    assert_eq!(document["profiles"][0]["variables"]["region"], "eu");
+    assert!(document.get("instances").is_none());
+
+    let build = &document["beams"][1];
+    assert_eq!(build["name"], "build");
+    assert_eq!(build["extends"], "rust-ci");
+    assert_eq!(build["depends_on"][0]["params"]["ref"], "dev");
+    assert_eq!(build["kill_timeout_ms"], 5000);
+    // References as written: only the instances are interpolated.
+    assert_eq!(build["run"]["commands"][0], "cargo build --${var.region}");
+}
+
+#[test]
+fn inspect_json_expanded_lists_the_instances_of_the_target() {
+    let document = inspect_json(&["--profile", "ci", "inspect", "--json", "--expanded"]);
+    assert_eq!(document["profile"], "ci");
+    assert_eq!(document["variables"][0]["value"], "eu");
+    assert_eq!(document["variables"][0]["origin"], "profile");
+    assert_eq!(document["target"], "build");
+
+    let instances = document["instances"].as_array().unwrap();
+    let ids: Vec<&str> = instances
+        .iter()
+        .map(|i| i["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, ["build", "fetch[ref=dev]", "fetch[ref=main]"]);
+    assert_eq!(
+        instances[0]["depends_on"],
+        serde_json::json!(["fetch[ref=dev]"])
+    );
+    assert_eq!(instances[0]["run"]["commands"][0], "cargo build --eu");
+    assert_eq!(instances[1]["beam"], "fetch");
+    assert_eq!(instances[1]["bindings"]["ref"], "dev");
+    // A sensitive value is masked wherever it was interpolated.
+    assert_eq!(instances[1]["run"]["commands"][0], "git fetch dev ***");
+}
+
+#[test]
+fn inspect_json_expanded_binds_the_target_arguments() {
+    let document = inspect_json(&["inspect", "--json", "--expanded", "fetch", "v1"]);
+    assert_eq!(document["target"], "fetch[ref=v1]");
+}
+
+#[test]
+fn inspect_json_reports_a_failure_as_an_error_event() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("Beamfile"), JSON_BEAMFILE).unwrap();
+    let output = aurora(&dir, &["inspect", "--json", "--expanded", "nope"]);
+    assert!(!output.status.success());
+    let error: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(error["event"], "error");
+    assert_eq!(error["kind"], "target");
+}
@@ -159,6 +159,56 @@ beam "b" { depends_on = ["a"] run { commands = ["echo b"] } }
     );
 }
 
+#[test]
+fn beamfile_error_event_carries_its_span() {
+    let beamfile = r#"variable "region" { default = "eu" }
+beam "deploy" {
+  run { commands = ["./deploy ${var.regoin}"] }
+}
+"#;
+    let dir = fixture_dir(beamfile);
+    let output = Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .args(["deploy", "--json"])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    let lines = parse_lines(&String::from_utf8_lossy(&output.stdout));
+    assert_eq!(
+        lines,
+        [serde_json::json!({
+            "schema": 1,
+            "event": "error",
+            "kind": "variable",
+            "message": "unknown variable 'regoin' referenced in beam 'deploy'; did you mean 'region'?",
+            "span": {
+                "file": "Beamfile",
+                "line": 3,
+                "column": 31,
+                "end_line": 3,
+                "end_column": 44
+            }
+        })]
+    );
+
+    // Outside `--json`, the same error is shown with its line and a caret.
+    let output = Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .env_remove("GITHUB_ACTIONS")
+        .args(["deploy", "--no-tui"])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(" --> Beamfile:3:31\n")
+            && stderr.contains("^^^^^^^^^^^^^ not declared")
+            && stderr.contains("= help: did you mean 'region'?"),
+        "{stderr}"
+    );
+}
+
 #[test]
 fn failing_environment_block_emits_error_event_on_stdout() {
     let beamfile = r#"
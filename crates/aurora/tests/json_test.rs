@@ -68,6 +68,38 @@ async fn emits_run_started_first_and_run_completed_last() {
     assert!(success);
 }
 
+#[tokio::test]
+async fn run_started_carries_the_dependencies_within_the_run() {
+    let (tx, rx) = mpsc::channel(4);
+    tx.send(SchedulerEvent::AllDone { success: true })
+        .await
+        .unwrap();
+    let mut buf: Vec<u8> = Vec::new();
+    JsonReporter::new(
+        "test".to_string(),
+        vec!["build".into(), "test".into()],
+        &mut buf,
+    )
+    .with_deps(vec![
+        ("build".into(), vec![]),
+        ("test".into(), vec!["build".into(), "fixtures".into()]),
+        ("lint".into(), vec!["build".into()]),
+    ])
+    .run(rx)
+    .await
+    .unwrap();
+    let first: Value =
+        serde_json::from_str(String::from_utf8(buf).unwrap().lines().next().unwrap()).unwrap();
+    assert_eq!(
+        first["deps"],
+        serde_json::json!({ "build": [], "test": ["build"] })
+    );
+
+    // Without them, the field is left out.
+    let (lines, _) = run_reporter("b", vec!["b".into()], vec![]).await;
+    assert!(lines[0].get("deps").is_none());
+}
+
 #[tokio::test]
 async fn beam_completed_success_carries_cached_and_duration() {
     let (lines, _) = run_reporter(
@@ -204,6 +236,25 @@ async fn beam_output_carries_stream_and_line() {
     assert_eq!(outs[1]["line"], "oops");
 }
 
+#[tokio::test]
+async fn beam_output_line_is_plain_text() {
+    let (lines, _) = run_reporter(
+        "b",
+        vec!["b".into()],
+        vec![
+            SchedulerEvent::BeamOutput {
+                name: "b".into(),
+                line: "\x1b[1;31merror\x1b[0m: 10%\r\x1b[1;31merror\x1b[0m: 100%".into(),
+                is_stderr: false,
+            },
+            SchedulerEvent::AllDone { success: true },
+        ],
+    )
+    .await;
+    let out = lines.iter().find(|l| l["event"] == "beam_output").unwrap();
+    assert_eq!(out["line"], "error: 100%");
+}
+
 #[tokio::test]
 async fn failed_allowed_beam_carries_exit_code_and_duration() {
     let (lines, success) = run_reporter(
@@ -264,6 +315,41 @@ async fn cancelled_beam_omits_extra_fields() {
     );
 }
 
+#[tokio::test]
+async fn stopping_beam_emits_beam_stopping_before_its_completion() {
+    let (lines, _) = run_reporter(
+        "b",
+        vec!["b".into()],
+        vec![
+            SchedulerEvent::BeamStarted { name: "b".into() },
+            SchedulerEvent::BeamStopping { name: "b".into() },
+            SchedulerEvent::BeamCompleted {
+                name: "b".into(),
+                status: BeamStatus::Cancelled,
+            },
+            SchedulerEvent::AllDone { success: false },
+        ],
+    )
+    .await;
+    let events: Vec<&str> = lines.iter().map(|l| l["event"].as_str().unwrap()).collect();
+    assert_eq!(
+        events,
+        vec![
+            "run_started",
+            "beam_started",
+            "beam_stopping",
+            "beam_completed",
+            "run_completed"
+        ]
+    );
+    let stopping = &lines[2];
+    assert_eq!(stopping["beam"], "b");
+    assert!(
+        stopping["at"].is_string(),
+        "beam_stopping carries a timestamp"
+    );
+}
+
 #[tokio::test]
 async fn emits_a_warning_event_for_a_dead_input_pattern() {
     let (lines, _success) = run_reporter(
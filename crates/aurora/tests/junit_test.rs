@@ -0,0 +1,181 @@
+use aurora::junit::{escape, JunitReporter};
+use aurora::reporter::{ReportSpec, Reporter};
+use aurora_core::events::{BeamStatus, SchedulerEvent, SkipReason};
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+fn completed(name: &str, status: BeamStatus) -> SchedulerEvent {
+    SchedulerEvent::BeamCompleted {
+        name: name.to_string(),
+        status,
+    }
+}
+
+fn output(name: &str, line: &str, is_stderr: bool) -> SchedulerEvent {
+    SchedulerEvent::BeamOutput {
+        name: name.to_string(),
+        line: line.to_string(),
+        is_stderr,
+    }
+}
+
+async fn report(events: Vec<SchedulerEvent>) -> (bool, String) {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("reports/junit.xml");
+    let (tx, rx) = mpsc::channel(64);
+    for event in events {
+        tx.send(event).await.unwrap();
+    }
+    drop(tx);
+    let success = JunitReporter::new("ci".to_string(), path.clone())
+        .run(rx)
+        .await
+        .unwrap();
+    (success, std::fs::read_to_string(path).unwrap())
+}
+
+#[tokio::test]
+async fn one_testcase_per_beam_with_failures_skips_and_timing() {
+    let (success, xml) = report(vec![
+        output("lint", "all good", false),
+        completed(
+            "lint",
+            BeamStatus::Success {
+                duration: Duration::from_millis(1500),
+                cached: false,
+            },
+        ),
+        completed(
+            "fmt",
+            BeamStatus::Skipped {
+                reason: SkipReason::Cached,
+            },
+        ),
+        output("deploy[env=prod]", "connecting", false),
+        output("deploy[env=prod]", "error: <denied> & retried", true),
+        completed(
+            "deploy[env=prod]",
+            BeamStatus::Failed {
+                exit_code: 3,
+                duration: Duration::from_secs(2),
+            },
+        ),
+        completed("notify", BeamStatus::Cancelled),
+        SchedulerEvent::AllDone { success: false },
+    ])
+    .await;
+
+    assert!(!success);
+    assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>"));
+    assert!(xml.contains(r#"<testsuite name="ci" tests="4" failures="1" errors="1" skipped="1""#));
+    assert!(xml.contains(r#"<testcase name="lint" classname="aurora" time="1.500"/>"#));
+    assert!(xml.contains(r#"<skipped message="cached"/>"#));
+    assert!(xml.contains(r#"<testcase name="deploy[env=prod]" classname="aurora" time="2.000">"#));
+    assert!(xml.contains(
+        r#"<failure message="exit code 3" type="exit_code">error: &lt;denied&gt; &amp; retried</failure>"#
+    ));
+    assert!(!xml.contains("connecting"), "stderr is preferred:\n{xml}");
+    assert!(xml.contains(r#"<error message="cancelled" type="cancelled"/>"#));
+}
+
+#[tokio::test]
+async fn a_failure_without_stderr_carries_the_stdout_tail() {
+    let mut events: Vec<SchedulerEvent> = (0..30)
+        .map(|i| output("tty", &format!("line {i}"), false))
+        .collect();
+    events.push(completed(
+        "tty",
+        BeamStatus::Failed {
+            exit_code: 1,
+            duration: Duration::ZERO,
+        },
+    ));
+    events.push(SchedulerEvent::AllDone { success: false });
+    let (_, xml) = report(events).await;
+    assert!(xml.contains("line 29</failure>"));
+    assert!(xml.contains(">line 10\n"), "only the last 20 lines:\n{xml}");
+    assert!(!xml.contains("line 9\n"));
+}
+
+#[tokio::test]
+async fn an_allowed_failure_passes_with_a_note() {
+    let (success, xml) = report(vec![
+        completed(
+            "flaky",
+            BeamStatus::FailedAllowed {
+                exit_code: 2,
+                duration: Duration::from_millis(10),
+            },
+        ),
+        SchedulerEvent::AllDone { success: true },
+    ])
+    .await;
+    assert!(success);
+    assert!(xml.contains("failures=\"0\""));
+    assert!(xml.contains("<system-out>failed with exit code 2 (allowed)"));
+}
+
+#[test]
+fn escape_drops_the_control_characters_xml_cannot_carry() {
+    assert_eq!(escape("a\u{1b}[31mb\"'\tc"), "a[31mb&quot;&apos;\tc");
+}
+
+#[test]
+fn report_spec_parses_the_format_and_path() {
+    assert_eq!(
+        ReportSpec::parse("junit=out/report.xml").unwrap(),
+        ReportSpec::Junit(PathBuf::from("out/report.xml"))
+    );
+    let err = ReportSpec::parse("tap=out.tap").unwrap_err().to_string();
+    assert!(err.contains("unknown report format 'tap'"), "{err}");
+    assert!(ReportSpec::parse("junit").is_err());
+    assert!(ReportSpec::parse("junit=").is_err());
+}
+
+const BEAMFILE: &str = r#"
+aurora { version = "1" }
+beam "ok"   { run { commands = ["echo hello"] } }
+beam "boom" {
+  depends_on = ["ok"]
+  run { commands = ["echo broken >&2", "exit 3"] }
+}
+"#;
+
+/// The report composes with the console output: `--json` still streams its
+/// events on stdout while the report is written.
+#[test]
+fn report_is_written_alongside_the_json_stream() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("Beamfile"), BEAMFILE).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .args(["boom", "--json", "--report", "junit=junit.xml"])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("\"event\":\"run_completed\""), "{stdout}");
+    let xml = std::fs::read_to_string(dir.path().join("junit.xml")).unwrap();
+    assert!(xml.contains(r#"<testcase name="ok""#), "{xml}");
+    assert!(xml.contains("<failure message=\"exit code 3\" type=\"exit_code\">broken</failure>"));
+}
+
+#[test]
+fn report_is_written_alongside_the_headless_output() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("Beamfile"), BEAMFILE).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .env_remove("GITHUB_ACTIONS")
+        .args(["ok", "--no-tui", "--report", "junit=junit.xml"])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("hello"));
+    let xml = std::fs::read_to_string(dir.path().join("junit.xml")).unwrap();
+    assert!(xml.contains("tests=\"1\" failures=\"0\""), "{xml}");
+}
@@ -0,0 +1,278 @@
+use aurora::lint::{lint, report, Diagnostic, Severity, Subject};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// `rule subject: message` for each diagnostic, for readable assertions.
+fn summary(diagnostics: &[Diagnostic]) -> Vec<String> {
+    diagnostics
+        .iter()
+        .map(|d| format!("{} {}: {}", d.rule, d.subject, d.message))
+        .collect()
+}
+
+fn project() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("src")).unwrap();
+    fs::write(dir.path().join("src/main.rs"), "fn main() {}").unwrap();
+    dir
+}
+
+#[test]
+fn a_clean_beamfile_has_no_diagnostics() {
+    let dir = project();
+    let source = r#"
+aurora { default = "build" }
+variable "profile" { default = "release" }
+beam "build" {
+  inputs = ["src/**/*.rs"]
+  outputs = ["target/app"]
+  run { commands = ["cargo build --${var.profile}"] }
+}
+"#;
+    assert!(summary(&lint(source, dir.path())).is_empty());
+}
+
+#[test]
+fn each_rule_reports_its_mistake() {
+    let dir = project();
+    let source = r#"
+aurora { default = "deploy" }
+variable "profile" { default = "release" }
+variable "region" { default = "eu" }
+beam "deploy" {
+  depends_on = ["biuld", "image"]
+  dir = "services/${var.profile}"
+  inputs = ["src/**/*.rs"]
+  run { commands = ["./deploy ${var.regoin} ${param.env}"] }
+}
+beam "image" {
+  description = "Build the image"
+  inputs = ["src/**/*.rs", "docker/**"]
+  outputs = ["src/version.rs"]
+  run {
+    commands = ["make"]
+    executor "docker" { workdir = "/src" }
+  }
+}
+beam "orphan" { run { commands = ["true"] } }
+"#;
+    assert_eq!(
+        summary(&lint(source, dir.path())),
+        [
+            "unknown-dependency beam 'deploy': depends on 'biuld', which is not declared",
+            "missing-dir beam 'deploy': dir 'services/release' does not exist",
+            "unknown-variable beam 'deploy': references variable 'regoin', which is not \
+             declared; did you mean 'region'?",
+            "undeclared-param beam 'deploy': references '${param.env}', but declares no param 'env'",
+            "docker-without-image beam 'image': runs on the docker executor without an `image`",
+            "dead-input beam 'image': input 'docker/**' matches no file",
+            "output-overlaps-input beam 'image': output 'src/version.rs' matches an input; each \
+             run invalidates the cache and re-triggers a watch",
+            "unused-variable variable 'region': is declared but never referenced",
+            "unreachable-beam beam 'orphan': is not the default, has no description and no \
+             target depends on it",
+        ]
+    );
+}
+
+#[test]
+fn unused_variables_and_unreachable_beams_are_warnings() {
+    let dir = project();
+    let source = r#"
+aurora { default = "build" }
+variable "unused" { default = "x" }
+beam "build" { depends_on = ["fmt"] run { commands = ["true"] } }
+beam "fmt" { run { commands = ["true"] } }
+beam "orphan" { run { commands = ["true"] } }
+beam "docs" { description = "Build the docs" run { commands = ["true"] } }
+"#;
+    let diagnostics = lint(source, dir.path());
+    assert_eq!(
+        summary(&diagnostics),
+        [
+            "unused-variable variable 'unused': is declared but never referenced",
+            "unreachable-beam beam 'orphan': is not the default, has no description and no \
+             target depends on it",
+        ]
+    );
+    assert!(diagnostics.iter().all(|d| d.severity == Severity::Warning));
+}
+
+#[test]
+fn a_beamfile_without_advertised_targets_has_no_unreachable_beam() {
+    let dir = project();
+    let source = r#"
+beam "a" { run { commands = ["true"] } }
+beam "b" { run { commands = ["true"] } }
+"#;
+    assert!(lint(source, dir.path()).is_empty());
+}
+
+#[test]
+fn params_declared_or_bound_by_a_dependent_are_fine() {
+    let dir = project();
+    let source = r#"
+beam "deploy" {
+  param "env" { default = "staging" }
+  depends_on = [{ beam = "push", params = { tag = "${param.env}" } }]
+  run { commands = ["./deploy ${param.env}"] }
+}
+beam "push" {
+  param "tag" {}
+  run { commands = ["docker push app:${param.tag}"] }
+}
+"#;
+    assert!(lint(source, dir.path()).is_empty());
+
+    let source = r#"
+beam "deploy" {
+  depends_on = [{ beam = "push", params = { tag = "${param.env}" } }]
+  run { commands = ["true"] }
+}
+beam "push" { param "tag" {} run { commands = ["true"] } }
+"#;
+    assert_eq!(
+        summary(&lint(source, dir.path())),
+        ["undeclared-param beam 'deploy': references '${param.env}', but declares no param 'env'"]
+    );
+}
+
+#[test]
+fn a_parse_error_or_a_cycle_is_a_file_level_error() {
+    let dir = project();
+    let diagnostics = lint(r#"beam "a" { run { commands = [ } }"#, dir.path());
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].rule, "parse-error");
+    assert_eq!(diagnostics[0].subject, Subject::File);
+
+    let source = r#"
+beam "a" { depends_on = ["b"] }
+beam "b" { depends_on = ["a"] }
+"#;
+    let diagnostics = lint(source, dir.path());
+    assert_eq!(summary(&diagnostics).len(), 1);
+    assert_eq!(diagnostics[0].rule, "dependency-cycle");
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+}
+
+#[test]
+fn suppression_comments_silence_a_rule_for_one_beam() {
+    let dir = project();
+    let source = r#"
+aurora { default = "a" }
+# Generated files may not exist yet.
+# aurora-lint: allow(dead-input, unreachable-beam)
+beam "generated" { inputs = ["gen/**"] }
+
+beam "a" {
+  # aurora-lint: allow(dead-input)
+  inputs = ["missing/**"]
+}
+
+beam "b" {
+  description = "b"
+  inputs = ["missing/**"]
+}
+
+# aurora-lint: allow(unused-varaible)
+variable "v" { default = "" }
+"#;
+    assert_eq!(
+        summary(&lint(source, dir.path())),
+        [
+            "dead-input beam 'b': input 'missing/**' matches no file",
+            "unused-variable variable 'v': is declared but never referenced",
+            "unknown-rule Beamfile: allows 'unused-varaible', which is no lint rule; did you \
+             mean 'unused-variable'?",
+        ]
+    );
+}
+
+#[test]
+fn json_report_is_one_document_with_counts() {
+    let dir = project();
+    let diagnostics = lint(
+        r#"
+variable "unused" { default = "" }
+beam "web" { dir = "frontend" }
+"#,
+        dir.path(),
+    );
+    let mut out = vec![];
+    report(&diagnostics, true, &mut out).unwrap();
+    let value: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(
+        value,
+        serde_json::json!({
+            "schema": 1,
+            "errors": 1,
+            "warnings": 1,
+            "diagnostics": [
+                {
+                    "rule": "missing-dir",
+                    "severity": "error",
+                    "beam": "web",
+                    "message": "dir 'frontend' does not exist"
+                },
+                {
+                    "rule": "unused-variable",
+                    "severity": "warning",
+                    "variable": "unused",
+                    "message": "is declared but never referenced"
+                }
+            ]
+        })
+    );
+
+    let mut out = vec![];
+    report(&diagnostics, false, &mut out).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "error[missing-dir]: beam 'web': dir 'frontend' does not exist\n\
+         warning[unused-variable]: variable 'unused': is declared but never referenced\n\
+         1 error, 1 warning\n"
+    );
+}
+
+fn aurora_lint(dir: &Path, args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .env_remove("GITHUB_ACTIONS")
+        .current_dir(dir)
+        .arg("lint")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn aurora_lint_fails_on_errors_and_on_warnings_when_denied() {
+    let dir = project();
+    fs::write(
+        dir.path().join("Beamfile"),
+        r#"beam "test" { inputs = ["tests/**"] run { commands = ["touch ran"] } }"#,
+    )
+    .unwrap();
+
+    let output = aurora_lint(dir.path(), &[]);
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "warning[dead-input]: beam 'test': input 'tests/**' matches no file\n0 errors, 1 warning\n"
+    );
+    assert!(!dir.path().join("ran").exists(), "lint runs nothing");
+
+    let output = aurora_lint(dir.path(), &["--deny-warnings"]);
+    assert_eq!(output.status.code(), Some(1));
+
+    fs::write(
+        dir.path().join("Beamfile"),
+        r#"beam "test" { dir = "nowhere" }"#,
+    )
+    .unwrap();
+    let output = aurora_lint(dir.path(), &["--json"]);
+    assert_eq!(output.status.code(), Some(1));
+    let value: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(value["errors"], 1);
+    assert_eq!(value["diagnostics"][0]["rule"], "missing-dir");
+}
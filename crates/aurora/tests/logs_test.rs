@@ -0,0 +1,305 @@
+use aurora::logs::{file_name, logs_command, LogStore};
+use aurora_core::events::{BeamStatus, SchedulerEvent};
+use std::fs;
+use std::process::Command;
+use std::time::Duration;
+
+fn output(name: &str, line: &str, is_stderr: bool) -> SchedulerEvent {
+    SchedulerEvent::BeamOutput {
+        name: name.into(),
+        line: line.into(),
+        is_stderr,
+    }
+}
+
+fn failed() -> BeamStatus {
+    BeamStatus::Failed {
+        exit_code: 1,
+        duration: Duration::from_millis(1800),
+    }
+}
+
+/// The text of each line of a log, without its timestamp.
+fn untimed(log: &str) -> Vec<String> {
+    log.lines()
+        .map(|line| line.split_once(' ').unwrap().1.to_string())
+        .collect()
+}
+
+#[test]
+fn a_run_writes_tagged_lines_per_beam_and_its_outcome() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = LogStore::new(dir.path());
+    let mut run = store.open_run("check").unwrap();
+    assert_eq!(run.id(), 1);
+
+    for event in [
+        SchedulerEvent::BeamStarted {
+            name: "test".into(),
+        },
+        output("test", "running 3 tests", false),
+        output("test", "\x1b[31mpanicked\x1b[0m", true),
+        SchedulerEvent::Warning {
+            name: "test".into(),
+            message: "input pattern matched no files".into(),
+        },
+        SchedulerEvent::BeamCompleted {
+            name: "test".into(),
+            status: failed(),
+        },
+        SchedulerEvent::BeamCompleted {
+            name: "check".into(),
+            status: BeamStatus::Cancelled,
+        },
+        SchedulerEvent::AllDone { success: false },
+    ] {
+        run.record(&event).unwrap();
+    }
+
+    let log = fs::read_to_string(run.dir().join("test.log")).unwrap();
+    assert_eq!(
+        untimed(&log),
+        [
+            "--- started",
+            "out running 3 tests",
+            "err panicked",
+            "--- warning: input pattern matched no files",
+            "--- done: failed (exit 1) 1.8s",
+        ],
+    );
+    // Every line starts with an RFC 3339 timestamp.
+    let stamp = log.split(' ').next().unwrap();
+    assert!(stamp.parse::<jiff::Timestamp>().is_ok(), "{stamp}");
+
+    let check = fs::read_to_string(run.dir().join("check.log")).unwrap();
+    assert_eq!(untimed(&check), ["--- done: cancelled"]);
+
+    let info = fs::read_to_string(run.dir().join("run.info")).unwrap();
+    assert!(info.contains("target = check\n"), "{info}");
+    assert!(info.contains("outcome = failed\n"), "{info}");
+}
+
+#[test]
+fn only_the_newest_runs_are_kept() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = LogStore::new(dir.path()).with_retention(Some(2));
+    for expected in 1..=3 {
+        assert_eq!(store.open_run("t").unwrap().id(), expected);
+    }
+    assert_eq!(store.runs().unwrap(), [2, 3]);
+    assert!(!LogStore::new(dir.path())
+        .with_retention(Some(0))
+        .is_enabled());
+}
+
+#[test]
+fn instance_ids_become_safe_file_names() {
+    assert_eq!(file_name("build"), "build.log");
+    assert_eq!(
+        file_name("deploy[env=prod,path=a/b]"),
+        "deploy[env=prod,path=a_b].log"
+    );
+}
+
+#[tokio::test]
+async fn logs_command_prints_a_run_a_beam_or_its_instances() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = LogStore::new(dir.path());
+    let mut first = store.open_run("build").unwrap();
+    first.record(&output("build", "old", false)).unwrap();
+    let mut run = store.open_run("all").unwrap();
+    for event in [
+        output("build", "compiling", false),
+        SchedulerEvent::BeamCompleted {
+            name: "build".into(),
+            status: BeamStatus::Success {
+                duration: Duration::from_millis(4200),
+                cached: false,
+            },
+        },
+        output("deploy[env=a]", "to a", false),
+        output("deploy[env=b]", "to b", false),
+    ] {
+        run.record(&event).unwrap();
+    }
+
+    let mut out = vec![];
+    logs_command(&store, None, None, false, &mut out)
+        .await
+        .unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.starts_with("run 2  target all  started "), "{out}");
+    assert!(out.trim_end().ends_with("running"), "not over yet:\n{out}");
+    assert!(out.contains("  build          passed 4.2s\n"), "{out}");
+    assert!(out.contains("  deploy[env=a]  running\n"), "{out}");
+
+    let mut out = vec![];
+    logs_command(&store, Some("build"), Some(1), false, &mut out)
+        .await
+        .unwrap();
+    assert_eq!(untimed(&String::from_utf8(out).unwrap()), ["out old"]);
+
+    let mut out = vec![];
+    logs_command(&store, Some("deploy"), None, false, &mut out)
+        .await
+        .unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.starts_with("==> deploy[env=a] <==\n"), "{out}");
+    assert!(out.contains("\n\n==> deploy[env=b] <==\n"), "{out}");
+
+    let err = logs_command(&store, Some("build"), Some(7), false, &mut vec![])
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("runs 1 to 2 are kept"), "{err}");
+    let err = logs_command(&store, Some("lint"), None, false, &mut vec![])
+        .await
+        .unwrap_err();
+    assert_eq!(err.to_string(), "no log for `lint` in run 2");
+}
+
+#[tokio::test]
+async fn follow_prints_lines_as_they_come_until_the_beam_completes() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = LogStore::new(dir.path());
+    let mut run = store.open_run("build").unwrap();
+
+    // The beam has not started when the follower attaches.
+    let writer = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        run.record(&output("build", "one", false)).unwrap();
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        run.record(&output("build", "two", true)).unwrap();
+        run.record(&SchedulerEvent::BeamCompleted {
+            name: "build".into(),
+            status: failed(),
+        })
+        .unwrap();
+        run
+    });
+
+    let mut out = vec![];
+    tokio::time::timeout(
+        Duration::from_secs(10),
+        logs_command(&store, Some("build"), None, true, &mut out),
+    )
+    .await
+    .expect("follow must stop at the closing line")
+    .unwrap();
+    writer.await.unwrap();
+    assert_eq!(
+        untimed(&String::from_utf8(out).unwrap()),
+        ["out one", "err two", "--- done: failed (exit 1) 1.8s"]
+    );
+}
+
+#[tokio::test]
+async fn follow_keeps_a_character_split_across_reads() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = LogStore::new(dir.path());
+    let run = store.open_run("build").unwrap();
+    let log = run.dir().join("build.log");
+    fs::write(&log, b"2026-01-01T00:00:00Z out caf\xc3").unwrap();
+
+    let writer = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let mut file = fs::OpenOptions::new().append(true).open(&log).unwrap();
+        std::io::Write::write_all(&mut file, b"\xa9\n2026-01-01T00:00:01Z --- done: ok 0.1s\n")
+            .unwrap();
+        run
+    });
+
+    let mut out = vec![];
+    tokio::time::timeout(
+        Duration::from_secs(10),
+        logs_command(&store, Some("build"), None, true, &mut out),
+    )
+    .await
+    .expect("follow must stop at the closing line")
+    .unwrap();
+    writer.await.unwrap();
+    assert_eq!(
+        untimed(&String::from_utf8(out).unwrap()),
+        ["out café", "--- done: ok 0.1s"]
+    );
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn follow_stops_when_the_writing_process_is_gone() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = LogStore::new(dir.path());
+    let mut run = store.open_run("build").unwrap();
+    run.record(&output("build", "one", false)).unwrap();
+
+    // The run's writer was killed: no outcome, and its pid is no process.
+    let mut gone = Command::new("true").spawn().unwrap();
+    gone.wait().unwrap();
+    let info_path = run.dir().join("run.info");
+    let info = fs::read_to_string(&info_path).unwrap();
+    let pid = format!("pid = {}\n", std::process::id());
+    assert!(info.contains(&pid), "{info}");
+    fs::write(
+        &info_path,
+        info.replace(&pid, &format!("pid = {}\n", gone.id())),
+    )
+    .unwrap();
+
+    let mut out = vec![];
+    tokio::time::timeout(
+        Duration::from_secs(10),
+        logs_command(&store, Some("build"), None, true, &mut out),
+    )
+    .await
+    .expect("follow must stop once the writer is gone")
+    .unwrap();
+    assert_eq!(untimed(&String::from_utf8(out).unwrap()), ["out one"]);
+
+    let mut out = vec![];
+    logs_command(&store, None, None, false, &mut out)
+        .await
+        .unwrap();
+    let listing = String::from_utf8(out).unwrap();
+    assert!(listing.contains("  interrupted\n"), "{listing}");
+    assert!(listing.contains("build  unfinished"), "{listing}");
+}
+
+#[test]
+fn a_cli_run_leaves_logs_that_aurora_logs_prints() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("Beamfile"),
+        r#"
+aurora { log_retention = 1 }
+beam "boom" { run { commands = ["echo partial", "exit 4"] } }
+"#,
+    )
+    .unwrap();
+    let aurora = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_aurora"))
+            .env_remove("GITHUB_ACTIONS")
+            .args(args)
+            .current_dir(dir.path())
+            .output()
+            .unwrap()
+    };
+
+    for _ in 0..2 {
+        assert!(!aurora(&["boom", "--no-tui"]).status.success());
+    }
+    assert!(!dir.path().join(".aurora/logs/1").exists(), "retention");
+
+    let logs = aurora(&["logs", "boom"]);
+    assert!(logs.status.success());
+    let stdout = String::from_utf8_lossy(&logs.stdout);
+    let lines = untimed(&stdout);
+    assert_eq!(lines[..2], ["--- started", "out partial"], "{stdout}");
+    assert!(
+        lines[2].starts_with("--- done: failed (exit 4) "),
+        "{stdout}"
+    );
+
+    let summary = aurora(&["logs"]);
+    let stdout = String::from_utf8_lossy(&summary.stdout);
+    assert!(stdout.starts_with("run 2  target boom"), "{stdout}");
+    assert!(stdout.contains("  boom  failed (exit 4)"), "{stdout}");
+}
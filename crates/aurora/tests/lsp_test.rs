@@ -0,0 +1,284 @@
+use aurora::lsp::serve;
+use serde_json::{json, Value};
+use std::io::Cursor;
+
+const URI: &str = "untitled:Beamfile";
+
+const BEAMFILE: &str = r#"variable "region" { default = "eu" }
+beam "build" {
+  param "target" { default = "debug" }
+  run { commands = ["cargo build --${param.target}"] }
+}
+beam "deploy" {
+  depends_on = ["build"]
+  run { commands = ["./deploy ${var.region}"] }
+}
+"#;
+
+fn frame(message: &Value) -> String {
+    let body = message.to_string();
+    format!("Content-Length: {}\r\n\r\n{body}", body.len())
+}
+
+/// Runs a session of `messages` and returns what the server sent back.
+fn session(messages: &[Value]) -> Vec<Value> {
+    exchange(messages.iter().map(frame).collect())
+}
+
+/// Serves the raw `input` and returns what the server sent back.
+fn exchange(input: String) -> Vec<Value> {
+    let mut out = vec![];
+    serve(
+        Cursor::new(input),
+        &mut out,
+        vec!["local".into(), "docker".into()],
+    )
+    .unwrap();
+    let mut out = out.as_slice();
+    let mut messages = vec![];
+    while !out.is_empty() {
+        let header_end = out.windows(4).position(|w| w == b"\r\n\r\n").unwrap();
+        let header = std::str::from_utf8(&out[..header_end]).unwrap();
+        let length: usize = header
+            .strip_prefix("Content-Length: ")
+            .unwrap()
+            .parse()
+            .unwrap();
+        let body = &out[header_end + 4..header_end + 4 + length];
+        messages.push(serde_json::from_slice(body).unwrap());
+        out = &out[header_end + 4 + length..];
+    }
+    messages
+}
+
+fn open(uri: &str, text: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": { "textDocument": { "uri": uri, "languageId": "beamfile", "version": 1, "text": text } },
+    })
+}
+
+fn request(id: u64, method: &str, line: u64, character: u64) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "method": method,
+        "params": {
+            "textDocument": { "uri": URI },
+            "position": { "line": line, "character": character },
+            "context": { "includeDeclaration": true },
+        },
+    })
+}
+
+/// The result of the request `id` in a session that opens `text` first.
+fn answer(text: &str, request: Value) -> Value {
+    let id = request["id"].clone();
+    session(&[open(URI, text), request])
+        .into_iter()
+        .find(|message| message["id"] == id)
+        .unwrap()["result"]
+        .clone()
+}
+
+fn labels(items: &Value) -> Vec<&str> {
+    items
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["label"].as_str().unwrap())
+        .collect()
+}
+
+fn start(location: &Value) -> (u64, u64) {
+    let start = &location["range"]["start"];
+    (
+        start["line"].as_u64().unwrap(),
+        start["character"].as_u64().unwrap(),
+    )
+}
+
+#[test]
+fn initialize_advertises_the_features_and_exit_ends_the_session() {
+    let messages = session(&[
+        json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} }),
+        json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }),
+        json!({ "jsonrpc": "2.0", "id": 2, "method": "workspace/symbol", "params": {} }),
+        json!({ "jsonrpc": "2.0", "id": 3, "method": "shutdown" }),
+        json!({ "jsonrpc": "2.0", "method": "exit" }),
+        // Never read.
+        json!({ "jsonrpc": "2.0", "id": 4, "method": "shutdown" }),
+    ]);
+    assert_eq!(messages.len(), 3);
+    let capabilities = &messages[0]["result"]["capabilities"];
+    assert_eq!(capabilities["textDocumentSync"], 1);
+    for provider in [
+        "definitionProvider",
+        "referencesProvider",
+        "hoverProvider",
+        "documentSymbolProvider",
+    ] {
+        assert_eq!(capabilities[provider], true, "{provider}");
+    }
+    assert_eq!(messages[1]["error"]["code"], -32601);
+    assert_eq!(
+        messages[2],
+        json!({ "jsonrpc": "2.0", "id": 3, "result": null })
+    );
+}
+
+#[test]
+fn an_error_is_published_where_it_points() {
+    let text = BEAMFILE.replace("${var.region}", "${var.regoin}");
+    let messages = session(&[open(URI, &text)]);
+    assert_eq!(messages[0]["method"], "textDocument/publishDiagnostics");
+    let diagnostics = messages[0]["params"]["diagnostics"].as_array().unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0]["severity"], 1);
+    assert_eq!(
+        diagnostics[0]["message"],
+        "unknown variable 'regoin' referenced in beam 'deploy'; did you mean 'region'?"
+    );
+    assert_eq!(
+        diagnostics[0]["range"],
+        json!({
+            "start": { "line": 7, "character": 30 },
+            "end": { "line": 7, "character": 43 },
+        })
+    );
+
+    // Fixed, the diagnostics are cleared.
+    let messages = session(&[open(URI, BEAMFILE)]);
+    assert_eq!(messages[0]["params"]["diagnostics"], json!([]));
+}
+
+#[test]
+fn lint_findings_are_published_for_a_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let uri = format!("file://{}/Beamfile", dir.path().display());
+    let text = format!("variable \"unused\" {{ default = \"x\" }}\n{BEAMFILE}");
+    let messages = session(&[open(&uri, &text)]);
+    let diagnostics = messages[0]["params"]["diagnostics"].as_array().unwrap();
+    let unused = diagnostics
+        .iter()
+        .find(|d| d["code"] == "unused-variable")
+        .unwrap_or_else(|| panic!("no unused-variable in {diagnostics:?}"));
+    assert_eq!(unused["severity"], 2);
+    assert_eq!(start(unused), (0, 9));
+}
+
+#[test]
+fn completion_offers_beams_variables_params_and_executors() {
+    // An unfinished string: completion works from the last parse.
+    let text = BEAMFILE.replace("[\"build\"]", "[\"build\", \"");
+    let messages = session(&[
+        open(URI, BEAMFILE),
+        json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didChange",
+            "params": { "textDocument": { "uri": URI, "version": 2 }, "contentChanges": [{ "text": text }] },
+        }),
+        request(1, "textDocument/completion", 6, 26),
+    ]);
+    let items = &messages.last().unwrap()["result"];
+    assert_eq!(labels(items), ["build", "deploy"]);
+    assert_eq!(items[0]["detail"], "build [target=debug]");
+
+    let text = BEAMFILE.replace("${var.region}", "${var.");
+    let items = answer(&text, request(1, "textDocument/completion", 7, 36));
+    assert_eq!(labels(&items), ["region"]);
+
+    let items = answer(BEAMFILE, request(1, "textDocument/completion", 3, 37));
+    assert_eq!(labels(&items), ["var.region", "param.target"]);
+
+    let text = BEAMFILE.replace(
+        "run { commands = [\"./deploy",
+        "run {\n    executor \"\n    commands = [\"./deploy",
+    );
+    let items = answer(&text, request(1, "textDocument/completion", 8, 14));
+    assert_eq!(labels(&items), ["local", "docker"]);
+}
+
+#[test]
+fn definition_and_references_follow_beams_and_variables() {
+    // `"build"` in `depends_on` leads to its declaration.
+    let location = answer(BEAMFILE, request(1, "textDocument/definition", 6, 19));
+    assert_eq!(location["uri"], URI);
+    assert_eq!(start(&location), (1, 5));
+
+    let location = answer(BEAMFILE, request(1, "textDocument/definition", 7, 37));
+    assert_eq!(start(&location), (0, 9));
+
+    let locations = answer(BEAMFILE, request(1, "textDocument/references", 0, 12));
+    let starts: Vec<_> = locations.as_array().unwrap().iter().map(start).collect();
+    assert_eq!(starts, [(0, 9), (7, 32)]);
+
+    let locations = answer(BEAMFILE, request(1, "textDocument/references", 1, 7));
+    let starts: Vec<_> = locations.as_array().unwrap().iter().map(start).collect();
+    assert_eq!(starts, [(1, 5), (6, 16)]);
+
+    let locations = answer(BEAMFILE, request(1, "textDocument/references", 2, 10));
+    let starts: Vec<_> = locations.as_array().unwrap().iter().map(start).collect();
+    assert_eq!(starts, [(2, 8), (3, 35)]);
+}
+
+#[test]
+fn hover_shows_the_signature_and_the_defaults() {
+    let hover = answer(BEAMFILE, request(1, "textDocument/hover", 6, 19));
+    assert_eq!(hover["contents"]["kind"], "markdown");
+    assert_eq!(
+        hover["contents"]["value"],
+        "```\nbeam build [target=debug]\n```"
+    );
+
+    let hover = answer(BEAMFILE, request(1, "textDocument/hover", 7, 37));
+    assert_eq!(
+        hover["contents"]["value"],
+        "```\nvariable \"region\" = \"eu\"\n```"
+    );
+
+    let hover = answer(BEAMFILE, request(1, "textDocument/hover", 5, 0));
+    assert_eq!(hover, Value::Null);
+}
+
+#[test]
+fn document_symbols_list_beams_with_their_params() {
+    let symbols = answer(BEAMFILE, request(1, "textDocument/documentSymbol", 0, 0));
+    let names: Vec<_> = symbols
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| (s["name"].as_str().unwrap(), s["kind"].as_u64().unwrap()))
+        .collect();
+    assert_eq!(names, [("region", 13), ("build", 12), ("deploy", 12)]);
+    let build = &symbols[1];
+    assert_eq!(
+        build["range"]["start"],
+        json!({ "line": 1, "character": 0 })
+    );
+    assert_eq!(build["range"]["end"], json!({ "line": 4, "character": 1 }));
+    assert_eq!(build["children"][0]["name"], "target");
+}
+
+#[test]
+fn a_malformed_message_is_answered_and_the_session_goes_on() {
+    let shutdown = json!({ "jsonrpc": "2.0", "id": 1, "method": "shutdown" });
+    let messages = exchange(
+        [
+            "Content-Length: 9\r\n\r\n{\"id\": 1,".to_string(),
+            "Content-Length: many\r\n\r\n".to_string(),
+            frame(&shutdown),
+        ]
+        .concat(),
+    );
+    assert_eq!(messages.len(), 3, "{messages:?}");
+    for error in &messages[..2] {
+        assert_eq!(error["id"], Value::Null);
+        assert_eq!(error["error"]["code"], -32700);
+    }
+    assert_eq!(
+        messages[2],
+        json!({ "jsonrpc": "2.0", "id": 1, "result": null })
+    );
+}
@@ -0,0 +1,264 @@
+#![cfg(feature = "otlp")]
+
+use aurora::otlp::{payload, BeamMeta, Clock, OtlpConfig, OtlpExport};
+use aurora_core::events::{BeamPhase, BeamStatus, SchedulerEvent};
+use aurora_core::trace_context::TraceContext;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::process::Command;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+fn export(beams: HashMap<String, BeamMeta>) -> OtlpExport {
+    OtlpExport {
+        config: OtlpConfig {
+            endpoint: "http://localhost:4318".to_string(),
+            headers: vec![],
+            service_name: "aurora".to_string(),
+        },
+        context: TraceContext::new(None),
+        beams,
+    }
+}
+
+fn spans(payload: &Value) -> &Vec<Value> {
+    payload["resourceSpans"][0]["scopeSpans"][0]["spans"]
+        .as_array()
+        .unwrap()
+}
+
+fn span<'a>(payload: &'a Value, name: &str) -> &'a Value {
+    spans(payload)
+        .iter()
+        .find(|s| s["name"] == name)
+        .unwrap_or_else(|| panic!("no span {name}"))
+}
+
+/// The value of the attribute `key` of `span`, unwrapped from its type.
+fn attribute<'a>(span: &'a Value, key: &str) -> Option<&'a Value> {
+    span["attributes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|a| a["key"] == key)
+        .and_then(|a| a["value"].as_object())
+        .and_then(|v| v.values().next())
+}
+
+#[test]
+fn traces_url_appends_the_signal_path_once() {
+    let mut config = export(HashMap::new()).config;
+    assert_eq!(config.traces_url(), "http://localhost:4318/v1/traces");
+    config.endpoint = "https://otel.example.com/v1/traces".to_string();
+    assert_eq!(config.traces_url(), "https://otel.example.com/v1/traces");
+}
+
+#[test]
+fn a_root_span_then_one_child_per_beam_with_its_attributes() {
+    let export = export(HashMap::from([(
+        "deploy[env=prod]".to_string(),
+        BeamMeta {
+            executor: "docker".to_string(),
+            bindings: BTreeMap::from([("env".to_string(), "prod".to_string())]),
+        },
+    )]));
+    let clock = Clock::now();
+    let t0 = Instant::now();
+    let at = |ms: u64| t0 + Duration::from_millis(ms);
+    let recorded = vec![
+        (
+            at(1),
+            SchedulerEvent::BeamCompleted {
+                name: "fmt".to_string(),
+                status: BeamStatus::Success {
+                    duration: Duration::ZERO,
+                    cached: true,
+                },
+            },
+        ),
+        (
+            at(2),
+            SchedulerEvent::BeamStarted {
+                name: "deploy[env=prod]".to_string(),
+            },
+        ),
+        (
+            at(9),
+            SchedulerEvent::BeamPhase {
+                name: "deploy[env=prod]".to_string(),
+                phase: BeamPhase::Execute,
+                start: at(3),
+                end: at(9),
+            },
+        ),
+        (
+            at(9),
+            SchedulerEvent::BeamCompleted {
+                name: "deploy[env=prod]".to_string(),
+                status: BeamStatus::Failed {
+                    exit_code: 3,
+                    duration: Duration::from_millis(6),
+                },
+            },
+        ),
+        (at(10), SchedulerEvent::AllDone { success: false }),
+    ];
+    let payload = payload("deploy", &export, &clock, &recorded);
+    let context = &export.context;
+
+    let resource = &payload["resourceSpans"][0]["resource"]["attributes"][0];
+    assert_eq!(resource["value"]["stringValue"], "aurora");
+
+    let root = span(&payload, "aurora deploy");
+    assert_eq!(root["spanId"], context.root_span_id.as_str());
+    assert!(root.get("parentSpanId").is_none());
+    assert_eq!(root["status"]["code"], 2);
+
+    let deploy = span(&payload, "deploy[env=prod]");
+    assert_eq!(deploy["traceId"], context.trace_id.as_str());
+    assert_eq!(deploy["parentSpanId"], context.root_span_id.as_str());
+    assert_eq!(
+        deploy["spanId"],
+        context.beam_span_id("deploy[env=prod]").as_str(),
+        "the span the beam was handed as TRACEPARENT"
+    );
+    assert_eq!(deploy["status"]["code"], 2);
+    assert_eq!(attribute(deploy, "aurora.beam.status").unwrap(), "failed");
+    assert_eq!(attribute(deploy, "aurora.beam.exit_code").unwrap(), "3");
+    assert_eq!(attribute(deploy, "aurora.beam.cache_hit").unwrap(), false);
+    assert_eq!(attribute(deploy, "aurora.beam.executor").unwrap(), "docker");
+    assert_eq!(
+        attribute(deploy, "aurora.beam.binding.env").unwrap(),
+        "prod"
+    );
+    let nanos = |s: &Value, key: &str| s[key].as_str().unwrap().parse::<u128>().unwrap();
+    assert_eq!(
+        nanos(deploy, "endTimeUnixNano") - nanos(deploy, "startTimeUnixNano"),
+        Duration::from_millis(7).as_nanos()
+    );
+
+    let execute = span(&payload, "execute");
+    assert_eq!(execute["parentSpanId"], deploy["spanId"]);
+
+    let fmt = span(&payload, "fmt");
+    assert_eq!(attribute(fmt, "aurora.beam.cache_hit").unwrap(), true);
+    assert!(attribute(fmt, "aurora.beam.exit_code").is_none());
+}
+
+/// A collector stand-in: accepts one OTLP/HTTP request, answers 200 and hands
+/// the request's headers and JSON body over.
+fn collector() -> (String, mpsc::Receiver<(Vec<String>, Value)>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let endpoint = format!("http://{}", listener.local_addr().unwrap());
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut headers = vec![];
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let line = line.trim_end().to_string();
+            if line.is_empty() {
+                break;
+            }
+            headers.push(line);
+        }
+        let length: usize = headers
+            .iter()
+            .find_map(|h| {
+                let (name, value) = h.split_once(':')?;
+                name.eq_ignore_ascii_case("content-length")
+                    .then(|| value.trim().parse().unwrap())
+            })
+            .expect("a content-length");
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+        let mut stream = stream;
+        stream
+            .write_all(
+                b"HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: 2\r\n\r\n{}",
+            )
+            .unwrap();
+        tx.send((headers, serde_json::from_slice(&body).unwrap()))
+            .unwrap();
+    });
+    (endpoint, rx)
+}
+
+#[test]
+fn the_run_is_exported_to_the_collector() {
+    let (endpoint, received) = collector();
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("Beamfile"),
+        r#"
+aurora { version = "1" }
+beam "build" { run { commands = ["printf '%s' \"$TRACEPARENT\" > tp.txt"] } }
+"#,
+    )
+    .unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .args(["build", "--no-tui", "--otlp", &endpoint])
+        .env("OTEL_EXPORTER_OTLP_HEADERS", "x-tenant=ci")
+        .env(
+            "TRACEPARENT",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+        )
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let (headers, payload) = received.recv_timeout(Duration::from_secs(10)).unwrap();
+    assert!(headers[0].starts_with("POST /v1/traces "), "{headers:?}");
+    assert!(headers
+        .iter()
+        .any(|h| h.eq_ignore_ascii_case("x-tenant: ci")));
+
+    // Aurora's own TRACEPARENT is the parent of the run.
+    let root = span(&payload, "aurora build");
+    assert_eq!(root["traceId"], "4bf92f3577b34da6a3ce929d0e0e4736");
+    assert_eq!(root["parentSpanId"], "00f067aa0ba902b7");
+
+    // The beam was handed its own span.
+    let build = span(&payload, "build");
+    let traceparent = std::fs::read_to_string(dir.path().join("tp.txt")).unwrap();
+    assert_eq!(
+        traceparent,
+        format!(
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-{}-01",
+            build["spanId"].as_str().unwrap()
+        )
+    );
+    assert_eq!(attribute(build, "aurora.beam.status").unwrap(), "success");
+}
+
+#[test]
+fn an_unreachable_collector_only_warns() {
+    // A port nothing listens on: bound, then released.
+    let endpoint = {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        format!("http://{}", listener.local_addr().unwrap())
+    };
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("Beamfile"),
+        "aurora { version = \"1\" }\nbeam \"ok\" { run { commands = [\"true\"] } }\n",
+    )
+    .unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .args(["ok", "--no-tui", "--otlp", &endpoint])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("cannot export the run"), "{stderr}");
+}
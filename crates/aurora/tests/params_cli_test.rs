@@ -38,6 +38,7 @@ beam "deploy" {
 fn positional_and_named_binding_reaches_target_and_dependency() {
     let dir = fixture_dir(BEAMFILE);
     let output = Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .env_remove("GITHUB_ACTIONS")
         .args(["--no-tui", "deploy", "1.2.3", "env=prod"])
         .current_dir(dir.path())
         .output()
@@ -63,6 +64,7 @@ fn positional_and_named_binding_reaches_target_and_dependency() {
 fn missing_required_param_exits_one_with_signature() {
     let dir = fixture_dir(BEAMFILE);
     let output = Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .env_remove("GITHUB_ACTIONS")
         .args(["--no-tui", "deploy"])
         .current_dir(dir.path())
         .output()
@@ -88,6 +90,7 @@ fn missing_required_param_exits_one_with_signature() {
 fn list_shows_param_signatures() {
     let dir = fixture_dir(BEAMFILE);
     let output = Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .env_remove("GITHUB_ACTIONS")
         .args(["--list"])
         .current_dir(dir.path())
         .output()
@@ -105,6 +108,7 @@ fn list_shows_param_signatures() {
 fn dry_run_prints_instance_ids() {
     let dir = fixture_dir(BEAMFILE);
     let output = Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .env_remove("GITHUB_ACTIONS")
         .args(["--dry-run", "deploy", "1.2.3"])
         .current_dir(dir.path())
         .output()
@@ -125,3 +129,79 @@ fn dry_run_prints_instance_ids() {
         "plan must show the target's instance id:\n{stdout}"
     );
 }
+
+const TYPED: &str = r#"
+variable "replicas" {
+  type = "int"
+  default = "2"
+  description = "How many to run"
+}
+
+variable "token" {
+  sensitive = true
+  default = "s3cr3t"
+}
+
+beam "deploy" {
+  param "env" { choices = ["staging", "prod"] }
+  run { commands = ["echo deploy to ${param.env} x${var.replicas}"] }
+}
+"#;
+
+#[test]
+fn a_value_of_the_wrong_type_fails_before_running() {
+    let dir = fixture_dir(TYPED);
+    let output = Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .env_remove("GITHUB_ACTIONS")
+        .args(["--no-tui", "--var", "replicas=banana", "deploy", "prod"])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(
+            "Invalid value for variable 'replicas' passed with --var replicas=banana: \
+             expected an int"
+        ),
+        "{stderr}"
+    );
+
+    let output = Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .env_remove("GITHUB_ACTIONS")
+        .args(["--no-tui", "deploy", "prodd"])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(
+            "invalid argument 'prodd' for param 'env' of beam 'deploy': expected one of \
+             staging, prod; did you mean 'prod'?"
+        ),
+        "{stderr}"
+    );
+    assert!(
+        !String::from_utf8_lossy(&output.stdout).contains("deploy to"),
+        "nothing may run"
+    );
+}
+
+#[test]
+fn list_shows_types_and_variables_without_secrets() {
+    let dir = fixture_dir(TYPED);
+    let output = Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .env_remove("GITHUB_ACTIONS")
+        .args(["--list"])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("deploy <env:staging|prod>"), "{stdout}");
+    assert!(stdout.contains("Variables:"), "{stdout}");
+    assert!(stdout.contains("replicas:int=2"), "{stdout}");
+    assert!(stdout.contains("How many to run"), "{stdout}");
+    assert!(stdout.contains("token=***"), "{stdout}");
+    assert!(!stdout.contains("s3cr3t"), "{stdout}");
+}
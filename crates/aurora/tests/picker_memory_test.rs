@@ -0,0 +1,39 @@
+use aurora::picker_memory::PickerMemory;
+
+#[test]
+fn remembered_values_survive_a_save_and_load() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut memory = PickerMemory::load(dir.path());
+    assert_eq!(memory, PickerMemory::default());
+
+    memory.remember(
+        "deploy",
+        &["version=1.2.0".to_string(), "env=prod".to_string()],
+    );
+    memory.save(dir.path());
+
+    let loaded = PickerMemory::load(dir.path());
+    assert_eq!(loaded.last("deploy", "version"), Some("1.2.0"));
+    assert_eq!(loaded.last("deploy", "env"), Some("prod"));
+    assert_eq!(loaded.last("build", "version"), None);
+}
+
+#[test]
+fn a_param_left_empty_keeps_its_previous_value() {
+    let mut memory = PickerMemory::default();
+    memory.remember(
+        "deploy",
+        &["version=1.2.0".to_string(), "env=prod".to_string()],
+    );
+    memory.remember("deploy", &["version=1.3.0".to_string()]);
+    assert_eq!(memory.last("deploy", "version"), Some("1.3.0"));
+    assert_eq!(memory.last("deploy", "env"), Some("prod"));
+}
+
+#[test]
+fn a_corrupt_memory_file_is_an_empty_memory() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(dir.path().join(".aurora")).unwrap();
+    std::fs::write(PickerMemory::path(dir.path()), "not json").unwrap();
+    assert_eq!(PickerMemory::load(dir.path()), PickerMemory::default());
+}
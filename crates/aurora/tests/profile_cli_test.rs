@@ -0,0 +1,113 @@
+//! CLI tests for `profile` blocks selected with `--profile` or
+//! `AURORA_PROFILE`.
+
+use std::fs;
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+const BEAMFILE: &str = r#"
+variable "region" { default = "us" }
+variable "tier" { default = "small" }
+
+environment {
+  MODE = "dev"
+}
+
+profile "ci" {
+  variables = { region = "eu" }
+  environment {
+    MODE = "ci"
+  }
+}
+
+profile "same" {
+  variables = { region = "us" }
+}
+
+beam "show" {
+  description = "Print the settings"
+  inputs      = ["input.txt"]
+  run { commands = ["echo region=${var.region} tier=${var.tier} mode=$MODE"] }
+}
+"#;
+
+fn fixture_dir() -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("Beamfile"), BEAMFILE).unwrap();
+    fs::write(dir.path().join("input.txt"), "content").unwrap();
+    dir
+}
+
+fn aurora(dir: &TempDir, profile_env: Option<&str>, args: &[&str]) -> Output {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_aurora"));
+    command
+        .env_remove("GITHUB_ACTIONS")
+        .env_remove("AURORA_PROFILE");
+    if let Some(profile) = profile_env {
+        command.env("AURORA_PROFILE", profile);
+    }
+    command.args(args).current_dir(dir.path()).output().unwrap()
+}
+
+#[test]
+fn a_profile_overlays_variables_and_environment() {
+    let dir = fixture_dir();
+    let output = aurora(
+        &dir,
+        None,
+        &["--no-tui", "--no-cache", "--profile", "ci", "show"],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    assert!(stdout.contains("region=eu tier=small mode=ci"), "{stdout}");
+
+    // Selected from the environment, and a `--var` still wins over it.
+    let output = aurora(
+        &dir,
+        Some("ci"),
+        &["--no-tui", "--no-cache", "--var", "region=ap", "show"],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("region=ap tier=small mode=ci"), "{stdout}");
+
+    let output = aurora(&dir, Some("cj"), &["--no-tui", "show"]);
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("Unknown profile 'cj'"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn list_shows_the_profiles_and_marks_the_active_one() {
+    let dir = fixture_dir();
+    let output = aurora(&dir, None, &["--list", "--profile", "ci"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    assert!(stdout.contains("  region=eu "), "{stdout}");
+    let profiles = &stdout[stdout.find("\nProfiles:\n").expect(&stdout)..];
+    assert!(profiles.contains("  ci (active) "), "{stdout}");
+    assert!(profiles.contains("region=eu, $MODE"), "{stdout}");
+    assert!(profiles.contains("  same "), "{stdout}");
+}
+
+#[test]
+fn only_the_values_a_profile_changes_reach_the_cache_key() {
+    let dir = fixture_dir();
+    let first = aurora(&dir, None, &["--no-tui", "show"]);
+    let stdout = String::from_utf8_lossy(&first.stdout);
+    assert!(!stdout.contains("cached"), "{stdout}");
+
+    // Same values under another name: a cache hit.
+    let second = aurora(&dir, None, &["--no-tui", "--profile", "same", "show"]);
+    let stdout = String::from_utf8_lossy(&second.stdout);
+    assert!(second.status.success(), "{stdout}");
+    assert!(stdout.contains("[SKIP] show  cached"), "{stdout}");
+
+    // Different values: the beam runs again.
+    let third = aurora(&dir, None, &["--no-tui", "--profile", "ci", "show"]);
+    let stdout = String::from_utf8_lossy(&third.stdout);
+    assert!(!stdout.contains("cached"), "{stdout}");
+    assert!(stdout.contains("region=eu"), "{stdout}");
+}
@@ -0,0 +1,163 @@
+use std::time::Duration;
+
+use aurora::json::JsonReporter;
+use aurora::replay::{parse_recording, play};
+use aurora::reporter::Reporter;
+use aurora_core::events::{BeamStatus, SchedulerEvent};
+use tokio::sync::mpsc;
+
+/// What a `--json` run of `events` writes.
+async fn record(events: Vec<SchedulerEvent>) -> String {
+    let (tx, rx) = mpsc::channel(64);
+    for event in events {
+        tx.send(event).await.unwrap();
+    }
+    drop(tx);
+    let mut out = vec![];
+    JsonReporter::new(
+        "test".to_string(),
+        vec!["build".to_string(), "test".to_string()],
+        &mut out,
+    )
+    .with_deps(vec![
+        ("build".to_string(), vec![]),
+        ("test".to_string(), vec!["build".to_string()]),
+        ("lint".to_string(), vec![]),
+    ])
+    .run(rx)
+    .await
+    .unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+fn debug(events: &[(Duration, SchedulerEvent)]) -> Vec<String> {
+    events.iter().map(|(_, e)| format!("{e:?}")).collect()
+}
+
+#[tokio::test]
+async fn a_recorded_run_reads_back_as_the_same_events() {
+    let events = vec![
+        SchedulerEvent::BeamStarted {
+            name: "build".into(),
+        },
+        SchedulerEvent::BeamOutput {
+            name: "build".into(),
+            line: "compiling".into(),
+            is_stderr: false,
+        },
+        SchedulerEvent::BeamCompleted {
+            name: "build".into(),
+            status: BeamStatus::Success {
+                duration: Duration::from_millis(1200),
+                cached: false,
+            },
+        },
+        SchedulerEvent::BeamStarted {
+            name: "test".into(),
+        },
+        SchedulerEvent::BeamOutput {
+            name: "test".into(),
+            line: "panicked".into(),
+            is_stderr: true,
+        },
+        SchedulerEvent::Warning {
+            name: "test".into(),
+            message: "no input".into(),
+        },
+        SchedulerEvent::BeamStopping {
+            name: "test".into(),
+        },
+        SchedulerEvent::BeamCompleted {
+            name: "test".into(),
+            status: BeamStatus::Failed {
+                exit_code: 101,
+                duration: Duration::from_millis(800),
+            },
+        },
+        SchedulerEvent::AllDone { success: false },
+    ];
+    let recording = parse_recording(&record(events.clone()).await).unwrap();
+
+    assert_eq!(recording.target, "test");
+    assert_eq!(
+        recording.beams,
+        [
+            ("build".to_string(), vec![]),
+            ("test".to_string(), vec!["build".to_string()]),
+        ]
+    );
+    let expected: Vec<String> = events.iter().map(|e| format!("{e:?}")).collect();
+    assert_eq!(debug(&recording.events), expected);
+}
+
+#[test]
+fn output_lines_are_spread_between_the_timestamps_around_them() {
+    let recording = parse_recording(
+        r#"{"schema":1,"event":"run_started","target":"t","beams":["t"],"at":"2026-10-18T10:00:00.000Z"}
+{"schema":1,"event":"beam_started","beam":"t","at":"2026-10-18T10:00:01.000Z"}
+{"schema":1,"event":"beam_output","beam":"t","stream":"stdout","line":"one"}
+{"schema":1,"event":"beam_output","beam":"t","stream":"stdout","line":"two"}
+{"schema":1,"event":"beam_output","beam":"t","stream":"stdout","line":"three"}
+{"schema":1,"event":"beam_completed","beam":"t","status":"success","cached":false,"duration_ms":4000,"at":"2026-10-18T10:00:05.000Z"}
+"#,
+    )
+    .unwrap();
+    let times: Vec<u128> = recording
+        .events
+        .iter()
+        .map(|(at, _)| at.as_millis())
+        .collect();
+    // Cut short: the replay closes the run itself, as a failure.
+    assert_eq!(times, [1000, 2000, 3000, 4000, 5000, 5000]);
+    assert!(matches!(
+        recording.events.last().unwrap().1,
+        SchedulerEvent::AllDone { success: false }
+    ));
+    assert_eq!(recording.beams, [("t".to_string(), vec![])]);
+}
+
+#[test]
+fn what_is_not_a_replayable_run_is_refused() {
+    let err = |text: &str| format!("{:#}", parse_recording(text).unwrap_err());
+
+    assert_eq!(
+        err(r#"{"schema":1,"event":"error","kind":"target","message":"unknown beam `x`"}"#),
+        "the recorded run failed before it started: unknown beam `x`"
+    );
+    assert!(err("").starts_with("no run_started event"));
+    assert!(err("[build] compiling\n").starts_with("line 1: not an `aurora --json` event"));
+    assert_eq!(
+        err(r#"{"schema":2,"event":"warning","beam":"b","message":"m"}"#),
+        "line 1: schema 2 is not supported (this Aurora reads schema 1)"
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn play_keeps_the_recorded_pace_scaled_by_the_speed() {
+    let event = |at: u64| {
+        (
+            Duration::from_millis(at),
+            SchedulerEvent::BeamStarted {
+                name: format!("b{at}"),
+            },
+        )
+    };
+    let (tx, mut rx) = mpsc::channel(8);
+    let start = tokio::time::Instant::now();
+    tokio::spawn(play(
+        vec![event(0), event(1000), event(3000)],
+        Some(2.0),
+        tx,
+    ));
+    let mut arrivals = vec![];
+    while rx.recv().await.is_some() {
+        arrivals.push(start.elapsed().as_millis());
+    }
+    assert_eq!(arrivals, [0, 500, 1500]);
+
+    // Without a speed, everything is there at once.
+    let (tx, mut rx) = mpsc::channel(8);
+    play(vec![event(0), event(60_000)], None, tx).await;
+    assert!(rx.recv().await.is_some() && rx.recv().await.is_some());
+    assert_eq!(start.elapsed().as_millis(), 1500);
+}
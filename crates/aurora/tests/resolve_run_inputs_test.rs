@@ -1,5 +1,15 @@
 use std::fs;
 
+fn selection(beam: &str, args: &[&str]) -> aurora::Selection {
+    aurora::Selection {
+        targets: vec![aurora::Target {
+            beam: beam.to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+        }],
+        ..Default::default()
+    }
+}
+
 const BEAMFILE: &str = r#"
 aurora {
   version = "1"
@@ -25,9 +35,11 @@ fn resolves_beams_env_and_overrides() {
     let loaded = aurora::resolve_run_inputs(
         &beamfile,
         dir.path(),
+        None,
         &["who=aurora".to_string()],
-        "greet",
         &[],
+        &selection("greet", &[]),
+        None,
     )
     .unwrap();
 
@@ -67,9 +79,16 @@ fn beam_environment_block_populates_env_overlay_end_to_end() {
     // Full wiring: parse the beam `environment {}` block (Task 2), interpolate
     // `${param.version}` into it (Task 3), then evaluate the overlay per
     // instance (Task 5). The instance id carries the bound param.
-    let loaded =
-        aurora::resolve_run_inputs(&beamfile, dir.path(), &[], "deploy", &["1.2.3".to_string()])
-            .unwrap();
+    let loaded = aurora::resolve_run_inputs(
+        &beamfile,
+        dir.path(),
+        None,
+        &[],
+        &[],
+        &selection("deploy", &["1.2.3"]),
+        None,
+    )
+    .unwrap();
 
     let deploy = loaded
         .beams
@@ -97,5 +116,30 @@ fn invalid_beamfile_is_an_error() {
     let dir = tempfile::tempdir().unwrap();
     let beamfile = dir.path().join("Beamfile");
     fs::write(&beamfile, "this is not valid hcl {{{").unwrap();
-    assert!(aurora::resolve_run_inputs(&beamfile, dir.path(), &[], "x", &[]).is_err());
+    let selection = selection("x", &[]);
+    assert!(
+        aurora::resolve_run_inputs(&beamfile, dir.path(), None, &[], &[], &selection, None)
+            .is_err()
+    );
+}
+
+#[test]
+fn jobs_override_the_configured_parallelism() {
+    let dir = tempfile::tempdir().unwrap();
+    let beamfile = dir.path().join("Beamfile");
+    fs::write(&beamfile, BEAMFILE).unwrap();
+
+    let loaded = aurora::resolve_run_inputs(
+        &beamfile,
+        dir.path(),
+        None,
+        &[],
+        &[],
+        &aurora::Selection::default(),
+        Some(1),
+    )
+    .unwrap();
+    assert_eq!(loaded.max_parallelism, Some(1));
+    // No target named: the default beam.
+    assert_eq!(loaded.target_id, "greet");
 }
@@ -47,6 +47,7 @@ fn sigint_kills_the_beam_subtree_and_exits_nonzero() {
     fs::write(dir.path().join("Beamfile"), BEAMFILE).unwrap();
 
     let mut child = Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .env_remove("GITHUB_ACTIONS")
         .args(["slow", "--no-tui"])
         .current_dir(dir.path())
         .spawn()
@@ -0,0 +1,187 @@
+//! CLI tests for runs of several targets: positionals naming more than one
+//! beam, `--tag`, and the `--only`, `--exclude` and `--jobs` flags.
+
+use std::fs;
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+const BEAMFILE: &str = r#"
+aurora { default = "test" }
+
+beam "fetch" {
+  run { commands = ["echo fetching"] }
+}
+
+beam "build" {
+  tags       = ["ci"]
+  depends_on = ["fetch"]
+  run { commands = ["echo building"] }
+}
+
+beam "test" {
+  tags       = ["ci", "fast"]
+  depends_on = ["build"]
+  run { commands = ["echo testing"] }
+}
+
+beam "greet" {
+  param "who" { default = "world" }
+  run { commands = ["echo hello ${param.who}"] }
+}
+
+beam "docs" {
+  run { commands = ["echo documenting"] }
+}
+"#;
+
+fn aurora(args: &[&str]) -> Output {
+    let dir: TempDir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("Beamfile"), BEAMFILE).unwrap();
+    Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .env_remove("GITHUB_ACTIONS")
+        .args(args)
+        .current_dir(dir.path())
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    assert!(output.status.success(), "{output:?}");
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn beams_without_params_are_targets_as_separate_words() {
+    let run = stdout(&aurora(&["--no-tui", "docs", "fetch"]));
+    assert!(run.contains("documenting"), "{run}");
+    assert!(run.contains("fetching"), "{run}");
+    // The virtual root of the run is no beam of the user's.
+    assert!(!run.contains("__multi__"), "{run}");
+}
+
+#[test]
+fn a_separator_gives_each_target_its_own_arguments() {
+    let run = stdout(&aurora(&["--no-tui", "greet", "aurora", "--", "docs"]));
+    assert!(run.contains("hello aurora"), "{run}");
+    assert!(run.contains("documenting"), "{run}");
+
+    let plan = stdout(&aurora(&["--dry-run", "docs", "--", "greet", "who=docs"]));
+    assert_eq!(
+        plan,
+        "Execution plan for 'docs greet[who=docs]':\n  level 0: docs, greet[who=docs]\n"
+    );
+}
+
+#[test]
+fn a_value_that_names_a_beam_is_still_an_argument() {
+    let plan = stdout(&aurora(&["--dry-run", "greet", "docs"]));
+    assert_eq!(
+        plan,
+        "Execution plan for 'greet[who=docs]':\n  level 0: greet[who=docs]\n"
+    );
+
+    // After `--`, a value that names no beam goes on with the arguments.
+    let plan = stdout(&aurora(&["--dry-run", "greet", "--", "-v"]));
+    assert_eq!(
+        plan,
+        "Execution plan for 'greet[who=-v]':\n  level 0: greet[who=-v]\n"
+    );
+}
+
+#[test]
+fn a_tag_selects_every_beam_carrying_it() {
+    let plan = stdout(&aurora(&["--dry-run", "--tag", "ci"]));
+    assert_eq!(
+        plan,
+        "Execution plan for 'build test':\n  level 0: fetch\n  level 1: build\n  level 2: test\n"
+    );
+
+    let plan = stdout(&aurora(&["--dry-run", "docs", "--tag", "fast"]));
+    assert!(
+        plan.starts_with("Execution plan for 'docs test':"),
+        "{plan}"
+    );
+}
+
+#[test]
+fn an_unknown_tag_fails_with_a_suggestion() {
+    let output = aurora(&["--no-tui", "--tag", "cii"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("No beam is tagged 'cii'. Did you mean 'ci'?"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn exclude_prunes_a_dependency_with_what_only_it_needs() {
+    let plan = stdout(&aurora(&["--dry-run", "test", "--exclude", "build"]));
+    assert_eq!(plan, "Execution plan for 'test':\n  level 0: test\n");
+}
+
+#[test]
+fn exclude_rejects_the_target_and_an_unknown_beam() {
+    let output = aurora(&["--no-tui", "test", "--exclude", "test"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("--exclude test would exclude the target 'test'"),
+        "{stderr}"
+    );
+
+    let output = aurora(&["--no-tui", "test", "--exclude", "buidl"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Did you mean 'build'?"), "{stderr}");
+}
+
+#[test]
+fn only_runs_the_targets_without_their_dependencies() {
+    let stdout = stdout(&aurora(&["--no-tui", "--only", "test"]));
+    assert!(stdout.contains("testing"), "{stdout}");
+    assert!(!stdout.contains("building"), "{stdout}");
+    assert!(!stdout.contains("fetching"), "{stdout}");
+}
+
+#[test]
+fn only_keeps_the_order_between_targets() {
+    let plan = stdout(&aurora(&["--dry-run", "--only", "test", "build"]));
+    assert_eq!(
+        plan,
+        "Execution plan for 'test build':\n  level 0: build\n  level 1: test\n"
+    );
+}
+
+#[test]
+fn jobs_takes_a_positive_count() {
+    let stdout = stdout(&aurora(&["--no-tui", "--jobs", "1", "docs", "greet"]));
+    assert!(stdout.contains("documenting"), "{stdout}");
+    assert!(stdout.contains("hello world"), "{stdout}");
+
+    let output = aurora(&["--no-tui", "-j", "0", "docs"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("`0` is not a positive integer"), "{stderr}");
+}
+
+#[test]
+fn json_names_the_run_after_its_targets() {
+    let stdout = stdout(&aurora(&["--json", "greet", "--", "docs"]));
+    let events: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(events[0]["event"], "run_started");
+    assert_eq!(events[0]["target"], "greet[who=world] docs");
+    let mut beams: Vec<&str> = events[0]["beams"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|b| b.as_str().unwrap())
+        .collect();
+    beams.sort();
+    assert_eq!(beams, ["docs", "greet[who=world]"]);
+    assert!(!stdout.contains("__multi__"), "{stdout}");
+    assert_eq!(events.last().unwrap()["event"], "run_completed");
+}
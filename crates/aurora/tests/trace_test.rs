@@ -0,0 +1,194 @@
+use aurora::reporter::ReportSpec;
+use aurora::trace::render;
+use aurora_core::events::{BeamPhase, BeamStatus, SchedulerEvent};
+use serde_json::Value;
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+fn started(name: &str) -> SchedulerEvent {
+    SchedulerEvent::BeamStarted {
+        name: name.to_string(),
+    }
+}
+
+fn completed(name: &str, status: BeamStatus) -> SchedulerEvent {
+    SchedulerEvent::BeamCompleted {
+        name: name.to_string(),
+        status,
+    }
+}
+
+fn success() -> BeamStatus {
+    BeamStatus::Success {
+        duration: Duration::ZERO,
+        cached: false,
+    }
+}
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+/// The trace events named `name` with phase `ph`.
+fn find<'a>(trace: &'a Value, ph: &str, name: &str) -> Vec<&'a Value> {
+    trace["traceEvents"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|e| e["ph"] == ph && e["name"] == name)
+        .collect()
+}
+
+#[test]
+fn beams_take_the_lowest_free_slot() {
+    let t0 = Instant::now();
+    let trace = render(
+        "ci",
+        t0,
+        &[
+            (t0, started("a")),
+            (t0 + ms(1), started("b")),
+            (t0 + ms(10), completed("a", success())),
+            (t0 + ms(11), started("c")),
+            (t0 + ms(20), completed("b", success())),
+            (t0 + ms(30), completed("c", success())),
+            (t0 + ms(30), SchedulerEvent::AllDone { success: true }),
+        ],
+    );
+
+    let tid = |name: &str| find(&trace, "X", name)[0]["tid"].as_u64().unwrap();
+    assert_eq!((tid("a"), tid("b"), tid("c")), (1, 2, 1));
+    let a = find(&trace, "X", "a")[0];
+    assert_eq!(
+        (a["ts"].as_u64(), a["dur"].as_u64()),
+        (Some(0), Some(10_000))
+    );
+    assert_eq!(a["args"]["status"], "success");
+
+    let names: Vec<&str> = find(&trace, "M", "thread_name")
+        .iter()
+        .map(|e| e["args"]["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["scheduler", "slot 1", "slot 2"]);
+    assert_eq!(
+        find(&trace, "M", "process_name")[0]["args"]["name"],
+        "aurora ci"
+    );
+}
+
+#[test]
+fn phases_nest_inside_their_beam() {
+    let t0 = Instant::now();
+    let phase = |phase, from: u64, to: u64| SchedulerEvent::BeamPhase {
+        name: "build".to_string(),
+        phase,
+        start: t0 + ms(from),
+        end: t0 + ms(to),
+    };
+    // The recorder started, and `BeamStarted` arrived, after the gate began:
+    // the time origin moves back and the span widens to the gate.
+    let trace = render(
+        "build",
+        t0 + ms(2),
+        &[
+            (t0 + ms(2), started("build")),
+            (t0 + ms(3), phase(BeamPhase::Gate, 1, 3)),
+            (t0 + ms(5), phase(BeamPhase::InputHash, 3, 4)),
+            (t0 + ms(5), phase(BeamPhase::CacheLookup, 4, 5)),
+            (
+                t0 + ms(6),
+                SchedulerEvent::Warning {
+                    name: "build".to_string(),
+                    message: "input pattern matched no files: src/**".to_string(),
+                },
+            ),
+            (t0 + ms(9), phase(BeamPhase::Execute, 5, 9)),
+            (
+                t0 + ms(9),
+                completed(
+                    "build",
+                    BeamStatus::Failed {
+                        exit_code: 2,
+                        duration: ms(4),
+                    },
+                ),
+            ),
+        ],
+    );
+
+    let build = find(&trace, "X", "build")[0];
+    assert_eq!(
+        (build["ts"].as_u64(), build["dur"].as_u64()),
+        (Some(0), Some(8_000))
+    );
+    assert_eq!(build["args"]["exit_code"], 2);
+    for (name, ts, dur) in [
+        ("gate", 0, 2_000),
+        ("input hash", 2_000, 1_000),
+        ("cache lookup", 3_000, 1_000),
+        ("execute", 4_000, 4_000),
+    ] {
+        let span = find(&trace, "X", name)[0];
+        assert_eq!(
+            (span["ts"].as_u64(), span["dur"].as_u64()),
+            (Some(ts), Some(dur))
+        );
+        assert_eq!(span["tid"], build["tid"], "{name}");
+    }
+
+    let warning = find(&trace, "i", "warning")[0];
+    assert_eq!(warning["tid"], build["tid"]);
+    assert_eq!(
+        warning["args"]["message"],
+        "input pattern matched no files: src/**"
+    );
+}
+
+#[test]
+fn a_beam_that_never_started_is_an_instant_on_the_scheduler_track() {
+    let t0 = Instant::now();
+    let trace = render(
+        "deploy",
+        t0,
+        &[(t0 + ms(4), completed("deploy", BeamStatus::Cancelled))],
+    );
+    let deploy = find(&trace, "i", "deploy")[0];
+    assert_eq!(deploy["tid"], 0);
+    assert_eq!(deploy["args"]["status"], "cancelled");
+    assert!(find(&trace, "X", "deploy").is_empty());
+}
+
+#[test]
+fn report_spec_parses_a_trace() {
+    assert_eq!(
+        ReportSpec::parse("trace=out/trace.json").unwrap(),
+        ReportSpec::Trace(PathBuf::from("out/trace.json"))
+    );
+}
+
+#[test]
+fn trace_flag_writes_the_run() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("Beamfile"),
+        r#"
+aurora { version = "1" }
+beam "ok" { run { commands = ["echo hello"] } }
+"#,
+    )
+    .unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .args(["ok", "--no-tui", "--trace", "trace.json"])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let trace: Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.path().join("trace.json")).unwrap())
+            .unwrap();
+    assert_eq!(find(&trace, "X", "ok")[0]["args"]["status"], "success");
+    assert_eq!(find(&trace, "X", "execute").len(), 1);
+    assert_eq!(find(&trace, "i", "output")[0]["args"]["line"], "hello");
+}
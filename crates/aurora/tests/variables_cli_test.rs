@@ -0,0 +1,125 @@
+//! CLI tests for variables sourced from the environment, a file or a
+//! command, and `--print-vars`.
+
+use std::fs;
+use std::process::Command;
+use tempfile::TempDir;
+
+const BEAMFILE: &str = r#"
+variable "version" {
+  from_file = "VERSION"
+  default   = shell("echo 0.0.0-dev")
+}
+
+variable "sha" {
+  default = shell("echo abc123")
+}
+
+variable "region" {
+  from_env = "AURORA_TEST_REGION"
+  default  = "eu"
+}
+
+variable "token" {
+  from_env  = "AURORA_TEST_TOKEN"
+  sensitive = true
+}
+
+beam "release" {
+  run { commands = ["echo release ${var.version} ${var.sha} in ${var.region}"] }
+}
+"#;
+
+fn fixture_dir() -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("Beamfile"), BEAMFILE).unwrap();
+    fs::write(dir.path().join("VERSION"), "1.4.2\n").unwrap();
+    dir
+}
+
+fn aurora(dir: &TempDir, args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .env_remove("GITHUB_ACTIONS")
+        .env_remove("AURORA_TEST_REGION")
+        .env("AURORA_TEST_TOKEN", "s3cr3t")
+        .args(args)
+        .current_dir(dir.path())
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn print_vars_shows_each_value_and_where_it_came_from() {
+    let dir = fixture_dir();
+    let output = aurora(&dir, &["--print-vars", "--var", "sha=fff"]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "version = 1.4.2  (file VERSION)\n\
+         sha     = fff    (--var)\n\
+         region  = eu     (default)\n\
+         token   = ***    (env AURORA_TEST_TOKEN)\n"
+    );
+}
+
+#[test]
+fn evaluated_values_reach_the_commands() {
+    let dir = fixture_dir();
+    let output = aurora(&dir, &["--no-tui", "release"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    assert!(stdout.contains("release 1.4.2 abc123 in eu"), "{stdout}");
+}
+
+#[test]
+fn a_failing_command_stops_the_run_before_it_starts() {
+    let dir = fixture_dir();
+    fs::write(
+        dir.path().join("Beamfile"),
+        BEAMFILE.replace("echo abc123", "echo no repo >&2; exit 128"),
+    )
+    .unwrap();
+    let output = aurora(&dir, &["--no-tui", "release"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("variable 'sha': shell command `echo no repo >&2; exit 128` failed"),
+        "{stderr}"
+    );
+    assert!(stderr.contains("no repo"), "{stderr}");
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("release"));
+}
+
+#[test]
+fn list_and_inspect_show_the_sources_without_evaluating_them() {
+    let dir = fixture_dir();
+    fs::write(
+        dir.path().join("Beamfile"),
+        BEAMFILE.replace("echo abc123", "touch evaluated; exit 1"),
+    )
+    .unwrap();
+
+    let output = aurora(&dir, &["--list"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{output:?}");
+    assert!(
+        stdout.contains("from file VERSION, shell `echo 0.0.0-dev`"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("from shell `touch evaluated; exit 1`"),
+        "{stdout}"
+    );
+
+    let output = aurora(&dir, &["inspect", "--json"]);
+    assert!(output.status.success(), "{output:?}");
+    let document: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(document["variables"][1]["value"], "");
+    assert_eq!(document["variables"][1]["origin"], "unset");
+    assert!(!dir.path().join("evaluated").exists());
+
+    // A run still evaluates them, and fails before it starts.
+    let output = aurora(&dir, &["--dry-run", "release"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(dir.path().join("evaluated").exists());
+}
@@ -5,8 +5,10 @@
 #![cfg(unix)]
 
 use std::fs;
+use std::io::{BufRead, BufReader};
 use std::path::Path;
-use std::process::Command;
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
 use std::time::{Duration, Instant};
 
 // The beam appends a line to runs.log on every real run. A cache hit would NOT
@@ -79,3 +81,55 @@ fn a_change_triggers_a_second_cycle_then_ctrl_c_exits_zero() {
         "leaving watch mode with Ctrl-C exits 0"
     );
 }
+
+#[test]
+fn a_watch_cycle_renders_for_github_actions() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("Beamfile"),
+        BEAMFILE.replace("echo run >> runs.log", "echo working"),
+    )
+    .unwrap();
+    fs::write(dir.path().join("watched.txt"), "v1").unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_aurora"))
+        .args(["work", "--ci", "github", "--watch"])
+        .current_dir(dir.path())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let (tx, rx) = mpsc::channel();
+    let stdout = BufReader::new(child.stdout.take().unwrap());
+    std::thread::spawn(move || {
+        for line in stdout.lines().map_while(Result::ok) {
+            let _ = tx.send(line);
+        }
+    });
+    // The cycle's output is folded into the beam's group, not prefixed.
+    let mut lines = vec![];
+    while let Ok(line) = rx.recv_timeout(Duration::from_secs(20)) {
+        let done = line == "::endgroup::";
+        lines.push(line);
+        if done {
+            break;
+        }
+    }
+
+    // A Ctrl-C landing between the cycle and the wait for changes can go
+    // unheard: signal until the process is gone.
+    let deadline = Instant::now() + Duration::from_secs(20);
+    while child.try_wait().unwrap().is_none() {
+        assert!(Instant::now() < deadline, "aurora did not leave watch mode");
+        let killed = Command::new("kill")
+            .args(["-INT", &child.id().to_string()])
+            .status()
+            .unwrap();
+        assert!(killed.success(), "failed to signal the aurora process");
+        std::thread::sleep(Duration::from_millis(200));
+    }
+
+    let group = &lines[lines.len().saturating_sub(3)..];
+    assert!(group[0].starts_with("::group::[PASS] work"), "{lines:?}");
+    assert_eq!(group[1..], ["working", "::endgroup::"], "{lines:?}");
+}